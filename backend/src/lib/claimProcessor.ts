@@ -519,8 +519,6 @@ export async function processCompressedClaim(
     console.log('[ClaimProcessor] Step 2: Queueing process_claim_v2...')
 
     const claimedSoFar = BigInt(0)
-    const PRECISION = BigInt(1_000_000)
-    const vestingNumerator = PRECISION // Assume fully vested for now
 
     // =======================================================================
     // MVP WORKAROUND: Use vault balance as total amount cap
@@ -535,7 +533,8 @@ export async function processCompressedClaim(
     // =======================================================================
 
     // Fetch vault balance to use as total amount cap
-    const [vaultPda] = findVaultPda(organization)
+    const orgAccount = await (program.account as any).organization.fetch(organization)
+    const [vaultPda] = findVaultPda(organization, orgAccount.tokenMint as PublicKey)
     const vaultAccountInfo = await connection.getTokenAccountBalance(vaultPda)
     const vaultBalance = BigInt(vaultAccountInfo.value.amount)
 
@@ -549,12 +548,13 @@ export async function processCompressedClaim(
     const nonce = randomBytes(16)
     const nonceAsBN = new BN(deserializeLE(nonce).toString())
 
-    // Encrypt values with our fresh keypair
-    // MPC will decrypt using our public key + its MXE private key
-    const encryptedTotalAmount = cipher.encrypt([vaultBalance], nonce)
-    const encryptedClaimedAmount = cipher.encrypt([claimedSoFar], nonce)
-    const encryptedVestingNumerator = cipher.encrypt([vestingNumerator], nonce)
-    const encryptedClaimAmount = cipher.encrypt([effectiveClaimAmount], nonce)
+    // Encrypt the position amounts with our fresh keypair
+    // MPC will decrypt using our public key + its MXE private key; the vesting
+    // numerator is computed on-chain and the claim amount is passed in plaintext
+    const [encryptedTotalAmount, encryptedClaimedAmount] = cipher.encrypt(
+      [vaultBalance, claimedSoFar],
+      nonce
+    )
 
     console.log('[ClaimProcessor] Effective claim amount:', effectiveClaimAmount.toString())
 
@@ -564,10 +564,8 @@ export async function processCompressedClaim(
       .queueProcessClaimCompressed(
         computationOffset,
         new BN(params.positionId),
-        Array.from(encryptedTotalAmount[0]) as any,       // From cipher.encrypt()
-        Array.from(encryptedClaimedAmount[0]) as any,     // From cipher.encrypt()
-        Array.from(encryptedVestingNumerator[0]) as any,  // From cipher.encrypt()
-        Array.from(encryptedClaimAmount[0]) as any,       // From cipher.encrypt()
+        Array.from(encryptedTotalAmount) as any,          // From cipher.encrypt()
+        Array.from(encryptedClaimedAmount) as any,        // From cipher.encrypt()
         new BN(effectiveClaimAmount.toString()),          // Use effective (capped) amount
         new BN(positionData.startTimestamp),
         Array.from(publicKey) as any,
@@ -579,6 +577,7 @@ export async function processCompressedClaim(
         schedule: userSchedulePda,  // USER's schedule for vesting calculation
         position: scratchPositionPda,  // SCRATCH position from SERVICE org for MPC callback
         claimAuthorization: claimAuthPda,
+        vault: vaultPda,
         signPdaAccount: signPda,
         mxeAccount: getMXEAccAddress(programId),
         mempoolAccount: getMempoolAccAddress(config.arciumClusterOffset),
@@ -662,12 +661,9 @@ export async function processCompressedClaim(
       Buffer.from(updatedCompressedAccount.data!.data!)
     )
 
-    // Get new encrypted_claimed_amount from scratch position
-    const scratchPosition = await (program.account as any).vestingPosition.fetch(scratchPositionPda)
-    const newEncryptedClaimedAmount = scratchPosition.encryptedClaimedAmount
-
-    // Determine if fully claimed
-    const newIsFullyClaimed = claimAmountBigInt >= BigInt(100_000_000) ? 1 : 0
+    // The program writes the amounts the MPC re-encrypted for this claim (kept on
+    // the claim authorization); the fully-claimed flag must match the revealed one
+    const newIsFullyClaimed = claimAuth.compressedIsFullyClaimed
 
     const updateTx = await (program.methods as any)
       .updateCompressedPositionClaimed(
@@ -684,7 +680,6 @@ export async function processCompressedClaim(
         new BN(updatedPositionData.startTimestamp),
         updatedPositionData.isActive,
         updatedPositionData.isFullyClaimed,
-        Array.from(newEncryptedClaimedAmount) as any,
         newIsFullyClaimed
       )
       .accountsPartial({
@@ -187,7 +187,7 @@ export async function initializeServiceOrganization(tokenMint: PublicKey): Promi
     // Derive schedule PDA
     const scheduleIndex = 0
     const [schedulePda] = findSchedulePda(orgPda, scheduleIndex)
-    const [vaultPda] = findVaultPda(orgPda)
+    const [vaultPda] = findVaultPda(orgPda, tokenMint)
     const [vaultAuthorityPda] = findVaultAuthorityPda(orgPda)
 
     state.organizationPda = orgPda
@@ -226,7 +226,7 @@ export async function initializeServiceOrganization(tokenMint: PublicKey): Promi
     // Account exists with correct size, set up state
     const scheduleIndex = 0
     const [schedulePda] = findSchedulePda(orgPda, scheduleIndex)
-    const [vaultPda] = findVaultPda(orgPda)
+    const [vaultPda] = findVaultPda(orgPda, tokenMint)
     const [vaultAuthorityPda] = findVaultAuthorityPda(orgPda)
 
     state.organizationPda = orgPda
@@ -250,7 +250,7 @@ export async function initializeServiceOrganization(tokenMint: PublicKey): Promi
       .digest()
 
     // Derive vault PDAs (for state tracking, not used in createOrganization)
-    const [vaultPda] = findVaultPda(orgPda)
+    const [vaultPda] = findVaultPda(orgPda, tokenMint)
     const [vaultAuthorityPda] = findVaultAuthorityPda(orgPda)
 
     const computeIx = ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })
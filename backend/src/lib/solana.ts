@@ -488,10 +488,10 @@ export function findVaultAuthorityPda(organization: PublicKey): [PublicKey, numb
   )
 }
 
-export function findVaultPda(organization: PublicKey): [PublicKey, number] {
+export function findVaultPda(organization: PublicKey, mint: PublicKey): [PublicKey, number] {
   const programId = new PublicKey(config.shadowvestProgramId)
   return PublicKey.findProgramAddressSync(
-    [Buffer.from('vault'), organization.toBuffer()],
+    [Buffer.from('vault'), organization.toBuffer(), mint.toBuffer()],
     programId
   )
 }
@@ -769,13 +769,8 @@ export interface PrepareClaimResult {
   // Vesting info
   vestingProgress: VestingProgressInfo
 
-  // Encrypted data for queue_process_claim
-  encryptedTotalAmount: number[]
-  encryptedClaimedAmount: number[]
-  encryptedVestingNumerator: number[]
-  encryptedClaimAmount: number[]
-  clientPubkey: number[]
-  nonce: string
+  // queue_process_claim reads the position's stored amounts and computes the
+  // vesting numerator on-chain, so only the computation offset is prepared
   computationOffset: string
 
   // Arcium accounts
@@ -812,7 +807,6 @@ export async function prepareClaimData(
   const [claimAuthorizationPda] = findClaimAuthorizationPda(organization, positionId, nullifier)
   const [nullifierPda] = findNullifierPda(organization, nullifier)
   const [signPda] = findSignPda()
-  const [vaultPda] = findVaultPda(organization)
   const [vaultAuthorityPda] = findVaultAuthorityPda(organization)
 
   // Fetch position and schedule data
@@ -827,6 +821,7 @@ export async function prepareClaimData(
   if (!orgData) {
     throw new Error('Organization not found on-chain')
   }
+  const [vaultPda] = findVaultPda(organization, orgData.tokenMint)
 
   // Find schedule by iterating (since we have the schedule pubkey)
   let schedule = null
@@ -845,18 +840,6 @@ export async function prepareClaimData(
   // Calculate vesting progress
   const vestingProgress = calculateVestingProgress(position, schedule)
 
-  // Create dummy provider for encryption
-  const dummyKeypair = Keypair.generate()
-  const prov = createProvider(dummyKeypair)
-
-  // Encrypt values for MPC
-  // Note: In production, totalAmount and claimedAmount come from the encrypted position data
-  // For MVP, we'll encrypt placeholder values - the MPC circuit handles the actual calculation
-  const encTotalAmount = await encryptAmount(prov, BigInt(0)) // Encrypted on-chain
-  const encClaimedAmount = await encryptAmount(prov, BigInt(0)) // Encrypted on-chain
-  const encVestingNumerator = await encryptAmount(prov, BigInt(vestingProgress.vestingNumerator))
-  const encClaimAmount = await encryptAmount(prov, claimAmount)
-
   // Generate computation offset (little-endian, matching the test pattern)
   const computationOffsetBytes = randomBytes(8)
   const computationOffset = new BN(computationOffsetBytes, 'le')
@@ -890,12 +873,6 @@ export async function prepareClaimData(
 
     vestingProgress,
 
-    encryptedTotalAmount: encTotalAmount.ciphertext,
-    encryptedClaimedAmount: encClaimedAmount.ciphertext,
-    encryptedVestingNumerator: encVestingNumerator.ciphertext,
-    encryptedClaimAmount: encClaimAmount.ciphertext,
-    clientPubkey: Array.from(encClaimAmount.publicKey),
-    nonce: encClaimAmount.nonce.toString(),
     computationOffset: computationOffset.toString(),
 
     arciumAccounts,
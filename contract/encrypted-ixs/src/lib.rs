@@ -27,10 +27,10 @@ mod circuits {
         total_amount: u64,
         /// Encrypted claimed amount
         claimed_amount: u64,
-        /// Vesting numerator - pre-computed from timestamps (0 to PRECISION)
-        /// Calculated off-chain as: (elapsed_intervals * interval) * PRECISION / vesting_duration
+        /// Vesting numerator - pre-computed from timestamps (0 to precision)
+        /// Calculated off-chain as: (elapsed_intervals * interval) * precision / vesting_duration
         /// = 0 if cliff not passed
-        /// = PRECISION (1_000_000) if fully vested
+        /// = precision (1_000_000 by default) if fully vested
         /// This preserves exact second-based vesting with interval snapshots
         vesting_numerator: u64,
     }
@@ -43,9 +43,6 @@ mod circuits {
         claimable_amount: u64,
     }
 
-    /// Precision constant for vesting fraction (10^6 = 0.0001% precision)
-    const PRECISION: u64 = 1_000_000;
-
     /// Input for processing a claim
     pub struct ProcessClaimInput {
         /// Encrypted claimed amount
@@ -84,24 +81,29 @@ mod circuits {
     /// if current_time < start_time + cliff_duration:
     ///     vesting_numerator = 0
     /// elif current_time >= start_time + total_duration:
-    ///     vesting_numerator = PRECISION (1_000_000)
+    ///     vesting_numerator = precision
     /// else:
     ///     elapsed = current_time - start_time - cliff_duration
     ///     intervals = elapsed / vesting_interval
     ///     vested_seconds = intervals * vesting_interval
-    ///     vesting_numerator = vested_seconds * PRECISION / (total_duration - cliff_duration)
+    ///     vesting_numerator = vested_seconds * precision / (total_duration - cliff_duration)
     /// ```
     ///
-    /// This keeps exact second-based semantics while avoiding expensive MPC division
+    /// `precision` is the schedule's numerator scale (10^6 by default), passed in
+    /// plaintext. This keeps exact second-based semantics while avoiding expensive
+    /// MPC division.
     #[instruction]
     pub fn calculate_vested(
         input: Enc<Shared, CalculateVestedInput>,
+        precision: u64,
     ) -> Enc<Shared, CalculateVestedResult> {
         let data = input.to_arcis();
 
-        // Simple calculation: vested = total * numerator / PRECISION
-        // The division by constant PRECISION is much cheaper than variable division
-        let vested_amount = data.total_amount * data.vesting_numerator / PRECISION;
+        // Simple calculation: vested = total * numerator / precision
+        // Dividing by the public precision is much cheaper than by a secret value.
+        // The product is taken in u128: total * precision overflows u64 for large grants.
+        let vested_amount = (data.total_amount as u128 * data.vesting_numerator as u128
+            / precision as u128) as u64;
 
         // Claimable = vested - claimed (if positive)
         let claimable_amount = if vested_amount > data.claimed_amount {
@@ -143,67 +145,297 @@ mod circuits {
         input.owner.from_arcis(result)
     }
 
+    /// Process up to four claims in one computation.
+    /// Each slot is a position's stored amounts (under its own key and nonce) with
+    /// its on-chain vesting numerator and plaintext claim amount, validated with the
+    /// same rule as process_claim_v2, so an invalid slot leaves its amounts unchanged
+    /// without failing the others. All positions share one schedule and so one
+    /// `precision`. Each slot's amounts are re-encrypted for its own key; the per-slot
+    /// flags are revealed packed into one byte: bit i is slot i's validity and
+    /// bit 4 + i whether slot i is now fully claimed.
+    #[instruction]
+    pub fn process_claims_batch(
+        position_0: Enc<Shared, PositionAmounts>,
+        vesting_numerator_0: u64,
+        claim_amount_0: u64,
+        position_1: Enc<Shared, PositionAmounts>,
+        vesting_numerator_1: u64,
+        claim_amount_1: u64,
+        position_2: Enc<Shared, PositionAmounts>,
+        vesting_numerator_2: u64,
+        claim_amount_2: u64,
+        position_3: Enc<Shared, PositionAmounts>,
+        vesting_numerator_3: u64,
+        claim_amount_3: u64,
+        precision: u64,
+    ) -> (
+        Enc<Shared, PositionAmounts>,
+        Enc<Shared, PositionAmounts>,
+        Enc<Shared, PositionAmounts>,
+        Enc<Shared, PositionAmounts>,
+        u8,
+    ) {
+        let (amounts_0, valid_0, fully_0) = apply_claim(
+            position_0.to_arcis(),
+            vesting_numerator_0,
+            claim_amount_0,
+            precision,
+        );
+        let (amounts_1, valid_1, fully_1) = apply_claim(
+            position_1.to_arcis(),
+            vesting_numerator_1,
+            claim_amount_1,
+            precision,
+        );
+        let (amounts_2, valid_2, fully_2) = apply_claim(
+            position_2.to_arcis(),
+            vesting_numerator_2,
+            claim_amount_2,
+            precision,
+        );
+        let (amounts_3, valid_3, fully_3) = apply_claim(
+            position_3.to_arcis(),
+            vesting_numerator_3,
+            claim_amount_3,
+            precision,
+        );
+
+        let status = (if valid_0 { 1u8 } else { 0u8 })
+            + (if valid_1 { 2u8 } else { 0u8 })
+            + (if valid_2 { 4u8 } else { 0u8 })
+            + (if valid_3 { 8u8 } else { 0u8 })
+            + (if fully_0 { 16u8 } else { 0u8 })
+            + (if fully_1 { 32u8 } else { 0u8 })
+            + (if fully_2 { 64u8 } else { 0u8 })
+            + (if fully_3 { 128u8 } else { 0u8 });
+
+        (
+            position_0.owner.from_arcis(amounts_0),
+            position_1.owner.from_arcis(amounts_1),
+            position_2.owner.from_arcis(amounts_2),
+            position_3.owner.from_arcis(amounts_3),
+            status.reveal(),
+        )
+    }
+
     // ============================================================
     // Process Claim V2: Integrated Vesting + Claim Validation
     // ============================================================
 
-    /// Input for process_claim_v2 (integrated vesting calculation)
-    /// The MPC computes claimable amount internally from total_amount and vesting_numerator,
-    /// removing the need for client-supplied max_claimable.
-    pub struct ProcessClaimV2Input {
-        /// Encrypted total vesting amount
-        total_amount: u64,
-        /// Encrypted amount already claimed
-        claimed_amount: u64,
-        /// Vesting numerator (computed on-chain from timestamps, 0 to PRECISION)
+    /// Apply a claim to a position's amounts.
+    /// Computes claimable = (total * numerator / precision) - claimed, where `precision`
+    /// is the schedule's numerator scale, and accepts the claim if claim_amount <= claimable.
+    /// Returns the new amounts, whether the claim is valid and whether the position
+    /// is now fully claimed (new claimed amount >= total).
+    fn apply_claim(
+        amounts: PositionAmounts,
         vesting_numerator: u64,
-        /// Amount being claimed
         claim_amount: u64,
-    }
+        precision: u64,
+    ) -> (PositionAmounts, bool, bool) {
+        // Calculate vested amount from total and on-chain-derived numerator
+        // (u128 product, see calculate_vested)
+        let vested_amount = (amounts.total_amount as u128 * vesting_numerator as u128
+            / precision as u128) as u64;
 
-    /// Output for process_claim_v2 (same structure as v1)
-    pub struct ProcessClaimV2Result {
-        /// New claimed amount
-        new_claimed_amount: u64,
-        /// Whether the claim is valid (1 = valid, 0 = invalid)
-        is_valid: u8,
+        // Calculate claimable (vested minus already claimed)
+        let claimable = if vested_amount > amounts.claimed_amount {
+            vested_amount - amounts.claimed_amount
+        } else {
+            0
+        };
+
+        // Validate claim amount against computed claimable, and never let the
+        // claimed amount exceed the total (guards against a numerator > precision)
+        let is_valid = claim_amount <= claimable
+            && amounts.claimed_amount as u128 + claim_amount as u128
+                <= amounts.total_amount as u128;
+
+        // Calculate new claimed amount
+        let claimed_amount = if is_valid {
+            amounts.claimed_amount + claim_amount
+        } else {
+            amounts.claimed_amount
+        };
+
+        let fully_claimed = claimed_amount >= amounts.total_amount;
+
+        let result = PositionAmounts {
+            total_amount: amounts.total_amount,
+            claimed_amount,
+        };
+
+        (result, is_valid, fully_claimed)
     }
 
     /// Process a claim with integrated vesting calculation (V2).
-    /// Computes claimable internally: claimable = (total * numerator / PRECISION) - claimed
-    /// Then validates: claim_amount <= claimable.
-    /// The vesting_numerator is computed on-chain from Clock + schedule, ensuring
-    /// the vesting fraction cannot be faked by the client.
+    /// The position's stored amounts are read under its own key and nonce, and both
+    /// are re-encrypted for that key so the stored ciphertexts stay consistent.
+    /// The vesting_numerator and claim_amount are plaintext: the numerator is
+    /// computed on-chain from Clock + schedule, so the vesting fraction cannot be
+    /// faked by the client, and the claim amount is public once withdrawn anyway.
+    /// The validity flag is revealed so the callback can reject an invalid claim, and
+    /// the fully-claimed flag so the callback can record it instead of trusting the client.
     #[instruction]
-    pub fn process_claim_v2(input: Enc<Shared, ProcessClaimV2Input>) -> Enc<Shared, ProcessClaimV2Result> {
-        let data = input.to_arcis();
+    pub fn process_claim_v2(
+        position: Enc<Shared, PositionAmounts>,
+        vesting_numerator: u64,
+        claim_amount: u64,
+        precision: u64,
+    ) -> (Enc<Shared, PositionAmounts>, bool, bool) {
+        let (amounts, is_valid, fully_claimed) =
+            apply_claim(position.to_arcis(), vesting_numerator, claim_amount, precision);
+
+        (
+            position.owner.from_arcis(amounts),
+            is_valid.reveal(),
+            fully_claimed.reveal(),
+        )
+    }
 
-        // Calculate vested amount from total and on-chain-derived numerator
-        let vested_amount = data.total_amount * data.vesting_numerator / PRECISION;
+    // ============================================================
+    // Position Top-Up: Claimed Amount Consistency
+    // ============================================================
 
-        // Calculate claimable (vested minus already claimed)
-        let claimable = if vested_amount > data.claimed_amount {
-            vested_amount - data.claimed_amount
+    /// Apply a top-up to a position's stored amounts. The new total must not be
+    /// below the current one (and so never below the claimed amount).
+    /// Both amounts are re-encrypted together for the position's key so the stored
+    /// ciphertexts stay consistent; the claimed amount is carried over unchanged.
+    /// The validity flag is revealed so the callback can reject an invalid top-up.
+    #[instruction]
+    pub fn top_up_position(
+        position: Enc<Shared, PositionAmounts>,
+        new_total_amount: Enc<Shared, u64>,
+    ) -> (Enc<Shared, PositionAmounts>, bool) {
+        let amounts = position.to_arcis();
+        let new_total_amount = new_total_amount.to_arcis();
+
+        let is_valid = new_total_amount >= amounts.total_amount
+            && amounts.claimed_amount <= new_total_amount;
+
+        let total_amount = if is_valid {
+            new_total_amount
         } else {
-            0
+            amounts.total_amount
         };
 
-        // Validate claim amount against computed claimable
-        let is_valid = data.claim_amount <= claimable;
+        let result = PositionAmounts {
+            total_amount,
+            claimed_amount: amounts.claimed_amount,
+        };
 
-        // Calculate new claimed amount
-        let new_claimed_amount = if is_valid {
-            data.claimed_amount + data.claim_amount
-        } else {
-            data.claimed_amount
+        (position.owner.from_arcis(result), is_valid.reveal())
+    }
+
+    /// Compare two encrypted position totals for a fairness audit.
+    /// Each total is the position's stored ciphertext under its own key and nonce.
+    /// Only the equality result is re-encrypted for the auditor; the amounts stay hidden.
+    #[instruction]
+    pub fn compare_totals(
+        total_a: Enc<Shared, u64>,
+        total_b: Enc<Shared, u64>,
+        auditor: Shared,
+    ) -> Enc<Shared, bool> {
+        let is_equal = total_a.to_arcis() == total_b.to_arcis();
+        auditor.from_arcis(is_equal)
+    }
+
+    /// A position's stored amounts, used by process_claim_v2, process_claims_batch,
+    /// reencrypt_position, top_up_position, merge_positions and check_solvency
+    pub struct PositionAmounts {
+        /// Total vesting amount
+        total_amount: u64,
+        /// Claimed amount
+        claimed_amount: u64,
+    }
+
+    /// Consolidate two positions into one by summing their stored totals and claimed
+    /// amounts. Each position is read under its own key and nonce; the result is
+    /// encrypted for `owner`. The sums are taken in u128 and the merge is rejected
+    /// (revealed false, amounts zeroed) if the total would not fit in a u64.
+    #[instruction]
+    pub fn merge_positions(
+        position_a: Enc<Shared, PositionAmounts>,
+        position_b: Enc<Shared, PositionAmounts>,
+        owner: Shared,
+    ) -> (Enc<Shared, PositionAmounts>, bool) {
+        let a = position_a.to_arcis();
+        let b = position_b.to_arcis();
+
+        let total = a.total_amount as u128 + b.total_amount as u128;
+        let claimed = a.claimed_amount as u128 + b.claimed_amount as u128;
+        // claimed <= total for each position, so the total bounds both sums
+        let is_valid = total <= u64::MAX as u128;
+
+        let total_amount = if is_valid { total as u64 } else { 0 };
+        let claimed_amount = if is_valid { claimed as u64 } else { 0 };
+
+        let result = PositionAmounts {
+            total_amount,
+            claimed_amount,
         };
 
-        let result = ProcessClaimV2Result {
-            new_claimed_amount,
-            is_valid: if is_valid { 1u8 } else { 0u8 },
+        (owner.from_arcis(result), is_valid.reveal())
+    }
+
+    /// Proof of reserves: sum the outstanding obligations (total - claimed) of the
+    /// positions and compare against the public vault balance.
+    /// Each position is read from its own stored ciphertexts; `counted_n` is false
+    /// for unused slots and deactivated positions, which then add nothing.
+    /// Only the solvency bit is revealed; per-position amounts stay hidden.
+    /// The sum is accumulated in u128 so four near-max positions cannot wrap.
+    #[instruction]
+    pub fn check_solvency(
+        position_0: Enc<Shared, PositionAmounts>,
+        counted_0: bool,
+        position_1: Enc<Shared, PositionAmounts>,
+        counted_1: bool,
+        position_2: Enc<Shared, PositionAmounts>,
+        counted_2: bool,
+        position_3: Enc<Shared, PositionAmounts>,
+        counted_3: bool,
+        vault_balance: u64,
+    ) -> bool {
+        let p0 = position_0.to_arcis();
+        let p1 = position_1.to_arcis();
+        let p2 = position_2.to_arcis();
+        let p3 = position_3.to_arcis();
+
+        let outstanding_0 = if counted_0 {
+            (p0.total_amount - p0.claimed_amount) as u128
+        } else {
+            0u128
+        };
+        let outstanding_1 = if counted_1 {
+            (p1.total_amount - p1.claimed_amount) as u128
+        } else {
+            0u128
+        };
+        let outstanding_2 = if counted_2 {
+            (p2.total_amount - p2.claimed_amount) as u128
+        } else {
+            0u128
+        };
+        let outstanding_3 = if counted_3 {
+            (p3.total_amount - p3.claimed_amount) as u128
+        } else {
+            0u128
         };
 
-        input.owner.from_arcis(result)
+        let outstanding = outstanding_0 + outstanding_1 + outstanding_2 + outstanding_3;
+
+        let is_solvent = outstanding <= vault_balance as u128;
+        is_solvent.reveal()
+    }
+
+    /// Re-wrap a position's amounts under a new shared key (e.g. after an MXE key
+    /// rotation). The plaintext amounts are unchanged.
+    #[instruction]
+    pub fn reencrypt_position(
+        input: Enc<Shared, PositionAmounts>,
+        new_owner: Shared,
+    ) -> Enc<Shared, PositionAmounts> {
+        new_owner.from_arcis(input.to_arcis())
     }
 
     // ============================================================
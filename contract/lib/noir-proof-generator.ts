@@ -111,6 +111,7 @@ export interface IdentityProofInputs {
  * - nullifier: Poseidon(identity_secret, position_id)
  * - position_id: Identifies the vesting position
  * - position_commitment: Poseidon(identity_commitment, vesting_amount)
+ * - withdrawal_destination: destinationScalar(destination) of the claim's payout account
  *
  * Private inputs:
  * - identity_secret: Stealth key material
@@ -122,6 +123,7 @@ export interface EligibilityProofInputs {
   nullifier: string;              // Field as hex string
   position_id: string;            // Field as hex string
   position_commitment: string;    // Field as hex string
+  withdrawal_destination: string; // Field as hex string
   // Private
   identity_secret: string;        // Field as hex string
   vesting_amount: bigint;         // u64 total vesting amount
@@ -315,6 +317,7 @@ export class ShadowVestProver {
       nullifier: normalizeHexField(inputs.nullifier),
       position_id: normalizeHexField(inputs.position_id),
       position_commitment: normalizeHexField(inputs.position_commitment),
+      withdrawal_destination: normalizeHexField(inputs.withdrawal_destination),
     };
 
     const { witness } = await noir.execute(witnessInputs);
@@ -413,6 +416,7 @@ export class ShadowVestProver {
     if (!inputs.nullifier) throw new Error('Missing nullifier');
     if (!inputs.position_id) throw new Error('Missing position_id');
     if (!inputs.position_commitment) throw new Error('Missing position_commitment');
+    if (!inputs.withdrawal_destination) throw new Error('Missing withdrawal_destination');
     if (!inputs.identity_secret) throw new Error('Missing identity_secret');
     if (inputs.vesting_amount === undefined) throw new Error('Missing vesting_amount');
   }
@@ -742,13 +746,38 @@ function serializeIdentityPublicInputs(publicInputs: Uint8Array[]): Buffer {
 /**
  * Serialize EligibilityPublicInputs into Anchor-compatible bytes.
  * Layout: beneficiary_commitment(32) || nullifier(32) || position_id(32) || position_commitment(32)
+ *         || withdrawal_destination(32)
  */
 function serializeEligibilityPublicInputs(publicInputs: Uint8Array[]): Buffer {
-  const buf = Buffer.alloc(128);
+  const buf = Buffer.alloc(160);
   buf.set(publicInputs[0], 0);   // beneficiary_commitment
   buf.set(publicInputs[1], 32);  // nullifier
   buf.set(publicInputs[2], 64);  // position_id
   buf.set(publicInputs[3], 96);  // position_commitment
+  buf.set(publicInputs[4], 128); // withdrawal_destination
+  return buf;
+}
+
+/**
+ * The eligibility circuit's withdrawal_destination input for a payout account:
+ * SHA-256 of the key with the top byte cleared (matches the on-chain
+ * EligibilityPublicInputs::destination_scalar).
+ *
+ * @param destination - The account the claim will pay out to
+ * @returns Field element as a 0x-prefixed hex string
+ */
+export function destinationScalar(destination: PublicKey): string {
+  const hash = createHash('sha256').update(destination.toBuffer()).digest();
+  hash[0] = 0;
+  return '0x' + hash.toString('hex');
+}
+
+/**
+ * Serialize the ttl_seconds argument (u32 LE) shared by the verify instructions.
+ */
+function serializeTtl(ttlSeconds: number): Buffer {
+  const buf = Buffer.alloc(4);
+  buf.writeUInt32LE(ttlSeconds);
   return buf;
 }
 
@@ -768,6 +797,7 @@ function serializeEligibilityPublicInputs(publicInputs: Uint8Array[]): Buffer {
  * @param verifier - The verifier/payer public key
  * @param vkAccount - The verification key account for the withdrawal circuit
  * @param proof - The proof result from generateWithdrawalProof
+ * @param ttlSeconds - How long the proof record stays valid (0 = never expires)
  * @returns The transaction instruction and derived proof record PDA
  */
 export function buildVerifyWithdrawalIx(
@@ -775,6 +805,7 @@ export function buildVerifyWithdrawalIx(
   verifier: PublicKey,
   vkAccount: PublicKey,
   proof: ProofResult,
+  ttlSeconds: number = 0,
 ): { instruction: TransactionInstruction; proofRecordPda: PublicKey; computeBudgetIx: TransactionInstruction } {
   // The nullifier is the 3rd public input (index 2): state_root, epoch_id, nullifier, ...
   const nullifierBytes = proof.publicInputs[2];
@@ -788,6 +819,7 @@ export function buildVerifyWithdrawalIx(
     Buffer.from(discriminator),
     proofData,
     publicInputsData,
+    serializeTtl(ttlSeconds),
   ]);
 
   const instruction = new TransactionInstruction({
@@ -818,6 +850,7 @@ export function buildVerifyWithdrawalIx(
  * @param verifier - The verifier/payer public key
  * @param vkAccount - The verification key account for the identity circuit
  * @param proof - The proof result from generateIdentityProof
+ * @param ttlSeconds - How long the proof record stays valid (0 = never expires)
  * @returns The transaction instruction and derived proof record PDA
  */
 export function buildVerifyIdentityIx(
@@ -825,6 +858,7 @@ export function buildVerifyIdentityIx(
   verifier: PublicKey,
   vkAccount: PublicKey,
   proof: ProofResult,
+  ttlSeconds: number = 0,
 ): { instruction: TransactionInstruction; proofRecordPda: PublicKey; computeBudgetIx: TransactionInstruction } {
   // The position_commitment is the 1st (only) public input
   const positionCommitmentBytes = proof.publicInputs[0];
@@ -838,6 +872,7 @@ export function buildVerifyIdentityIx(
     Buffer.from(discriminator),
     proofData,
     publicInputsData,
+    serializeTtl(ttlSeconds),
   ]);
 
   const instruction = new TransactionInstruction({
@@ -865,6 +900,7 @@ export function buildVerifyIdentityIx(
  * @param verifier - The verifier/payer public key
  * @param vkAccount - The verification key account for the eligibility circuit
  * @param proof - The proof result from generateEligibilityProof
+ * @param ttlSeconds - How long the proof record stays valid (0 = never expires)
  * @returns The transaction instruction and derived proof record PDA
  */
 export function buildVerifyEligibilityIx(
@@ -872,6 +908,7 @@ export function buildVerifyEligibilityIx(
   verifier: PublicKey,
   vkAccount: PublicKey,
   proof: ProofResult,
+  ttlSeconds: number = 0,
 ): { instruction: TransactionInstruction; proofRecordPda: PublicKey; computeBudgetIx: TransactionInstruction } {
   // The nullifier is the 2nd public input (index 1): beneficiary_commitment, nullifier, ...
   const nullifierBytes = proof.publicInputs[1];
@@ -885,6 +922,7 @@ export function buildVerifyEligibilityIx(
     Buffer.from(discriminator),
     proofData,
     publicInputsData,
+    serializeTtl(ttlSeconds),
   ]);
 
   const instruction = new TransactionInstruction({
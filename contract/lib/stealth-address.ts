@@ -68,6 +68,8 @@ export interface StealthPaymentData {
   ephemeralPubkey: string; // base58
   /** Encrypted payload containing ephemeral private key (for memo field) */
   encryptedPayload: string; // base58
+  /** First byte of H(shared) - lets the recipient cheaply skip foreign payments */
+  viewTag: number;
 }
 
 /**
@@ -479,6 +481,13 @@ export async function generateStealthPayment(
     ephPriv32
   );
 
+  // View tag: first byte of the same H(shared) used for the tweak
+  const shared = await ed.getSharedSecret(
+    ephPriv32,
+    new PublicKey(metaAddress.viewPubkey).toBytes(),
+  );
+  const viewTag = sha256(shared)[0];
+
   // Encrypt ephemeral private key AND note for recipient
   const encryptedPayload = await encryptPayloadWithNote(
     ephPriv32,
@@ -490,6 +499,7 @@ export async function generateStealthPayment(
     stealthAddress,
     ephemeralPubkey: eph.publicKey.toBase58(),
     encryptedPayload,
+    viewTag,
   };
 }
 
@@ -586,6 +596,27 @@ export async function isMyStealthPayment(
   }
 }
 
+/**
+ * Compute the view tag of a payment from the recipient side
+ *
+ * One ECDH and hash, no point arithmetic: a payment whose event tag differs
+ * is not ours and can be skipped before isMyStealthPayment.
+ *
+ * @param viewPrivHex - Our view private key (hex)
+ * @param ephPub58 - Ephemeral public key from event (base58)
+ * @returns First byte of H(v * R)
+ */
+export async function computeViewTag(
+  viewPrivHex: string,
+  ephPub58: string
+): Promise<number> {
+  const shared = await ed.getSharedSecret(
+    to32u8(viewPrivHex),
+    to32u8(ephPub58),
+  );
+  return sha256(shared)[0];
+}
+
 /**
  * Scan a list of payments to find ours
  *
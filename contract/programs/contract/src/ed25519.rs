@@ -0,0 +1,291 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
+
+use crate::errors::ShadowVestError;
+
+// ============================================================
+// Ed25519 Claim Signature Verification
+//
+// Beneficiaries holding Ed25519 keys (stealth addresses) authorize claims
+// through Solana's Ed25519 precompile. The precompile fails the transaction
+// if the signature is invalid, so the program only has to check that the
+// preceding precompile instruction verified the expected signer over the
+// expected message.
+// ============================================================
+
+/// Ed25519 signature verification program ID
+pub const ED25519_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("Ed25519SigVerify111111111111111111111111111");
+
+/// Size of an Ed25519 public key
+pub const ED25519_PUBKEY_SIZE: usize = 32;
+
+/// Offsets table starts after num_signatures (u8) and a padding byte
+const SIGNATURE_OFFSETS_START: usize = 2;
+
+/// Serialized size of one Ed25519SignatureOffsets entry:
+/// signature_offset(u16), signature_ix(u16), pubkey_offset(u16), pubkey_ix(u16),
+/// message_offset(u16), message_size(u16), message_ix(u16)
+const SIGNATURE_OFFSETS_SIZE: usize = 14;
+
+/// Instruction index the precompile interprets as "this instruction"
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Verify that the instruction immediately before the current one is an Ed25519
+/// precompile instruction that checked a signature from `expected_signer`
+/// over `expected_message`.
+pub fn verify_ed25519_claim_signature(
+    ix_sysvar: &AccountInfo,
+    expected_signer: &[u8; ED25519_PUBKEY_SIZE],
+    expected_message: &[u8],
+) -> Result<()> {
+    let current_ix_index = sysvar_instructions::load_current_index_checked(ix_sysvar)
+        .map_err(|_| ShadowVestError::InvalidEligibilitySignature)?;
+
+    // The Ed25519 instruction must be the one immediately before this instruction
+    require!(
+        current_ix_index > 0,
+        ShadowVestError::InvalidEligibilitySignature
+    );
+
+    let ed25519_ix_index = current_ix_index - 1;
+
+    let ed25519_ix =
+        sysvar_instructions::load_instruction_at_checked(ed25519_ix_index as usize, ix_sysvar)
+            .map_err(|_| ShadowVestError::InvalidEligibilitySignature)?;
+
+    require!(
+        ed25519_ix.program_id == ED25519_PROGRAM_ID,
+        ShadowVestError::InvalidEligibilitySignature
+    );
+
+    check_ed25519_instruction_data(
+        &ed25519_ix.data,
+        ed25519_ix_index,
+        expected_signer,
+        expected_message,
+    )
+}
+
+/// Check the data of an Ed25519 precompile instruction located at `own_index`.
+///
+/// Every offset is bounds-checked against the instruction data, so malformed
+/// offsets are rejected instead of panicking on an out-of-range slice. The
+/// signature, pubkey and message must all live in the precompile instruction
+/// itself (`u16::MAX` or `own_index`), otherwise the precompile could have
+/// verified different bytes than the ones read here.
+pub fn check_ed25519_instruction_data(
+    data: &[u8],
+    own_index: u16,
+    expected_signer: &[u8; ED25519_PUBKEY_SIZE],
+    expected_message: &[u8],
+) -> Result<()> {
+    require!(
+        data.len() >= SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE,
+        ShadowVestError::InvalidEligibilitySignature
+    );
+
+    let num_signatures = data[0];
+    require!(
+        num_signatures == 1,
+        ShadowVestError::InvalidEligibilitySignature
+    );
+
+    let offsets = &data[SIGNATURE_OFFSETS_START..SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE];
+    let signature_ix = u16::from_le_bytes([offsets[2], offsets[3]]);
+    let pubkey_offset = u16::from_le_bytes([offsets[4], offsets[5]]) as usize;
+    let pubkey_ix = u16::from_le_bytes([offsets[6], offsets[7]]);
+    let message_data_offset = u16::from_le_bytes([offsets[8], offsets[9]]) as usize;
+    let message_data_size = u16::from_le_bytes([offsets[10], offsets[11]]) as usize;
+    let message_ix = u16::from_le_bytes([offsets[12], offsets[13]]);
+
+    let is_own = |ix: u16| ix == CURRENT_INSTRUCTION || ix == own_index;
+    require!(
+        is_own(signature_ix) && is_own(pubkey_ix) && is_own(message_ix),
+        ShadowVestError::InvalidEligibilitySignature
+    );
+
+    // Verify the signer matches the expected beneficiary key
+    let signer_pubkey = data
+        .get(pubkey_offset..pubkey_offset + ED25519_PUBKEY_SIZE)
+        .ok_or(ShadowVestError::InvalidEligibilitySignature)?;
+    require!(
+        signer_pubkey == expected_signer,
+        ShadowVestError::SignerMismatch
+    );
+
+    // Verify the signed message
+    let signed_message = data
+        .get(message_data_offset..message_data_offset + message_data_size)
+        .ok_or(ShadowVestError::InvalidEligibilitySignature)?;
+    require!(
+        signed_message == expected_message,
+        ShadowVestError::InvalidEligibilitySignature
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIGNER: [u8; ED25519_PUBKEY_SIZE] = [0xAB; ED25519_PUBKEY_SIZE];
+
+    /// Build Ed25519 instruction data in the same layout as
+    /// `Ed25519Program.createInstructionWithPublicKey` from web3.js:
+    /// header | pubkey | signature | message
+    fn build_ed25519_ix_data(signer: &[u8; 32], message: &[u8]) -> Vec<u8> {
+        let data_start = SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE;
+        let pubkey_offset = data_start as u16;
+        let signature_offset = pubkey_offset + ED25519_PUBKEY_SIZE as u16;
+        let message_offset = signature_offset + 64;
+
+        let mut data = vec![1u8, 0u8];
+        data.extend_from_slice(&signature_offset.to_le_bytes());
+        data.extend_from_slice(&u16::MAX.to_le_bytes());
+        data.extend_from_slice(&pubkey_offset.to_le_bytes());
+        data.extend_from_slice(&u16::MAX.to_le_bytes());
+        data.extend_from_slice(&message_offset.to_le_bytes());
+        data.extend_from_slice(&(message.len() as u16).to_le_bytes());
+        data.extend_from_slice(&u16::MAX.to_le_bytes());
+        data.extend_from_slice(signer);
+        data.extend_from_slice(&[0x11; 64]); // signature (checked by the precompile)
+        data.extend_from_slice(message);
+        data
+    }
+
+    fn claim_message() -> [u8; 72] {
+        let mut msg = [0u8; 72];
+        msg[..8].copy_from_slice(&7u64.to_le_bytes());
+        msg[8..40].copy_from_slice(&[0x22; 32]);
+        msg[40..72].copy_from_slice(&[0x33; 32]);
+        msg
+    }
+
+    #[test]
+    fn test_ed25519_valid_claim_signature() {
+        let msg = claim_message();
+        let data = build_ed25519_ix_data(&SIGNER, &msg);
+        assert!(check_ed25519_instruction_data(&data, 0, &SIGNER, &msg).is_ok());
+    }
+
+    #[test]
+    fn test_ed25519_signer_mismatch() {
+        let msg = claim_message();
+        let data = build_ed25519_ix_data(&[0xCD; 32], &msg);
+        let result = check_ed25519_instruction_data(&data, 0, &SIGNER, &msg);
+        assert_eq!(result.unwrap_err(), ShadowVestError::SignerMismatch.into());
+    }
+
+    #[test]
+    fn test_ed25519_message_mismatch() {
+        let msg = claim_message();
+        let data = build_ed25519_ix_data(&SIGNER, &msg);
+        let mut other = msg;
+        other[0] ^= 1;
+        let result = check_ed25519_instruction_data(&data, 0, &SIGNER, &other);
+        assert_eq!(
+            result.unwrap_err(),
+            ShadowVestError::InvalidEligibilitySignature.into()
+        );
+    }
+
+    #[test]
+    fn test_ed25519_accepts_explicit_own_index() {
+        let msg = claim_message();
+        let mut data = build_ed25519_ix_data(&SIGNER, &msg);
+        for field in [4, 8, 14] {
+            data[field..field + 2].copy_from_slice(&3u16.to_le_bytes());
+        }
+        assert!(check_ed25519_instruction_data(&data, 3, &SIGNER, &msg).is_ok());
+    }
+
+    #[test]
+    fn test_ed25519_rejects_cross_instruction_message() {
+        // message_ix pointing at an unrelated instruction would let the precompile
+        // verify a different message than the one checked here
+        let msg = claim_message();
+        let mut data = build_ed25519_ix_data(&SIGNER, &msg);
+        data[14..16].copy_from_slice(&2u16.to_le_bytes());
+        let result = check_ed25519_instruction_data(&data, 0, &SIGNER, &msg);
+        assert_eq!(
+            result.unwrap_err(),
+            ShadowVestError::InvalidEligibilitySignature.into()
+        );
+    }
+
+    #[test]
+    fn test_ed25519_rejects_cross_instruction_signature_or_pubkey() {
+        let msg = claim_message();
+        for field in [4, 8] {
+            let mut data = build_ed25519_ix_data(&SIGNER, &msg);
+            data[field..field + 2].copy_from_slice(&1u16.to_le_bytes());
+            let result = check_ed25519_instruction_data(&data, 0, &SIGNER, &msg);
+            assert_eq!(
+                result.unwrap_err(),
+                ShadowVestError::InvalidEligibilitySignature.into()
+            );
+        }
+    }
+
+    #[test]
+    fn test_ed25519_rejects_multiple_signatures() {
+        let msg = claim_message();
+        let mut data = build_ed25519_ix_data(&SIGNER, &msg);
+        data[0] = 2;
+        let result = check_ed25519_instruction_data(&data, 0, &SIGNER, &msg);
+        assert_eq!(
+            result.unwrap_err(),
+            ShadowVestError::InvalidEligibilitySignature.into()
+        );
+    }
+
+    #[test]
+    fn test_ed25519_rejects_out_of_range_pubkey_offset() {
+        let msg = claim_message();
+        let mut data = build_ed25519_ix_data(&SIGNER, &msg);
+        data[6..8].copy_from_slice(&u16::MAX.to_le_bytes());
+        let result = check_ed25519_instruction_data(&data, 0, &SIGNER, &msg);
+        assert_eq!(
+            result.unwrap_err(),
+            ShadowVestError::InvalidEligibilitySignature.into()
+        );
+    }
+
+    #[test]
+    fn test_ed25519_rejects_out_of_range_message_fields() {
+        let msg = claim_message();
+
+        // Message offset past the end of the data
+        let mut data = build_ed25519_ix_data(&SIGNER, &msg);
+        data[10..12].copy_from_slice(&u16::MAX.to_le_bytes());
+        let result = check_ed25519_instruction_data(&data, 0, &SIGNER, &msg);
+        assert_eq!(
+            result.unwrap_err(),
+            ShadowVestError::InvalidEligibilitySignature.into()
+        );
+
+        // Message size running past the end of the data
+        let mut data = build_ed25519_ix_data(&SIGNER, &msg);
+        data[12..14].copy_from_slice(&(msg.len() as u16 + 1).to_le_bytes());
+        let result = check_ed25519_instruction_data(&data, 0, &SIGNER, &msg);
+        assert_eq!(
+            result.unwrap_err(),
+            ShadowVestError::InvalidEligibilitySignature.into()
+        );
+    }
+
+    #[test]
+    fn test_ed25519_rejects_truncated_data() {
+        let msg = claim_message();
+        let data = build_ed25519_ix_data(&SIGNER, &msg);
+        for len in [0, 5, 15, 40] {
+            let result = check_ed25519_instruction_data(&data[..len], 0, &SIGNER, &msg);
+            assert_eq!(
+                result.unwrap_err(),
+                ShadowVestError::InvalidEligibilitySignature.into()
+            );
+        }
+    }
+}
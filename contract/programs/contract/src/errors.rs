@@ -26,6 +26,9 @@ pub enum ShadowVestError {
     #[msg("Invalid schedule parameters")]
     InvalidScheduleParams,
 
+    #[msg("Schedule total_duration exceeds the 100-year maximum")]
+    ScheduleDurationTooLong,
+
     #[msg("Cliff period not passed")]
     CliffNotPassed,
 
@@ -50,8 +53,32 @@ pub enum ShadowVestError {
     #[msg("Invalid address tree for Light Protocol")]
     InvalidAddressTree,
 
+    #[msg("Invalid output state tree for Light Protocol")]
+    InvalidOutputTree,
+
+    #[msg("Light Protocol validity proof could not be deserialized")]
+    LightProofDeserializationFailed,
+
+    #[msg("Light Protocol account meta could not be deserialized")]
+    LightAccountMetaDeserializationFailed,
+
+    #[msg("Light Protocol compressed account could not be derived from its address and data")]
+    LightAddressDerivationFailed,
+
     #[msg("Light Protocol CPI failed")]
-    LightProtocolCpiFailed,
+    LightCpiInvokeFailed,
+
+    #[msg("Position ID does not match the compressed position's address")]
+    CompressedPositionIdMismatch,
+
+    #[msg("Compressed position has not been updated for this claim")]
+    CompressedPositionNotUpdated,
+
+    #[msg("Compressed position state does not match the processed claim")]
+    CompressedPositionStateMismatch,
+
+    #[msg("Fully-claimed flag does not match the MPC result")]
+    FullyClaimedFlagMismatch,
 
     // Phase 4: Stealth address errors
     #[msg("Unauthorized owner")]
@@ -66,6 +93,18 @@ pub enum ShadowVestError {
     #[msg("Invalid stealth payment")]
     InvalidStealthPayment,
 
+    #[msg("Meta-keys vault has a write still pending in MPC")]
+    MetaKeysWriteInProgress,
+
+    #[msg("Meta-keys vault version does not match the expected version")]
+    MetaKeysVersionMismatch,
+
+    #[msg("Meta-keys vault still holds user-encrypted keys awaiting MPC re-encryption")]
+    MetaKeysWritePending,
+
+    #[msg("Auditor payload must be set exactly when the organization has an auditor")]
+    InvalidAuditorPayload,
+
     // Phase 5: Claim and withdrawal errors
     #[msg("Claim is not authorized")]
     ClaimNotAuthorized,
@@ -76,6 +115,9 @@ pub enum ShadowVestError {
     #[msg("Claim has already been withdrawn")]
     AlreadyWithdrawn,
 
+    #[msg("Claim has not been fully withdrawn")]
+    ClaimNotWithdrawn,
+
     #[msg("Invalid Ed25519 eligibility signature")]
     InvalidEligibilitySignature,
 
@@ -91,6 +133,60 @@ pub enum ShadowVestError {
     #[msg("Insufficient vault balance for withdrawal")]
     InsufficientVaultBalance,
 
+    #[msg("Withdrawal would breach the organization's minimum vault reserve")]
+    ReserveFloorBreached,
+
+    #[msg("Unsupported signature scheme")]
+    UnsupportedSignatureScheme,
+
+    #[msg("Claim authorization has been revoked")]
+    ClaimRevoked,
+
+    #[msg("Claim authorization has not expired")]
+    ClaimNotExpired,
+
+    #[msg("Claim queued before the schedule's minimum claim interval has passed")]
+    ClaimTooSoon,
+
+    #[msg("Claims are paused for this vesting schedule")]
+    ScheduleClaimsPaused,
+
+    #[msg("Claims are paused for this vesting position")]
+    PositionPaused,
+
+    #[msg("Position is in privacy mode; its total amount has not been revealed")]
+    PositionIsPrivate,
+
+    #[msg("Organization is paused; withdrawals are disabled")]
+    OrganizationPaused,
+
+    #[msg("Withdrawal fee exceeds 10,000 basis points")]
+    InvalidFeeBps,
+
+    #[msg("Fee destination does not match the organization's fee account")]
+    InvalidFeeDestination,
+
+    #[msg("Withdrawal destination must be an associated token account")]
+    NonAtaDestination,
+
+    #[msg("Withdrawal destination is not on the organization's allowlist")]
+    DestinationNotAllowed,
+
+    #[msg("Organization has a challenge period; queue the withdrawal first")]
+    WithdrawalNotQueued,
+
+    #[msg("Withdrawal is already queued")]
+    WithdrawalAlreadyQueued,
+
+    #[msg("Withdrawal challenge period has not elapsed")]
+    ChallengePeriodActive,
+
+    #[msg("Claim amount requires the organization guardian's signature")]
+    GuardianSignatureRequired,
+
+    #[msg("Priority fee account does not match the organization's incentive account")]
+    InvalidPriorityFeeAccount,
+
     // Phase 6: Groth16 ZK proof verification errors
     #[msg("Proof verification failed")]
     ProofVerificationFailed,
@@ -98,6 +194,9 @@ pub enum ShadowVestError {
     #[msg("Invalid number of public inputs for this circuit")]
     InvalidPublicInputCount,
 
+    #[msg("Number of public inputs does not match the verification key's IC points")]
+    ProofInputCountMismatch,
+
     #[msg("alt_bn128 group operation failed")]
     Bn128GroupOpFailed,
 
@@ -107,12 +206,119 @@ pub enum ShadowVestError {
     #[msg("Verification key is not active")]
     VerificationKeyNotActive,
 
+    #[msg("Verification key is still active")]
+    VerificationKeyStillActive,
+
     #[msg("Invalid verification key data")]
     InvalidVerificationKeyData,
 
     #[msg("Circuit ID mismatch")]
     CircuitIdMismatch,
 
+    #[msg("Proof type does not match the verification key's circuit")]
+    ProofTypeMismatch,
+
     #[msg("Proof record already exists for this nullifier")]
     ProofRecordAlreadyExists,
+
+    #[msg("Proof record has expired")]
+    ProofRecordExpired,
+
+    #[msg("Proof record is not valid")]
+    ProofRecordInvalid,
+
+    #[msg("Proof record nullifier does not match the claim")]
+    NullifierMismatch,
+
+    #[msg("Eligibility proof was not issued for this position, beneficiary and destination")]
+    EligibilityBindingMismatch,
+
+    #[msg("Schedule has no beneficiary Merkle root")]
+    MerkleRootNotSet,
+
+    #[msg("Merkle proof does not lead to the beneficiary root")]
+    InvalidMerkleProof,
+
+    // Phase 7: Organization administration errors
+    #[msg("Name and salt do not open the organization's name hash")]
+    NameHashMismatch,
+
+    #[msg("Registry page does not match the next registry slot")]
+    InvalidRegistryPage,
+
+    #[msg("Vesting schedule is referenced by existing positions")]
+    ScheduleInUse,
+
+    #[msg("Vesting schedule already uses the current account layout")]
+    ScheduleAlreadyMigrated,
+
+    #[msg("Organization has no default vesting schedule")]
+    NoDefaultSchedule,
+
+    #[msg("Vesting position has already been revoked")]
+    PositionAlreadyRevoked,
+
+    #[msg("Invalid vesting position state transition")]
+    InvalidPositionStateTransition,
+
+    #[msg("Vesting position is not fully claimed")]
+    PositionNotFullyClaimed,
+
+    #[msg("Vesting position has claims that are not yet withdrawn, revoked or rejected")]
+    PositionHasOpenClaims,
+
+    #[msg("A regular position's claim must be settled with its position account")]
+    ClaimPositionRequired,
+
+    #[msg("Batch is empty, too large, or does not match the accounts passed")]
+    InvalidBatchSize,
+
+    #[msg("The same position was passed more than once")]
+    DuplicatePosition,
+
+    #[msg("The positions passed are not the organization's full set of open positions")]
+    IncompletePositionSet,
+
+    #[msg("Batch position account does not match the expected PDA")]
+    InvalidBatchPositionAccount,
+
+    #[msg("Batch proof record account does not match the expected PDA")]
+    InvalidBatchProofRecordAccount,
+
+    #[msg("Organization requires compressed position storage")]
+    CompressedStorageRequired,
+
+    #[msg("Positions cannot be merged")]
+    InvalidMergePositions,
+
+    #[msg("Merged positions must carry the same metadata commitment")]
+    MetadataCommitmentMismatch,
+
+    #[msg("Organization still has positions that can claim from the vault")]
+    PositionsStillOpen,
+
+    #[msg("Position cannot be swept before its vesting end plus the grace period")]
+    SweepTooEarly,
+
+    #[msg("Position has no reserved vault balance left to sweep")]
+    NothingToSweep,
+
+    #[msg("Position's reserved amount has already been set")]
+    ReservedAmountAlreadySet,
+
+    #[msg("Token account is not owned by the organization treasury")]
+    InvalidTreasuryAccount,
+
+    #[msg("Organization already tracks reservations for the maximum number of vaults")]
+    TooManyVaults,
+
+    // Phase 8: MPC computation lifecycle errors
+    #[msg("A computation is already pending for this claim")]
+    ComputationPending,
+
+    #[msg("Pending computation has not timed out")]
+    ComputationNotExpired,
+
+    #[msg("Callback does not match the pending computation")]
+    StaleComputation,
 }
@@ -21,6 +21,16 @@ const ALT_BN128_FIELD_MODULUS: [u8; 32] = [
     0x3C, 0x20, 0x8C, 0x16, 0xD8, 0x7C, 0xFD, 0x47,
 ];
 
+/// The bn254 scalar field modulus r. Public inputs are elements of this field,
+/// so a canonical input is strictly less than r.
+/// r = 21888242871839275222246405745257275088548364400416034343698204186575808495617
+pub const BN254_SCALAR_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4E, 0x72, 0xE1, 0x31, 0xA0, 0x29,
+    0xB8, 0x50, 0x45, 0xB6, 0x81, 0x81, 0x58, 0x5D,
+    0x28, 0x33, 0xE8, 0x48, 0x79, 0xB9, 0x70, 0x91,
+    0x43, 0xE1, 0xF5, 0x93, 0xF0, 0x00, 0x00, 0x01,
+];
+
 // Note: Operation type constants are defined in solana_bn254::prelude
 // ALT_BN128_ADD = 0, ALT_BN128_SUB = 1, ALT_BN128_MUL = 2, ALT_BN128_PAIRING = 3
 
@@ -38,12 +48,21 @@ pub struct VerificationKey {
     pub delta_g2: [u8; 128],
     /// IC (Initial Conditions) points on G1 - one per public input + 1.
     /// ic[0] is the base, ic[1..] correspond to each public input.
+    /// The circuit's arity is read from this length, e.g.
     /// For withdrawal_proof: 5 points (4 public inputs + 1)
     /// For identity_proof: 2 points (1 public input + 1)
     /// For eligibility_proof: 5 points (4 public inputs + 1)
     pub ic: Vec<[u8; 64]>,
 }
 
+impl VerificationKey {
+    /// Number of public inputs the circuit takes (IC points - 1), or None for
+    /// a malformed key without the base IC point
+    pub fn num_public_inputs(&self) -> Option<usize> {
+        self.ic.len().checked_sub(1)
+    }
+}
+
 /// Groth16 proof consisting of three curve points (A, B, C).
 /// Generated by the Noir circuit prover (Barretenberg backend).
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
@@ -91,6 +110,9 @@ pub struct EligibilityPublicInputs {
     pub position_id: [u8; 32],
     /// Commitment binding to a specific position state
     pub position_commitment: [u8; 32],
+    /// Destination the claim pays out to (see destination_scalar), so a
+    /// verified proof cannot be replayed toward another account
+    pub withdrawal_destination: [u8; 32],
 }
 
 /// Verify a Groth16 proof against public inputs.
@@ -117,11 +139,13 @@ pub fn verify_groth16(
     proof: &Groth16Proof,
     public_inputs: &[[u8; 32]],
 ) -> Result<bool> {
-    // Validate that we have the right number of IC points
+    // The circuit arity comes from the VK: one IC point per public input + 1.
+    // A mismatch would pair inputs with the wrong IC points, so reject it.
     require!(
-        vk.ic.len() == public_inputs.len() + 1,
-        crate::errors::ShadowVestError::InvalidPublicInputCount
+        vk.num_public_inputs() == Some(public_inputs.len()),
+        crate::errors::ShadowVestError::ProofInputCountMismatch
     );
+    require_canonical_scalars(public_inputs)?;
 
     // Step 1: Compute the public input linear combination
     // vk_x = IC[0] + IC[1]*input[0] + IC[2]*input[1] + ... + IC[n]*input[n-1]
@@ -148,6 +172,177 @@ pub fn verify_groth16(
     )
 }
 
+/// Whether a 32-byte big-endian scalar is a canonical field element (< r)
+pub fn is_canonical_scalar(scalar: &[u8; 32]) -> bool {
+    // Big-endian byte arrays compare lexicographically in numeric order
+    scalar < &BN254_SCALAR_MODULUS
+}
+
+/// Reject public inputs that are not canonical field elements. The scalar
+/// multiplication would reduce them mod r, so a non-canonical input would
+/// verify as a different value than the one recorded on-chain.
+fn require_canonical_scalars(public_inputs: &[[u8; 32]]) -> Result<()> {
+    require!(
+        public_inputs.iter().all(is_canonical_scalar),
+        crate::errors::ShadowVestError::ProofVerificationFailed
+    );
+    Ok(())
+}
+
+/// Maximum proofs accepted by verify_groth16_batch. Each withdrawal proof plus
+/// its public inputs is 360 bytes of instruction data, so two proofs (and their
+/// ProofRecord accounts) are what fits in a single 1232-byte transaction.
+pub const MAX_BATCH_PROOFS: usize = 2;
+
+/// Domain separator for the batch verification challenge
+const BATCH_CHALLENGE_DOMAIN: &[u8] = b"shadowvest_groth16_batch";
+
+/// Verify several Groth16 proofs for the same verification key with one pairing check.
+///
+/// Uses the random-linear-combination trick: each proof's equation is scaled by a
+/// challenge r_i and the equations are multiplied together, so
+///   prod_i e(r_i*A_i, B_i) * e(-(sum r_i)*alpha, beta)
+///     * e(-sum r_i*vk_x_i, gamma) * e(-sum r_i*C_i, delta) == 1
+/// holds for all valid proofs and fails (except with negligible probability)
+/// if any proof is invalid. This costs n + 3 pairings instead of 4n.
+///
+/// r_0 = 1 and the other challenges are derived from a hash over every proof and
+/// public input, so a prover cannot pick proofs that cancel each other out.
+///
+/// # Returns
+/// * `Ok(true)` if every proof is valid
+/// * `Ok(false)` if at least one proof is invalid
+/// * `Err(...)` on a malformed batch or a failed curve operation
+pub fn verify_groth16_batch(
+    vk: &VerificationKey,
+    proofs: &[Groth16Proof],
+    public_inputs: &[Vec<[u8; 32]>],
+) -> Result<bool> {
+    require!(
+        !proofs.is_empty()
+            && proofs.len() <= MAX_BATCH_PROOFS
+            && proofs.len() == public_inputs.len(),
+        crate::errors::ShadowVestError::InvalidBatchSize
+    );
+    for inputs in public_inputs {
+        require!(
+            vk.num_public_inputs() == Some(inputs.len()),
+            crate::errors::ShadowVestError::ProofInputCountMismatch
+        );
+        require_canonical_scalars(inputs)?;
+    }
+
+    let challenges = batch_challenges(proofs, public_inputs);
+
+    let mut pairing_input = Vec::with_capacity((proofs.len() + 3) * 192);
+    let mut r_sum = [0u8; 32];
+    let mut vk_x_sum: Option<[u8; 64]> = None;
+    let mut c_sum: Option<[u8; 64]> = None;
+
+    for ((proof, inputs), r) in proofs.iter().zip(public_inputs).zip(challenges.iter()) {
+        // Pair (r_i * A_i, B_i)
+        pairing_input.extend_from_slice(&g1_mul(&proof.a, r)?);
+        pairing_input.extend_from_slice(&proof.b);
+
+        let vk_x = compute_public_input_lc(&vk.ic, inputs)?;
+        let scaled_vk_x = g1_mul(&vk_x, r)?;
+        vk_x_sum = Some(match vk_x_sum {
+            Some(acc) => g1_add(&acc, &scaled_vk_x)?,
+            None => scaled_vk_x,
+        });
+
+        let scaled_c = g1_mul(&proof.c, r)?;
+        c_sum = Some(match c_sum {
+            Some(acc) => g1_add(&acc, &scaled_c)?,
+            None => scaled_c,
+        });
+
+        add_scalar_be(&mut r_sum, r);
+    }
+
+    // (-(sum r_i) * alpha, beta)
+    pairing_input.extend_from_slice(&negate_g1(&g1_mul(&vk.alpha_g1, &r_sum)?));
+    pairing_input.extend_from_slice(&vk.beta_g2);
+
+    // (-sum r_i * vk_x_i, gamma)
+    pairing_input.extend_from_slice(&negate_g1(&vk_x_sum.unwrap_or([0u8; 64])));
+    pairing_input.extend_from_slice(&vk.gamma_g2);
+
+    // (-sum r_i * C_i, delta)
+    pairing_input.extend_from_slice(&negate_g1(&c_sum.unwrap_or([0u8; 64])));
+    pairing_input.extend_from_slice(&vk.delta_g2);
+
+    let pairing_result = alt_bn128_pairing(&pairing_input)
+        .map_err(|_| crate::errors::ShadowVestError::Bn128PairingFailed)?;
+
+    Ok(is_pairing_identity(&pairing_result))
+}
+
+/// Derive the batch challenges r_i as 32-byte big-endian scalars.
+///
+/// r_0 = 1; r_i (i > 0) is the first 16 bytes of
+/// sha256(domain || transcript || i), where the transcript hashes every proof
+/// and public input. 128-bit challenges keep every r_i (and their sum) below
+/// the bn254 scalar field order.
+fn batch_challenges(proofs: &[Groth16Proof], public_inputs: &[Vec<[u8; 32]>]) -> Vec<[u8; 32]> {
+    let mut transcript: Vec<&[u8]> = vec![BATCH_CHALLENGE_DOMAIN];
+    for (proof, inputs) in proofs.iter().zip(public_inputs) {
+        transcript.push(&proof.a);
+        transcript.push(&proof.b);
+        transcript.push(&proof.c);
+        for input in inputs {
+            transcript.push(input);
+        }
+    }
+    let transcript_hash = anchor_lang::solana_program::hash::hashv(&transcript).to_bytes();
+
+    (0..proofs.len())
+        .map(|i| {
+            let mut r = [0u8; 32];
+            if i == 0 {
+                r[31] = 1;
+            } else {
+                let h = anchor_lang::solana_program::hash::hashv(&[
+                    BATCH_CHALLENGE_DOMAIN,
+                    &transcript_hash,
+                    &(i as u64).to_le_bytes(),
+                ])
+                .to_bytes();
+                r[16..32].copy_from_slice(&h[..16]);
+            }
+            r
+        })
+        .collect()
+}
+
+/// Add two 32-byte big-endian scalars in place (callers keep the sum below 2^256)
+fn add_scalar_be(acc: &mut [u8; 32], value: &[u8; 32]) {
+    let mut carry: u16 = 0;
+    for i in (0..32).rev() {
+        let sum = acc[i] as u16 + value[i] as u16 + carry;
+        acc[i] = sum as u8;
+        carry = sum >> 8;
+    }
+}
+
+/// Scalar multiplication of a G1 point by a 32-byte big-endian scalar
+fn g1_mul(point: &[u8; 64], scalar: &[u8; 32]) -> Result<[u8; 64]> {
+    let mut mul_input = [0u8; 96];
+    mul_input[..64].copy_from_slice(point);
+    mul_input[64..96].copy_from_slice(scalar);
+    alt_bn128_multiplication(&mul_input)
+        .map_err(|_| crate::errors::ShadowVestError::Bn128GroupOpFailed.into())
+}
+
+/// Addition of two G1 points
+fn g1_add(p: &[u8; 64], q: &[u8; 64]) -> Result<[u8; 64]> {
+    let mut add_input = [0u8; 128];
+    add_input[..64].copy_from_slice(p);
+    add_input[64..128].copy_from_slice(q);
+    alt_bn128_addition(&add_input)
+        .map_err(|_| crate::errors::ShadowVestError::Bn128GroupOpFailed.into())
+}
+
 /// Compute the public input linear combination on G1.
 ///
 /// vk_x = IC[0] + sum(IC[i+1] * public_input[i]) for i in 0..n
@@ -294,8 +489,12 @@ fn pairing_check(
     let pairing_result = alt_bn128_pairing(&pairing_input)
         .map_err(|_| crate::errors::ShadowVestError::Bn128PairingFailed)?;
 
-    // Result is 32 bytes: value of 1 (big-endian) means pairing product == identity
-    // Check if result == 1 (last byte is 1, all others are 0)
+    Ok(is_pairing_identity(&pairing_result))
+}
+
+/// Interpret a pairing syscall result: a 32-byte big-endian value of 1 means the
+/// pairing product equals the identity (last byte is 1, all others are 0).
+fn is_pairing_identity(pairing_result: &[u8; 32]) -> bool {
     let mut is_valid = pairing_result[31] == 1;
     for i in 0..31 {
         if pairing_result[i] != 0 {
@@ -303,8 +502,7 @@ fn pairing_check(
             break;
         }
     }
-
-    Ok(is_valid)
+    is_valid
 }
 
 // ============================================================
@@ -396,11 +594,27 @@ impl EligibilityPublicInputs {
             self.nullifier,
             self.position_id,
             self.position_commitment,
+            self.withdrawal_destination,
         ]
     }
 
     /// Number of public inputs for this circuit
-    pub const NUM_PUBLIC_INPUTS: usize = 4;
+    pub const NUM_PUBLIC_INPUTS: usize = 5;
+
+    /// position_id input for an on-chain position id (big-endian, like epoch_id)
+    pub fn position_id_scalar(position_id: u64) -> [u8; 32] {
+        let mut scalar = [0u8; 32];
+        scalar[24..32].copy_from_slice(&position_id.to_be_bytes());
+        scalar
+    }
+
+    /// withdrawal_destination input for a destination account: sha256 of the
+    /// key with the top byte cleared, so it is always a canonical field element
+    pub fn destination_scalar(destination: &Pubkey) -> [u8; 32] {
+        let mut scalar = anchor_lang::solana_program::hash::hash(destination.as_ref()).to_bytes();
+        scalar[0] = 0;
+        scalar
+    }
 }
 
 #[cfg(test)]
@@ -447,6 +661,183 @@ mod tests {
         assert_eq!(double_negated, point);
     }
 
+    /// G2 generator in EIP-197 encoding (imaginary part first)
+    const G2_GENERATOR: [u8; 128] = [
+        // x (imaginary)
+        0x19, 0x8E, 0x93, 0x93, 0x92, 0x0D, 0x48, 0x3A,
+        0x72, 0x60, 0xBF, 0xB7, 0x31, 0xFB, 0x5D, 0x25,
+        0xF1, 0xAA, 0x49, 0x33, 0x35, 0xA9, 0xE7, 0x12,
+        0x97, 0xE4, 0x85, 0xB7, 0xAE, 0xF3, 0x12, 0xC2,
+        // x (real)
+        0x18, 0x00, 0xDE, 0xEF, 0x12, 0x1F, 0x1E, 0x76,
+        0x42, 0x6A, 0x00, 0x66, 0x5E, 0x5C, 0x44, 0x79,
+        0x67, 0x43, 0x22, 0xD4, 0xF7, 0x5E, 0xDA, 0xDD,
+        0x46, 0xDE, 0xBD, 0x5C, 0xD9, 0x92, 0xF6, 0xED,
+        // y (imaginary)
+        0x09, 0x06, 0x89, 0xD0, 0x58, 0x5F, 0xF0, 0x75,
+        0xEC, 0x9E, 0x99, 0xAD, 0x69, 0x0C, 0x33, 0x95,
+        0xBC, 0x4B, 0x31, 0x33, 0x70, 0xB3, 0x8E, 0xF3,
+        0x55, 0xAC, 0xDA, 0xDC, 0xD1, 0x22, 0x97, 0x5B,
+        // y (real)
+        0x12, 0xC8, 0x5E, 0xA5, 0xDB, 0x8C, 0x6D, 0xEB,
+        0x4A, 0xAB, 0x71, 0x80, 0x8D, 0xCB, 0x40, 0x8F,
+        0xE3, 0xD1, 0xE7, 0x69, 0x0C, 0x43, 0xD3, 0x7B,
+        0x4C, 0xE6, 0xCC, 0x01, 0x66, 0xFA, 0x7D, 0xAA,
+    ];
+
+    fn scalar(n: u8) -> [u8; 32] {
+        let mut s = [0u8; 32];
+        s[31] = n;
+        s
+    }
+
+    fn g1_generator_times(n: u8) -> [u8; 64] {
+        let mut g1 = [0u8; 64];
+        g1[31] = 1;
+        g1[63] = 2;
+        g1_mul(&g1, &scalar(n)).unwrap()
+    }
+
+    /// A verification key with every G2 point set to the generator, so a proof
+    /// with B = G2 and A = alpha + vk_x + C satisfies the Groth16 equation
+    /// without a trusted setup.
+    fn synthetic_vk() -> VerificationKey {
+        VerificationKey {
+            alpha_g1: g1_generator_times(3),
+            beta_g2: G2_GENERATOR,
+            gamma_g2: G2_GENERATOR,
+            delta_g2: G2_GENERATOR,
+            ic: vec![g1_generator_times(5), g1_generator_times(7)],
+        }
+    }
+
+    fn synthetic_proof(vk: &VerificationKey, inputs: &[[u8; 32]], c_scalar: u8) -> Groth16Proof {
+        let vk_x = compute_public_input_lc(&vk.ic, inputs).unwrap();
+        let c = g1_generator_times(c_scalar);
+        let a = g1_add(&g1_add(&vk.alpha_g1, &vk_x).unwrap(), &c).unwrap();
+        Groth16Proof { a, b: G2_GENERATOR, c }
+    }
+
+    #[test]
+    fn test_synthetic_proof_verifies() {
+        let vk = synthetic_vk();
+        let inputs = vec![scalar(11)];
+        let proof = synthetic_proof(&vk, &inputs, 13);
+        assert!(verify_groth16(&vk, &proof, &inputs).unwrap());
+    }
+
+    #[test]
+    fn test_batch_of_valid_proofs_verifies() {
+        let vk = synthetic_vk();
+        let inputs = vec![vec![scalar(11)], vec![scalar(17)]];
+        let proofs = vec![
+            synthetic_proof(&vk, &inputs[0], 13),
+            synthetic_proof(&vk, &inputs[1], 19),
+        ];
+        assert!(verify_groth16_batch(&vk, &proofs, &inputs).unwrap());
+    }
+
+    #[test]
+    fn test_batch_with_one_invalid_proof_fails() {
+        let vk = synthetic_vk();
+        let inputs = vec![vec![scalar(11)], vec![scalar(17)]];
+        let mut proofs = vec![
+            synthetic_proof(&vk, &inputs[0], 13),
+            synthetic_proof(&vk, &inputs[1], 19),
+        ];
+        // Swap in a C that no longer matches A
+        proofs[1].c = g1_generator_times(23);
+        assert!(!verify_groth16_batch(&vk, &proofs, &inputs).unwrap());
+
+        // A proof valid for different public inputs also fails the batch
+        let proofs = vec![
+            synthetic_proof(&vk, &inputs[0], 13),
+            synthetic_proof(&vk, &[scalar(29)], 19),
+        ];
+        assert!(!verify_groth16_batch(&vk, &proofs, &inputs).unwrap());
+    }
+
+    fn modulus_minus_one() -> [u8; 32] {
+        let mut s = BN254_SCALAR_MODULUS;
+        s[31] -= 1; // r ends in 0x01
+        s
+    }
+
+    #[test]
+    fn test_canonical_scalar_bounds() {
+        assert!(is_canonical_scalar(&[0u8; 32]));
+        assert!(is_canonical_scalar(&modulus_minus_one()));
+        assert!(!is_canonical_scalar(&BN254_SCALAR_MODULUS));
+        assert!(!is_canonical_scalar(&[0xFFu8; 32]));
+    }
+
+    #[test]
+    fn test_public_input_equal_to_modulus_rejected() {
+        let vk = synthetic_vk();
+        let accepted = vec![modulus_minus_one()];
+        let proof = synthetic_proof(&vk, &accepted, 13);
+        assert!(verify_groth16(&vk, &proof, &accepted).unwrap());
+
+        // The range check runs before any curve operation
+        let rejected = vec![BN254_SCALAR_MODULUS];
+        assert_eq!(
+            verify_groth16(&vk, &proof, &rejected).unwrap_err(),
+            crate::errors::ShadowVestError::ProofVerificationFailed.into()
+        );
+    }
+
+    #[test]
+    fn test_three_input_circuit_verifies() {
+        let mut vk = synthetic_vk();
+        vk.ic = vec![
+            g1_generator_times(5),
+            g1_generator_times(7),
+            g1_generator_times(9),
+            g1_generator_times(11),
+        ];
+        assert_eq!(vk.num_public_inputs(), Some(3));
+
+        let inputs = vec![scalar(2), scalar(3), scalar(4)];
+        let proof = synthetic_proof(&vk, &inputs, 13);
+        assert!(verify_groth16(&vk, &proof, &inputs).unwrap());
+
+        // Too few or too many inputs for the VK's arity
+        for mismatched in [vec![scalar(2), scalar(3)], vec![scalar(2); 4]] {
+            assert_eq!(
+                verify_groth16(&vk, &proof, &mismatched).unwrap_err(),
+                crate::errors::ShadowVestError::ProofInputCountMismatch.into()
+            );
+        }
+    }
+
+    #[test]
+    fn test_vk_without_ic_points_rejected() {
+        let mut vk = synthetic_vk();
+        vk.ic = vec![];
+        assert_eq!(vk.num_public_inputs(), None);
+        let proof = synthetic_proof(&synthetic_vk(), &[scalar(11)], 13);
+        assert_eq!(
+            verify_groth16(&vk, &proof, &[]).unwrap_err(),
+            crate::errors::ShadowVestError::ProofInputCountMismatch.into()
+        );
+    }
+
+    #[test]
+    fn test_batch_size_limits() {
+        let vk = synthetic_vk();
+        assert_eq!(
+            verify_groth16_batch(&vk, &[], &[]).unwrap_err(),
+            crate::errors::ShadowVestError::InvalidBatchSize.into()
+        );
+
+        let inputs = vec![vec![scalar(11)]; MAX_BATCH_PROOFS + 1];
+        let proofs: Vec<_> = inputs.iter().map(|i| synthetic_proof(&vk, i, 13)).collect();
+        assert_eq!(
+            verify_groth16_batch(&vk, &proofs, &inputs).unwrap_err(),
+            crate::errors::ShadowVestError::InvalidBatchSize.into()
+        );
+    }
+
     #[test]
     fn test_withdrawal_public_inputs_to_scalars() {
         let inputs = WithdrawalPublicInputs {
@@ -1,9 +1,8 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-
-/// Ed25519 signature verification program ID
-const ED25519_PROGRAM_ID: Pubkey = Pubkey::from_str_const("Ed25519SigVerify111111111111111111111111111");
+use anchor_lang::solana_program::hash::hash;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
 use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::{CallbackAccount, CircuitSource, OffChainCircuitSource};
 use arcium_macros::circuit_hash;
@@ -25,9 +24,14 @@ use light_sdk::{
     PackedAddressTreeInfoExt,
 };
 
+pub mod ed25519;
 pub mod errors;
 pub mod groth16_verifier;
+pub mod merkle;
+pub mod mpc_output;
+pub mod secp256k1;
 pub mod state;
+pub mod vesting;
 
 use errors::ShadowVestError;
 use groth16_verifier::{
@@ -35,9 +39,11 @@ use groth16_verifier::{
     WithdrawalPublicInputs,
 };
 use state::{
-    ClaimAuthorization, CompressedVestingPosition, MetaKeysVault, NullifierRecord,
-    Organization, ProofRecord, StealthMetaAddress, StealthPaymentEvent,
-    VerificationKeyAccount, VestingPosition, VestingSchedule,
+    AllowedDestination, BeneficiaryClaimHistory, ClaimAuthorization, ClaimFlowStep, CompressedVestingPosition, MetaKeysVault, NullifierRecord,
+    OrgRegistry, OrgRegistryEntry, OrgRegistryPage, Organization, PendingComputation,
+    PositionParams, PositionState, ProofRecord, StealthMetaAddress, StealthPaymentEvent,
+    ScheduleTemplate, StealthWithdrawalEvent, VerificationKeyAccount, VestedSnapshot,
+    VestingPosition, VestingSchedule,
 };
 
 // Computation definition offsets for Arcium circuits
@@ -47,6 +53,12 @@ const COMP_DEF_OFFSET_PROCESS_CLAIM: u32 = comp_def_offset("process_claim");
 const COMP_DEF_OFFSET_PROCESS_CLAIM_V2: u32 = comp_def_offset("process_claim_v2");
 const COMP_DEF_OFFSET_STORE_META_KEYS: u32 = comp_def_offset("store_meta_keys");
 const COMP_DEF_OFFSET_FETCH_META_KEYS: u32 = comp_def_offset("fetch_meta_keys");
+const COMP_DEF_OFFSET_TOP_UP_POSITION: u32 = comp_def_offset("top_up_position");
+const COMP_DEF_OFFSET_COMPARE_TOTALS: u32 = comp_def_offset("compare_totals");
+const COMP_DEF_OFFSET_MERGE_POSITIONS: u32 = comp_def_offset("merge_positions");
+const COMP_DEF_OFFSET_CHECK_SOLVENCY: u32 = comp_def_offset("check_solvency");
+const COMP_DEF_OFFSET_REENCRYPT_POSITION: u32 = comp_def_offset("reencrypt_position");
+const COMP_DEF_OFFSET_PROCESS_CLAIMS_BATCH: u32 = comp_def_offset("process_claims_batch");
 
 declare_id!("6KLNfkNWdqPCdzPVMivEHSt3FR2NLnHX4w1T76kiFqp2");
 
@@ -111,6 +123,78 @@ pub mod contract {
         Ok(())
     }
 
+    pub fn init_top_up_position_comp_def(ctx: Context<InitTopUpPositionCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://wajsatfcmlfkijmawyuq.supabase.co/storage/v1/object/public/init_position/top_up_position.arcis".to_string(),
+                hash: circuit_hash!("top_up_position"),
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_compare_totals_comp_def(ctx: Context<InitCompareTotalsCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://wajsatfcmlfkijmawyuq.supabase.co/storage/v1/object/public/init_position/compare_totals.arcis".to_string(),
+                hash: circuit_hash!("compare_totals"),
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_merge_positions_comp_def(ctx: Context<InitMergePositionsCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://wajsatfcmlfkijmawyuq.supabase.co/storage/v1/object/public/init_position/merge_positions.arcis".to_string(),
+                hash: circuit_hash!("merge_positions"),
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_check_solvency_comp_def(ctx: Context<InitCheckSolvencyCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://wajsatfcmlfkijmawyuq.supabase.co/storage/v1/object/public/init_position/check_solvency.arcis".to_string(),
+                hash: circuit_hash!("check_solvency"),
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_reencrypt_position_comp_def(ctx: Context<InitReencryptPositionCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://wajsatfcmlfkijmawyuq.supabase.co/storage/v1/object/public/init_position/reencrypt_position.arcis".to_string(),
+                hash: circuit_hash!("reencrypt_position"),
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_process_claims_batch_comp_def(ctx: Context<InitProcessClaimsBatchCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://wajsatfcmlfkijmawyuq.supabase.co/storage/v1/object/public/init_position/process_claims_batch.arcis".to_string(),
+                hash: circuit_hash!("process_claims_batch"),
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
     // ============================================================
     // Organization Management
     // ============================================================
@@ -131,6 +215,29 @@ pub mod contract {
         organization.treasury = treasury;
         organization.token_mint = token_mint;
         organization.is_active = true;
+        organization.closed_position_count = 0;
+        organization.min_reserve = 0;
+        organization.computation_timeout = 0;
+        organization.compressed_only = false;
+        organization.paused = false;
+        organization.auditor_pubkey = [0u8; 32];
+        organization.fee_bps = 0;
+        organization.fee_destination = Pubkey::default();
+        organization.nullifier_namespace = [0u8; 8];
+        organization.ata_only_withdrawals = false;
+        organization.lazy_init = false;
+        organization.priority_fee_account = Pubkey::default();
+        organization.reserved_amount = 0;
+        organization.position_reserved_amount = 0;
+        organization.vault_reserves = [VaultReserve::default(); Organization::MAX_VAULTS];
+        organization.freeze_vesting_on_pause = false;
+        organization.default_schedule = None;
+        organization.total_claimed_positions = 0;
+        organization.total_withdrawals = 0;
+        organization.enforce_destination_allowlist = false;
+        organization.challenge_period = 0;
+        organization.guardian = None;
+        organization.guardian_threshold = 0;
         organization.bump = ctx.bumps.organization;
 
         emit!(OrganizationCreated {
@@ -140,1259 +247,1604 @@ pub mod contract {
             token_mint,
         });
 
+        // Optionally record the organization in the global registry for discovery
+        if let Some(org_registry) = ctx.accounts.org_registry.as_mut() {
+            let registry_page = ctx
+                .accounts
+                .registry_page
+                .as_mut()
+                .ok_or(ShadowVestError::InvalidRegistryPage)?;
+
+            let page_index = org_registry.current_page_index();
+            require!(
+                registry_page.page_index == page_index,
+                ShadowVestError::InvalidRegistryPage
+            );
+
+            let sequence = org_registry.org_count;
+            registry_page.entries.push(OrgRegistryEntry {
+                organization: organization.key(),
+                name_hash,
+            });
+            org_registry.org_count = org_registry
+                .org_count
+                .checked_add(1)
+                .ok_or(ShadowVestError::ArithmeticOverflow)?;
+
+            emit!(OrganizationRegistered {
+                organization: organization.key(),
+                name_hash,
+                sequence,
+                page_index,
+            });
+        }
+
         Ok(())
     }
 
-    // ============================================================
-    // Vesting Schedule Management
-    // ============================================================
-
-    pub fn create_vesting_schedule(
-        ctx: Context<CreateVestingSchedule>,
-        cliff_duration: u64,
-        total_duration: u64,
-        vesting_interval: u64,
-    ) -> Result<()> {
+    /// Rotate the treasury address recorded on the organization.
+    pub fn update_treasury(ctx: Context<UpdateTreasury>, new_treasury: Pubkey) -> Result<()> {
         let organization = &mut ctx.accounts.organization;
-        let schedule = &mut ctx.accounts.schedule;
+        let old_treasury = organization.treasury;
 
-        require!(
-            total_duration > 0 && vesting_interval > 0,
-            ShadowVestError::InvalidScheduleParams
-        );
-        require!(
-            cliff_duration <= total_duration,
-            ShadowVestError::InvalidScheduleParams
-        );
-        require!(
-            organization.is_active,
-            ShadowVestError::OrganizationNotActive
-        );
+        organization.treasury = new_treasury;
 
-        let schedule_id = organization.schedule_count;
+        emit!(TreasuryUpdated {
+            organization: organization.key(),
+            old_treasury,
+            new_treasury,
+        });
 
-        schedule.organization = organization.key();
-        schedule.schedule_id = schedule_id;
-        schedule.cliff_duration = cliff_duration;
-        schedule.total_duration = total_duration;
-        schedule.vesting_interval = vesting_interval;
-        schedule.token_mint = organization.token_mint;
-        schedule.is_active = true;
-        schedule.position_count = 0;
-        schedule.compressed_position_count = 0;
-        schedule.bump = ctx.bumps.schedule;
-
-        organization.schedule_count = organization
-            .schedule_count
-            .checked_add(1)
-            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Publicly reveal the organization's name, e.g. for a public registry.
+    ///
+    /// name_hash stays unchanged; the reveal only succeeds when sha256(name || salt)
+    /// equals it, so the emitted name is a verifiable opening of the commitment.
+    pub fn reveal_organization_name(
+        ctx: Context<RevealOrganizationName>,
+        name: String,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        let organization = &ctx.accounts.organization;
+        organization.check_name_opening(&name, &salt)?;
 
-        emit!(VestingScheduleCreated {
+        emit!(OrganizationNameRevealed {
             organization: organization.key(),
-            schedule: schedule.key(),
-            schedule_id,
-            cliff_duration,
-            total_duration,
-            vesting_interval,
+            name,
         });
 
         Ok(())
     }
 
-    // ============================================================
-    // Vesting Position Management (with MPC)
-    // ============================================================
+    /// Set the minimum vault balance that claim withdrawals must leave in place.
+    pub fn set_min_reserve(ctx: Context<SetMinReserve>, min_reserve: u64) -> Result<()> {
+        let organization = &mut ctx.accounts.organization;
+        organization.min_reserve = min_reserve;
 
-    pub fn create_vesting_position(
-        ctx: Context<CreateVestingPosition>,
-        computation_offset: u64,
-        beneficiary_commitment: [u8; 32],
-        encrypted_total_amount: [u8; 32],
-        pubkey: [u8; 32],
-        nonce: u128,
-    ) -> Result<()> {
-        // Validate state first
-        require!(ctx.accounts.organization.is_active, ShadowVestError::OrganizationNotActive);
+        emit!(MinReserveUpdated {
+            organization: organization.key(),
+            min_reserve,
+        });
+
+        Ok(())
+    }
+
+    /// Set the schedule create_vesting_position_default assigns new positions to.
+    /// The schedule must belong to the organization and be active.
+    pub fn set_default_schedule(ctx: Context<SetDefaultSchedule>, schedule: Pubkey) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.schedule.key(),
+            schedule,
+            ShadowVestError::InvalidScheduleParams
+        );
         require!(ctx.accounts.schedule.is_active, ShadowVestError::ScheduleNotActive);
 
-        // Capture values needed for event before mutable borrows
-        let position_id = ctx.accounts.organization.position_count;
-        let clock = Clock::get()?;
-        let org_key = ctx.accounts.organization.key();
-        let schedule_key = ctx.accounts.schedule.key();
+        let organization = &mut ctx.accounts.organization;
+        organization.default_schedule = Some(schedule);
 
-        // Initialize position
-        {
-            let position = &mut ctx.accounts.position;
-            position.organization = org_key;
-            position.schedule = schedule_key;
-            position.position_id = position_id;
-            position.beneficiary_commitment = beneficiary_commitment;
-            position.encrypted_total_amount = encrypted_total_amount;
-            position.encrypted_claimed_amount = [0u8; 32];
-            position.nonce = nonce;
-            position.start_timestamp = clock.unix_timestamp;
-            position.is_active = true;
-            position.is_fully_claimed = false;
-            position.bump = ctx.bumps.position;
-        }
+        emit!(DefaultScheduleUpdated {
+            organization: organization.key(),
+            schedule,
+        });
 
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        Ok(())
+    }
 
-        let args = ArgBuilder::new()
-            .x25519_pubkey(pubkey)
-            .plaintext_u128(nonce)
-            .encrypted_u64(encrypted_total_amount)
-            .build();
+    /// Set how long a pending MPC computation may stay unanswered before
+    /// anyone can expire it with expire_computation (0 disables expiry).
+    pub fn set_computation_timeout(
+        ctx: Context<SetComputationTimeout>,
+        computation_timeout: u64,
+    ) -> Result<()> {
+        let organization = &mut ctx.accounts.organization;
+        organization.computation_timeout = computation_timeout;
 
-        let position_callback_account = CallbackAccount {
-            pubkey: ctx.accounts.position.key(),
-            is_writable: true,
-        };
+        emit!(ComputationTimeoutUpdated {
+            organization: organization.key(),
+            computation_timeout,
+        });
 
-        let callback_ix = InitPositionCallback::callback_ix(
-            computation_offset,
-            &ctx.accounts.mxe_account,
-            &[position_callback_account],
-        )?;
+        Ok(())
+    }
 
-        queue_computation(
-            ctx.accounts,
-            computation_offset,
-            args,
-            vec![callback_ix],
-            1,
-            0,
-        )?;
+    /// Require all new positions to use compressed storage. When enabled, the
+    /// regular (PDA) position creation instructions are rejected, so create the
+    /// scratch position used as the compressed claim callback target beforehand.
+    pub fn set_compressed_only(ctx: Context<SetCompressedOnly>, compressed_only: bool) -> Result<()> {
+        let organization = &mut ctx.accounts.organization;
+        organization.compressed_only = compressed_only;
 
-        // Update counters after queue_computation
-        ctx.accounts.organization.position_count = ctx.accounts.organization
-            .position_count
-            .checked_add(1)
-            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+        emit!(CompressedOnlyUpdated {
+            organization: organization.key(),
+            compressed_only,
+        });
 
-        ctx.accounts.schedule.position_count = ctx.accounts.schedule
-            .position_count
-            .checked_add(1)
-            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+        Ok(())
+    }
 
-        let position_key = ctx.accounts.position.key();
-        let start_timestamp = ctx.accounts.position.start_timestamp;
+    /// Emergency pause: while paused, no tokens can leave the vault.
+    /// Position creation and MPC queuing are unaffected.
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        let organization = &mut ctx.accounts.organization;
+        organization.paused = paused;
 
-        emit!(VestingPositionCreated {
-            organization: org_key,
-            schedule: schedule_key,
-            position: position_key,
-            position_id,
-            beneficiary_commitment,
-            start_timestamp,
+        emit!(OrganizationPausedUpdated {
+            organization: organization.key(),
+            paused,
         });
 
         Ok(())
     }
 
-    /// Create a vesting position with stealth address beneficiary.
+    /// Set the X25519 key stealth payments are additionally encrypted to.
     ///
-    /// The stealth address is derived off-chain by the employer:
-    /// 1. Employer fetches employee's (S, V) from StealthMetaAddress
-    /// 2. Generates ephemeral keypair (r, R = r*G)
-    /// 3. Computes stealth_address = S + H(r * V) * G
+    /// Once set, every stealth position must carry an auditor_encrypted_payload so
+    /// the organization can later reconstruct which stealth payments it made.
+    /// Pass all zeros to disable auditing.
+    pub fn set_auditor_key(ctx: Context<SetAuditorKey>, auditor_pubkey: [u8; 32]) -> Result<()> {
+        let organization = &mut ctx.accounts.organization;
+        organization.auditor_pubkey = auditor_pubkey;
+
+        emit!(AuditorKeyUpdated {
+            organization: organization.key(),
+            auditor_pubkey,
+        });
+
+        Ok(())
+    }
+
+    /// Configure the protocol fee taken from each withdrawal.
     ///
-    /// This instruction stores the position and emits StealthPaymentEvent
-    /// so the employee can scan and discover the payment.
-    pub fn create_stealth_vesting_position(
-        ctx: Context<CreateVestingPosition>,
-        computation_offset: u64,
-        stealth_address: Pubkey,
-        ephemeral_pubkey: [u8; 32],
-        encrypted_payload: [u8; 128],
-        encrypted_total_amount: [u8; 32],
-        pubkey: [u8; 32],
-        nonce: u128,
+    /// `fee_bps` of every withdrawn amount (rounded down) is sent to the
+    /// `fee_destination` token account; the remainder goes to the claim's
+    /// destination. A fee_bps of 0 disables the fee.
+    pub fn set_withdrawal_fee(
+        ctx: Context<SetWithdrawalFee>,
+        fee_bps: u16,
+        fee_destination: Pubkey,
     ) -> Result<()> {
-        // Validate state first
-        require!(ctx.accounts.organization.is_active, ShadowVestError::OrganizationNotActive);
-        require!(ctx.accounts.schedule.is_active, ShadowVestError::ScheduleNotActive);
+        require!(
+            fee_bps <= Organization::MAX_FEE_BPS,
+            ShadowVestError::InvalidFeeBps
+        );
+        require!(
+            fee_bps == 0 || fee_destination != Pubkey::default(),
+            ShadowVestError::InvalidFeeDestination
+        );
 
-        // Use stealth address as beneficiary commitment
-        let beneficiary_commitment = stealth_address.to_bytes();
+        let organization = &mut ctx.accounts.organization;
+        organization.fee_bps = fee_bps;
+        organization.fee_destination = fee_destination;
 
-        // Capture values needed for events before mutable borrows
-        let position_id = ctx.accounts.organization.position_count;
-        let clock = Clock::get()?;
-        let org_key = ctx.accounts.organization.key();
-        let schedule_key = ctx.accounts.schedule.key();
-        let token_mint = ctx.accounts.organization.token_mint;
+        emit!(WithdrawalFeeUpdated {
+            organization: organization.key(),
+            fee_bps,
+            fee_destination,
+        });
 
-        // Initialize position
-        {
-            let position = &mut ctx.accounts.position;
-            position.organization = org_key;
-            position.schedule = schedule_key;
-            position.position_id = position_id;
-            position.beneficiary_commitment = beneficiary_commitment;
-            position.encrypted_total_amount = encrypted_total_amount;
-            position.encrypted_claimed_amount = [0u8; 32];
-            position.nonce = nonce;
-            position.start_timestamp = clock.unix_timestamp;
-            position.is_active = true;
-            position.is_fully_claimed = false;
-            position.bump = ctx.bumps.position;
-        }
+        Ok(())
+    }
 
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+    /// Restrict withdrawals to associated token accounts.
+    ///
+    /// When enabled, withdraw/withdraw_partial/withdraw_compressed reject any
+    /// destination that is not the ATA of its owner for the vault mint,
+    /// guarding against claims authorized to a mistyped token account.
+    pub fn set_ata_only_withdrawals(
+        ctx: Context<SetAtaOnlyWithdrawals>,
+        ata_only_withdrawals: bool,
+    ) -> Result<()> {
+        let organization = &mut ctx.accounts.organization;
+        organization.ata_only_withdrawals = ata_only_withdrawals;
 
-        let args = ArgBuilder::new()
-            .x25519_pubkey(pubkey)
-            .plaintext_u128(nonce)
-            .encrypted_u64(encrypted_total_amount)
-            .build();
+        emit!(AtaOnlyWithdrawalsUpdated {
+            organization: organization.key(),
+            ata_only_withdrawals,
+        });
 
-        let position_callback_account = CallbackAccount {
-            pubkey: ctx.accounts.position.key(),
-            is_writable: true,
-        };
+        Ok(())
+    }
 
-        let callback_ix = InitPositionCallback::callback_ix(
-            computation_offset,
-            &ctx.accounts.mxe_account,
-            &[position_callback_account],
-        )?;
+    /// Approve a withdrawal destination for the organization (admin only)
+    pub fn add_allowed_destination(
+        ctx: Context<AddAllowedDestination>,
+        destination: Pubkey,
+    ) -> Result<()> {
+        let allowed = &mut ctx.accounts.allowed_destination;
+        allowed.organization = ctx.accounts.organization.key();
+        allowed.destination = destination;
+        allowed.added_at = Clock::get()?.unix_timestamp;
+        allowed.bump = ctx.bumps.allowed_destination;
+
+        emit!(AllowedDestinationAdded {
+            organization: allowed.organization,
+            destination,
+            allowed_destination: allowed.key(),
+        });
 
-        queue_computation(
-            ctx.accounts,
-            computation_offset,
-            args,
-            vec![callback_ix],
-            1,
-            0,
-        )?;
+        Ok(())
+    }
 
-        // Update counters after queue_computation
-        ctx.accounts.organization.position_count = ctx.accounts.organization
-            .position_count
-            .checked_add(1)
-            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+    /// Toggle whether claims may only be authorized to approved destinations (admin only)
+    pub fn set_destination_allowlist_enforced(
+        ctx: Context<SetDestinationAllowlistEnforced>,
+        enforced: bool,
+    ) -> Result<()> {
+        let organization = &mut ctx.accounts.organization;
+        organization.enforce_destination_allowlist = enforced;
 
-        ctx.accounts.schedule.position_count = ctx.accounts.schedule
-            .position_count
-            .checked_add(1)
-            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+        emit!(DestinationAllowlistUpdated {
+            organization: organization.key(),
+            enforced,
+        });
 
-        let position_key = ctx.accounts.position.key();
-        let start_timestamp = ctx.accounts.position.start_timestamp;
+        Ok(())
+    }
 
-        // Emit both events for indexing
-        emit!(VestingPositionCreated {
-            organization: org_key,
-            schedule: schedule_key,
-            position: position_key,
-            position_id,
-            beneficiary_commitment,
-            start_timestamp,
-        });
+    /// Set how long queued withdrawals wait before they can execute (admin only).
+    /// While non-zero, tokens only leave the vault through queue_withdraw followed
+    /// by execute_queued_withdraw (or withdraw_compressed once the period has passed),
+    /// giving the admin a window to cancel_withdraw a fraudulent claim. Already
+    /// queued withdrawals keep the period they were queued with.
+    pub fn set_challenge_period(
+        ctx: Context<SetChallengePeriod>,
+        challenge_period: u64,
+    ) -> Result<()> {
+        let organization = &mut ctx.accounts.organization;
+        organization.challenge_period = challenge_period;
 
-        // Emit stealth payment event for employee scanning
-        emit!(StealthPaymentEvent {
-            organization: org_key,
-            stealth_address,
-            ephemeral_pubkey,
-            encrypted_payload,
-            position_id,
-            token_mint,
-            timestamp: clock.unix_timestamp,
+        emit!(ChallengePeriodUpdated {
+            organization: organization.key(),
+            challenge_period,
         });
 
         Ok(())
     }
 
-    #[arcium_callback(encrypted_ix = "init_position")]
-    pub fn init_position_callback(
-        ctx: Context<InitPositionCallback>,
-        output: SignedComputationOutputs<InitPositionOutput>,
+    /// Require a guardian co-signature on withdrawals of large claims (admin only).
+    ///
+    /// While a guardian is set, every withdrawal path rejects a claim whose
+    /// claim_amount is at least `threshold` unless the guardian signs. Claim amounts
+    /// are cleartext on the ClaimAuthorization, so partial withdrawals of a large
+    /// claim need the guardian too. None removes the requirement.
+    pub fn set_guardian(
+        ctx: Context<SetGuardian>,
+        guardian: Option<Pubkey>,
+        threshold: u64,
     ) -> Result<()> {
-        let verified = output
-            .verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account)
-            .map_err(|_| ErrorCode::AbortedComputation)?;
-
-        let position = &mut ctx.accounts.position;
-        position.encrypted_total_amount = verified.field_0.ciphertexts[0];
-        position.encrypted_claimed_amount = verified.field_0.ciphertexts[1];
+        let organization = &mut ctx.accounts.organization;
+        organization.guardian = guardian;
+        organization.guardian_threshold = threshold;
 
-        emit!(VestingPositionInitialized {
-            position: position.key(),
-            position_id: position.position_id,
+        emit!(GuardianUpdated {
+            organization: organization.key(),
+            guardian,
+            threshold,
         });
 
         Ok(())
     }
 
-    // ============================================================
-    // Vesting Calculations (with MPC)
-    // ============================================================
+    /// Defer the init_position computation of new positions until first use.
+    ///
+    /// When enabled, every regular position creator (create_vesting_position and its
+    /// default, stealth, cosigned, schedule-with-position and batch variants) stores
+    /// the encrypted total without queuing MPC, leaving the position in the Created
+    /// state. The first calculate_vested_amount or authorize_claim accepts such a
+    /// position and the client queues queue_init_position in the same transaction,
+    /// so MPC is only paid for positions that are actually used. Claims cannot be
+    /// processed until the init computation has finalized.
+    pub fn set_lazy_init(ctx: Context<SetLazyInit>, lazy_init: bool) -> Result<()> {
+        let organization = &mut ctx.accounts.organization;
+        organization.lazy_init = lazy_init;
 
-    pub fn calculate_vested_amount(
-        ctx: Context<CalculateVestedAmount>,
-        computation_offset: u64,
-        encrypted_total_amount: [u8; 32],
-        encrypted_claimed_amount: [u8; 32],
-        encrypted_vesting_numerator: [u8; 32],
-        pubkey: [u8; 32],
-        nonce: u128,
-    ) -> Result<()> {
-        let position = &ctx.accounts.position;
+        emit!(LazyInitUpdated {
+            organization: organization.key(),
+            lazy_init,
+        });
 
-        require!(position.is_active, ShadowVestError::PositionNotActive);
-        require!(!position.is_fully_claimed, ShadowVestError::PositionFullyClaimed);
+        Ok(())
+    }
 
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+    /// Set the account that receives claim priority fees.
+    ///
+    /// Beneficiaries may attach a lamport priority_fee to queue_process_claim; it is
+    /// paid to this account and reported in ClaimProcessQueued so MPC operators can
+    /// prioritize. The default pubkey disables priority fees.
+    pub fn set_priority_fee_account(
+        ctx: Context<SetPriorityFeeAccount>,
+        priority_fee_account: Pubkey,
+    ) -> Result<()> {
+        let organization = &mut ctx.accounts.organization;
+        organization.priority_fee_account = priority_fee_account;
 
-        // All values must be encrypted with the same key/nonce for MPC
-        let args = ArgBuilder::new()
-            .x25519_pubkey(pubkey)
-            .plaintext_u128(nonce)
-            .encrypted_u64(encrypted_total_amount)
-            .encrypted_u64(encrypted_claimed_amount)
-            .encrypted_u64(encrypted_vesting_numerator)
-            .build();
+        emit!(PriorityFeeAccountUpdated {
+            organization: organization.key(),
+            priority_fee_account,
+        });
 
-        let position_callback_account = CallbackAccount {
-            pubkey: ctx.accounts.position.key(),
-            is_writable: false,
-        };
+        Ok(())
+    }
 
-        queue_computation(
-            ctx.accounts,
-            computation_offset,
-            args,
-            vec![CalculateVestedCallback::callback_ix(
-                computation_offset,
-                &ctx.accounts.mxe_account,
-                &[position_callback_account],
-            )?],
-            1,
-            0,
-        )?;
+    /// Set the namespace mixed into NullifierRecord seeds.
+    ///
+    /// A nullifier can be consumed once per namespace, so switching namespaces lets
+    /// the same beneficiary nullifiers be reused (e.g. a fresh grant program).
+    /// Existing records keep the namespace they were created under.
+    pub fn set_nullifier_namespace(
+        ctx: Context<SetNullifierNamespace>,
+        namespace: [u8; 8],
+    ) -> Result<()> {
+        let organization = &mut ctx.accounts.organization;
+        organization.nullifier_namespace = namespace;
 
-        emit!(VestedAmountCalculationQueued {
-            position: position.key(),
-            position_id: position.position_id,
-            computation_offset,
+        emit!(NullifierNamespaceUpdated {
+            organization: organization.key(),
+            namespace,
         });
 
         Ok(())
     }
 
-    #[arcium_callback(encrypted_ix = "calculate_vested")]
-    pub fn calculate_vested_callback(
-        ctx: Context<CalculateVestedCallback>,
-        output: SignedComputationOutputs<CalculateVestedOutput>,
+    /// Initialize the global organization registry singleton.
+    pub fn init_org_registry(ctx: Context<InitOrgRegistry>) -> Result<()> {
+        let org_registry = &mut ctx.accounts.org_registry;
+
+        org_registry.org_count = 0;
+        org_registry.page_count = 0;
+        org_registry.bump = ctx.bumps.org_registry;
+
+        Ok(())
+    }
+
+    /// Append a new page to the organization registry chain.
+    /// Pages must be created in order (page_index == registry.page_count).
+    pub fn create_org_registry_page(
+        ctx: Context<CreateOrgRegistryPage>,
+        page_index: u64,
     ) -> Result<()> {
-        let verified = output
-            .verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account)
-            .map_err(|_| ErrorCode::AbortedComputation)?;
+        let org_registry = &mut ctx.accounts.org_registry;
+        let registry_page = &mut ctx.accounts.registry_page;
 
-        let position = &ctx.accounts.position;
+        registry_page.page_index = page_index;
+        registry_page.entries = Vec::new();
+        registry_page.bump = ctx.bumps.registry_page;
 
-        emit!(VestedAmountCalculated {
-            position: position.key(),
-            position_id: position.position_id,
-            encrypted_vested_amount: verified.field_0.ciphertexts[0],
-            encrypted_claimable_amount: verified.field_0.ciphertexts[1],
-            nonce: verified.field_0.nonce.to_le_bytes(),
-        });
+        org_registry.page_count = org_registry
+            .page_count
+            .checked_add(1)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
 
         Ok(())
     }
 
     // ============================================================
-    // Claim Authorization & Withdrawal
+    // Vesting Schedule Management
     // ============================================================
 
-    /// Authorize a claim using Ed25519 stealth signature verification.
+    /// Create a vesting schedule paid out in `token_mint`.
     ///
-    /// The caller must prepend an Ed25519Program instruction that verifies
-    /// a signature from the stealth address (beneficiary_commitment) over
-    /// the message: hash(position_id, nullifier, withdrawal_destination).
-    ///
-    /// This creates a ClaimAuthorization PDA and a NullifierRecord PDA.
-    /// The NullifierRecord uses init constraint for double-claim prevention.
-    pub fn authorize_claim(
-        ctx: Context<AuthorizeClaim>,
-        nullifier: [u8; 32],
-        withdrawal_destination: Pubkey,
+    /// Each mint has its own organization vault (see initialize_vault), so one
+    /// organization can run grants in several tokens.
+    pub fn create_vesting_schedule(
+        ctx: Context<CreateVestingSchedule>,
+        cliff_duration: u64,
+        total_duration: u64,
+        vesting_interval: u64,
+        vesting_mode: u8,
+        token_mint: Pubkey,
     ) -> Result<()> {
-        let position = &ctx.accounts.position;
+        create_schedule(
+            &mut ctx.accounts.organization,
+            &mut ctx.accounts.schedule,
+            ctx.bumps.schedule,
+            cliff_duration,
+            total_duration,
+            vesting_interval,
+            vesting_mode,
+            token_mint,
+        )
+    }
 
-        require!(position.is_active, ShadowVestError::PositionNotActive);
-        require!(!position.is_fully_claimed, ShadowVestError::PositionFullyClaimed);
+    /// Create a vesting schedule from a named preset (see ScheduleTemplate).
+    ///
+    /// Expands the template to its cliff, total duration and interval and then
+    /// behaves exactly like create_vesting_schedule in interval mode.
+    pub fn create_schedule_from_template(
+        ctx: Context<CreateVestingSchedule>,
+        template: ScheduleTemplate,
+        token_mint: Pubkey,
+    ) -> Result<()> {
+        let (cliff_duration, total_duration, vesting_interval) = template.params();
+        create_schedule(
+            &mut ctx.accounts.organization,
+            &mut ctx.accounts.schedule,
+            ctx.bumps.schedule,
+            cliff_duration,
+            total_duration,
+            vesting_interval,
+            vesting::VESTING_MODE_INTERVAL,
+            token_mint,
+        )
+    }
 
-        // Verify the Ed25519 signature from the preceding instruction
-        // The instructions sysvar lets us read the previous instruction
-        let ix_sysvar = &ctx.accounts.instructions_sysvar;
-        let current_ix_index = sysvar_instructions::load_current_index_checked(ix_sysvar)
-            .map_err(|_| ShadowVestError::InvalidEligibilitySignature)?;
+    /// Update the parameters of a schedule that no position references yet.
+    /// Changing params under live positions would corrupt their vesting math.
+    pub fn update_vesting_schedule(
+        ctx: Context<UpdateVestingSchedule>,
+        cliff_duration: u64,
+        total_duration: u64,
+        vesting_interval: u64,
+    ) -> Result<()> {
+        let schedule = &mut ctx.accounts.schedule;
 
-        // The Ed25519 instruction must be the one immediately before this instruction
-        require!(
-            current_ix_index > 0,
-            ShadowVestError::InvalidEligibilitySignature
-        );
+        require!(!schedule.is_in_use(), ShadowVestError::ScheduleInUse);
+        VestingSchedule::validate_params(cliff_duration, total_duration, vesting_interval)?;
 
-        let ed25519_ix = sysvar_instructions::load_instruction_at_checked(
-            (current_ix_index - 1) as usize,
-            ix_sysvar,
-        )
-        .map_err(|_| ShadowVestError::InvalidEligibilitySignature)?;
+        schedule.cliff_duration = cliff_duration;
+        schedule.total_duration = total_duration;
+        schedule.vesting_interval = vesting_interval;
 
-        // Verify it's an Ed25519 program instruction
-        require!(
-            ed25519_ix.program_id == ED25519_PROGRAM_ID,
-            ShadowVestError::InvalidEligibilitySignature
-        );
+        emit!(VestingScheduleUpdated {
+            organization: ctx.accounts.organization.key(),
+            schedule: schedule.key(),
+            schedule_id: schedule.schedule_id,
+            cliff_duration,
+            total_duration,
+            vesting_interval,
+        });
 
-        // Parse Ed25519 instruction data to verify pubkey matches beneficiary_commitment
-        // Ed25519 instruction format: num_signatures (u8) + padding (u8) + signature_offsets...
-        // Each signature offset struct: signature_offset(u16), signature_ix(u16),
-        //   pubkey_offset(u16), pubkey_ix(u16), message_offset(u16), message_size(u16), message_ix(u16)
-        require!(
-            ed25519_ix.data.len() >= 16,
-            ShadowVestError::InvalidEligibilitySignature
-        );
+        Ok(())
+    }
 
-        let num_signatures = ed25519_ix.data[0];
-        require!(
-            num_signatures == 1,
-            ShadowVestError::InvalidEligibilitySignature
-        );
+    /// Activate or deactivate a schedule. Deactivated schedules reject new
+    /// positions; existing positions keep vesting and claiming.
+    pub fn set_schedule_active(ctx: Context<SetScheduleActive>, active: bool) -> Result<()> {
+        let schedule = &mut ctx.accounts.schedule;
+        schedule.is_active = active;
 
-        // Extract pubkey offset (bytes 6-7, little-endian)
-        let pubkey_offset = u16::from_le_bytes([ed25519_ix.data[6], ed25519_ix.data[7]]) as usize;
+        emit!(ScheduleActiveChanged {
+            schedule: schedule.key(),
+            is_active: active,
+        });
 
-        // Extract the signing pubkey (32 bytes at pubkey_offset)
-        require!(
-            ed25519_ix.data.len() >= pubkey_offset + 32,
-            ShadowVestError::InvalidEligibilitySignature
-        );
-        let signer_pubkey = &ed25519_ix.data[pubkey_offset..pubkey_offset + 32];
+        Ok(())
+    }
 
-        // Verify the signer matches the position's beneficiary_commitment (stealth address)
-        require!(
-            signer_pubkey == position.beneficiary_commitment,
-            ShadowVestError::SignerMismatch
-        );
+    /// Set the minimum time between processed claims on each position of a schedule.
+    /// Limits how fast a compromised beneficiary key can drain a position through
+    /// rapid small claims. 0 disables the limit.
+    pub fn set_min_claim_interval(
+        ctx: Context<SetMinClaimInterval>,
+        min_claim_interval: u64,
+    ) -> Result<()> {
+        let schedule = &mut ctx.accounts.schedule;
+        schedule.min_claim_interval = min_claim_interval;
 
-        // Verify message is hash(position_id, nullifier, withdrawal_destination)
-        let message_data_offset = u16::from_le_bytes([ed25519_ix.data[10], ed25519_ix.data[11]]) as usize;
-        let message_data_size = u16::from_le_bytes([ed25519_ix.data[12], ed25519_ix.data[13]]) as usize;
+        emit!(MinClaimIntervalChanged {
+            schedule: schedule.key(),
+            min_claim_interval,
+        });
 
-        require!(
-            ed25519_ix.data.len() >= message_data_offset + message_data_size,
-            ShadowVestError::InvalidEligibilitySignature
-        );
+        Ok(())
+    }
 
-        let signed_message = &ed25519_ix.data[message_data_offset..message_data_offset + message_data_size];
+    /// Set the vesting numerator precision of a schedule that no position references yet.
+    /// The default (vesting::PRECISION = 10^6) rounds by up to total / 10^6; orgs with
+    /// very large totals can opt into e.g. 10^9. Clients must encrypt numerators at
+    /// the same scale.
+    pub fn set_schedule_precision(
+        ctx: Context<UpdateVestingSchedule>,
+        precision: u64,
+    ) -> Result<()> {
+        let schedule = &mut ctx.accounts.schedule;
 
-        // Construct expected message: position_id || nullifier || withdrawal_destination (72 bytes)
-        let mut expected_msg = [0u8; 72];
-        expected_msg[..8].copy_from_slice(&position.position_id.to_le_bytes());
-        expected_msg[8..40].copy_from_slice(&nullifier);
-        expected_msg[40..72].copy_from_slice(withdrawal_destination.as_ref());
+        require!(!schedule.is_in_use(), ShadowVestError::ScheduleInUse);
+        VestingSchedule::validate_precision(precision)?;
 
-        require!(
-            signed_message == expected_msg,
-            ShadowVestError::InvalidEligibilitySignature
-        );
+        schedule.precision = precision;
 
-        // Initialize ClaimAuthorization
-        let clock = Clock::get()?;
-        let claim_auth = &mut ctx.accounts.claim_authorization;
-        claim_auth.position = position.key();
-        claim_auth.nullifier = nullifier;
-        claim_auth.withdrawal_destination = withdrawal_destination;
-        claim_auth.claim_amount = 0;
-        claim_auth.is_authorized = true;
-        claim_auth.is_processed = false;
-        claim_auth.is_withdrawn = false;
-        claim_auth.authorized_at = clock.unix_timestamp;
-        claim_auth.bump = ctx.bumps.claim_authorization;
+        emit!(SchedulePrecisionUpdated {
+            schedule: schedule.key(),
+            precision,
+        });
 
-        // Initialize NullifierRecord (init constraint prevents double-use)
-        let nullifier_record = &mut ctx.accounts.nullifier_record;
-        nullifier_record.nullifier = nullifier;
-        nullifier_record.position = position.key();
-        nullifier_record.used_at = clock.unix_timestamp;
-        nullifier_record.bump = ctx.bumps.nullifier_record;
+        Ok(())
+    }
 
-        emit!(ClaimAuthorized {
-            position: position.key(),
-            nullifier,
-            withdrawal_destination,
+    /// Freeze or unfreeze claims on every position of one schedule, e.g. a grant
+    /// tranche under investigation. Checked when claims are queued; claims already
+    /// processed can still be withdrawn.
+    pub fn set_schedule_claims_paused(
+        ctx: Context<SetScheduleClaimsPaused>,
+        paused: bool,
+    ) -> Result<()> {
+        let schedule = &mut ctx.accounts.schedule;
+        schedule.claims_paused = paused;
+
+        emit!(ScheduleClaimsPausedChanged {
+            schedule: schedule.key(),
+            claims_paused: paused,
         });
 
         Ok(())
     }
 
-    /// Queue the process_claim_v2 MPC computation with integrated vesting calculation.
-    ///
-    /// Computes vesting_numerator on-chain from Clock + schedule parameters.
-    /// Submits encrypted (total_amount, claimed_amount, vesting_numerator, claim_amount) to MPC.
-    /// The MPC circuit internally computes: claimable = (total * numerator / PRECISION) - claimed
-    /// Then validates: claim_amount <= claimable.
-    /// Callback updates position.encrypted_claimed_amount and sets is_processed=true.
-    pub fn queue_process_claim(
-        ctx: Context<QueueProcessClaim>,
-        computation_offset: u64,
-        encrypted_total_amount: [u8; 32],
-        encrypted_claimed_amount: [u8; 32],
-        encrypted_vesting_numerator: [u8; 32],
-        encrypted_claim_amount: [u8; 32],
-        claim_amount: u64,
-        pubkey: [u8; 32],
-        nonce: u128,
+    /// Commit the schedule to an off-chain beneficiary list (see claim_with_merkle_proof).
+    /// An all-zero root disables Merkle claims.
+    pub fn set_schedule_beneficiary_root(
+        ctx: Context<SetScheduleBeneficiaryRoot>,
+        beneficiary_root: [u8; 32],
     ) -> Result<()> {
-        let claim_auth = &ctx.accounts.claim_authorization;
-
-        require!(claim_auth.is_authorized, ShadowVestError::ClaimNotAuthorized);
-        require!(!claim_auth.is_processed, ShadowVestError::ClaimNotProcessed);
-
-        let position = &ctx.accounts.position;
-        let schedule = &ctx.accounts.schedule;
-        require!(position.is_active, ShadowVestError::PositionNotActive);
-
-        // Compute vesting_numerator on-chain from verifiable data
-        let clock = Clock::get()?;
-        let current_time = clock.unix_timestamp;
-        let start_time = position.start_timestamp;
-        let cliff_end = start_time + schedule.cliff_duration as i64;
-        let vesting_end = start_time + schedule.total_duration as i64;
-
-        const PRECISION: u64 = 1_000_000;
-
-        let vesting_numerator = if current_time < cliff_end {
-            0u64
-        } else if current_time >= vesting_end {
-            PRECISION
-        } else {
-            let elapsed = (current_time - cliff_end) as u64;
-            let intervals = elapsed / schedule.vesting_interval;
-            let vested_seconds = intervals * schedule.vesting_interval;
-            let vesting_duration = schedule.total_duration - schedule.cliff_duration;
-            if vesting_duration > 0 {
-                vested_seconds * PRECISION / vesting_duration
-            } else {
-                PRECISION
-            }
-        };
+        let schedule = &mut ctx.accounts.schedule;
+        schedule.beneficiary_root = beneficiary_root;
 
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        emit!(ScheduleBeneficiaryRootSet {
+            schedule: schedule.key(),
+            beneficiary_root,
+        });
 
-        // Args order matches ProcessClaimV2Input: total_amount, claimed_amount, vesting_numerator, claim_amount
-        let args = ArgBuilder::new()
-            .x25519_pubkey(pubkey)
-            .plaintext_u128(nonce)
-            .encrypted_u64(encrypted_total_amount)
-            .encrypted_u64(encrypted_claimed_amount)
-            .encrypted_u64(encrypted_vesting_numerator)
-            .encrypted_u64(encrypted_claim_amount)
-            .build();
+        Ok(())
+    }
 
-        let position_callback_account = CallbackAccount {
-            pubkey: ctx.accounts.position.key(),
-            is_writable: true,
-        };
-        let claim_auth_callback_account = CallbackAccount {
-            pubkey: ctx.accounts.claim_authorization.key(),
-            is_writable: true,
-        };
+    /// Grow a schedule created before vesting_mode, min_claim_interval, claims_paused,
+    /// beneficiary_root and precision were appended to VestingSchedule. Until then the
+    /// account is too short to load; afterwards it vests in interval mode at
+    /// vesting::PRECISION with no claim limits, as it did before the upgrade.
+    pub fn migrate_vesting_schedule(
+        ctx: Context<MigrateVestingSchedule>,
+        schedule_id: u64,
+    ) -> Result<()> {
+        let info = ctx.accounts.schedule.to_account_info();
+        require!(
+            info.data_len() == VestingSchedule::LEGACY_SIZE,
+            ShadowVestError::ScheduleAlreadyMigrated
+        );
 
-        let callback_ix = ProcessClaimV2Callback::callback_ix(
-            computation_offset,
-            &ctx.accounts.mxe_account,
-            &[position_callback_account, claim_auth_callback_account],
-        )?;
+        // Top up rent for the appended bytes, then grow the account (zero-filled)
+        let rent_shortfall = Rent::get()?
+            .minimum_balance(VestingSchedule::SIZE)
+            .saturating_sub(info.lamports());
+        if rent_shortfall > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.admin.to_account_info(),
+                        to: info.clone(),
+                    },
+                ),
+                rent_shortfall,
+            )?;
+        }
+        info.resize(VestingSchedule::SIZE)?;
 
-        queue_computation(
-            ctx.accounts,
-            computation_offset,
-            args,
-            vec![callback_ix],
-            1,
-            0,
-        )?;
+        let mut schedule: VestingSchedule = load_program_account(&info)?;
+        require!(
+            schedule.organization == ctx.accounts.organization.key(),
+            ShadowVestError::InvalidScheduleParams
+        );
 
-        // Store claim_amount in the authorization for the callback to verify
-        let claim_auth_mut = &mut ctx.accounts.claim_authorization;
-        claim_auth_mut.claim_amount = claim_amount;
+        schedule.apply_migration_defaults();
+        store_program_account(&info, &schedule)?;
 
-        emit!(ClaimProcessQueued {
-            position: position.key(),
-            position_id: position.position_id,
-            claim_amount,
-            computation_offset,
-            vesting_numerator,
+        emit!(VestingScheduleMigrated {
+            organization: ctx.accounts.organization.key(),
+            schedule: info.key(),
+            schedule_id,
         });
 
         Ok(())
     }
 
-    /// Callback from the process_claim_v2 MPC computation.
-    ///
-    /// Verifies the MPC output and updates:
-    /// - position.encrypted_claimed_amount from output ciphertexts[0]
-    /// - claim_authorization.is_processed = true
-    #[arcium_callback(encrypted_ix = "process_claim_v2")]
-    pub fn process_claim_v2_callback(
-        ctx: Context<ProcessClaimV2Callback>,
-        output: SignedComputationOutputs<ProcessClaimV2Output>,
-    ) -> Result<()> {
-        let verified = output
-            .verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account)
-            .map_err(|_| ErrorCode::AbortedComputation)?;
+    // ============================================================
+    // Vesting Position Management (with MPC)
+    // ============================================================
 
-        // Update position's encrypted claimed amount from MPC output
-        let position = &mut ctx.accounts.position;
-        position.encrypted_claimed_amount = verified.field_0.ciphertexts[0];
+    pub fn create_vesting_position(
+        ctx: Context<CreateVestingPosition>,
+        computation_offset: u64,
+        beneficiary_commitment: [u8; 32],
+        encrypted_total_amount: [u8; 32],
+        pubkey: [u8; 32],
+        nonce: u128,
+        sig_scheme: u8,
+        metadata_commitment: [u8; 32],
+    ) -> Result<()> {
+        // Validate state first
+        require!(ctx.accounts.organization.is_active, ShadowVestError::OrganizationNotActive);
+        require!(ctx.accounts.schedule.is_active, ShadowVestError::ScheduleNotActive);
+        VestingPosition::validate_sig_scheme(sig_scheme, &beneficiary_commitment)?;
+        require!(
+            !ctx.accounts.organization.compressed_only,
+            ShadowVestError::CompressedStorageRequired
+        );
 
-        // Mark authorization as processed
-        let claim_auth = &mut ctx.accounts.claim_authorization;
-        claim_auth.is_processed = true;
+        // Capture values needed for event before mutable borrows
+        let position_id = ctx.accounts.organization.position_count;
+        let clock = Clock::get()?;
+        let org_key = ctx.accounts.organization.key();
+        let schedule_key = ctx.accounts.schedule.key();
 
-        emit!(ClaimProcessed {
-            position: position.key(),
-            position_id: position.position_id,
-            claim_amount: claim_auth.claim_amount,
-        });
+        // Initialize position
+        {
+            let position = &mut ctx.accounts.position;
+            position.organization = org_key;
+            position.schedule = schedule_key;
+            position.position_id = position_id;
+            position.beneficiary_commitment = beneficiary_commitment;
+            position.encrypted_total_amount = encrypted_total_amount;
+            position.encrypted_claimed_amount = [0u8; 32];
+            position.nonce = nonce;
+            position.encryption_pubkey = pubkey;
+            position.start_timestamp = clock.unix_timestamp;
+            position.state = PositionState::Created;
+            position.sig_scheme = sig_scheme;
+            position.revoked_at = 0;
+            position.is_stealth = false;
+            position.last_claim_at = 0;
+            position.metadata_commitment = metadata_commitment;
+            position.privacy_mode = true;
+            position.plaintext_total_amount = None;
+            position.is_paused = false;
+            position.paused_at = 0;
+            position.total_paused_seconds = 0;
+            position.reserved_amount = 0;
+            position.merkle_claim_count = 0;
+            position.open_claim_count = 0;
+            position.is_reserve_set = false;
+            position.pause_freezes_vesting = false;
+            position.bump = ctx.bumps.position;
+        }
 
-        Ok(())
-    }
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
-    /// Initialize the token vault for an organization.
-    ///
-    /// Creates a token account owned by a vault_authority PDA.
-    /// The organization admin can then deposit tokens to this vault.
-    pub fn initialize_vault(ctx: Context<InitializeVault>) -> Result<()> {
-        let organization = &ctx.accounts.organization;
-        require!(organization.is_active, ShadowVestError::OrganizationNotActive);
+        // Lazy-init organizations leave the position Created; init_position is
+        // queued via queue_init_position on first interaction
+        let lazy_init = ctx.accounts.organization.lazy_init;
+        if !lazy_init {
+            let args = ArgBuilder::new()
+                .x25519_pubkey(pubkey)
+                .plaintext_u128(nonce)
+                .encrypted_u64(encrypted_total_amount)
+                .build();
+
+            let position_callback_account = CallbackAccount {
+                pubkey: ctx.accounts.position.key(),
+                is_writable: true,
+            };
+
+            let callback_ix = InitPositionCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[position_callback_account],
+            )?;
 
-        emit!(VaultInitialized {
-            organization: organization.key(),
-            vault: ctx.accounts.vault.key(),
-            vault_authority: ctx.accounts.vault_authority.key(),
-            token_mint: organization.token_mint,
-        });
+            queue_computation(
+                ctx.accounts,
+                computation_offset,
+                args,
+                vec![callback_ix],
+                1,
+                0,
+            )?;
+        }
 
-        Ok(())
-    }
+        // Update counters after queue_computation
+        ctx.accounts.organization.position_count = ctx.accounts.organization
+            .position_count
+            .checked_add(1)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
 
-    /// Deposit tokens into the organization vault.
-    /// The depositor transfers SPL tokens from their token account to the vault.
-    pub fn deposit_to_vault(ctx: Context<DepositToVault>, amount: u64) -> Result<()> {
-        require!(ctx.accounts.organization.is_active, ShadowVestError::OrganizationNotActive);
-        require!(amount > 0, ShadowVestError::InvalidClaimAmount);
+        ctx.accounts.schedule.position_count = ctx.accounts.schedule
+            .position_count
+            .checked_add(1)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
 
-        // Transfer tokens from admin's token account to vault
-        let transfer_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.admin_token_account.to_account_info(),
-                to: ctx.accounts.vault.to_account_info(),
-                authority: ctx.accounts.admin.to_account_info(),
-            },
-        );
-        token::transfer(transfer_ctx, amount)?;
+        let position_key = ctx.accounts.position.key();
+        let start_timestamp = ctx.accounts.position.start_timestamp;
+        ctx.accounts
+            .position_index
+            .record(org_key, ctx.bumps.position_index, position_key);
 
-        emit!(VaultDeposited {
-            organization: ctx.accounts.organization.key(),
-            vault: ctx.accounts.vault.key(),
-            depositor: ctx.accounts.admin.key(),
-            amount,
+        emit!(VestingPositionCreated {
+            organization: org_key,
+            schedule: schedule_key,
+            position: position_key,
+            position_id,
+            beneficiary_commitment,
+            start_timestamp,
+            metadata_commitment,
         });
 
+        if lazy_init {
+            emit!(PositionInitDeferred {
+                position: position_key,
+                position_id,
+            });
+        }
+
         Ok(())
     }
 
-    /// Withdraw tokens from the organization vault to the beneficiary's destination.
+    /// Create a vesting position under the organization's default schedule.
     ///
-    /// Verifies the claim has been authorized, processed by MPC, and not yet withdrawn.
-    /// Transfers claim_amount tokens from vault to destination.
-    pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
-        let claim_auth = &ctx.accounts.claim_authorization;
+    /// Same as create_vesting_position, but the schedule account must be the one
+    /// set with set_default_schedule; fails with NoDefaultSchedule if none is set.
+    pub fn create_vesting_position_default(
+        ctx: Context<CreateVestingPosition>,
+        computation_offset: u64,
+        beneficiary_commitment: [u8; 32],
+        encrypted_total_amount: [u8; 32],
+        pubkey: [u8; 32],
+        nonce: u128,
+        sig_scheme: u8,
+        metadata_commitment: [u8; 32],
+    ) -> Result<()> {
+        let default_schedule = ctx
+            .accounts
+            .organization
+            .default_schedule
+            .ok_or(ShadowVestError::NoDefaultSchedule)?;
+        require_keys_eq!(
+            ctx.accounts.schedule.key(),
+            default_schedule,
+            ShadowVestError::InvalidScheduleParams
+        );
 
-        require!(claim_auth.is_authorized, ShadowVestError::ClaimNotAuthorized);
-        require!(claim_auth.is_processed, ShadowVestError::ClaimNotProcessed);
-        require!(!claim_auth.is_withdrawn, ShadowVestError::AlreadyWithdrawn);
+        create_vesting_position(
+            ctx,
+            computation_offset,
+            beneficiary_commitment,
+            encrypted_total_amount,
+            pubkey,
+            nonce,
+            sig_scheme,
+            metadata_commitment,
+        )
+    }
 
-        // Verify destination matches what was authorized
+    /// Create a vesting position set up by the beneficiary and funded by the employer.
+    ///
+    /// The beneficiary supplies the beneficiary_commitment (its Ed25519 claim key,
+    /// typically a stealth address) and the encryption pubkey/nonce; the employer
+    /// supplies encrypted_total_amount. Both must sign, and the commitment must be
+    /// the beneficiary signer's key, so the position is bound to a key that
+    /// demonstrably signed at creation.
+    pub fn create_vesting_position_cosigned(
+        ctx: Context<CreateVestingPositionCosigned>,
+        computation_offset: u64,
+        beneficiary_commitment: [u8; 32],
+        pubkey: [u8; 32],
+        nonce: u128,
+        encrypted_total_amount: [u8; 32],
+        metadata_commitment: [u8; 32],
+    ) -> Result<()> {
+        require!(ctx.accounts.organization.is_active, ShadowVestError::OrganizationNotActive);
+        require!(ctx.accounts.schedule.is_active, ShadowVestError::ScheduleNotActive);
         require!(
-            ctx.accounts.destination.key() == claim_auth.withdrawal_destination,
-            ShadowVestError::InvalidWithdrawalDestination
+            ctx.accounts.beneficiary.key().to_bytes() == beneficiary_commitment,
+            ShadowVestError::SignerMismatch
         );
-
-        let amount = claim_auth.claim_amount;
-
-        // Verify vault has sufficient balance
         require!(
-            ctx.accounts.vault.amount >= amount,
-            ShadowVestError::InsufficientVaultBalance
+            !ctx.accounts.organization.compressed_only,
+            ShadowVestError::CompressedStorageRequired
         );
 
-        // Transfer tokens from vault to destination
+        let position_id = ctx.accounts.organization.position_count;
+        let clock = Clock::get()?;
         let org_key = ctx.accounts.organization.key();
-        let bump = ctx.bumps.vault_authority;
-        let vault_authority_seeds: &[&[u8]] = &[
-            b"vault_authority",
-            org_key.as_ref(),
-            std::slice::from_ref(&bump),
-        ];
-        let signer_seeds = &[vault_authority_seeds];
+        let schedule_key = ctx.accounts.schedule.key();
 
-        let transfer_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.vault.to_account_info(),
-                to: ctx.accounts.destination.to_account_info(),
-                authority: ctx.accounts.vault_authority.to_account_info(),
-            },
-            signer_seeds,
-        );
-        token::transfer(transfer_ctx, amount)?;
+        {
+            let position = &mut ctx.accounts.position;
+            position.organization = org_key;
+            position.schedule = schedule_key;
+            position.position_id = position_id;
+            position.beneficiary_commitment = beneficiary_commitment;
+            position.encrypted_total_amount = encrypted_total_amount;
+            position.encrypted_claimed_amount = [0u8; 32];
+            position.nonce = nonce;
+            position.encryption_pubkey = pubkey;
+            position.start_timestamp = clock.unix_timestamp;
+            position.state = PositionState::Created;
+            position.sig_scheme = VestingPosition::SIG_SCHEME_ED25519;
+            position.revoked_at = 0;
+            position.is_stealth = false;
+            position.last_claim_at = 0;
+            position.metadata_commitment = metadata_commitment;
+            position.privacy_mode = true;
+            position.plaintext_total_amount = None;
+            position.is_paused = false;
+            position.paused_at = 0;
+            position.total_paused_seconds = 0;
+            position.reserved_amount = 0;
+            position.merkle_claim_count = 0;
+            position.open_claim_count = 0;
+            position.is_reserve_set = false;
+            position.pause_freezes_vesting = false;
+            position.bump = ctx.bumps.position;
+        }
 
-        // Mark as withdrawn
-        let claim_auth_mut = &mut ctx.accounts.claim_authorization;
-        claim_auth_mut.is_withdrawn = true;
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
-        let token_mint = ctx.accounts.vault.mint;
+        // Lazy-init organizations leave the position Created, as in create_vesting_position
+        let lazy_init = ctx.accounts.organization.lazy_init;
+        if !lazy_init {
+            let args = ArgBuilder::new()
+                .x25519_pubkey(pubkey)
+                .plaintext_u128(nonce)
+                .encrypted_u64(encrypted_total_amount)
+                .build();
+
+            let position_callback_account = CallbackAccount {
+                pubkey: ctx.accounts.position.key(),
+                is_writable: true,
+            };
+
+            let callback_ix = InitPositionCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[position_callback_account],
+            )?;
 
-        emit!(ClaimWithdrawn {
-            position: claim_auth_mut.position,
-            destination: claim_auth_mut.withdrawal_destination,
-            amount,
-            token_mint,
+            queue_computation(
+                ctx.accounts,
+                computation_offset,
+                args,
+                vec![callback_ix],
+                1,
+                0,
+            )?;
+        }
+
+        ctx.accounts.organization.position_count = ctx.accounts.organization
+            .position_count
+            .checked_add(1)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+
+        ctx.accounts.schedule.position_count = ctx.accounts.schedule
+            .position_count
+            .checked_add(1)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+
+        let position_key = ctx.accounts.position.key();
+        let start_timestamp = ctx.accounts.position.start_timestamp;
+        ctx.accounts
+            .position_index
+            .record(org_key, ctx.bumps.position_index, position_key);
+
+        emit!(VestingPositionCreated {
+            organization: org_key,
+            schedule: schedule_key,
+            position: position_key,
+            position_id,
+            beneficiary_commitment,
+            start_timestamp,
+            metadata_commitment,
+        });
+
+        emit!(PositionCosigned {
+            organization: org_key,
+            position: position_key,
+            position_id,
+            admin: ctx.accounts.admin.key(),
+            beneficiary: ctx.accounts.beneficiary.key(),
         });
 
+        if lazy_init {
+            emit!(PositionInitDeferred {
+                position: position_key,
+                position_id,
+            });
+        }
+
         Ok(())
     }
 
-    // ============================================================
-    // Compressed Vesting Positions (Light Protocol - 5000x cost reduction)
-    // ============================================================
-
-    /// Create a compressed vesting position using Light Protocol.
-    /// This stores the position in a Merkle tree for 5000x cost reduction.
-    ///
-    /// The position data is hashed and stored in Light Protocol's state tree,
-    /// while encrypted amounts are stored for Arcium MPC processing.
+    /// Create a vesting position with stealth address beneficiary.
     ///
-    /// # Arguments
-    /// * `proof_bytes` - Serialized validity proof for Light Protocol state transition
-    /// * `address_tree_info_bytes` - Serialized address tree info for derivation
-    /// * `output_tree_index` - Index of the output state tree
-    /// * `beneficiary_commitment` - Hash commitment of beneficiary identity
-    /// * `encrypted_total_amount` - Arcium-encrypted total vesting amount
-    /// * `nonce` - Nonce for Arcium encryption
+    /// The stealth address is derived off-chain by the employer:
+    /// 1. Employer fetches employee's (S, V) from StealthMetaAddress
+    /// 2. Generates ephemeral keypair (r, R = r*G)
+    /// 3. Computes stealth_address = S + H(r * V) * G
     ///
-    /// Note: This instruction requires Light Protocol accounts in remaining_accounts:
-    /// - light_system_program
-    /// - account_compression_program
-    /// - registered_program_pda
-    /// - noop_program
-    /// - cpi_authority_pda
-    /// - state_merkle_tree
-    /// - address_merkle_tree
-    /// - address_queue
-    pub fn create_compressed_vesting_position<'info>(
-        ctx: Context<'_, '_, '_, 'info, CreateCompressedVestingPosition<'info>>,
-        proof_bytes: Vec<u8>,
-        address_tree_info_bytes: Vec<u8>,
-        output_tree_index: u8,
-        beneficiary_commitment: [u8; 32],
+    /// This instruction stores the position and emits StealthPaymentEvent
+    /// so the employee can scan and discover the payment. `view_tag` is the
+    /// first byte of H(r * V), letting the employee skip most foreign events
+    /// before doing the full derivation.
+    pub fn create_stealth_vesting_position(
+        ctx: Context<CreateVestingPosition>,
+        computation_offset: u64,
+        stealth_address: Pubkey,
+        ephemeral_pubkey: [u8; 32],
+        view_tag: u8,
+        encrypted_payload: [u8; 128],
+        auditor_encrypted_payload: [u8; 128],
         encrypted_total_amount: [u8; 32],
+        pubkey: [u8; 32],
         nonce: u128,
+        metadata_commitment: [u8; 32],
     ) -> Result<()> {
-        // Validate organization and schedule state
+        // Validate state first
+        require!(ctx.accounts.organization.is_active, ShadowVestError::OrganizationNotActive);
+        require!(ctx.accounts.schedule.is_active, ShadowVestError::ScheduleNotActive);
         require!(
-            ctx.accounts.organization.is_active,
-            ShadowVestError::OrganizationNotActive
+            !ctx.accounts.organization.compressed_only,
+            ShadowVestError::CompressedStorageRequired
         );
+
         require!(
-            ctx.accounts.schedule.is_active,
-            ShadowVestError::ScheduleNotActive
+            state::is_valid_stealth_address(&stealth_address),
+            ShadowVestError::InvalidStealthPayment
         );
+        ctx.accounts
+            .organization
+            .check_auditor_payload(&auditor_encrypted_payload)?;
 
-        // Deserialize the Light Protocol types from bytes
-        let proof: ValidityProof = borsh::BorshDeserialize::try_from_slice(&proof_bytes)
-            .map_err(|_| ShadowVestError::LightProtocolCpiFailed)?;
-        let address_tree_info: PackedAddressTreeInfo =
-            borsh::BorshDeserialize::try_from_slice(&address_tree_info_bytes)
-                .map_err(|_| ShadowVestError::LightProtocolCpiFailed)?;
+        // Use stealth address as beneficiary commitment
+        let beneficiary_commitment = stealth_address.to_bytes();
 
-        // Get current position ID and timestamp
-        let position_id = ctx.accounts.organization.compressed_position_count;
+        // Capture values needed for events before mutable borrows
+        let position_id = ctx.accounts.organization.position_count;
         let clock = Clock::get()?;
+        let org_key = ctx.accounts.organization.key();
+        let schedule_key = ctx.accounts.schedule.key();
+        let token_mint = ctx.accounts.schedule.token_mint;
 
-        // Initialize CPI accounts for Light Protocol
-        let cpi_accounts = CpiAccounts::new(
-            ctx.accounts.fee_payer.as_ref(),
-            ctx.remaining_accounts,
-            crate::LIGHT_CPI_SIGNER,
-        );
-
-        // For V2 API, system accounts are at indices 0-5 (6 accounts)
-        // Tree accounts start at index 6 in remaining_accounts
-        // The address_tree_info.address_merkle_tree_pubkey_index is relative to tree section
-        const V2_SYSTEM_ACCOUNTS_COUNT: usize = 6;
-        let tree_idx = V2_SYSTEM_ACCOUNTS_COUNT + address_tree_info.address_merkle_tree_pubkey_index as usize;
-
-        let address_tree_pubkey = ctx.remaining_accounts
-            .get(tree_idx)
-            .ok_or(ShadowVestError::InvalidAddressTree)?
-            .key();
-
-        // Derive unique address for this compressed position
-        // Seeds: [prefix, organization, position_id]
-        let (address, address_seed) = derive_address(
-            &[
-                CompressedVestingPosition::SEED_PREFIX,
-                ctx.accounts.organization.key().as_ref(),
-                &position_id.to_le_bytes(),
-            ],
-            &address_tree_pubkey,
-            &crate::ID,
-        );
-
-        // Create new address parameters for the Merkle tree
-        let new_address_params = address_tree_info.into_new_address_params_assigned_packed(address_seed, Some(0));
+        // Initialize position
+        {
+            let position = &mut ctx.accounts.position;
+            position.organization = org_key;
+            position.schedule = schedule_key;
+            position.position_id = position_id;
+            position.beneficiary_commitment = beneficiary_commitment;
+            position.encrypted_total_amount = encrypted_total_amount;
+            position.encrypted_claimed_amount = [0u8; 32];
+            position.nonce = nonce;
+            position.encryption_pubkey = pubkey;
+            position.start_timestamp = clock.unix_timestamp;
+            position.state = PositionState::Created;
+            position.sig_scheme = VestingPosition::SIG_SCHEME_ED25519;
+            position.revoked_at = 0;
+            position.is_stealth = true;
+            position.last_claim_at = 0;
+            position.metadata_commitment = metadata_commitment;
+            position.privacy_mode = true;
+            position.plaintext_total_amount = None;
+            position.is_paused = false;
+            position.paused_at = 0;
+            position.total_paused_seconds = 0;
+            position.reserved_amount = 0;
+            position.merkle_claim_count = 0;
+            position.open_claim_count = 0;
+            position.is_reserve_set = false;
+            position.pause_freezes_vesting = false;
+            position.bump = ctx.bumps.position;
+        }
 
-        // Initialize the compressed vesting position
-        let mut compressed_position =
-            LightAccount::<CompressedVestingPosition>::new_init(&crate::ID, Some(address), output_tree_index);
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
-        // Set position data
-        compressed_position.owner = ctx.accounts.admin.key();
-        compressed_position.organization = ctx.accounts.organization.key();
-        compressed_position.schedule = ctx.accounts.schedule.key();
-        compressed_position.position_id = position_id;
-        compressed_position.beneficiary_commitment = beneficiary_commitment;
-        compressed_position.encrypted_total_amount = encrypted_total_amount;
-        compressed_position.encrypted_claimed_amount = [0u8; 32];
-        compressed_position.nonce = nonce;
-        compressed_position.start_timestamp = clock.unix_timestamp;
-        compressed_position.is_active = 1;
-        compressed_position.is_fully_claimed = 0;
+        // Lazy-init organizations leave the position Created, as in create_vesting_position
+        let lazy_init = ctx.accounts.organization.lazy_init;
+        if !lazy_init {
+            let args = ArgBuilder::new()
+                .x25519_pubkey(pubkey)
+                .plaintext_u128(nonce)
+                .encrypted_u64(encrypted_total_amount)
+                .build();
+
+            let position_callback_account = CallbackAccount {
+                pubkey: ctx.accounts.position.key(),
+                is_writable: true,
+            };
+
+            let callback_ix = InitPositionCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[position_callback_account],
+            )?;
 
-        // Execute Light Protocol CPI to create the compressed account
-        LightSystemProgramCpi::new_cpi(crate::LIGHT_CPI_SIGNER, proof)
-            .with_new_addresses(&[new_address_params])
-            .with_light_account(compressed_position)?
-            .invoke(cpi_accounts)?;
+            queue_computation(
+                ctx.accounts,
+                computation_offset,
+                args,
+                vec![callback_ix],
+                1,
+                0,
+            )?;
+        }
 
-        // Update organization counter
-        ctx.accounts.organization.compressed_position_count = ctx
-            .accounts
-            .organization
-            .compressed_position_count
+        // Update counters after queue_computation
+        ctx.accounts.organization.position_count = ctx.accounts.organization
+            .position_count
             .checked_add(1)
             .ok_or(ShadowVestError::ArithmeticOverflow)?;
 
-        // Update schedule counter
-        ctx.accounts.schedule.compressed_position_count = ctx
-            .accounts
-            .schedule
-            .compressed_position_count
+        ctx.accounts.schedule.position_count = ctx.accounts.schedule
+            .position_count
             .checked_add(1)
             .ok_or(ShadowVestError::ArithmeticOverflow)?;
 
-        // Emit event for indexing
-        emit!(CompressedPositionCreated {
-            organization: ctx.accounts.organization.key(),
-            schedule: ctx.accounts.schedule.key(),
+        let position_key = ctx.accounts.position.key();
+        let start_timestamp = ctx.accounts.position.start_timestamp;
+        ctx.accounts
+            .position_index
+            .record(org_key, ctx.bumps.position_index, position_key);
+
+        // Emit both events for indexing
+        emit!(VestingPositionCreated {
+            organization: org_key,
+            schedule: schedule_key,
+            position: position_key,
             position_id,
-            address,
             beneficiary_commitment,
-            start_timestamp: clock.unix_timestamp,
+            start_timestamp,
+            metadata_commitment,
+        });
+
+        // Emit stealth payment event for employee scanning
+        emit!(StealthPaymentEvent {
+            organization: org_key,
+            stealth_address,
+            ephemeral_pubkey,
+            view_tag,
+            encrypted_payload,
+            auditor_encrypted_payload,
+            position_id,
+            token_mint,
+            timestamp: clock.unix_timestamp,
         });
 
+        if lazy_init {
+            emit!(PositionInitDeferred {
+                position: position_key,
+                position_id,
+            });
+        }
+
         Ok(())
     }
 
-    /// Create a compressed vesting position with stealth address beneficiary.
+    /// Create a vesting schedule and its first position in one transaction.
     ///
-    /// Combines Light Protocol's 5000x cost reduction with stealth address privacy.
-    /// The stealth address is derived off-chain by the employer using employee's (S, V).
-    ///
-    /// Emits both CompressedPositionCreated and StealthPaymentEvent for indexing/scanning.
-    pub fn create_compressed_stealth_vesting_position<'info>(
-        ctx: Context<'_, '_, '_, 'info, CreateCompressedVestingPosition<'info>>,
-        proof_bytes: Vec<u8>,
-        address_tree_info_bytes: Vec<u8>,
-        output_tree_index: u8,
-        stealth_address: Pubkey,
-        ephemeral_pubkey: [u8; 32],
-        encrypted_payload: [u8; 128],
+    /// Convenience for the one-employee-one-schedule case: avoids a separate
+    /// round-trip and orphan schedules if position creation fails. The schedule is
+    /// created by the same helper as create_vesting_schedule (so it takes the same
+    /// vesting_mode and token_mint), the position is validated as in
+    /// create_vesting_position, and the init_position computation is queued for it
+    /// unless the organization uses lazy init.
+    pub fn create_schedule_with_position(
+        ctx: Context<CreateScheduleWithPosition>,
+        computation_offset: u64,
+        cliff_duration: u64,
+        total_duration: u64,
+        vesting_interval: u64,
+        vesting_mode: u8,
+        token_mint: Pubkey,
+        beneficiary_commitment: [u8; 32],
         encrypted_total_amount: [u8; 32],
+        pubkey: [u8; 32],
         nonce: u128,
+        sig_scheme: u8,
+        metadata_commitment: [u8; 32],
     ) -> Result<()> {
-        // Validate organization and schedule state
-        require!(
-            ctx.accounts.organization.is_active,
-            ShadowVestError::OrganizationNotActive
-        );
+        VestingPosition::validate_sig_scheme(sig_scheme, &beneficiary_commitment)?;
         require!(
-            ctx.accounts.schedule.is_active,
-            ShadowVestError::ScheduleNotActive
+            !ctx.accounts.organization.compressed_only,
+            ShadowVestError::CompressedStorageRequired
         );
 
-        // Use stealth address as beneficiary commitment
-        let beneficiary_commitment = stealth_address.to_bytes();
+        let position_id = ctx.accounts.organization.position_count;
+        let clock = Clock::get()?;
+        let org_key = ctx.accounts.organization.key();
+        let schedule_key = ctx.accounts.schedule.key();
 
-        // Deserialize the Light Protocol types from bytes
-        let proof: ValidityProof = borsh::BorshDeserialize::try_from_slice(&proof_bytes)
-            .map_err(|_| ShadowVestError::LightProtocolCpiFailed)?;
-        let address_tree_info: PackedAddressTreeInfo =
-            borsh::BorshDeserialize::try_from_slice(&address_tree_info_bytes)
-                .map_err(|_| ShadowVestError::LightProtocolCpiFailed)?;
-
-        // Get current position ID and timestamp
-        let position_id = ctx.accounts.organization.compressed_position_count;
-        let clock = Clock::get()?;
-        let token_mint = ctx.accounts.organization.token_mint;
-        let org_key = ctx.accounts.organization.key();
-        let schedule_key = ctx.accounts.schedule.key();
-
-        // Initialize CPI accounts for Light Protocol
-        let cpi_accounts = CpiAccounts::new(
-            ctx.accounts.fee_payer.as_ref(),
-            ctx.remaining_accounts,
-            crate::LIGHT_CPI_SIGNER,
-        );
-
-        // For V2 API, system accounts are at indices 0-5 (6 accounts)
-        // Tree accounts start at index 6 in remaining_accounts
-        // The address_tree_info.address_merkle_tree_pubkey_index is relative to tree section
-        const V2_SYSTEM_ACCOUNTS_COUNT: usize = 6;
-        let tree_idx = V2_SYSTEM_ACCOUNTS_COUNT + address_tree_info.address_merkle_tree_pubkey_index as usize;
-        let address_tree_pubkey = ctx.remaining_accounts
-            .get(tree_idx)
-            .ok_or(ShadowVestError::InvalidAddressTree)?
-            .key();
-
-        // Derive unique address for this compressed position
-        // Seeds: [prefix, organization, position_id]
-        let (address, address_seed) = derive_address(
-            &[
-                CompressedVestingPosition::SEED_PREFIX,
-                ctx.accounts.organization.key().as_ref(),
-                &position_id.to_le_bytes(),
-            ],
-            &address_tree_pubkey,
-            &crate::ID,
-        );
+        create_schedule(
+            &mut ctx.accounts.organization,
+            &mut ctx.accounts.schedule,
+            ctx.bumps.schedule,
+            cliff_duration,
+            total_duration,
+            vesting_interval,
+            vesting_mode,
+            token_mint,
+        )?;
+        // The schedule already counts the position created below
+        ctx.accounts.schedule.position_count = 1;
 
-        // Create new address parameters for the Merkle tree
-        let new_address_params = address_tree_info.into_new_address_params_assigned_packed(address_seed, Some(0));
+        // Initialize position
+        {
+            let position = &mut ctx.accounts.position;
+            position.organization = org_key;
+            position.schedule = schedule_key;
+            position.position_id = position_id;
+            position.beneficiary_commitment = beneficiary_commitment;
+            position.encrypted_total_amount = encrypted_total_amount;
+            position.encrypted_claimed_amount = [0u8; 32];
+            position.nonce = nonce;
+            position.encryption_pubkey = pubkey;
+            position.start_timestamp = clock.unix_timestamp;
+            position.state = PositionState::Created;
+            position.sig_scheme = sig_scheme;
+            position.revoked_at = 0;
+            position.is_stealth = false;
+            position.last_claim_at = 0;
+            position.metadata_commitment = metadata_commitment;
+            position.privacy_mode = true;
+            position.plaintext_total_amount = None;
+            position.is_paused = false;
+            position.paused_at = 0;
+            position.total_paused_seconds = 0;
+            position.reserved_amount = 0;
+            position.merkle_claim_count = 0;
+            position.open_claim_count = 0;
+            position.is_reserve_set = false;
+            position.pause_freezes_vesting = false;
+            position.bump = ctx.bumps.position;
+        }
 
-        // Initialize the compressed vesting position
-        let mut compressed_position =
-            LightAccount::<CompressedVestingPosition>::new_init(&crate::ID, Some(address), output_tree_index);
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
-        // Set position data with stealth address as beneficiary
-        compressed_position.owner = ctx.accounts.admin.key();
-        compressed_position.organization = org_key;
-        compressed_position.schedule = schedule_key;
-        compressed_position.position_id = position_id;
-        compressed_position.beneficiary_commitment = beneficiary_commitment;
-        compressed_position.encrypted_total_amount = encrypted_total_amount;
-        compressed_position.encrypted_claimed_amount = [0u8; 32];
-        compressed_position.nonce = nonce;
-        compressed_position.start_timestamp = clock.unix_timestamp;
-        compressed_position.is_active = 1;
-        compressed_position.is_fully_claimed = 0;
+        // Lazy-init organizations leave the position Created, as in create_vesting_position
+        let lazy_init = ctx.accounts.organization.lazy_init;
+        if !lazy_init {
+            let args = ArgBuilder::new()
+                .x25519_pubkey(pubkey)
+                .plaintext_u128(nonce)
+                .encrypted_u64(encrypted_total_amount)
+                .build();
+
+            let position_callback_account = CallbackAccount {
+                pubkey: ctx.accounts.position.key(),
+                is_writable: true,
+            };
+
+            let callback_ix = InitPositionCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[position_callback_account],
+            )?;
 
-        // Execute Light Protocol CPI to create the compressed account
-        LightSystemProgramCpi::new_cpi(crate::LIGHT_CPI_SIGNER, proof)
-            .with_new_addresses(&[new_address_params])
-            .with_light_account(compressed_position)?
-            .invoke(cpi_accounts)?;
+            queue_computation(
+                ctx.accounts,
+                computation_offset,
+                args,
+                vec![callback_ix],
+                1,
+                0,
+            )?;
+        }
 
-        // Update organization counter
-        ctx.accounts.organization.compressed_position_count = ctx
-            .accounts
-            .organization
-            .compressed_position_count
+        // Update counters after queue_computation
+        let organization = &mut ctx.accounts.organization;
+        organization.position_count = organization
+            .position_count
             .checked_add(1)
             .ok_or(ShadowVestError::ArithmeticOverflow)?;
 
-        // Update schedule counter
-        ctx.accounts.schedule.compressed_position_count = ctx
-            .accounts
-            .schedule
-            .compressed_position_count
-            .checked_add(1)
-            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+        let position_key = ctx.accounts.position.key();
+        let start_timestamp = ctx.accounts.position.start_timestamp;
+        ctx.accounts
+            .position_index
+            .record(org_key, ctx.bumps.position_index, position_key);
 
-        // Emit event for indexing
-        emit!(CompressedPositionCreated {
+        emit!(VestingPositionCreated {
             organization: org_key,
             schedule: schedule_key,
+            position: position_key,
             position_id,
-            address,
             beneficiary_commitment,
-            start_timestamp: clock.unix_timestamp,
+            start_timestamp,
+            metadata_commitment,
         });
 
-        // Emit stealth payment event for employee scanning
-        emit!(StealthPaymentEvent {
-            organization: org_key,
-            stealth_address,
-            ephemeral_pubkey,
-            encrypted_payload,
-            position_id,
-            token_mint,
-            timestamp: clock.unix_timestamp,
-        });
+        if lazy_init {
+            emit!(PositionInitDeferred {
+                position: position_key,
+                position_id,
+            });
+        }
 
         Ok(())
     }
 
-    // ============================================================
-    // Compressed Position Claim & Withdraw Flow
-    // ============================================================
-
-    /// Authorize a claim from a compressed vesting position.
-    ///
-    /// Similar to authorize_claim but works with Light Protocol compressed accounts.
-    /// The compressed position data is read via Light Protocol CPI (validity proof verification).
-    /// An Ed25519 signature from the stealth keypair authorizes the claim.
+    /// Create up to MAX_BATCH_POSITIONS positions in one transaction (airdrop-style grants).
     ///
-    /// This creates a ClaimAuthorization PDA that the withdraw_compressed() can reference.
-    pub fn authorize_claim_compressed<'info>(
-        ctx: Context<'_, '_, '_, 'info, AuthorizeClaimCompressed<'info>>,
-        proof_bytes: Vec<u8>,
-        account_meta_bytes: Vec<u8>,
-        // Compressed position data (client fetches from Light RPC):
-        position_owner: Pubkey,
-        position_organization: Pubkey,
-        position_schedule: Pubkey,
-        position_id: u64,
-        beneficiary_commitment: [u8; 32],
-        encrypted_total_amount: [u8; 32],
-        encrypted_claimed_amount: [u8; 32],
-        position_nonce: u128,
-        position_start_timestamp: i64,
-        position_is_active: u8,
-        position_is_fully_claimed: u8,
-        // Claim params:
-        nullifier: [u8; 32],
-        withdrawal_destination: Pubkey,
+    /// Position PDAs are passed in remaining_accounts in position_id order and are
+    /// created here in the Created state. An Arcium instruction can only queue a
+    /// computation for its own computation account, so init_position is queued per
+    /// position afterwards with queue_init_position (several can share a transaction).
+    /// In a lazy-init organization that can wait until each position is first used.
+    pub fn batch_create_vesting_positions<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchCreateVestingPositions<'info>>,
+        positions: Vec<PositionParams>,
     ) -> Result<()> {
-        // 1. Verify organization is active
         require!(ctx.accounts.organization.is_active, ShadowVestError::OrganizationNotActive);
-
-        // 2. Verify position is active and not fully claimed
-        require!(position_is_active == 1, ShadowVestError::PositionNotActive);
-        require!(position_is_fully_claimed == 0, ShadowVestError::PositionFullyClaimed);
-
-        // 3. Verify position belongs to this organization
+        require!(ctx.accounts.schedule.is_active, ShadowVestError::ScheduleNotActive);
         require!(
-            position_organization == ctx.accounts.organization.key(),
-            ShadowVestError::InvalidPositionOrganization
+            !ctx.accounts.organization.compressed_only,
+            ShadowVestError::CompressedStorageRequired
         );
-
-        // 4. Deserialize Light Protocol types
-        let proof: ValidityProof = borsh::BorshDeserialize::try_from_slice(&proof_bytes)
-            .map_err(|_| ShadowVestError::LightProtocolCpiFailed)?;
-        let account_meta: CompressedAccountMeta =
-            borsh::BorshDeserialize::try_from_slice(&account_meta_bytes)
-                .map_err(|_| ShadowVestError::LightProtocolCpiFailed)?;
-
-        // 5. Initialize CPI accounts for Light Protocol
-        let cpi_accounts = CpiAccounts::new(
-            ctx.accounts.fee_payer.as_ref(),
-            ctx.remaining_accounts,
-            crate::LIGHT_CPI_SIGNER,
+        require!(
+            !positions.is_empty() && positions.len() <= VestingPosition::MAX_BATCH_POSITIONS,
+            ShadowVestError::InvalidBatchSize
+        );
+        require!(
+            ctx.remaining_accounts.len() == positions.len(),
+            ShadowVestError::InvalidBatchSize
         );
 
-        // 6. Get the address from account_meta (set during creation)
-        let address = account_meta.address;
-
-        // 7. Load the existing compressed position via Light Protocol
-        //    This verifies the data matches what's in the Merkle tree
-        let compressed_position = LightAccount::<CompressedVestingPosition>::new_mut(
-            &crate::ID,
-            &account_meta,
-            CompressedVestingPosition {
-                owner: position_owner,
-                organization: position_organization,
-                schedule: position_schedule,
+        let clock = Clock::get()?;
+        let rent = Rent::get()?;
+        let org_key = ctx.accounts.organization.key();
+        let schedule_key = ctx.accounts.schedule.key();
+        let lazy_init = ctx.accounts.organization.lazy_init;
+
+        for (params, position_info) in positions.iter().zip(ctx.remaining_accounts.iter()) {
+            let position_id = ctx.accounts.organization.position_count;
+            let position_id_bytes = position_id.to_le_bytes();
+            let (expected_position, bump) = Pubkey::find_program_address(
+                &[VestingPosition::SEED_PREFIX, org_key.as_ref(), position_id_bytes.as_ref()],
+                &crate::ID,
+            );
+            require!(
+                position_info.key() == expected_position,
+                ShadowVestError::InvalidBatchPositionAccount
+            );
+
+            let signer_seeds: &[&[u8]] = &[
+                VestingPosition::SEED_PREFIX,
+                org_key.as_ref(),
+                position_id_bytes.as_ref(),
+                std::slice::from_ref(&bump),
+            ];
+            anchor_lang::system_program::create_account(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::CreateAccount {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: position_info.clone(),
+                    },
+                    &[signer_seeds],
+                ),
+                rent.minimum_balance(VestingPosition::SIZE),
+                VestingPosition::SIZE as u64,
+                &crate::ID,
+            )?;
+
+            let position = VestingPosition {
+                organization: org_key,
+                schedule: schedule_key,
                 position_id,
-                beneficiary_commitment,
-                encrypted_total_amount,
-                encrypted_claimed_amount,
-                nonce: position_nonce,
-                start_timestamp: position_start_timestamp,
-                is_active: position_is_active,
-                is_fully_claimed: position_is_fully_claimed,
-            },
-        ).map_err(|_| ShadowVestError::LightProtocolCpiFailed)?;
+                beneficiary_commitment: params.beneficiary_commitment,
+                encrypted_total_amount: params.encrypted_total_amount,
+                encrypted_claimed_amount: [0u8; 32],
+                nonce: params.nonce,
+                encryption_pubkey: params.pubkey,
+                start_timestamp: clock.unix_timestamp,
+                state: PositionState::Created,
+                sig_scheme: VestingPosition::SIG_SCHEME_ED25519,
+                revoked_at: 0,
+                is_stealth: false,
+                last_claim_at: 0,
+                metadata_commitment: params.metadata_commitment,
+                privacy_mode: true,
+                plaintext_total_amount: None,
+                is_paused: false,
+                paused_at: 0,
+                total_paused_seconds: 0,
+                reserved_amount: 0,
+                merkle_claim_count: 0,
+                open_claim_count: 0,
+                is_reserve_set: false,
+                pause_freezes_vesting: false,
+                bump,
+            };
+            {
+                let mut data = position_info.try_borrow_mut_data()?;
+                let mut writer: &mut [u8] = &mut data;
+                position.try_serialize(&mut writer)?;
+            }
 
-        // 8. Verify Ed25519 signature (same as regular authorize_claim)
-        let ix_sysvar = &ctx.accounts.instructions_sysvar;
-        let current_ix_index = sysvar_instructions::load_current_index_checked(ix_sysvar)
-            .map_err(|_| ShadowVestError::InvalidEligibilitySignature)?;
-        require!(current_ix_index > 0, ShadowVestError::InvalidEligibilitySignature);
+            ctx.accounts.organization.position_count = position_id
+                .checked_add(1)
+                .ok_or(ShadowVestError::ArithmeticOverflow)?;
+            ctx.accounts
+                .position_index
+                .record(org_key, ctx.bumps.position_index, expected_position);
+            ctx.accounts.schedule.position_count = ctx.accounts.schedule
+                .position_count
+                .checked_add(1)
+                .ok_or(ShadowVestError::ArithmeticOverflow)?;
+
+            emit!(VestingPositionCreated {
+                organization: org_key,
+                schedule: schedule_key,
+                position: expected_position,
+                position_id,
+                beneficiary_commitment: params.beneficiary_commitment,
+                start_timestamp: clock.unix_timestamp,
+                metadata_commitment: params.metadata_commitment,
+            });
+
+            if lazy_init {
+                emit!(PositionInitDeferred {
+                    position: expected_position,
+                    position_id,
+                });
+            }
+        }
 
-        let ed25519_ix = sysvar_instructions::load_instruction_at_checked(
-            (current_ix_index - 1) as usize,
-            ix_sysvar,
-        ).map_err(|_| ShadowVestError::InvalidEligibilitySignature)?;
+        Ok(())
+    }
 
+    /// Queue the init_position computation for a position in the Created state,
+    /// using the encrypted total, nonce and key stored on the position.
+    pub fn queue_init_position(
+        ctx: Context<QueueInitPosition>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        let position = &ctx.accounts.position;
         require!(
-            ed25519_ix.program_id == ED25519_PROGRAM_ID,
-            ShadowVestError::InvalidEligibilitySignature
+            position.state == PositionState::Created,
+            ShadowVestError::InvalidPositionStateTransition
         );
-        require!(ed25519_ix.data.len() >= 16, ShadowVestError::InvalidEligibilitySignature);
 
-        let num_signatures = ed25519_ix.data[0];
-        require!(num_signatures == 1, ShadowVestError::InvalidEligibilitySignature);
+        let args = ArgBuilder::new()
+            .x25519_pubkey(position.encryption_pubkey)
+            .plaintext_u128(position.nonce)
+            .encrypted_u64(position.encrypted_total_amount)
+            .build();
 
-        let pubkey_offset = u16::from_le_bytes([ed25519_ix.data[6], ed25519_ix.data[7]]) as usize;
-        require!(
-            ed25519_ix.data.len() >= pubkey_offset + 32,
-            ShadowVestError::InvalidEligibilitySignature
-        );
-        let signer_pubkey = &ed25519_ix.data[pubkey_offset..pubkey_offset + 32];
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
-        // Verify signer matches beneficiary_commitment
-        require!(
-            signer_pubkey == beneficiary_commitment,
-            ShadowVestError::SignerMismatch
-        );
+        let position_callback_account = CallbackAccount {
+            pubkey: ctx.accounts.position.key(),
+            is_writable: true,
+        };
 
-        // Verify message content: position_id || nullifier || withdrawal_destination
-        let message_data_offset = u16::from_le_bytes([ed25519_ix.data[10], ed25519_ix.data[11]]) as usize;
-        let message_data_size = u16::from_le_bytes([ed25519_ix.data[12], ed25519_ix.data[13]]) as usize;
-        require!(
-            ed25519_ix.data.len() >= message_data_offset + message_data_size,
-            ShadowVestError::InvalidEligibilitySignature
-        );
-        let signed_message = &ed25519_ix.data[message_data_offset..message_data_offset + message_data_size];
+        let callback_ix = InitPositionCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[position_callback_account],
+        )?;
 
-        let mut expected_msg = [0u8; 72];
-        expected_msg[..8].copy_from_slice(&position_id.to_le_bytes());
-        expected_msg[8..40].copy_from_slice(&nullifier);
-        expected_msg[40..72].copy_from_slice(withdrawal_destination.as_ref());
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![callback_ix],
+            1,
+            0,
+        )?;
 
-        require!(
-            signed_message == expected_msg,
-            ShadowVestError::InvalidEligibilitySignature
-        );
+        Ok(())
+    }
 
-        // 9. Verify compressed position exists via Light Protocol CPI
-        //    We pass the same data as output (no state change here).
-        LightSystemProgramCpi::new_cpi(crate::LIGHT_CPI_SIGNER, proof)
-            .with_light_account(compressed_position)?
-            .invoke(cpi_accounts)?;
+    #[arcium_callback(encrypted_ix = "init_position")]
+    pub fn init_position_callback(
+        ctx: Context<InitPositionCallback>,
+        output: SignedComputationOutputs<InitPositionOutput>,
+    ) -> Result<()> {
+        let verified = output
+            .verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account)
+            .map_err(|_| ErrorCode::AbortedComputation)?;
+        mpc_output::require_ciphertexts(&verified.field_0.ciphertexts, 2)?;
 
-        // 10. Initialize ClaimAuthorization
-        let clock = Clock::get()?;
-        let claim_auth = &mut ctx.accounts.claim_authorization;
-        claim_auth.position = Pubkey::new_from_array(address);
-        claim_auth.nullifier = nullifier;
-        claim_auth.withdrawal_destination = withdrawal_destination;
-        claim_auth.claim_amount = 0;
-        claim_auth.is_authorized = true;
-        claim_auth.is_processed = false;
-        claim_auth.is_withdrawn = false;
-        claim_auth.authorized_at = clock.unix_timestamp;
-        claim_auth.bump = ctx.bumps.claim_authorization;
-
-        // 11. Initialize NullifierRecord
-        let nullifier_record = &mut ctx.accounts.nullifier_record;
-        nullifier_record.nullifier = nullifier;
-        nullifier_record.position = Pubkey::new_from_array(address);
-        nullifier_record.used_at = clock.unix_timestamp;
-        nullifier_record.bump = ctx.bumps.nullifier_record;
+        let position = &mut ctx.accounts.position;
+        position.transition_to(PositionState::Initialized)?;
+        position.encrypted_total_amount = verified.field_0.ciphertexts[0];
+        position.encrypted_claimed_amount = verified.field_0.ciphertexts[1];
+        position.nonce = verified.field_0.nonce;
 
-        emit!(ClaimAuthorized {
-            position: Pubkey::new_from_array(address),
-            nullifier,
-            withdrawal_destination,
+        emit!(VestingPositionInitialized {
+            position: position.key(),
+            position_id: position.position_id,
         });
 
         Ok(())
     }
 
-    /// Queue MPC computation for a compressed position claim.
-    /// Computes vesting_numerator on-chain from Clock + schedule parameters.
-    pub fn queue_process_claim_compressed(
-        ctx: Context<QueueProcessClaimCompressed>,
+    /// Close a fully claimed position and return its rent to the org admin.
+    ///
+    /// organization.position_count is the position_id seed and is never decremented;
+    /// closures are tracked in organization.closed_position_count instead.
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        require!(
+            ctx.accounts.position.state == PositionState::FullyClaimed,
+            ShadowVestError::PositionNotFullyClaimed
+        );
+
+        // Whatever reserve the withdrawals left over is no longer earmarked
+        let token_mint = ctx.accounts.schedule.token_mint;
+        ctx.accounts
+            .organization
+            .release_position_reserve(&mut ctx.accounts.position, &token_mint, u64::MAX);
+        let position = &ctx.accounts.position;
+
+        let schedule = &mut ctx.accounts.schedule;
+        schedule.position_count = schedule
+            .position_count
+            .checked_sub(1)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+
+        let organization = &mut ctx.accounts.organization;
+        organization.closed_position_count = organization
+            .closed_position_count
+            .checked_add(1)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+
+        emit!(PositionClosed {
+            position: position.key(),
+            position_id: position.position_id,
+        });
+
+        Ok(())
+    }
+
+    /// Top up a position's encrypted total amount.
+    ///
+    /// The top_up_position circuit reads the position's stored amounts with its
+    /// stored key and nonce; only the new total (encrypted for `pubkey`/`nonce`)
+    /// comes from the caller. The new total must not be below the current total.
+    /// Both amounts are re-encrypted together for the position's key. If the check
+    /// fails the callback leaves the position untouched and emits
+    /// PositionTopUpRejected. As with reencrypt_position, the position may not have
+    /// an unsettled claim, whose callback would overwrite the re-encrypted amounts.
+    pub fn top_up_position(
+        ctx: Context<TopUpPosition>,
         computation_offset: u64,
-        position_id: u64,
-        encrypted_total_amount: [u8; 32],
-        encrypted_claimed_amount: [u8; 32],
-        encrypted_vesting_numerator: [u8; 32],
-        encrypted_claim_amount: [u8; 32],
-        claim_amount: u64,
-        start_timestamp: i64,
+        encrypted_new_total_amount: [u8; 32],
         pubkey: [u8; 32],
         nonce: u128,
     ) -> Result<()> {
-        require!(ctx.accounts.claim_authorization.is_authorized, ShadowVestError::ClaimNotAuthorized);
-        require!(!ctx.accounts.claim_authorization.is_processed, ShadowVestError::ClaimNotProcessed);
-
-        // Capture position key before mutable borrow
-        let claim_position = ctx.accounts.claim_authorization.position;
-
-        let schedule = &ctx.accounts.schedule;
+        let position = &ctx.accounts.position;
 
-        // Compute vesting_numerator on-chain
-        let clock = Clock::get()?;
-        let current_time = clock.unix_timestamp;
-        let cliff_end = start_timestamp + schedule.cliff_duration as i64;
-        let vesting_end = start_timestamp + schedule.total_duration as i64;
-
-        const PRECISION: u64 = 1_000_000;
-        let vesting_numerator = if current_time < cliff_end {
-            0u64
-        } else if current_time >= vesting_end {
-            PRECISION
-        } else {
-            let elapsed = (current_time - cliff_end) as u64;
-            let intervals = elapsed / schedule.vesting_interval;
-            let vested_seconds = intervals * schedule.vesting_interval;
-            let vesting_duration = schedule.total_duration - schedule.cliff_duration;
-            if vesting_duration > 0 {
-                vested_seconds * PRECISION / vesting_duration
-            } else {
-                PRECISION
-            }
-        };
+        require!(position.state.is_vesting(), ShadowVestError::PositionNotActive);
+        position.check_no_open_claims()?;
 
         ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
         let args = ArgBuilder::new()
+            // Stored amounts under the position's key and nonce
+            .x25519_pubkey(position.encryption_pubkey)
+            .plaintext_u128(position.nonce)
+            .encrypted_u64(position.encrypted_total_amount)
+            .encrypted_u64(position.encrypted_claimed_amount)
+            // New total under the caller's key and nonce
             .x25519_pubkey(pubkey)
             .plaintext_u128(nonce)
-            .encrypted_u64(encrypted_total_amount)
-            .encrypted_u64(encrypted_claimed_amount)
-            .encrypted_u64(encrypted_vesting_numerator)
-            .encrypted_u64(encrypted_claim_amount)
+            .encrypted_u64(encrypted_new_total_amount)
             .build();
 
         let position_callback_account = CallbackAccount {
             pubkey: ctx.accounts.position.key(),
             is_writable: true,
         };
-        let claim_auth_callback_account = CallbackAccount {
-            pubkey: ctx.accounts.claim_authorization.key(),
-            is_writable: true,
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![TopUpPositionCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[position_callback_account],
+            )?],
+            1,
+            0,
+        )?;
+
+        emit!(PositionTopUpQueued {
+            position: ctx.accounts.position.key(),
+            position_id: ctx.accounts.position.position_id,
+            computation_offset,
+        });
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "top_up_position")]
+    pub fn top_up_position_callback(
+        ctx: Context<TopUpPositionCallback>,
+        output: SignedComputationOutputs<TopUpPositionOutput>,
+    ) -> Result<()> {
+        let verified = output
+            .verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account)
+            .map_err(|_| ErrorCode::AbortedComputation)?;
+
+        let amounts = &verified.field_0.field_0;
+        let is_valid = verified.field_0.field_1;
+        mpc_output::require_ciphertexts(&amounts.ciphertexts, 2)?;
+
+        let position = &mut ctx.accounts.position;
+
+        if !is_valid {
+            // New total would fall below the current total - keep existing ciphertexts
+            emit!(PositionTopUpRejected {
+                position: position.key(),
+                position_id: position.position_id,
+            });
+            return Ok(());
+        }
+
+        position.encrypted_total_amount = amounts.ciphertexts[0];
+        position.encrypted_claimed_amount = amounts.ciphertexts[1];
+        position.nonce = amounts.nonce;
+
+        emit!(PositionToppedUp {
+            position: position.key(),
+            position_id: position.position_id,
+            encrypted_total_amount: position.encrypted_total_amount,
+        });
+
+        Ok(())
+    }
+
+    /// Compare the encrypted totals of two positions for a fairness audit.
+    ///
+    /// Each position's stored total is passed under its own encryption key and
+    /// nonce, so the comparison is over the amounts actually on-chain. The equality
+    /// result is re-encrypted for the auditor's x25519 key and emitted in
+    /// TotalsCompared; neither amount is revealed.
+    pub fn compare_totals(
+        ctx: Context<CompareTotals>,
+        computation_offset: u64,
+        auditor_pubkey: [u8; 32],
+        auditor_nonce: u128,
+    ) -> Result<()> {
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let position_a = &ctx.accounts.position_a;
+        let position_b = &ctx.accounts.position_b;
+        let args = ArgBuilder::new()
+            .x25519_pubkey(position_a.encryption_pubkey)
+            .plaintext_u128(position_a.nonce)
+            .encrypted_u64(position_a.encrypted_total_amount)
+            .x25519_pubkey(position_b.encryption_pubkey)
+            .plaintext_u128(position_b.nonce)
+            .encrypted_u64(position_b.encrypted_total_amount)
+            // Shared - auditor's x25519 pubkey and nonce
+            .x25519_pubkey(auditor_pubkey)
+            .plaintext_u128(auditor_nonce)
+            .build();
+
+        let position_a_callback_account = CallbackAccount {
+            pubkey: ctx.accounts.position_a.key(),
+            is_writable: false,
+        };
+        let position_b_callback_account = CallbackAccount {
+            pubkey: ctx.accounts.position_b.key(),
+            is_writable: false,
         };
 
-        let callback_ix = ProcessClaimV2Callback::callback_ix(
+        let callback_ix = CompareTotalsCallback::callback_ix(
             computation_offset,
             &ctx.accounts.mxe_account,
-            &[position_callback_account, claim_auth_callback_account],
+            &[position_a_callback_account, position_b_callback_account],
         )?;
 
         queue_computation(
@@ -1404,326 +1856,283 @@ pub mod contract {
             0,
         )?;
 
-        let claim_auth_mut = &mut ctx.accounts.claim_authorization;
-        claim_auth_mut.claim_amount = claim_amount;
-
-        emit!(ClaimProcessQueued {
-            position: claim_position,
-            position_id,
-            claim_amount,
+        emit!(TotalsComparisonQueued {
+            position_a: ctx.accounts.position_a.key(),
+            position_b: ctx.accounts.position_b.key(),
+            auditor_pubkey,
             computation_offset,
-            vesting_numerator,
         });
 
         Ok(())
     }
 
-    /// Update the encrypted_claimed_amount on a compressed vesting position.
-    ///
-    /// Called after process_claim_v2_compressed_callback() confirms the claim is valid.
-    /// This updates the Light Protocol Merkle tree with the new claimed amount.
-    ///
-    /// Can only be called when the associated ClaimAuthorization is_processed=true
-    /// and is_withdrawn=false (prevents unauthorized updates).
-    pub fn update_compressed_position_claimed<'info>(
-        ctx: Context<'_, '_, '_, 'info, UpdateCompressedPositionClaimed<'info>>,
-        proof_bytes: Vec<u8>,
-        account_meta_bytes: Vec<u8>,
-        // Current compressed position data:
-        position_owner: Pubkey,
-        position_organization: Pubkey,
-        position_schedule: Pubkey,
-        position_id: u64,
-        beneficiary_commitment: [u8; 32],
-        encrypted_total_amount: [u8; 32],
-        encrypted_claimed_amount: [u8; 32],
-        position_nonce: u128,
-        position_start_timestamp: i64,
-        position_is_active: u8,
-        position_is_fully_claimed: u8,
-        // New values:
-        new_encrypted_claimed_amount: [u8; 32],
-        new_is_fully_claimed: u8,
+    #[arcium_callback(encrypted_ix = "compare_totals")]
+    pub fn compare_totals_callback(
+        ctx: Context<CompareTotalsCallback>,
+        output: SignedComputationOutputs<CompareTotalsOutput>,
     ) -> Result<()> {
-        let claim_auth = &ctx.accounts.claim_authorization;
-        require!(claim_auth.is_authorized, ShadowVestError::ClaimNotAuthorized);
-        require!(claim_auth.is_processed, ShadowVestError::ClaimNotProcessed);
-        require!(!claim_auth.is_withdrawn, ShadowVestError::AlreadyWithdrawn);
+        let verified = output
+            .verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account)
+            .map_err(|_| ErrorCode::AbortedComputation)?;
+        mpc_output::require_ciphertexts(&verified.field_0.ciphertexts, 1)?;
 
-        // Verify position belongs to organization
+        // Encrypted for the auditor - only they can decrypt the equality bit
+        emit!(TotalsCompared {
+            position_a: ctx.accounts.position_a.key(),
+            position_b: ctx.accounts.position_b.key(),
+            encrypted_is_equal: verified.field_0.ciphertexts[0],
+            nonce: verified.field_0.nonce.to_le_bytes(),
+        });
+
+        Ok(())
+    }
+
+    /// Prove that the vault covers the outstanding obligations of the organization's
+    /// open positions without revealing any per-position amount.
+    ///
+    /// The positions are passed in remaining_accounts and must be exactly the
+    /// organization's open positions (at most MAX_SOLVENCY_POSITIONS, and no
+    /// compressed positions), each listed once. Every position's stored totals and
+    /// claimed amounts are passed under its own encryption key and nonce; deactivated
+    /// positions and unused circuit slots are not counted. The check_solvency circuit
+    /// compares the summed obligations against the vault's public balance and only
+    /// the solvency bit is revealed in SolvencyChecked.
+    pub fn check_solvency(ctx: Context<CheckSolvency>, computation_offset: u64) -> Result<()> {
+        let position_count = ctx.remaining_accounts.len();
         require!(
-            position_organization == ctx.accounts.organization.key(),
-            ShadowVestError::InvalidPositionOrganization
+            position_count > 0 && position_count <= VestingPosition::MAX_SOLVENCY_POSITIONS,
+            ShadowVestError::InvalidBatchSize
+        );
+        // A partial set would let obligations be left out of the sum; compressed
+        // positions cannot be passed as accounts, so they rule the check out
+        let organization = &ctx.accounts.organization;
+        require!(
+            position_count as u64 == organization.open_position_count()
+                && organization.compressed_position_count == 0,
+            ShadowVestError::IncompletePositionSet
         );
 
-        // Deserialize Light Protocol types
-        let proof: ValidityProof = borsh::BorshDeserialize::try_from_slice(&proof_bytes)
-            .map_err(|_| ShadowVestError::LightProtocolCpiFailed)?;
-        let account_meta: CompressedAccountMeta =
-            borsh::BorshDeserialize::try_from_slice(&account_meta_bytes)
-                .map_err(|_| ShadowVestError::LightProtocolCpiFailed)?;
+        let organization_key = ctx.accounts.organization.key();
+        let mut positions: Vec<VestingPosition> = Vec::with_capacity(position_count);
+        for (i, account) in ctx.remaining_accounts.iter().enumerate() {
+            require!(
+                !ctx.remaining_accounts[..i].iter().any(|a| a.key == account.key),
+                ShadowVestError::DuplicatePosition
+            );
+            require!(
+                account.owner == &crate::ID,
+                ShadowVestError::InvalidPositionOrganization
+            );
+            let data = account.try_borrow_data()?;
+            let position = VestingPosition::try_deserialize(&mut &data[..])?;
+            require!(
+                position.organization == organization_key,
+                ShadowVestError::InvalidPositionOrganization
+            );
+            // Created positions have no MPC-initialized claimed amount yet
+            require!(
+                position.state != PositionState::Created,
+                ShadowVestError::InvalidPositionStateTransition
+            );
+            positions.push(position);
+        }
 
-        let cpi_accounts = CpiAccounts::new(
-            ctx.accounts.fee_payer.as_ref(),
-            ctx.remaining_accounts,
-            crate::LIGHT_CPI_SIGNER,
-        );
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
-        // Get the address from account_meta
-        let address = account_meta.address;
+        let vault_balance = ctx.accounts.vault.amount;
+
+        let mut builder = ArgBuilder::new();
+        for slot in 0..VestingPosition::MAX_SOLVENCY_POSITIONS {
+            // Unused slots replay the first position's ciphertexts and are not counted
+            let position = positions.get(slot).unwrap_or(&positions[0]);
+            let counted =
+                slot < position_count && position.state != PositionState::Deactivated;
+            builder = builder
+                .x25519_pubkey(position.encryption_pubkey)
+                .plaintext_u128(position.nonce)
+                .encrypted_u64(position.encrypted_total_amount)
+                .encrypted_u64(position.encrypted_claimed_amount)
+                .plaintext_bool(counted);
+        }
+        // Public vault balance read on-chain, not supplied by the caller
+        let args = builder.plaintext_u64(vault_balance).build();
 
-        // Load existing compressed position for mutation
-        let mut compressed_position = LightAccount::<CompressedVestingPosition>::new_mut(
-            &crate::ID,
-            &account_meta,
-            CompressedVestingPosition {
-                owner: position_owner,
-                organization: position_organization,
-                schedule: position_schedule,
-                position_id,
-                beneficiary_commitment,
-                encrypted_total_amount,
-                encrypted_claimed_amount,
-                nonce: position_nonce,
-                start_timestamp: position_start_timestamp,
-                is_active: position_is_active,
-                is_fully_claimed: position_is_fully_claimed,
-            },
-        ).map_err(|_| ShadowVestError::LightProtocolCpiFailed)?;
+        let organization_callback_account = CallbackAccount {
+            pubkey: organization_key,
+            is_writable: false,
+        };
+        let vault_callback_account = CallbackAccount {
+            pubkey: ctx.accounts.vault.key(),
+            is_writable: false,
+        };
 
-        // Update the claimed amount and fully_claimed flag
-        compressed_position.encrypted_claimed_amount = new_encrypted_claimed_amount;
-        compressed_position.is_fully_claimed = new_is_fully_claimed;
+        let callback_ix = CheckSolvencyCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[organization_callback_account, vault_callback_account],
+        )?;
 
-        // Execute Light Protocol CPI to commit the state transition
-        LightSystemProgramCpi::new_cpi(crate::LIGHT_CPI_SIGNER, proof)
-            .with_light_account(compressed_position)?
-            .invoke(cpi_accounts)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![callback_ix],
+            1,
+            0,
+        )?;
 
-        emit!(CompressedPositionClaimUpdated {
+        emit!(SolvencyCheckQueued {
+            organization: organization_key,
+            vault: ctx.accounts.vault.key(),
+            vault_balance,
+            position_count: position_count as u8,
+            computation_offset,
+        });
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "check_solvency")]
+    pub fn check_solvency_callback(
+        ctx: Context<CheckSolvencyCallback>,
+        output: SignedComputationOutputs<CheckSolvencyOutput>,
+    ) -> Result<()> {
+        let verified = output
+            .verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account)
+            .map_err(|_| ErrorCode::AbortedComputation)?;
+
+        emit!(SolvencyChecked {
             organization: ctx.accounts.organization.key(),
-            position_id,
-            address,
-            new_is_fully_claimed: new_is_fully_claimed == 1,
+            vault: ctx.accounts.vault.key(),
+            is_solvent: verified.field_0,
         });
 
         Ok(())
     }
 
-    /// Withdraw tokens from the organization vault for a compressed position claim.
+    /// Merge two positions of the same beneficiary and schedule into a new position.
     ///
-    /// Similar to withdraw() but uses ClaimAuthorization derived from compressed position seeds
-    /// (organization + position_id + nullifier) instead of regular position account key.
-    pub fn withdraw_compressed(
-        ctx: Context<WithdrawCompressed>,
-        _position_id: u64,
-        _nullifier: [u8; 32],
+    /// Both the admin and the beneficiary (Ed25519 commitment) must sign. Neither
+    /// position may have an unsettled claim, so their stored claimed amounts are
+    /// final and no claim callback can write to them mid-merge. Both must carry the
+    /// same metadata_commitment, which the merged position inherits. The originals
+    /// are frozen while the merge_positions circuit sums their stored totals and
+    /// claimed amounts (each under its own key and nonce) and re-encrypts the result
+    /// for `pubkey`; they are deactivated once the merged position is initialized,
+    /// or unfrozen if the summed total would overflow a u64. The merged position
+    /// starts at the later of the two start timestamps so no tokens vest earlier
+    /// than originally scheduled. To consolidate more than two positions, merge
+    /// them pairwise.
+    pub fn merge_positions(
+        ctx: Context<MergePositions>,
+        computation_offset: u64,
+        pubkey: [u8; 32],
+        nonce: u128,
     ) -> Result<()> {
-        let claim_auth = &ctx.accounts.claim_authorization;
-        require!(claim_auth.is_authorized, ShadowVestError::ClaimNotAuthorized);
-        require!(claim_auth.is_processed, ShadowVestError::ClaimNotProcessed);
-        require!(!claim_auth.is_withdrawn, ShadowVestError::AlreadyWithdrawn);
+        require!(ctx.accounts.organization.is_active, ShadowVestError::OrganizationNotActive);
+        // The merged position is a regular account
         require!(
-            ctx.accounts.destination.key() == claim_auth.withdrawal_destination,
-            ShadowVestError::InvalidWithdrawalDestination
+            !ctx.accounts.organization.compressed_only,
+            ShadowVestError::CompressedStorageRequired
         );
 
-        let amount = claim_auth.claim_amount;
+        let beneficiary = ctx.accounts.beneficiary.key().to_bytes();
+        for position in [&ctx.accounts.position_a, &ctx.accounts.position_b] {
+            require!(position.state.is_vesting(), ShadowVestError::PositionNotActive);
+            require!(
+                position.sig_scheme == VestingPosition::SIG_SCHEME_ED25519
+                    && position.beneficiary_commitment == beneficiary,
+                ShadowVestError::SignerMismatch
+            );
+            position.check_no_open_claims()?;
+        }
+        let metadata_commitment = ctx.accounts.position_a.metadata_commitment;
         require!(
-            ctx.accounts.vault.amount >= amount,
-            ShadowVestError::InsufficientVaultBalance
+            ctx.accounts.position_b.metadata_commitment == metadata_commitment,
+            ShadowVestError::MetadataCommitmentMismatch
         );
 
+        ctx.accounts.position_a.transition_to(PositionState::Frozen)?;
+        ctx.accounts.position_b.transition_to(PositionState::Frozen)?;
+
+        let position_id = ctx.accounts.organization.position_count;
         let org_key = ctx.accounts.organization.key();
-        let bump = ctx.bumps.vault_authority;
-        let vault_authority_seeds: &[&[u8]] = &[
-            b"vault_authority",
-            org_key.as_ref(),
-            std::slice::from_ref(&bump),
-        ];
-        let signer_seeds = &[vault_authority_seeds];
+        let schedule_key = ctx.accounts.schedule.key();
+        let start_timestamp = ctx
+            .accounts
+            .position_a
+            .start_timestamp
+            .max(ctx.accounts.position_b.start_timestamp);
 
-        let transfer_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.vault.to_account_info(),
-                to: ctx.accounts.destination.to_account_info(),
-                authority: ctx.accounts.vault_authority.to_account_info(),
-            },
-            signer_seeds,
-        );
-        token::transfer(transfer_ctx, amount)?;
-
-        let claim_auth_mut = &mut ctx.accounts.claim_authorization;
-        claim_auth_mut.is_withdrawn = true;
-
-        emit!(ClaimWithdrawn {
-            position: claim_auth_mut.position,
-            destination: claim_auth_mut.withdrawal_destination,
-            amount,
-            token_mint: ctx.accounts.vault.mint,
-        });
-
-        Ok(())
-    }
-
-    // ============================================================
-    // Stealth Address Management
-    // ============================================================
-
-    /// Register stealth meta-address (S, V) for an employee.
-    /// Employees call this to publish their public stealth keys.
-    /// Employers fetch these to derive one-time stealth addresses for payments.
-    pub fn register_stealth_meta(
-        ctx: Context<RegisterStealthMeta>,
-        spend_pubkey: [u8; 32],
-        view_pubkey: [u8; 32],
-    ) -> Result<()> {
-        let meta = &mut ctx.accounts.stealth_meta;
-        let clock = Clock::get()?;
-
-        meta.owner = ctx.accounts.owner.key();
-        meta.spend_pubkey = spend_pubkey;
-        meta.view_pubkey = view_pubkey;
-        meta.is_active = true;
-        meta.registered_at = clock.unix_timestamp;
-        meta.bump = ctx.bumps.stealth_meta;
-
-        emit!(StealthMetaRegistered {
-            owner: meta.owner,
-            spend_pubkey,
-            view_pubkey,
-            registered_at: meta.registered_at,
-        });
-
-        Ok(())
-    }
-
-    /// Update stealth meta-address keys.
-    /// Allows employee to rotate their stealth keys.
-    pub fn update_stealth_meta(
-        ctx: Context<UpdateStealthMeta>,
-        spend_pubkey: [u8; 32],
-        view_pubkey: [u8; 32],
-    ) -> Result<()> {
-        let meta = &mut ctx.accounts.stealth_meta;
-
-        require!(meta.is_active, ShadowVestError::StealthMetaNotActive);
-
-        meta.spend_pubkey = spend_pubkey;
-        meta.view_pubkey = view_pubkey;
-
-        emit!(StealthMetaUpdated {
-            owner: meta.owner,
-            spend_pubkey,
-            view_pubkey,
-        });
-
-        Ok(())
-    }
-
-    /// Deactivate stealth meta-address.
-    /// Employee can deactivate to stop receiving stealth payments.
-    pub fn deactivate_stealth_meta(ctx: Context<DeactivateStealthMeta>) -> Result<()> {
-        let meta = &mut ctx.accounts.stealth_meta;
-
-        require!(meta.is_active, ShadowVestError::StealthMetaNotActive);
-
-        meta.is_active = false;
-
-        emit!(StealthMetaDeactivated { owner: meta.owner });
-
-        Ok(())
-    }
-
-    // ============================================================
-    // MPC Meta-Keys Vault (Optional Secure Storage)
-    // ============================================================
-
-    /// Initialize MPC computation definition for store_meta_keys
-    pub fn init_store_meta_keys_comp_def(ctx: Context<InitStoreMetaKeysCompDef>) -> Result<()> {
-        init_comp_def(
-            ctx.accounts,
-            Some(CircuitSource::OffChain(OffChainCircuitSource {
-                source: "https://wajsatfcmlfkijmawyuq.supabase.co/storage/v1/object/public/init_position/store_meta_keys.arcis".to_string(),
-                hash: circuit_hash!("store_meta_keys"),
-            })),
-            None,
-        )?;
-        Ok(())
-    }
-
-    /// Initialize MPC computation definition for fetch_meta_keys
-    pub fn init_fetch_meta_keys_comp_def(ctx: Context<InitFetchMetaKeysCompDef>) -> Result<()> {
-        init_comp_def(
-            ctx.accounts,
-            Some(CircuitSource::OffChain(OffChainCircuitSource {
-                source: "https://wajsatfcmlfkijmawyuq.supabase.co/storage/v1/object/public/init_position/fetch_meta_keys.arcis".to_string(),
-                hash: circuit_hash!("fetch_meta_keys"),
-            })),
-            None,
-        )?;
-        Ok(())
-    }
-
-    /// Store meta-keys in MPC vault.
-    /// Employee's private keys are encrypted via Arcium MPC.
-    /// Only the MPC cluster can decrypt; re-encrypts specifically for user on read.
-    pub fn write_meta_keys_to_vault(
-        ctx: Context<WriteMetaKeysToVault>,
-        computation_offset: u64,
-        encrypted_spend_lo: [u8; 32],
-        encrypted_spend_hi: [u8; 32],
-        encrypted_view_lo: [u8; 32],
-        encrypted_view_hi: [u8; 32],
-        pubkey: [u8; 32],
-        nonce: u128,
-        mxe_nonce: u128,
-    ) -> Result<()> {
-        // Capture keys first before mutable borrow
-        let owner_key = ctx.accounts.owner.key();
-        let vault_key = ctx.accounts.meta_keys_vault.key();
-
-        // Initialize vault
         {
-            let vault = &mut ctx.accounts.meta_keys_vault;
-            vault.owner = owner_key;
-            vault.ciphertexts = [
-                encrypted_spend_lo,
-                encrypted_spend_hi,
-                encrypted_view_lo,
-                encrypted_view_hi,
-            ];
-            vault.nonce = nonce;
-            vault.is_initialized = false; // Will be set true in callback
-            vault.bump = ctx.bumps.meta_keys_vault;
+            let merged = &mut ctx.accounts.merged_position;
+            merged.organization = org_key;
+            merged.schedule = schedule_key;
+            merged.position_id = position_id;
+            merged.beneficiary_commitment = beneficiary;
+            merged.encrypted_total_amount = [0u8; 32];
+            merged.encrypted_claimed_amount = [0u8; 32];
+            merged.nonce = nonce;
+            merged.encryption_pubkey = pubkey;
+            merged.start_timestamp = start_timestamp;
+            merged.state = PositionState::Created;
+            merged.sig_scheme = VestingPosition::SIG_SCHEME_ED25519;
+            merged.revoked_at = 0;
+            merged.is_stealth = ctx.accounts.position_a.is_stealth;
+            merged.last_claim_at = 0;
+            merged.metadata_commitment = metadata_commitment;
+            merged.privacy_mode = true;
+            merged.plaintext_total_amount = None;
+            merged.is_paused = false;
+            merged.paused_at = 0;
+            merged.total_paused_seconds = 0;
+            merged.reserved_amount = 0;
+            merged.merkle_claim_count = 0;
+            merged.open_claim_count = 0;
+            merged.is_reserve_set = false;
+            merged.pause_freezes_vesting = false;
+            merged.bump = ctx.bumps.merged_position;
         }
 
         ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
-        // Queue MPC computation to re-encrypt with MXE key
-        // Circuit: write_meta_keys(user_input: Enc<Shared, MetaKeys>, mxe: Mxe) -> Enc<Mxe, MetaKeys>
+        let position_a = &ctx.accounts.position_a;
+        let position_b = &ctx.accounts.position_b;
         let args = ArgBuilder::new()
-            // Enc<Shared, MetaKeys> - user's encrypted keys
+            .x25519_pubkey(position_a.encryption_pubkey)
+            .plaintext_u128(position_a.nonce)
+            .encrypted_u64(position_a.encrypted_total_amount)
+            .encrypted_u64(position_a.encrypted_claimed_amount)
+            .x25519_pubkey(position_b.encryption_pubkey)
+            .plaintext_u128(position_b.nonce)
+            .encrypted_u64(position_b.encrypted_total_amount)
+            .encrypted_u64(position_b.encrypted_claimed_amount)
+            // Shared - x25519 pubkey and nonce of the merged position
             .x25519_pubkey(pubkey)
             .plaintext_u128(nonce)
-            .encrypted_u128(encrypted_spend_lo)
-            .encrypted_u128(encrypted_spend_hi)
-            .encrypted_u128(encrypted_view_lo)
-            .encrypted_u128(encrypted_view_hi)
-            // Mxe parameter - nonce for MXE re-encryption
-            .plaintext_u128(mxe_nonce)
             .build();
 
-        let vault_callback_account = CallbackAccount {
-            pubkey: vault_key,
+        let position_a_callback_account = CallbackAccount {
+            pubkey: ctx.accounts.position_a.key(),
+            is_writable: true,
+        };
+        let position_b_callback_account = CallbackAccount {
+            pubkey: ctx.accounts.position_b.key(),
+            is_writable: true,
+        };
+        let merged_callback_account = CallbackAccount {
+            pubkey: ctx.accounts.merged_position.key(),
             is_writable: true,
         };
 
-        let callback_ix = StoreMetaKeysCallback::callback_ix(
+        let callback_ix = MergePositionsCallback::callback_ix(
             computation_offset,
             &ctx.accounts.mxe_account,
-            &[vault_callback_account],
+            &[
+                position_a_callback_account,
+                position_b_callback_account,
+                merged_callback_account,
+            ],
         )?;
 
         queue_computation(
@@ -1735,380 +2144,6250 @@ pub mod contract {
             0,
         )?;
 
-        emit!(MetaKeysVaultCreated {
-            owner: owner_key,
-            vault: vault_key,
+        ctx.accounts.organization.position_count = ctx.accounts.organization
+            .position_count
+            .checked_add(1)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+
+        ctx.accounts.schedule.position_count = ctx.accounts.schedule
+            .position_count
+            .checked_add(1)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+
+        let merged_key = ctx.accounts.merged_position.key();
+        ctx.accounts
+            .position_index
+            .record(org_key, ctx.bumps.position_index, merged_key);
+
+        emit!(PositionMergeQueued {
+            organization: org_key,
+            merged_position: merged_key,
+            merged_position_id: position_id,
+            position_a: ctx.accounts.position_a.key(),
+            position_b: ctx.accounts.position_b.key(),
+            computation_offset,
         });
 
         Ok(())
     }
 
-    #[arcium_callback(encrypted_ix = "store_meta_keys")]
-    pub fn store_meta_keys_callback(
-        ctx: Context<StoreMetaKeysCallback>,
-        output: SignedComputationOutputs<StoreMetaKeysOutput>,
+    #[arcium_callback(encrypted_ix = "merge_positions")]
+    pub fn merge_positions_callback(
+        ctx: Context<MergePositionsCallback>,
+        output: SignedComputationOutputs<MergePositionsOutput>,
     ) -> Result<()> {
         let verified = output
             .verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account)
             .map_err(|_| ErrorCode::AbortedComputation)?;
+        let amounts = &verified.field_0.field_0;
+        let is_valid = verified.field_0.field_1;
+        mpc_output::require_ciphertexts(&amounts.ciphertexts, 2)?;
+
+        if !is_valid {
+            // Summed total would overflow - retire the merged position and let the
+            // originals vest again
+            ctx.accounts.merged_position.transition_to(PositionState::Deactivated)?;
+            ctx.accounts.position_a.transition_to(PositionState::Active)?;
+            ctx.accounts.position_b.transition_to(PositionState::Active)?;
+
+            emit!(PositionMergeRejected {
+                merged_position: ctx.accounts.merged_position.key(),
+                merged_position_id: ctx.accounts.merged_position.position_id,
+                position_a: ctx.accounts.position_a.key(),
+                position_b: ctx.accounts.position_b.key(),
+            });
+            return Ok(());
+        }
 
-        let vault = &mut ctx.accounts.meta_keys_vault;
-
-        // Store MXE-encrypted ciphertexts and nonce
-        vault.ciphertexts[0] = verified.field_0.ciphertexts[0];
-        vault.ciphertexts[1] = verified.field_0.ciphertexts[1];
-        vault.ciphertexts[2] = verified.field_0.ciphertexts[2];
-        vault.ciphertexts[3] = verified.field_0.ciphertexts[3];
-        vault.nonce = verified.field_0.nonce;
-        vault.is_initialized = true;
-
-        emit!(MetaKeysVaultInitialized {
-            owner: vault.owner,
-            vault: vault.key(),
+        let merged = &mut ctx.accounts.merged_position;
+        merged.transition_to(PositionState::Initialized)?;
+        merged.encrypted_total_amount = amounts.ciphertexts[0];
+        merged.encrypted_claimed_amount = amounts.ciphertexts[1];
+        merged.nonce = amounts.nonce;
+        // The originals' reserves carry over within the schedule's vault, so its sum is unchanged
+        merged.reserved_amount = ctx
+            .accounts
+            .position_a
+            .reserved_amount
+            .checked_add(ctx.accounts.position_b.reserved_amount)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+        merged.is_reserve_set =
+            ctx.accounts.position_a.is_reserve_set || ctx.accounts.position_b.is_reserve_set;
+
+        let position_a = &mut ctx.accounts.position_a;
+        position_a.transition_to(PositionState::Deactivated)?;
+        position_a.reserved_amount = 0;
+        let position_b = &mut ctx.accounts.position_b;
+        position_b.transition_to(PositionState::Deactivated)?;
+        position_b.reserved_amount = 0;
+
+        emit!(PositionsMerged {
+            merged_position: ctx.accounts.merged_position.key(),
+            merged_position_id: ctx.accounts.merged_position.position_id,
+            position_a: ctx.accounts.position_a.key(),
+            position_b: ctx.accounts.position_b.key(),
+            encrypted_total_amount: ctx.accounts.merged_position.encrypted_total_amount,
+            encrypted_claimed_amount: ctx.accounts.merged_position.encrypted_claimed_amount,
+            nonce: amounts.nonce.to_le_bytes(),
         });
 
         Ok(())
     }
 
-    /// Read meta-keys from MPC vault.
-    /// MPC re-encrypts stored keys specifically for the requesting user.
-    pub fn read_meta_keys_from_vault(
-        ctx: Context<ReadMetaKeysFromVault>,
+    /// Re-encrypt a position's stored amounts under a new shared key.
+    ///
+    /// After an MXE cluster key rotation the stored ciphertexts can no longer be
+    /// decrypted. The reencrypt_position circuit reads them with the position's
+    /// stored key and nonce and re-wraps them for `pubkey`; the callback overwrites
+    /// the amounts, nonce and encryption_pubkey. The position may not have an
+    /// unsettled claim, as its callback would write amounts under the old key.
+    pub fn reencrypt_position(
+        ctx: Context<ReencryptPosition>,
         computation_offset: u64,
         pubkey: [u8; 32],
         nonce: u128,
     ) -> Result<()> {
-        let vault = &ctx.accounts.meta_keys_vault;
-
+        let position = &ctx.accounts.position;
+        // Created positions have no MPC-initialized amounts yet
         require!(
-            vault.is_initialized,
-            ShadowVestError::MetaKeysVaultNotInitialized
+            position.state != PositionState::Created,
+            ShadowVestError::InvalidPositionStateTransition
         );
+        position.check_no_open_claims()?;
 
         ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
-        // Queue MPC computation to re-encrypt for user
-        // Circuit: read_meta_keys(requester: Shared, stored_keys: Enc<Mxe, MetaKeys>) -> Enc<Shared, MetaKeys>
-        let vault_key = vault.key();
         let args = ArgBuilder::new()
-            // Shared - requester's x25519 pubkey and nonce
+            .x25519_pubkey(position.encryption_pubkey)
+            .plaintext_u128(position.nonce)
+            .encrypted_u64(position.encrypted_total_amount)
+            .encrypted_u64(position.encrypted_claimed_amount)
+            // Shared - new x25519 pubkey and nonce
             .x25519_pubkey(pubkey)
             .plaintext_u128(nonce)
-            // Enc<Mxe, MetaKeys> - nonce + account data
-            // Account layout: 8 (discriminator) + 32 (owner) + 128 (ciphertexts)
-            .plaintext_u128(vault.nonce)
-            .account(vault_key, 40, 128) // Skip discriminator + owner, read 4 x 32 bytes ciphertexts
             .build();
 
-        let vault_callback_account = CallbackAccount {
-            pubkey: vault.key(),
-            is_writable: false,
+        let position_callback_account = CallbackAccount {
+            pubkey: position.key(),
+            is_writable: true,
         };
 
+        let callback_ix = ReencryptPositionCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[position_callback_account],
+        )?;
+
         queue_computation(
             ctx.accounts,
             computation_offset,
             args,
-            vec![FetchMetaKeysCallback::callback_ix(
-                computation_offset,
-                &ctx.accounts.mxe_account,
-                &[vault_callback_account],
-            )?],
+            vec![callback_ix],
             1,
             0,
         )?;
 
-        emit!(MetaKeysReadRequested {
-            owner: vault.owner,
-            vault: vault.key(),
+        emit!(PositionReencryptionQueued {
+            position: ctx.accounts.position.key(),
+            position_id: ctx.accounts.position.position_id,
+            new_pubkey: pubkey,
             computation_offset,
         });
 
         Ok(())
     }
 
-    #[arcium_callback(encrypted_ix = "fetch_meta_keys")]
-    pub fn fetch_meta_keys_callback(
-        ctx: Context<FetchMetaKeysCallback>,
-        output: SignedComputationOutputs<FetchMetaKeysOutput>,
+    #[arcium_callback(encrypted_ix = "reencrypt_position")]
+    pub fn reencrypt_position_callback(
+        ctx: Context<ReencryptPositionCallback>,
+        output: SignedComputationOutputs<ReencryptPositionOutput>,
     ) -> Result<()> {
         let verified = output
             .verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account)
             .map_err(|_| ErrorCode::AbortedComputation)?;
+        mpc_output::require_ciphertexts(&verified.field_0.ciphertexts, 2)?;
 
-        let vault = &ctx.accounts.meta_keys_vault;
+        let position = &mut ctx.accounts.position;
+        position.encrypted_total_amount = verified.field_0.ciphertexts[0];
+        position.encrypted_claimed_amount = verified.field_0.ciphertexts[1];
+        position.nonce = verified.field_0.nonce;
+        position.encryption_pubkey = verified.field_0.encryption_key;
 
-        // Emit event with re-encrypted keys for the user to decrypt
-        emit!(MetaKeysRetrieved {
-            owner: vault.owner,
-            vault: vault.key(),
-            encrypted_spend_lo: verified.field_0.ciphertexts[0],
-            encrypted_spend_hi: verified.field_0.ciphertexts[1],
-            encrypted_view_lo: verified.field_0.ciphertexts[2],
-            encrypted_view_hi: verified.field_0.ciphertexts[3],
-            nonce: verified.field_0.nonce.to_le_bytes(),
+        emit!(PositionReencrypted {
+            position: position.key(),
+            position_id: position.position_id,
+            encrypted_total_amount: position.encrypted_total_amount,
+            encrypted_claimed_amount: position.encrypted_claimed_amount,
+            nonce: position.nonce.to_le_bytes(),
         });
 
         Ok(())
     }
 
-    // ============================================================
-    // Groth16 ZK Proof Verification (Noir Circuits)
-    // ============================================================
-
-    /// Store a verification key on-chain for a specific Noir circuit.
+    /// Revoke a position (e.g. for a terminated employee).
     ///
-    /// Only the designated authority (DAO admin) can store VKs.
-    /// The VK is derived from the circuit's trusted setup and contains
-    /// the parameters needed for Groth16 proof verification.
-    ///
-    /// Each circuit type (withdrawal_proof, identity_proof, eligibility)
-    /// has a unique circuit_id derived as sha256(circuit_name).
-    ///
-    /// # Arguments
-    /// * `circuit_id` - 32-byte identifier for the circuit
-    /// * `vk_data` - Serialized VerificationKey bytes
-    pub fn store_verification_key(
-        ctx: Context<StoreVerificationKey>,
-        circuit_id: [u8; 32],
-        vk_data: Vec<u8>,
-    ) -> Result<()> {
-        require!(
-            vk_data.len() <= VerificationKeyAccount::MAX_VK_DATA_SIZE,
-            ShadowVestError::InvalidVerificationKeyData
-        );
+    /// Vesting stops at revoked_at: the amount vested before revocation stays
+    /// claimable, anything vesting afterwards is forfeited.
+    pub fn revoke_position(ctx: Context<RevokePosition>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
 
-        // Validate the VK data can be deserialized
-        let _vk: VerificationKey = AnchorDeserialize::try_from_slice(&vk_data)
-            .map_err(|_| ShadowVestError::InvalidVerificationKeyData)?;
+        require!(!position.is_revoked(), ShadowVestError::PositionAlreadyRevoked);
+        position.transition_to(PositionState::Revoked)?;
 
-        let vk_account = &mut ctx.accounts.vk_account;
-        vk_account.authority = ctx.accounts.authority.key();
-        vk_account.circuit_id = circuit_id;
-        vk_account.vk_data = vk_data;
-        vk_account.is_active = true;
-        vk_account.bump = ctx.bumps.vk_account;
+        let clock = Clock::get()?;
+        position.revoked_at = clock.unix_timestamp;
 
-        emit!(VerificationKeyStored {
-            authority: vk_account.authority,
-            circuit_id,
-            vk_account: vk_account.key(),
+        emit!(VestingPositionRevoked {
+            organization: position.organization,
+            position: position.key(),
+            position_id: position.position_id,
+            revoked_at: position.revoked_at,
         });
 
         Ok(())
     }
 
-    /// Update a verification key (e.g., after a new trusted setup).
+    /// Re-point a position to a new beneficiary key (e.g. after a stealth key rotation).
     ///
-    /// Only the original authority can update. This allows key rotation
-    /// without changing the circuit_id PDA.
-    pub fn update_verification_key(
-        ctx: Context<UpdateVerificationKey>,
-        vk_data: Vec<u8>,
+    /// The caller must prepend a signature verification instruction by the current
+    /// beneficiary over VestingPosition::rotation_message (a hash of "rotate",
+    /// position_id and the old and new commitments), checked like authorize_claim.
+    /// Unvested and unclaimed funds stay with the position. Claims already
+    /// authorized keep their withdrawal destination.
+    pub fn rotate_position_beneficiary(
+        ctx: Context<RotatePositionBeneficiary>,
+        new_commitment: [u8; 32],
+    ) -> Result<()> {
+        let position = &ctx.accounts.position;
+        let expected_msg = position.rotation_message(&new_commitment);
+        verify_beneficiary_signature(&ctx.accounts.instructions_sysvar, position, &expected_msg)?;
+
+        let position = &mut ctx.accounts.position;
+        let old_commitment = position.beneficiary_commitment;
+        position.rotate_beneficiary(new_commitment)?;
+
+        emit!(PositionBeneficiaryRotated {
+            position: position.key(),
+            position_id: position.position_id,
+            old_commitment,
+            new_commitment,
+        });
+
+        Ok(())
+    }
+
+    // ============================================================
+    // Vesting Calculations (with MPC)
+    // ============================================================
+
+    pub fn calculate_vested_amount(
+        ctx: Context<CalculateVestedAmount>,
+        computation_offset: u64,
+        encrypted_total_amount: [u8; 32],
+        encrypted_claimed_amount: [u8; 32],
+        encrypted_vesting_numerator: [u8; 32],
+        pubkey: [u8; 32],
+        nonce: u128,
     ) -> Result<()> {
+        let position = &ctx.accounts.position;
+
         require!(
-            vk_data.len() <= VerificationKeyAccount::MAX_VK_DATA_SIZE,
-            ShadowVestError::InvalidVerificationKeyData
+            position.state.is_vesting()
+                || position.is_pending_lazy_init(ctx.accounts.organization.lazy_init),
+            ShadowVestError::PositionNotActive
         );
 
-        // Validate the VK data can be deserialized
-        let _vk: VerificationKey = AnchorDeserialize::try_from_slice(&vk_data)
-            .map_err(|_| ShadowVestError::InvalidVerificationKeyData)?;
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
-        let vk_account = &mut ctx.accounts.vk_account;
-        vk_account.vk_data = vk_data;
+        // Created on the first calculation; the callback overwrites it each time
+        let snapshot = &mut ctx.accounts.vested_snapshot;
+        snapshot.position = position.key();
+        snapshot.bump = ctx.bumps.vested_snapshot;
 
-        emit!(VerificationKeyUpdated {
-            circuit_id: vk_account.circuit_id,
-            vk_account: vk_account.key(),
+        // All values must be encrypted with the same key/nonce for MPC;
+        // the schedule's precision follows as a plaintext argument
+        let args = ArgBuilder::new()
+            .x25519_pubkey(pubkey)
+            .plaintext_u128(nonce)
+            .encrypted_u64(encrypted_total_amount)
+            .encrypted_u64(encrypted_claimed_amount)
+            .encrypted_u64(encrypted_vesting_numerator)
+            .plaintext_u64(ctx.accounts.schedule.precision)
+            .build();
+
+        let position_callback_account = CallbackAccount {
+            pubkey: ctx.accounts.position.key(),
+            is_writable: false,
+        };
+        let snapshot_callback_account = CallbackAccount {
+            pubkey: ctx.accounts.vested_snapshot.key(),
+            is_writable: true,
+        };
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![CalculateVestedCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[position_callback_account, snapshot_callback_account],
+            )?],
+            1,
+            0,
+        )?;
+
+        emit!(VestedAmountCalculationQueued {
+            position: position.key(),
+            position_id: position.position_id,
+            computation_offset,
         });
 
         Ok(())
     }
 
-    /// Verify a withdrawal proof on-chain.
-    ///
-    /// Performs Groth16 verification using the stored VK for the withdrawal circuit.
-    /// On success, creates a ProofRecord PDA that other instructions can reference.
-    ///
-    /// The withdrawal proof demonstrates:
-    /// - Knowledge of a valid vesting position in the state tree
-    /// - The position is in the correct epoch
-    /// - The nullifier has not been used before
-    /// - The prover is entitled to the withdrawal
-    ///
-    /// Requires ~1,400,000 compute units (pairing is expensive).
-    pub fn verify_withdrawal_proof(
-        ctx: Context<VerifyWithdrawalProof>,
-        proof: Groth16Proof,
-        public_inputs: WithdrawalPublicInputs,
+    #[arcium_callback(encrypted_ix = "calculate_vested")]
+    pub fn calculate_vested_callback(
+        ctx: Context<CalculateVestedCallback>,
+        output: SignedComputationOutputs<CalculateVestedOutput>,
     ) -> Result<()> {
-        let vk_account = &ctx.accounts.vk_account;
-        require!(vk_account.is_active, ShadowVestError::VerificationKeyNotActive);
-
-        // Deserialize the verification key
-        let vk: VerificationKey = AnchorDeserialize::try_from_slice(&vk_account.vk_data)
-            .map_err(|_| ShadowVestError::InvalidVerificationKeyData)?;
+        let verified = output
+            .verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account)
+            .map_err(|_| ErrorCode::AbortedComputation)?;
+        mpc_output::require_ciphertexts(&verified.field_0.ciphertexts, 2)?;
 
-        // Convert public inputs to scalars
-        let scalars = public_inputs.to_scalars();
+        let position = &ctx.accounts.position;
 
-        // Perform Groth16 verification
-        let is_valid = groth16_verifier::verify_groth16(&vk, &proof, &scalars)?;
-        require!(is_valid, ShadowVestError::ProofVerificationFailed);
+        // Persist the latest result so clients need not replay logs
+        let snapshot = &mut ctx.accounts.vested_snapshot;
+        snapshot.encrypted_vested_amount = verified.field_0.ciphertexts[0];
+        snapshot.encrypted_claimable_amount = verified.field_0.ciphertexts[1];
+        snapshot.nonce = verified.field_0.nonce;
+        snapshot.calculated_at = Clock::get()?.unix_timestamp;
 
-        // Create proof record
-        let clock = Clock::get()?;
-        let proof_record = &mut ctx.accounts.proof_record;
-        proof_record.verifier = ctx.accounts.verifier.key();
-        proof_record.circuit_id = vk_account.circuit_id;
-        proof_record.nullifier = public_inputs.nullifier;
-        proof_record.verified_at = clock.unix_timestamp;
-        proof_record.is_valid = true;
-        proof_record.bump = ctx.bumps.proof_record;
-
-        emit!(ProofVerified {
-            verifier: proof_record.verifier,
-            circuit_id: proof_record.circuit_id,
-            nullifier: proof_record.nullifier,
-            proof_type: ProofType::Withdrawal,
-            verified_at: proof_record.verified_at,
+        emit!(VestedAmountCalculated {
+            position: position.key(),
+            position_id: position.position_id,
+            encrypted_vested_amount: verified.field_0.ciphertexts[0],
+            encrypted_claimable_amount: verified.field_0.ciphertexts[1],
+            nonce: verified.field_0.nonce.to_le_bytes(),
         });
 
         Ok(())
     }
 
-    /// Verify an identity proof on-chain.
+    // ============================================================
+    // Claim Authorization & Withdrawal
+    // ============================================================
+
+    /// Authorize a claim using the beneficiary's signature.
     ///
-    /// The identity proof demonstrates knowledge of the secret behind
-    /// a position commitment, without revealing the secret itself.
-    /// This is used to prove ownership of a vesting position.
+    /// The caller must prepend a signature verification instruction over
+    /// the message: hash(position_id, nullifier, withdrawal_destination).
+    /// - Ed25519 positions: an Ed25519Program instruction signed by the
+    ///   stealth address (beneficiary_commitment)
+    /// - secp256k1 positions: a Secp256k1Program instruction signed by the
+    ///   Ethereum address stored in beneficiary_commitment[..20]
     ///
-    /// Creates a ProofRecord keyed by position_commitment as the nullifier.
-    pub fn verify_identity_proof(
-        ctx: Context<VerifyIdentityProof>,
-        proof: Groth16Proof,
-        public_inputs: IdentityPublicInputs,
+    /// This creates a ClaimAuthorization PDA and a NullifierRecord PDA.
+    /// The NullifierRecord uses init constraint for double-claim prevention.
+    /// If the claim is not processed within `claim_expiry_seconds` (0 = never),
+    /// anyone can call expire_claim_authorization to release the nullifier.
+    pub fn authorize_claim(
+        ctx: Context<AuthorizeClaim>,
+        nullifier: [u8; 32],
+        withdrawal_destination: Pubkey,
+        claim_expiry_seconds: u64,
     ) -> Result<()> {
-        let vk_account = &ctx.accounts.vk_account;
-        require!(vk_account.is_active, ShadowVestError::VerificationKeyNotActive);
+        let position = &ctx.accounts.position;
 
-        // Deserialize the verification key
-        let vk: VerificationKey = AnchorDeserialize::try_from_slice(&vk_account.vk_data)
-            .map_err(|_| ShadowVestError::InvalidVerificationKeyData)?;
+        require!(
+            position.accepts_claims()
+                || position.is_pending_lazy_init(ctx.accounts.organization.lazy_init),
+            ShadowVestError::PositionNotActive
+        );
 
-        // Convert public inputs to scalars
-        let scalars = public_inputs.to_scalars();
+        ctx.accounts.organization.check_destination_allowlist(
+            &ctx.accounts.organization.key(),
+            ctx.accounts.allowed_destination.as_deref(),
+            &withdrawal_destination,
+        )?;
 
-        // Perform Groth16 verification
-        let is_valid = groth16_verifier::verify_groth16(&vk, &proof, &scalars)?;
-        require!(is_valid, ShadowVestError::ProofVerificationFailed);
+        // Construct expected message: position_id || nullifier || withdrawal_destination (72 bytes)
+        let mut expected_msg = [0u8; 72];
+        expected_msg[..8].copy_from_slice(&position.position_id.to_le_bytes());
+        expected_msg[8..40].copy_from_slice(&nullifier);
+        expected_msg[40..72].copy_from_slice(withdrawal_destination.as_ref());
 
-        // Create proof record (use position_commitment as nullifier for identity proofs)
+        verify_beneficiary_signature(&ctx.accounts.instructions_sysvar, position, &expected_msg)?;
+
+        // Initialize ClaimAuthorization
         let clock = Clock::get()?;
-        let proof_record = &mut ctx.accounts.proof_record;
-        proof_record.verifier = ctx.accounts.verifier.key();
-        proof_record.circuit_id = vk_account.circuit_id;
-        proof_record.nullifier = public_inputs.position_commitment;
-        proof_record.verified_at = clock.unix_timestamp;
-        proof_record.is_valid = true;
-        proof_record.bump = ctx.bumps.proof_record;
-
-        emit!(ProofVerified {
-            verifier: proof_record.verifier,
-            circuit_id: proof_record.circuit_id,
-            nullifier: proof_record.nullifier,
-            proof_type: ProofType::Identity,
-            verified_at: proof_record.verified_at,
+        ctx.accounts.claim_authorization.set_inner(ClaimAuthorization::init(
+            position.key(),
+            nullifier,
+            withdrawal_destination,
+            clock.unix_timestamp,
+            claim_expiry_seconds,
+            ctx.accounts.payer.key(),
+            position.stealth_address(),
+            ctx.bumps.claim_authorization,
+        ));
+
+        // Initialize NullifierRecord (init constraint prevents double-use)
+        ctx.accounts.nullifier_record.set_inner(NullifierRecord::init(
+            nullifier,
+            position.key(),
+            clock.unix_timestamp,
+            ctx.accounts.organization.nullifier_namespace,
+            ctx.bumps.nullifier_record,
+        ));
+
+        emit!(ClaimAuthorized {
+            organization: position.organization,
+            position: position.key(),
+            position_id: position.position_id,
+            nullifier,
+            withdrawal_destination,
         });
 
+        ctx.accounts.position.open_claim()?;
+
         Ok(())
     }
 
-    /// Verify an eligibility proof on-chain.
-    ///
-    /// The eligibility proof demonstrates:
-    /// - The prover is the designated beneficiary of a position
-    /// - The nullifier has not been used (prevents double-claim)
-    /// - The prover knows the opening to the position commitment
+    /// Authorize a claim using a verified eligibility proof instead of a signature.
     ///
-    /// This is the primary proof used in the claim-withdraw flow,
-    /// replacing or augmenting the Ed25519 signature verification.
-    pub fn verify_eligibility_proof(
-        ctx: Context<VerifyEligibilityProof>,
-        proof: Groth16Proof,
-        public_inputs: EligibilityPublicInputs,
+    /// The payer must have verified an eligibility proof for `nullifier` with
+    /// verify_eligibility_proof; the resulting ProofRecord
+    /// [b"proof_record", payer, nullifier] replaces the Ed25519/secp256k1
+    /// instruction of authorize_claim. The record must still be valid, unexpired,
+    /// attested by the eligibility circuit, and proven for this position's id and
+    /// beneficiary_commitment with `withdrawal_destination` as the destination
+    /// input, so a record cannot be redirected. Creates the same ClaimAuthorization
+    /// and NullifierRecord as authorize_claim (without an expiry).
+    pub fn authorize_claim_with_proof(
+        ctx: Context<AuthorizeClaimWithProof>,
+        nullifier: [u8; 32],
+        withdrawal_destination: Pubkey,
     ) -> Result<()> {
-        let vk_account = &ctx.accounts.vk_account;
-        require!(vk_account.is_active, ShadowVestError::VerificationKeyNotActive);
+        let position = &ctx.accounts.position;
+        let proof_record = &ctx.accounts.proof_record;
+        let clock = Clock::get()?;
 
-        // Deserialize the verification key
-        let vk: VerificationKey = AnchorDeserialize::try_from_slice(&vk_account.vk_data)
-            .map_err(|_| ShadowVestError::InvalidVerificationKeyData)?;
+        require!(
+            position.accepts_claims()
+                || position.is_pending_lazy_init(ctx.accounts.organization.lazy_init),
+            ShadowVestError::PositionNotActive
+        );
 
-        // Convert public inputs to scalars
-        let scalars = public_inputs.to_scalars();
+        proof_record.check_usable(clock.unix_timestamp)?;
+        require!(
+            proof_record.circuit_id
+                == VerificationKeyAccount::circuit_id_for(VerificationKeyAccount::ELIGIBILITY_CIRCUIT_NAME),
+            ShadowVestError::CircuitIdMismatch
+        );
+        require!(
+            proof_record.nullifier == nullifier,
+            ShadowVestError::NullifierMismatch
+        );
+        proof_record.check_eligibility_binding(
+            position.position_id,
+            &position.beneficiary_commitment,
+            &withdrawal_destination,
+        )?;
 
-        // Perform Groth16 verification
-        let is_valid = groth16_verifier::verify_groth16(&vk, &proof, &scalars)?;
-        require!(is_valid, ShadowVestError::ProofVerificationFailed);
+        // Initialize ClaimAuthorization
+        ctx.accounts.claim_authorization.set_inner(ClaimAuthorization::init(
+            position.key(),
+            nullifier,
+            withdrawal_destination,
+            clock.unix_timestamp,
+            0,
+            ctx.accounts.payer.key(),
+            position.stealth_address(),
+            ctx.bumps.claim_authorization,
+        ));
 
-        // Create proof record
-        let clock = Clock::get()?;
-        let proof_record = &mut ctx.accounts.proof_record;
-        proof_record.verifier = ctx.accounts.verifier.key();
-        proof_record.circuit_id = vk_account.circuit_id;
-        proof_record.nullifier = public_inputs.nullifier;
-        proof_record.verified_at = clock.unix_timestamp;
-        proof_record.is_valid = true;
-        proof_record.bump = ctx.bumps.proof_record;
-
-        emit!(ProofVerified {
-            verifier: proof_record.verifier,
-            circuit_id: proof_record.circuit_id,
-            nullifier: proof_record.nullifier,
-            proof_type: ProofType::Eligibility,
-            verified_at: proof_record.verified_at,
+        // Initialize NullifierRecord (init constraint prevents double-use)
+        ctx.accounts.nullifier_record.set_inner(NullifierRecord::init(
+            nullifier,
+            position.key(),
+            clock.unix_timestamp,
+            ctx.accounts.organization.nullifier_namespace,
+            ctx.bumps.nullifier_record,
+        ));
+
+        emit!(ClaimAuthorized {
+            organization: position.organization,
+            position: position.key(),
+            position_id: position.position_id,
+            nullifier,
+            withdrawal_destination,
         });
 
+        ctx.accounts.position.open_claim()?;
+
         Ok(())
     }
-}
 
-// ============================================================
-// Account Contexts - Position Creation
-// ============================================================
+    /// Authorize a claim for a beneficiary listed in the schedule's Merkle root.
+    ///
+    /// The signer must be the position's beneficiary (beneficiary_commitment), and
+    /// `proof` must lead from merkle::beneficiary_leaf(position_id, beneficiary,
+    /// withdrawal_destination) to schedule.beneficiary_root. The claim's nullifier
+    /// is merkle::claim_nullifier(leaf, merkle_claim_count), so every claim through
+    /// the list spends a fresh nullifier. Creates the same ClaimAuthorization and
+    /// NullifierRecord as authorize_claim (without an expiry).
+    pub fn claim_with_merkle_proof(
+        ctx: Context<ClaimWithMerkleProof>,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        let schedule = &ctx.accounts.schedule;
+        let beneficiary = ctx.accounts.beneficiary.key();
+        let withdrawal_destination = ctx.accounts.withdrawal_destination.key();
+        let clock = Clock::get()?;
 
-#[queue_computation_accounts("init_position", payer)]
-#[derive(Accounts)]
-#[instruction(computation_offset: u64, beneficiary_commitment: [u8; 32])]
-pub struct CreateVestingPosition<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(mut)]
-    pub admin: Signer<'info>,
-    #[account(
-        mut,
-        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
-        bump = organization.bump,
+        require!(
+            position.accepts_claims()
+                || position.is_pending_lazy_init(ctx.accounts.organization.lazy_init),
+            ShadowVestError::PositionNotActive
+        );
+        require!(
+            schedule.beneficiary_root != [0u8; 32],
+            ShadowVestError::MerkleRootNotSet
+        );
+        let leaf =
+            merkle::beneficiary_leaf(position.position_id, &beneficiary, &withdrawal_destination);
+        merkle::verify_proof(&schedule.beneficiary_root, &leaf, &proof)?;
+
+        let nullifier = merkle::claim_nullifier(&leaf, position.merkle_claim_count);
+        position.merkle_claim_count = position
+            .merkle_claim_count
+            .checked_add(1)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+
+        // Initialize ClaimAuthorization
+        ctx.accounts.claim_authorization.set_inner(ClaimAuthorization::init(
+            position.key(),
+            nullifier,
+            withdrawal_destination,
+            clock.unix_timestamp,
+            0,
+            beneficiary,
+            position.stealth_address(),
+            ctx.bumps.claim_authorization,
+        ));
+
+        // Initialize NullifierRecord (init constraint prevents double-use)
+        ctx.accounts.nullifier_record.set_inner(NullifierRecord::init(
+            nullifier,
+            position.key(),
+            clock.unix_timestamp,
+            ctx.accounts.organization.nullifier_namespace,
+            ctx.bumps.nullifier_record,
+        ));
+
+        emit!(ClaimAuthorized {
+            organization: position.organization,
+            position: position.key(),
+            position_id: position.position_id,
+            nullifier,
+            withdrawal_destination,
+        });
+
+        position.open_claim()?;
+
+        Ok(())
+    }
+
+    /// Move an authorized, not yet withdrawn claim to a new destination.
+    ///
+    /// The beneficiary signs position_id || nullifier || current_destination ||
+    /// new_destination (104 bytes) with the same scheme as authorize_claim. Binding
+    /// the current destination keeps the authorize_claim signature and earlier
+    /// updates from being replayed. The nullifier stays spent; only the
+    /// destination changes. Regular positions only.
+    pub fn update_claim_destination(
+        ctx: Context<UpdateClaimDestination>,
+        new_destination: Pubkey,
+    ) -> Result<()> {
+        let position = &ctx.accounts.position;
+        let claim_auth = &ctx.accounts.claim_authorization;
+
+        require!(claim_auth.is_authorized, ShadowVestError::ClaimNotAuthorized);
+        require!(!claim_auth.is_withdrawn, ShadowVestError::AlreadyWithdrawn);
+
+        let old_destination = claim_auth.withdrawal_destination;
+
+        let mut expected_msg = [0u8; 104];
+        expected_msg[..8].copy_from_slice(&position.position_id.to_le_bytes());
+        expected_msg[8..40].copy_from_slice(&claim_auth.nullifier);
+        expected_msg[40..72].copy_from_slice(old_destination.as_ref());
+        expected_msg[72..104].copy_from_slice(new_destination.as_ref());
+
+        verify_beneficiary_signature(&ctx.accounts.instructions_sysvar, position, &expected_msg)?;
+
+        let claim_auth = &mut ctx.accounts.claim_authorization;
+        claim_auth.withdrawal_destination = new_destination;
+
+        emit!(ClaimDestinationUpdated {
+            position: position.key(),
+            nullifier: claim_auth.nullifier,
+            old_destination,
+            new_destination,
+        });
+
+        Ok(())
+    }
+
+    /// Queue the process_claim_v2 MPC computation with integrated vesting calculation.
+    ///
+    /// Computes vesting_numerator on-chain from Clock + schedule parameters.
+    /// Submits the position's stored (total_amount, claimed_amount) under its own key
+    /// and nonce, with the numerator, claim_amount and schedule precision in plaintext.
+    /// The MPC circuit internally computes: claimable = (total * numerator / precision) - claimed,
+    /// then validates: claim_amount <= claimable.
+    /// Callback re-stores both amounts and the new nonce and sets is_processed=true.
+    pub fn queue_process_claim(
+        ctx: Context<QueueProcessClaim>,
+        computation_offset: u64,
+        claim_amount: u64,
+        priority_fee: u64,
+    ) -> Result<()> {
+        let position = &ctx.accounts.position;
+        let schedule = &ctx.accounts.schedule;
+
+        // Compute vesting_numerator on-chain from verifiable data
+        let clock = Clock::get()?;
+        let vesting_numerator = check_queueable_claim(
+            position,
+            schedule,
+            &ctx.accounts.claim_authorization,
+            ctx.accounts.pending_computation.is_pending,
+            clock.unix_timestamp,
+        )?;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let args = ArgBuilder::new()
+            // Stored amounts under the position's key and nonce
+            .x25519_pubkey(position.encryption_pubkey)
+            .plaintext_u128(position.nonce)
+            .encrypted_u64(position.encrypted_total_amount)
+            .encrypted_u64(position.encrypted_claimed_amount)
+            .plaintext_u64(vesting_numerator)
+            .plaintext_u64(claim_amount)
+            .plaintext_u64(schedule.precision)
+            .build();
+
+        let position_callback_account = CallbackAccount {
+            pubkey: ctx.accounts.position.key(),
+            is_writable: true,
+        };
+        let claim_auth_callback_account = CallbackAccount {
+            pubkey: ctx.accounts.claim_authorization.key(),
+            is_writable: true,
+        };
+        let pending_callback_account = CallbackAccount {
+            pubkey: ctx.accounts.pending_computation.key(),
+            is_writable: true,
+        };
+        let organization_callback_account = CallbackAccount {
+            pubkey: ctx.accounts.organization.key(),
+            is_writable: true,
+        };
+
+        let callback_ix = ProcessClaimV2Callback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                position_callback_account,
+                claim_auth_callback_account,
+                pending_callback_account,
+                organization_callback_account,
+            ],
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![callback_ix],
+            1,
+            0,
+        )?;
+
+        // Optional lamport tip to the org's incentive account, signalling priority
+        // to MPC operators (who read it from ClaimProcessQueued)
+        if priority_fee > 0 {
+            let organization = &ctx.accounts.organization;
+            let priority_fee_account = ctx
+                .accounts
+                .priority_fee_account
+                .as_ref()
+                .ok_or(ShadowVestError::InvalidPriorityFeeAccount)?;
+            require!(
+                organization.priority_fee_account != Pubkey::default()
+                    && priority_fee_account.key() == organization.priority_fee_account,
+                ShadowVestError::InvalidPriorityFeeAccount
+            );
+
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: priority_fee_account.to_account_info(),
+                    },
+                ),
+                priority_fee,
+            )?;
+        }
+
+        // Store claim_amount in the authorization for the callback to verify
+        let claim_auth_mut = &mut ctx.accounts.claim_authorization;
+        claim_auth_mut.claim_amount = claim_amount;
+
+        // Reserve the claim against the vault so queued claims cannot over-commit it
+        ctx.accounts
+            .organization
+            .reserve_claim(claim_auth_mut, &ctx.accounts.vault.mint, ctx.accounts.vault.amount)?;
+
+        // Track the in-flight computation so it can be expired if the callback never lands
+        let claim_auth_key = claim_auth_mut.key();
+        ctx.accounts.pending_computation.start(
+            claim_auth_key,
+            ctx.accounts.computation_account.key(),
+            clock.unix_timestamp,
+            ctx.bumps.pending_computation,
+        );
+
+        emit!(ClaimProcessQueued {
+            position: position.key(),
+            position_id: position.position_id,
+            claim_amount,
+            computation_offset,
+            vesting_numerator,
+            priority_fee,
+        });
+
+        Ok(())
+    }
+
+    /// Callback from the process_claim_v2 MPC computation.
+    ///
+    /// Verifies the MPC output and, if the circuit reported the claim valid, updates:
+    /// - position.encrypted_total_amount, encrypted_claimed_amount and nonce from the
+    ///   output, which is encrypted for the position's key
+    /// - claim_authorization.is_processed = true
+    ///
+    /// An invalid claim (more than the claimable amount) leaves the claimed amount
+    /// untouched, revokes the authorization (is_authorized = false) and emits ClaimRejected.
+    #[arcium_callback(encrypted_ix = "process_claim_v2")]
+    pub fn process_claim_v2_callback(
+        ctx: Context<ProcessClaimV2Callback>,
+        output: SignedComputationOutputs<ProcessClaimV2Output>,
+    ) -> Result<()> {
+        let verified = output
+            .verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account)
+            .map_err(|_| ErrorCode::AbortedComputation)?;
+
+        let amounts = &verified.field_0.field_0;
+        let is_valid = verified.field_0.field_1;
+        let fully_claimed = verified.field_0.field_2;
+        mpc_output::require_ciphertexts(&amounts.ciphertexts, 2)?;
+
+        // Only the latest queued computation may apply; an expired one is ignored
+        let pending = &mut ctx.accounts.pending_computation;
+        require!(
+            pending.is_pending && pending.computation_account == ctx.accounts.computation_account.key(),
+            ShadowVestError::StaleComputation
+        );
+        pending.is_pending = false;
+
+        let position_key = ctx.accounts.position.key();
+        apply_claim_result(
+            position_key,
+            &mut ctx.accounts.position,
+            &mut ctx.accounts.claim_authorization,
+            &mut ctx.accounts.organization,
+            &amounts.ciphertexts,
+            amounts.nonce,
+            is_valid,
+            fully_claimed,
+        )
+    }
+
+    /// Queue one process_claims_batch computation covering up to MAX_BATCH_CLAIMS
+    /// authorized claims on positions of one schedule.
+    ///
+    /// A beneficiary with many small positions otherwise pays one MPC round-trip
+    /// per claim. The batch circuit validates every slot exactly like process_claim_v2,
+    /// so its MPC cost grows linearly with the fixed slot count while the queue and
+    /// callback overhead is paid once; a batch of one costs about four single claims.
+    /// The callback rewrites every claim's accounts, so the batch is capped to keep
+    /// it within one transaction's compute and account limits.
+    ///
+    /// Each claim is passed in remaining_accounts as a writable (position,
+    /// claim_authorization, pending_computation) triple, in the order of
+    /// `claim_amounts`. Every claim passes the same checks as queue_process_claim,
+    /// is reserved against the schedule's vault and gets its PendingComputation
+    /// (created if needed), so a lost batch is expired claim by claim. The circuit
+    /// reads each position's stored amounts with the vesting numerator computed here.
+    pub fn queue_process_claims_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, QueueProcessClaimsBatch<'info>>,
+        computation_offset: u64,
+        claim_amounts: Vec<u64>,
+    ) -> Result<()> {
+        require!(ctx.accounts.organization.is_active, ShadowVestError::OrganizationNotActive);
+
+        let claim_count = claim_amounts.len();
+        require!(
+            claim_count > 0
+                && claim_count <= VestingPosition::MAX_BATCH_CLAIMS
+                && ctx.remaining_accounts.len() == 3 * claim_count,
+            ShadowVestError::InvalidBatchSize
+        );
+
+        let organization_key = ctx.accounts.organization.key();
+        let schedule_key = ctx.accounts.schedule.key();
+        let computation_key = ctx.accounts.computation_account.key();
+        let now = Clock::get()?.unix_timestamp;
+
+        let mut positions = Vec::with_capacity(claim_count);
+        let mut slots = Vec::with_capacity(claim_count);
+        let mut callback_accounts = vec![CallbackAccount {
+            pubkey: organization_key,
+            is_writable: true,
+        }];
+        for (accounts, &claim_amount) in ctx.remaining_accounts.chunks(3).zip(claim_amounts.iter()) {
+            let [position_info, claim_auth_info, pending_info] = accounts else {
+                return err!(ShadowVestError::InvalidBatchSize);
+            };
+            require!(
+                !positions.contains(position_info.key),
+                ShadowVestError::InvalidBatchSize
+            );
+
+            let position: VestingPosition = load_program_account(position_info)?;
+            require!(
+                position.organization == organization_key,
+                ShadowVestError::InvalidPositionOrganization
+            );
+            require!(position.schedule == schedule_key, ShadowVestError::InvalidScheduleParams);
+
+            let mut claim_auth: ClaimAuthorization = load_program_account(claim_auth_info)?;
+            require!(
+                claim_auth.position == *position_info.key && claim_auth.is_regular(claim_auth_info.key),
+                ShadowVestError::InvalidPositionOrganization
+            );
+
+            let (pending_key, pending_bump) = Pubkey::find_program_address(
+                &[PendingComputation::SEED_PREFIX, claim_auth_info.key.as_ref()],
+                &crate::ID,
+            );
+            require_keys_eq!(pending_key, *pending_info.key, ShadowVestError::StaleComputation);
+            let mut pending = if pending_info.data_is_empty() {
+                let signer_seeds: &[&[u8]] = &[
+                    PendingComputation::SEED_PREFIX,
+                    claim_auth_info.key.as_ref(),
+                    std::slice::from_ref(&pending_bump),
+                ];
+                anchor_lang::system_program::create_account(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.system_program.to_account_info(),
+                        anchor_lang::system_program::CreateAccount {
+                            from: ctx.accounts.payer.to_account_info(),
+                            to: pending_info.clone(),
+                        },
+                        &[signer_seeds],
+                    ),
+                    Rent::get()?.minimum_balance(PendingComputation::SIZE),
+                    PendingComputation::SIZE as u64,
+                    &crate::ID,
+                )?;
+                PendingComputation {
+                    claim_authorization: *claim_auth_info.key,
+                    computation_account: Pubkey::default(),
+                    queued_at: 0,
+                    is_pending: false,
+                    bump: pending_bump,
+                }
+            } else {
+                load_program_account(pending_info)?
+            };
+
+            let vesting_numerator = check_queueable_claim(
+                &position,
+                &ctx.accounts.schedule,
+                &claim_auth,
+                pending.is_pending,
+                now,
+            )?;
+
+            // Reserve the claim against the vault so queued claims cannot over-commit it
+            claim_auth.claim_amount = claim_amount;
+            ctx.accounts
+                .organization
+                .reserve_claim(&mut claim_auth, &ctx.accounts.vault.mint, ctx.accounts.vault.amount)?;
+            pending.start(*claim_auth_info.key, computation_key, now, pending_bump);
+            store_program_account(claim_auth_info, &claim_auth)?;
+            store_program_account(pending_info, &pending)?;
+
+            callback_accounts.extend(accounts.iter().map(|account| CallbackAccount {
+                pubkey: *account.key,
+                is_writable: true,
+            }));
+            positions.push(*position_info.key);
+            slots.push((position, vesting_numerator, claim_amount));
+        }
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        // Args order matches process_claims_batch: per slot the stored amounts under
+        // the position's key and nonce, its vesting numerator and claim amount. Unused
+        // slots replay the first position with nothing to claim and are discarded.
+        let mut builder = ArgBuilder::new();
+        for slot in 0..VestingPosition::MAX_BATCH_CLAIMS {
+            let (position, vesting_numerator, claim_amount) = match slots.get(slot) {
+                Some((position, vesting_numerator, claim_amount)) => {
+                    (position, *vesting_numerator, *claim_amount)
+                }
+                None => (&slots[0].0, 0, 0),
+            };
+            builder = builder
+                .x25519_pubkey(position.encryption_pubkey)
+                .plaintext_u128(position.nonce)
+                .encrypted_u64(position.encrypted_total_amount)
+                .encrypted_u64(position.encrypted_claimed_amount)
+                .plaintext_u64(vesting_numerator)
+                .plaintext_u64(claim_amount);
+        }
+        let args = builder.plaintext_u64(ctx.accounts.schedule.precision).build();
+
+        let callback_ix = ProcessClaimsBatchCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &callback_accounts,
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![callback_ix],
+            1,
+            0,
+        )?;
+
+        emit!(ClaimsBatchQueued {
+            organization: organization_key,
+            positions,
+            computation_offset,
+        });
+
+        Ok(())
+    }
+
+    /// Callback from the process_claims_batch MPC computation.
+    ///
+    /// Slot i is applied to the i-th claim triple in remaining_accounts exactly like
+    /// process_claim_v2_callback applies a single claim. A claim whose computation
+    /// was expired (and possibly re-queued) in the meantime is skipped; unused slots
+    /// are discarded.
+    #[arcium_callback(encrypted_ix = "process_claims_batch")]
+    pub fn process_claims_batch_callback<'info>(
+        ctx: Context<'_, '_, '_, 'info, ProcessClaimsBatchCallback<'info>>,
+        output: SignedComputationOutputs<ProcessClaimsBatchOutput>,
+    ) -> Result<()> {
+        let verified = output
+            .verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account)
+            .map_err(|_| ErrorCode::AbortedComputation)?;
+
+        let outputs = [
+            &verified.field_0.field_0,
+            &verified.field_0.field_1,
+            &verified.field_0.field_2,
+            &verified.field_0.field_3,
+        ];
+        // Bit i: slot i is valid; bit 4 + i: slot i fully claims its position
+        let status = verified.field_0.field_4;
+        let computation_key = ctx.accounts.computation_account.key();
+
+        let mut positions = Vec::with_capacity(outputs.len());
+        let mut is_valid = Vec::with_capacity(outputs.len());
+        for (slot, accounts) in ctx.remaining_accounts.chunks(3).enumerate().take(outputs.len()) {
+            let [position_info, claim_auth_info, pending_info] = accounts else {
+                return err!(ShadowVestError::InvalidBatchSize);
+            };
+
+            // Only the latest queued computation may apply; an expired one is ignored
+            let mut pending: PendingComputation = load_program_account(pending_info)?;
+            if !pending.is_pending || pending.computation_account != computation_key {
+                continue;
+            }
+            pending.is_pending = false;
+
+            let amounts = outputs[slot];
+            mpc_output::require_ciphertexts(&amounts.ciphertexts, 2)?;
+            let slot_valid = status & (1 << slot) != 0;
+            let mut position: VestingPosition = load_program_account(position_info)?;
+            let mut claim_auth: ClaimAuthorization = load_program_account(claim_auth_info)?;
+            apply_claim_result(
+                *position_info.key,
+                &mut position,
+                &mut claim_auth,
+                &mut ctx.accounts.organization,
+                &amounts.ciphertexts,
+                amounts.nonce,
+                slot_valid,
+                status & (1 << (slot + 4)) != 0,
+            )?;
+            store_program_account(position_info, &position)?;
+            store_program_account(claim_auth_info, &claim_auth)?;
+            store_program_account(pending_info, &pending)?;
+
+            positions.push(*position_info.key);
+            is_valid.push(slot_valid);
+        }
+
+        emit!(ClaimsBatchProcessed {
+            organization: ctx.accounts.organization.key(),
+            positions,
+            is_valid,
+        });
+
+        Ok(())
+    }
+
+    /// Initialize the organization's token vault for `token_mint`.
+    ///
+    /// Creates a token account owned by a vault_authority PDA, one per mint.
+    /// The organization admin can then deposit tokens to this vault.
+    pub fn initialize_vault(ctx: Context<InitializeVault>) -> Result<()> {
+        let organization = &ctx.accounts.organization;
+        require!(organization.is_active, ShadowVestError::OrganizationNotActive);
+
+        emit!(VaultInitialized {
+            organization: organization.key(),
+            vault: ctx.accounts.vault.key(),
+            vault_authority: ctx.accounts.vault_authority.key(),
+            token_mint: ctx.accounts.token_mint.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Deposit tokens into the organization vault.
+    /// The depositor transfers SPL tokens from their token account to the vault.
+    pub fn deposit_to_vault(ctx: Context<DepositToVault>, amount: u64) -> Result<()> {
+        require!(ctx.accounts.organization.is_active, ShadowVestError::OrganizationNotActive);
+        require!(amount > 0, ShadowVestError::InvalidClaimAmount);
+
+        // Transfer tokens from admin's token account to vault
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.admin_token_account.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.admin.to_account_info(),
+            },
+        );
+        token::transfer(transfer_ctx, amount)?;
+        ctx.accounts.vault.reload()?;
+
+        emit!(VaultDeposited {
+            organization: ctx.accounts.organization.key(),
+            vault: ctx.accounts.vault.key(),
+            depositor: ctx.accounts.admin.key(),
+            amount,
+            new_balance: ctx.accounts.vault.amount,
+        });
+
+        Ok(())
+    }
+
+    /// Return leftover vault tokens (over-deposits, revoked grants) to the admin.
+    ///
+    /// Only allowed once no position can still claim from the vault: every
+    /// regular position is fully claimed and closed, and the organization has
+    /// no compressed positions. Beneficiaries therefore never compete with the
+    /// admin for the remaining balance.
+    pub fn reclaim_vault_surplus(ctx: Context<ReclaimVaultSurplus>, amount: u64) -> Result<()> {
+        require!(amount > 0, ShadowVestError::InvalidClaimAmount);
+        require!(
+            amount <= ctx.accounts.vault.amount,
+            ShadowVestError::InsufficientVaultBalance
+        );
+        ctx.accounts.organization.check_no_open_positions()?;
+
+        let org_key = ctx.accounts.organization.key();
+        let bump = ctx.bumps.vault_authority;
+        let vault_authority_seeds: &[&[u8]] = &[
+            b"vault_authority",
+            org_key.as_ref(),
+            std::slice::from_ref(&bump),
+        ];
+        let signer_seeds = &[vault_authority_seeds];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.admin_token_account.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::transfer(transfer_ctx, amount)?;
+
+        emit!(VaultSurplusReclaimed {
+            organization: org_key,
+            vault: ctx.accounts.vault.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Record how much of the vault balance is earmarked for a position (admin only).
+    ///
+    /// Claim amounts are encrypted, so this cleartext figure is what
+    /// sweep_unclaimed_position can later recover. Withdrawals from the position
+    /// reduce it automatically. It can be set once per position, and the reserves
+    /// of all positions paid from the vault together cannot exceed its balance.
+    pub fn set_position_reserved_amount(
+        ctx: Context<SetPositionReservedAmount>,
+        reserved_amount: u64,
+    ) -> Result<()> {
+        let vault_mint = ctx.accounts.vault.mint;
+        let vault_balance = ctx.accounts.vault.amount;
+        ctx.accounts.organization.reserve_position(
+            &mut ctx.accounts.position,
+            &vault_mint,
+            reserved_amount,
+            vault_balance,
+        )?;
+        let position = &ctx.accounts.position;
+
+        emit!(PositionReservedAmountSet {
+            position: position.key(),
+            position_id: position.position_id,
+            reserved_amount,
+        });
+
+        Ok(())
+    }
+
+    /// Recover the reserve of an abandoned position to the treasury (admin only).
+    ///
+    /// For positions whose beneficiary keys are lost: allowed once the vesting end
+    /// (shifted by any pauses) plus VestingPosition::SWEEP_GRACE_PERIOD has passed and
+    /// the position is not fully claimed. Transfers what is left of reserved_amount,
+    /// never touching balance reserved for processed claims, and deactivates the
+    /// position so it cannot be claimed afterwards.
+    pub fn sweep_unclaimed_position(ctx: Context<SweepUnclaimedPosition>) -> Result<()> {
+        let clock = Clock::get()?;
+        ctx.accounts
+            .position
+            .check_sweepable(ctx.accounts.schedule.total_duration, clock.unix_timestamp)?;
+
+        let available = ctx
+            .accounts
+            .vault
+            .amount
+            .saturating_sub(ctx.accounts.organization.vault_reserved_amount(&ctx.accounts.vault.mint));
+        let amount = ctx.accounts.position.reserved_amount.min(available);
+        require!(amount > 0, ShadowVestError::NothingToSweep);
+
+        ctx.accounts.position.transition_to(PositionState::Deactivated)?;
+        // The deactivated position can no longer claim, so none of its reserve stays earmarked
+        let vault_mint = ctx.accounts.vault.mint;
+        ctx.accounts
+            .organization
+            .release_position_reserve(&mut ctx.accounts.position, &vault_mint, u64::MAX);
+
+        let org_key = ctx.accounts.organization.key();
+        let bump = ctx.bumps.vault_authority;
+        let vault_authority_seeds: &[&[u8]] = &[
+            b"vault_authority",
+            org_key.as_ref(),
+            std::slice::from_ref(&bump),
+        ];
+        let signer_seeds = &[vault_authority_seeds];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.treasury_token_account.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::transfer(transfer_ctx, amount)?;
+
+        emit!(PositionSwept {
+            organization: org_key,
+            position: ctx.accounts.position.key(),
+            position_id: ctx.accounts.position.position_id,
+            treasury_token_account: ctx.accounts.treasury_token_account.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw tokens from the organization vault to the beneficiary's destination.
+    ///
+    /// Verifies the claim has been authorized, processed by MPC, and not yet withdrawn.
+    /// Transfers claim_amount tokens from vault to destination. Organizations with a
+    /// challenge period go through queue_withdraw and execute_queued_withdraw instead.
+    pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
+        ctx.accounts
+            .withdraw_remaining(ctx.bumps.vault_authority, ctx.bumps.claim_history)?;
+        Ok(())
+    }
+
+    /// Start the challenge period of a processed claim (permissionless, like withdraw).
+    ///
+    /// Records withdraw_requested_at and the organization's current challenge_period
+    /// on the ClaimAuthorization. Works for regular and compressed position claims.
+    pub fn queue_withdraw(ctx: Context<QueueWithdraw>) -> Result<()> {
+        require!(!ctx.accounts.organization.paused, ShadowVestError::OrganizationPaused);
+
+        let claim_auth = &mut ctx.accounts.claim_authorization;
+
+        require!(claim_auth.is_authorized, ShadowVestError::ClaimNotAuthorized);
+        require!(!claim_auth.is_revoked, ShadowVestError::ClaimRevoked);
+        require!(claim_auth.is_processed, ShadowVestError::ClaimNotProcessed);
+        require!(!claim_auth.is_withdrawn, ShadowVestError::AlreadyWithdrawn);
+        require!(
+            !claim_auth.is_withdrawal_queued(),
+            ShadowVestError::WithdrawalAlreadyQueued
+        );
+
+        claim_auth.withdraw_requested_at = Clock::get()?.unix_timestamp;
+        claim_auth.challenge_period = ctx.accounts.organization.challenge_period;
+
+        emit!(WithdrawalQueued {
+            organization: ctx.accounts.organization.key(),
+            claim_authorization: claim_auth.key(),
+            position: claim_auth.position,
+            requested_at: claim_auth.withdraw_requested_at,
+            executable_at: claim_auth.withdrawal_executable_at(),
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw a queued claim once its challenge period has elapsed.
+    ///
+    /// Same accounts and transfer as `withdraw`; fails with ChallengePeriodActive
+    /// while the window is still open.
+    pub fn execute_queued_withdraw(ctx: Context<Withdraw>) -> Result<()> {
+        require!(
+            ctx.accounts.claim_authorization.is_withdrawal_queued(),
+            ShadowVestError::WithdrawalNotQueued
+        );
+
+        let amount = ctx
+            .accounts
+            .withdraw_remaining(ctx.bumps.vault_authority, ctx.bumps.claim_history)?;
+
+        emit!(WithdrawalExecuted {
+            organization: ctx.accounts.organization.key(),
+            claim_authorization: ctx.accounts.claim_authorization.key(),
+            position: ctx.accounts.claim_authorization.position,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Cancel a queued withdrawal before it executes (admin only, e.g. on fraud).
+    ///
+    /// The claim is revoked like revoke_authorization: its reservation is released
+    /// and the nullifier stays spent.
+    pub fn cancel_withdraw(ctx: Context<CancelWithdraw>) -> Result<()> {
+        let claim_auth = &mut ctx.accounts.claim_authorization;
+
+        require!(
+            claim_auth.is_withdrawal_queued(),
+            ShadowVestError::WithdrawalNotQueued
+        );
+        require!(!claim_auth.is_withdrawn, ShadowVestError::AlreadyWithdrawn);
+        require!(!claim_auth.is_revoked, ShadowVestError::ClaimRevoked);
+
+        settle_open_claim(claim_auth, ctx.accounts.position.as_mut())?;
+        claim_auth.is_revoked = true;
+        ctx.accounts.organization.release_claim(claim_auth, u64::MAX);
+
+        emit!(WithdrawalCancelled {
+            organization: ctx.accounts.organization.key(),
+            claim_authorization: claim_auth.key(),
+            position: claim_auth.position,
+            nullifier: claim_auth.nullifier,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw part of an authorized claim.
+    ///
+    /// Transfers `amount` (at most claim_amount - withdrawn_so_far) to the authorized
+    /// destination. The claim is marked withdrawn once the running total reaches
+    /// claim_amount; any remainder can still be taken with `withdraw`.
+    pub fn withdraw_partial(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        check_withdrawable_claim(
+            &ctx.accounts.organization,
+            &ctx.accounts.claim_authorization,
+            ctx.accounts.guardian.as_ref().map(|guardian| guardian.key()),
+            &ctx.accounts.destination.key(),
+        )?;
+        ctx.accounts.organization.check_withdrawal_destination(
+            &ctx.accounts.destination.key(),
+            &ctx.accounts.destination.owner,
+            &ctx.accounts.destination.mint,
+        )?;
+
+        // Validates amount against the remaining authorized balance; the last
+        // partial withdrawal completes the claim
+        ctx.accounts.claim_authorization.record_withdrawal(amount)?;
+
+        let org_key = ctx.accounts.organization.key();
+        let bump = ctx.bumps.vault_authority;
+        let vault_authority_seeds: &[&[u8]] = &[
+            b"vault_authority",
+            org_key.as_ref(),
+            std::slice::from_ref(&bump),
+        ];
+        let signer_seeds = &[vault_authority_seeds];
+
+        let (fee, _) = pay_out_withdrawal(
+            &ctx.accounts.organization,
+            amount,
+            &mut ctx.accounts.vault,
+            ctx.accounts.destination.to_account_info(),
+            ctx.accounts.fee_destination.as_ref(),
+            &ctx.accounts.vault_authority,
+            &ctx.accounts.token_program,
+            signer_seeds,
+        )?;
+
+        record_claim_withdrawal(
+            &mut ctx.accounts.organization,
+            &mut ctx.accounts.position,
+            &mut ctx.accounts.claim_authorization,
+            &mut ctx.accounts.claim_history,
+            ctx.bumps.claim_history,
+            amount,
+            fee,
+            &ctx.accounts.vault,
+        )
+    }
+
+    /// Withdraw an authorized claim into the beneficiary wallet's associated token
+    /// account, creating the ATA first if it does not exist yet.
+    ///
+    /// Variant of `withdraw` for beneficiaries receiving a token they have never
+    /// held. The claim must have been authorized with the ATA of `beneficiary_wallet`
+    /// for the schedule's mint as withdrawal_destination; the payer covers the ATA
+    /// rent when it is created.
+    pub fn withdraw_to_ata(ctx: Context<WithdrawToAta>) -> Result<()> {
+        // The derived ATA must be what the beneficiary authorized
+        check_withdrawable_claim(
+            &ctx.accounts.organization,
+            &ctx.accounts.claim_authorization,
+            ctx.accounts.guardian.as_ref().map(|guardian| guardian.key()),
+            &ctx.accounts.destination.key(),
+        )?;
+        ctx.accounts.organization.check_withdrawal_destination(
+            &ctx.accounts.destination.key(),
+            &ctx.accounts.destination.owner,
+            &ctx.accounts.destination.mint,
+        )?;
+
+        let amount = ctx.accounts.claim_authorization.remaining_amount();
+
+        let org_key = ctx.accounts.organization.key();
+        let bump = ctx.bumps.vault_authority;
+        let vault_authority_seeds: &[&[u8]] = &[
+            b"vault_authority",
+            org_key.as_ref(),
+            std::slice::from_ref(&bump),
+        ];
+        let signer_seeds = &[vault_authority_seeds];
+
+        let (fee, _) = pay_out_withdrawal(
+            &ctx.accounts.organization,
+            amount,
+            &mut ctx.accounts.vault,
+            ctx.accounts.destination.to_account_info(),
+            ctx.accounts.fee_destination.as_ref(),
+            &ctx.accounts.vault_authority,
+            &ctx.accounts.token_program,
+            signer_seeds,
+        )?;
+
+        let claim_auth = &mut ctx.accounts.claim_authorization;
+        claim_auth.withdrawn_so_far = claim_auth.claim_amount;
+        claim_auth.is_withdrawn = true;
+
+        record_claim_withdrawal(
+            &mut ctx.accounts.organization,
+            &mut ctx.accounts.position,
+            &mut ctx.accounts.claim_authorization,
+            &mut ctx.accounts.claim_history,
+            ctx.bumps.claim_history,
+            amount,
+            fee,
+            &ctx.accounts.vault,
+        )
+    }
+
+    /// Withdraw an authorized claim from a wSOL vault as native SOL.
+    ///
+    /// Variant of `withdraw` for organizations whose vault mint is the native mint.
+    /// The claim must have been authorized with the recipient's system account as
+    /// withdrawal_destination. Extra accounts compared to `withdraw`:
+    /// - `unwrap_account`: temporary wSOL account PDA [b"unwrap", claim_authorization],
+    ///   created and closed within this instruction
+    /// - `recipient`: system account receiving the lamports
+    ///
+    /// The temporary account's rent is returned to the payer. Organizations with
+    /// ATA-only withdrawals cannot unwrap, since the temporary account is not an ATA.
+    pub fn withdraw_and_unwrap(ctx: Context<WithdrawAndUnwrap>) -> Result<()> {
+        check_withdrawable_claim(
+            &ctx.accounts.organization,
+            &ctx.accounts.claim_authorization,
+            ctx.accounts.guardian.as_ref().map(|guardian| guardian.key()),
+            &ctx.accounts.recipient.key(),
+        )?;
+        // The tokens leave the vault through the temporary account, which is not an
+        // associated token account, so ATA-only organizations cannot unwrap
+        ctx.accounts.organization.check_withdrawal_destination(
+            &ctx.accounts.unwrap_account.key(),
+            &ctx.accounts.unwrap_account.owner,
+            &ctx.accounts.unwrap_account.mint,
+        )?;
+
+        let amount = ctx.accounts.claim_authorization.remaining_amount();
+
+        let org_key = ctx.accounts.organization.key();
+        let bump = ctx.bumps.vault_authority;
+        let vault_authority_seeds: &[&[u8]] = &[
+            b"vault_authority",
+            org_key.as_ref(),
+            std::slice::from_ref(&bump),
+        ];
+        let signer_seeds = &[vault_authority_seeds];
+
+        // Move the wrapped net amount into the temporary account (the fee is paid in wSOL)
+        let (fee, net_amount) = pay_out_withdrawal(
+            &ctx.accounts.organization,
+            amount,
+            &mut ctx.accounts.vault,
+            ctx.accounts.unwrap_account.to_account_info(),
+            ctx.accounts.fee_destination.as_ref(),
+            &ctx.accounts.vault_authority,
+            &ctx.accounts.token_program,
+            signer_seeds,
+        )?;
+
+        // Closing a wSOL account releases all its lamports (rent + amount) to the payer
+        token::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.unwrap_account.to_account_info(),
+                destination: ctx.accounts.payer.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        // Forward the unwrapped net amount to the recipient; the payer keeps the rent
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: ctx.accounts.recipient.to_account_info(),
+                },
+            ),
+            net_amount,
+        )?;
+
+        let claim_auth = &mut ctx.accounts.claim_authorization;
+        claim_auth.withdrawn_so_far = claim_auth.claim_amount;
+        claim_auth.is_withdrawn = true;
+
+        record_claim_withdrawal(
+            &mut ctx.accounts.organization,
+            &mut ctx.accounts.position,
+            &mut ctx.accounts.claim_authorization,
+            &mut ctx.accounts.claim_history,
+            ctx.bumps.claim_history,
+            amount,
+            fee,
+            &ctx.accounts.vault,
+        )
+    }
+
+    /// Expire a claim authorization that was never processed.
+    ///
+    /// Permissionless: once authorized_at + claim_expiry_seconds has passed, closes the
+    /// ClaimAuthorization and its NullifierRecord, refunding rent to the original payer,
+    /// so the beneficiary can authorize the claim again.
+    pub fn expire_claim_authorization(ctx: Context<ExpireClaimAuthorization>) -> Result<()> {
+        let claim_auth = &ctx.accounts.claim_authorization;
+        let clock = Clock::get()?;
+
+        require!(
+            claim_auth.is_expired(clock.unix_timestamp),
+            ShadowVestError::ClaimNotExpired
+        );
+
+        settle_open_claim(&ctx.accounts.claim_authorization, ctx.accounts.position.as_mut())?;
+
+        // A queued but never processed claim still holds a vault reservation
+        ctx.accounts
+            .organization
+            .release_claim(&mut ctx.accounts.claim_authorization, u64::MAX);
+        let claim_auth = &ctx.accounts.claim_authorization;
+
+        emit!(ClaimAuthorizationExpired {
+            organization: ctx.accounts.organization.key(),
+            position: claim_auth.position,
+            nullifier: claim_auth.nullifier,
+        });
+
+        Ok(())
+    }
+
+    /// Close a fully withdrawn ClaimAuthorization, refunding rent to its original payer.
+    ///
+    /// Permissionless. The NullifierRecord is deliberately left open: it is what
+    /// stops the same nullifier from being authorized again.
+    pub fn close_spent_claim(ctx: Context<CloseSpentClaim>) -> Result<()> {
+        let claim_auth = &ctx.accounts.claim_authorization;
+
+        emit!(ClaimAuthorizationClosed {
+            claim_authorization: claim_auth.key(),
+            position: claim_auth.position,
+            nullifier: claim_auth.nullifier,
+        });
+
+        Ok(())
+    }
+
+    /// Expire an MPC computation whose callback never arrived.
+    ///
+    /// Permissionless: once queued_at + organization.computation_timeout has passed,
+    /// clears the pending flag so queue_process_claim can be called again for the
+    /// same authorization. A late callback for the expired computation is rejected.
+    pub fn expire_computation(ctx: Context<ExpireComputation>) -> Result<()> {
+        let timeout = ctx.accounts.organization.computation_timeout;
+        let clock = Clock::get()?;
+        let pending = &mut ctx.accounts.pending_computation;
+
+        require!(
+            pending.is_expired(clock.unix_timestamp, timeout),
+            ShadowVestError::ComputationNotExpired
+        );
+
+        pending.is_pending = false;
+
+        // Re-queueing reserves the claim again
+        ctx.accounts
+            .organization
+            .release_claim(&mut ctx.accounts.claim_authorization, u64::MAX);
+        let pending = &ctx.accounts.pending_computation;
+
+        emit!(ComputationExpired {
+            organization: ctx.accounts.organization.key(),
+            claim_authorization: pending.claim_authorization,
+            computation_account: pending.computation_account,
+            queued_at: pending.queued_at,
+        });
+
+        Ok(())
+    }
+
+    /// Report which step of the claim flow an authorization has reached.
+    ///
+    /// Read-only: emits ClaimFlowStatusReported so a client that was interrupted
+    /// between authorize_claim, queue_process_claim, the MPC callback and withdraw
+    /// knows which instruction to send next. Pass the authorization's PendingComputation
+    /// PDA if it exists; omit it if queue_process_claim was never called.
+    pub fn claim_flow_status(ctx: Context<ClaimFlowStatus>) -> Result<()> {
+        let claim_auth = &ctx.accounts.claim_authorization;
+        let computation_pending = ctx
+            .accounts
+            .pending_computation
+            .as_ref()
+            .is_some_and(|pending| pending.is_pending);
+
+        emit!(ClaimFlowStatusReported {
+            claim_authorization: claim_auth.key(),
+            position: claim_auth.position,
+            step: claim_auth.flow_step(computation_pending),
+            is_revoked: claim_auth.is_revoked,
+            claim_amount: claim_auth.claim_amount,
+            withdrawn_so_far: claim_auth.withdrawn_so_far,
+        });
+
+        Ok(())
+    }
+
+    /// Report whether a position's cliff has passed.
+    ///
+    /// Read-only and MPC-free: emits CliffStatus with the seconds left until
+    /// start_timestamp + cliff_duration, so wallets can show a countdown.
+    pub fn check_cliff(ctx: Context<CheckCliff>) -> Result<()> {
+        let position = &ctx.accounts.position;
+        let clock = Clock::get()?;
+        let seconds_until_cliff = vesting::seconds_until_cliff(
+            clock.unix_timestamp,
+            position.start_timestamp,
+            ctx.accounts.schedule.cliff_duration,
+        );
+
+        emit!(CliffStatus {
+            position: position.key(),
+            cliff_reached: seconds_until_cliff == 0,
+            seconds_until_cliff,
+        });
+
+        Ok(())
+    }
+
+    /// Report whether the organization has revoked a position.
+    ///
+    /// Read-only: emits PositionRevocationStatus so escrows and other verifiers can
+    /// refuse claims against revoked positions without parsing the account. A
+    /// revoked position stays reported as revoked after its last claim.
+    pub fn is_position_revoked(ctx: Context<IsPositionRevoked>) -> Result<()> {
+        let position = &ctx.accounts.position;
+
+        emit!(PositionRevocationStatus {
+            position: position.key(),
+            is_revoked: position.revoked_at != 0,
+            revoked_at: position.revoked_at,
+        });
+
+        Ok(())
+    }
+
+    /// Publish a position's total amount and take it out of privacy mode (admin only).
+    ///
+    /// For non-private grants: afterwards calculate_vested_plaintext can compute the
+    /// vested amount on-chain without an MPC round trip. The encrypted amounts are
+    /// kept, so MPC claims work as before. Cannot be undone.
+    pub fn reveal_position_total(
+        ctx: Context<RevealPositionTotal>,
+        total_amount: u64,
+    ) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.privacy_mode = false;
+        position.plaintext_total_amount = Some(total_amount);
+
+        emit!(PositionTotalRevealed {
+            position: position.key(),
+            position_id: position.position_id,
+            total_amount,
+        });
+
+        Ok(())
+    }
+
+    /// Pause or resume claims on a single position (admin only), e.g. during a dispute.
+    ///
+    /// Unlike revocation this is reversible, and unlike freezing it leaves the
+    /// lifecycle state alone. By default vesting keeps accruing on real elapsed time
+    /// while paused, so resuming does not extend the schedule. If the organization
+    /// has freeze_vesting_on_pause set when the pause starts, vesting does not accrue
+    /// during it: on resume its length is added to total_paused_seconds, which
+    /// vesting_time subtracts. Checked when claims are queued; claims already
+    /// processed can still be withdrawn.
+    pub fn set_position_paused(ctx: Context<SetPositionPaused>, paused: bool) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let freeze_vesting = ctx.accounts.organization.freeze_vesting_on_pause;
+        let position = &mut ctx.accounts.position;
+        let was_paused = position.is_paused;
+        if paused {
+            position.pause(now, freeze_vesting);
+        } else {
+            position.resume(now);
+        }
+
+        emit!(PositionPausedChanged {
+            position: position.key(),
+            position_id: position.position_id,
+            is_paused: paused,
+        });
+        if was_paused && !paused {
+            emit!(PositionResumed {
+                position: position.key(),
+                total_paused_seconds: position.total_paused_seconds,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Choose whether position pauses started from now on also suspend vesting
+    /// accrual (admin only).
+    ///
+    /// Off by default: a paused position only stops claiming. Some legal setups
+    /// require that nothing vests during a pause; with this set, set_position_paused
+    /// excludes the paused interval from the position's vesting time. Each pause
+    /// keeps the behavior it started with.
+    pub fn set_freeze_vesting_on_pause(
+        ctx: Context<SetFreezeVestingOnPause>,
+        freeze_vesting_on_pause: bool,
+    ) -> Result<()> {
+        let organization = &mut ctx.accounts.organization;
+        organization.freeze_vesting_on_pause = freeze_vesting_on_pause;
+
+        emit!(FreezeVestingOnPauseUpdated {
+            organization: organization.key(),
+            freeze_vesting_on_pause,
+        });
+
+        Ok(())
+    }
+
+    /// Compute the vested amount of a revealed (non-private) position on-chain.
+    ///
+    /// Read-only and MPC-free: emits PlaintextVestedCalculated with
+    /// total * numerator / schedule.precision. Fails with PositionIsPrivate while the
+    /// position is in privacy mode.
+    pub fn calculate_vested_plaintext(ctx: Context<CalculateVestedPlaintext>) -> Result<()> {
+        let position = &ctx.accounts.position;
+        let schedule = &ctx.accounts.schedule;
+        let total_amount = position.plaintext_total()?;
+        schedule.validate()?;
+
+        let clock = Clock::get()?;
+        let vesting_numerator = vesting::compute_vesting_numerator(
+            position.vesting_time(clock.unix_timestamp),
+            position.start_timestamp,
+            schedule.cliff_duration,
+            schedule.total_duration,
+            schedule.vesting_interval,
+            schedule.vesting_mode,
+            schedule.precision,
+        );
+
+        emit!(PlaintextVestedCalculated {
+            position: position.key(),
+            total_amount,
+            vesting_numerator,
+            precision: schedule.precision,
+            vested_amount: vesting::compute_vested_amount(
+                total_amount,
+                vesting_numerator,
+                schedule.precision,
+            ),
+        });
+
+        Ok(())
+    }
+
+    /// Derive the ClaimAuthorization PDA for a claim.
+    ///
+    /// Read-only: emits ClaimAuthorizationResolved so clients can confirm the
+    /// address before submitting. Regular positions seed the PDA with the position
+    /// key; compressed positions (is_compressed) use the organization key and
+    /// position_id instead, so `position` is ignored for them.
+    pub fn resolve_claim_authorization(
+        ctx: Context<ResolveClaimAuthorization>,
+        is_compressed: bool,
+        position: Pubkey,
+        position_id: u64,
+        nullifier: [u8; 32],
+    ) -> Result<()> {
+        let organization = ctx.accounts.organization.key();
+        let (claim_authorization, bump) = if is_compressed {
+            ClaimAuthorization::find_compressed_pda(&organization, position_id, &nullifier)
+        } else {
+            ClaimAuthorization::find_pda(&position, &nullifier)
+        };
+
+        emit!(ClaimAuthorizationResolved {
+            organization,
+            is_compressed,
+            claim_authorization,
+            bump,
+        });
+
+        Ok(())
+    }
+
+    /// Revoke a claim authorization before it is withdrawn (e.g. on detected fraud).
+    ///
+    /// The nullifier record is left in place, so the same nullifier cannot be
+    /// used to authorize a new claim.
+    pub fn revoke_authorization(ctx: Context<RevokeAuthorization>) -> Result<()> {
+        let claim_auth = &mut ctx.accounts.claim_authorization;
+
+        require!(!claim_auth.is_withdrawn, ShadowVestError::AlreadyWithdrawn);
+        require!(!claim_auth.is_revoked, ShadowVestError::ClaimRevoked);
+
+        settle_open_claim(claim_auth, ctx.accounts.position.as_mut())?;
+        claim_auth.is_revoked = true;
+        ctx.accounts.organization.release_claim(claim_auth, u64::MAX);
+
+        emit!(ClaimAuthorizationRevoked {
+            organization: ctx.accounts.organization.key(),
+            position: claim_auth.position,
+            nullifier: claim_auth.nullifier,
+        });
+
+        Ok(())
+    }
+
+    /// Invalidate a nullifier consumed by a buggy or malicious authorization.
+    ///
+    /// Org admin only. Closes the NullifierRecord and its unwithdrawn
+    /// ClaimAuthorization, refunding rent to the claim's original payer. The
+    /// ClaimAuthorization PDA is derived from the same nullifier, so closing it
+    /// (rather than only clearing is_authorized) is what lets the beneficiary
+    /// authorize a fresh claim with that nullifier.
+    pub fn invalidate_nullifier(
+        ctx: Context<InvalidateNullifier>,
+        nullifier: [u8; 32],
+    ) -> Result<()> {
+        let claim_auth = &ctx.accounts.claim_authorization;
+
+        require!(!claim_auth.is_withdrawn, ShadowVestError::AlreadyWithdrawn);
+
+        settle_open_claim(&ctx.accounts.claim_authorization, ctx.accounts.position.as_mut())?;
+        ctx.accounts
+            .organization
+            .release_claim(&mut ctx.accounts.claim_authorization, u64::MAX);
+        let claim_auth = &ctx.accounts.claim_authorization;
+
+        emit!(NullifierInvalidated {
+            organization: ctx.accounts.organization.key(),
+            position: claim_auth.position,
+            claim_authorization: claim_auth.key(),
+            nullifier,
+        });
+
+        Ok(())
+    }
+
+    /// Re-emit a snapshot of a position's lifecycle from current on-chain state.
+    ///
+    /// For dispute resolution when original logs are unavailable. Emits one
+    /// PositionReplayed event for the position and one ClaimReplayed event per
+    /// ClaimAuthorization passed in remaining_accounts (each must belong to the position).
+    pub fn replay_position_events(ctx: Context<ReplayPositionEvents>) -> Result<()> {
+        let position = &ctx.accounts.position;
+        let position_key = position.key();
+
+        emit!(PositionReplayed {
+            organization: position.organization,
+            schedule: position.schedule,
+            position: position_key,
+            position_id: position.position_id,
+            beneficiary_commitment: position.beneficiary_commitment,
+            start_timestamp: position.start_timestamp,
+            state: position.state,
+            revoked_at: position.revoked_at,
+            encrypted_total_amount: position.encrypted_total_amount,
+            encrypted_claimed_amount: position.encrypted_claimed_amount,
+        });
+
+        for account in ctx.remaining_accounts.iter() {
+            require!(
+                account.owner == &crate::ID,
+                ShadowVestError::InvalidPositionOrganization
+            );
+            let data = account.try_borrow_data()?;
+            let claim_auth = ClaimAuthorization::try_deserialize(&mut &data[..])?;
+            require!(
+                claim_auth.position == position_key,
+                ShadowVestError::InvalidPositionOrganization
+            );
+
+            emit!(ClaimReplayed {
+                position: position_key,
+                claim_authorization: account.key(),
+                nullifier: claim_auth.nullifier,
+                withdrawal_destination: claim_auth.withdrawal_destination,
+                claim_amount: claim_auth.claim_amount,
+                withdrawn_so_far: claim_auth.withdrawn_so_far,
+                authorized_at: claim_auth.authorized_at,
+                is_processed: claim_auth.is_processed,
+                is_withdrawn: claim_auth.is_withdrawn,
+                is_revoked: claim_auth.is_revoked,
+            });
+        }
+
+        Ok(())
+    }
+
+    // ============================================================
+    // Compressed Vesting Positions (Light Protocol - 5000x cost reduction)
+    // ============================================================
+
+    /// Create a compressed vesting position using Light Protocol.
+    /// This stores the position in a Merkle tree for 5000x cost reduction.
+    ///
+    /// The position data is hashed and stored in Light Protocol's state tree,
+    /// while encrypted amounts are stored for Arcium MPC processing.
+    ///
+    /// # Arguments
+    /// * `proof_bytes` - Serialized validity proof for Light Protocol state transition
+    /// * `address_tree_info_bytes` - Serialized address tree info for derivation
+    /// * `output_tree_index` - Index of the output state tree
+    /// * `beneficiary_commitment` - Hash commitment of beneficiary identity
+    /// * `encrypted_total_amount` - Arcium-encrypted total vesting amount
+    /// * `nonce` - Nonce for Arcium encryption
+    ///
+    /// Note: This instruction requires Light Protocol accounts in remaining_accounts:
+    /// - light_system_program
+    /// - account_compression_program
+    /// - registered_program_pda
+    /// - noop_program
+    /// - cpi_authority_pda
+    /// - state_merkle_tree
+    /// - address_merkle_tree
+    /// - address_queue
+    pub fn create_compressed_vesting_position<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateCompressedVestingPosition<'info>>,
+        proof_bytes: Vec<u8>,
+        address_tree_info_bytes: Vec<u8>,
+        output_tree_index: u8,
+        beneficiary_commitment: [u8; 32],
+        encrypted_total_amount: [u8; 32],
+        nonce: u128,
+        metadata_commitment: [u8; 32],
+    ) -> Result<()> {
+        // Validate organization and schedule state
+        require!(
+            ctx.accounts.organization.is_active,
+            ShadowVestError::OrganizationNotActive
+        );
+        require!(
+            ctx.accounts.schedule.is_active,
+            ShadowVestError::ScheduleNotActive
+        );
+
+        // Deserialize the Light Protocol types from bytes
+        let proof: ValidityProof = borsh::BorshDeserialize::try_from_slice(&proof_bytes)
+            .map_err(|_| ShadowVestError::LightProofDeserializationFailed)?;
+        let address_tree_info: PackedAddressTreeInfo =
+            borsh::BorshDeserialize::try_from_slice(&address_tree_info_bytes)
+                .map_err(|_| ShadowVestError::LightAccountMetaDeserializationFailed)?;
+
+        // Get current position ID and timestamp
+        let position_id = ctx.accounts.organization.compressed_position_count;
+        let clock = Clock::get()?;
+
+        // Initialize CPI accounts for Light Protocol
+        let cpi_accounts = CpiAccounts::new(
+            ctx.accounts.fee_payer.as_ref(),
+            ctx.remaining_accounts,
+            crate::LIGHT_CPI_SIGNER,
+        );
+
+        // Derive unique address for this compressed position
+        let (address, address_seed) = derive_compressed_position_address(
+            ctx.remaining_accounts,
+            &address_tree_info,
+            &ctx.accounts.organization.key(),
+            position_id,
+        )?;
+
+        // Create new address parameters for the Merkle tree
+        let new_address_params = address_tree_info.into_new_address_params_assigned_packed(address_seed, Some(0));
+
+        // Initialize the compressed vesting position
+        let mut compressed_position =
+            LightAccount::<CompressedVestingPosition>::new_init(&crate::ID, Some(address), output_tree_index);
+
+        // Set position data
+        compressed_position.owner = ctx.accounts.admin.key();
+        compressed_position.organization = ctx.accounts.organization.key();
+        compressed_position.schedule = ctx.accounts.schedule.key();
+        compressed_position.position_id = position_id;
+        compressed_position.beneficiary_commitment = beneficiary_commitment;
+        compressed_position.encrypted_total_amount = encrypted_total_amount;
+        compressed_position.encrypted_claimed_amount = [0u8; 32];
+        compressed_position.nonce = nonce;
+        compressed_position.start_timestamp = clock.unix_timestamp;
+        compressed_position.is_active = 1;
+        compressed_position.is_fully_claimed = 0;
+        compressed_position.is_stealth = 0;
+        compressed_position.metadata_commitment = metadata_commitment;
+
+        // Execute Light Protocol CPI to create the compressed account
+        LightSystemProgramCpi::new_cpi(crate::LIGHT_CPI_SIGNER, proof)
+            .with_new_addresses(&[new_address_params])
+            .with_light_account(compressed_position)?
+            .invoke(cpi_accounts)
+            .map_err(|_| ShadowVestError::LightCpiInvokeFailed)?;
+
+        // Update organization counter
+        ctx.accounts.organization.compressed_position_count = ctx
+            .accounts
+            .organization
+            .compressed_position_count
+            .checked_add(1)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+
+        // Update schedule counter
+        ctx.accounts.schedule.compressed_position_count = ctx
+            .accounts
+            .schedule
+            .compressed_position_count
+            .checked_add(1)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+
+        // Emit event for indexing
+        emit!(CompressedPositionCreated {
+            organization: ctx.accounts.organization.key(),
+            schedule: ctx.accounts.schedule.key(),
+            position_id,
+            address,
+            beneficiary_commitment,
+            start_timestamp: clock.unix_timestamp,
+            metadata_commitment,
+        });
+
+        Ok(())
+    }
+
+    /// Create a compressed vesting position with stealth address beneficiary.
+    ///
+    /// Combines Light Protocol's 5000x cost reduction with stealth address privacy.
+    /// The stealth address is derived off-chain by the employer using employee's (S, V).
+    ///
+    /// Emits both CompressedPositionCreated and StealthPaymentEvent for indexing/scanning.
+    pub fn create_compressed_stealth_vesting_position<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateCompressedVestingPosition<'info>>,
+        proof_bytes: Vec<u8>,
+        address_tree_info_bytes: Vec<u8>,
+        output_tree_index: u8,
+        stealth_address: Pubkey,
+        ephemeral_pubkey: [u8; 32],
+        view_tag: u8,
+        encrypted_payload: [u8; 128],
+        auditor_encrypted_payload: [u8; 128],
+        encrypted_total_amount: [u8; 32],
+        nonce: u128,
+        metadata_commitment: [u8; 32],
+    ) -> Result<()> {
+        // Validate organization and schedule state
+        require!(
+            ctx.accounts.organization.is_active,
+            ShadowVestError::OrganizationNotActive
+        );
+        require!(
+            ctx.accounts.schedule.is_active,
+            ShadowVestError::ScheduleNotActive
+        );
+
+        require!(
+            state::is_valid_stealth_address(&stealth_address),
+            ShadowVestError::InvalidStealthPayment
+        );
+        ctx.accounts
+            .organization
+            .check_auditor_payload(&auditor_encrypted_payload)?;
+
+        // Use stealth address as beneficiary commitment
+        let beneficiary_commitment = stealth_address.to_bytes();
+
+        // Deserialize the Light Protocol types from bytes
+        let proof: ValidityProof = borsh::BorshDeserialize::try_from_slice(&proof_bytes)
+            .map_err(|_| ShadowVestError::LightProofDeserializationFailed)?;
+        let address_tree_info: PackedAddressTreeInfo =
+            borsh::BorshDeserialize::try_from_slice(&address_tree_info_bytes)
+                .map_err(|_| ShadowVestError::LightAccountMetaDeserializationFailed)?;
+
+        // Get current position ID and timestamp
+        let position_id = ctx.accounts.organization.compressed_position_count;
+        let clock = Clock::get()?;
+        let token_mint = ctx.accounts.schedule.token_mint;
+        let org_key = ctx.accounts.organization.key();
+        let schedule_key = ctx.accounts.schedule.key();
+
+        // Initialize CPI accounts for Light Protocol
+        let cpi_accounts = CpiAccounts::new(
+            ctx.accounts.fee_payer.as_ref(),
+            ctx.remaining_accounts,
+            crate::LIGHT_CPI_SIGNER,
+        );
+
+        // Derive unique address for this compressed position
+        let (address, address_seed) = derive_compressed_position_address(
+            ctx.remaining_accounts,
+            &address_tree_info,
+            &ctx.accounts.organization.key(),
+            position_id,
+        )?;
+
+        // Create new address parameters for the Merkle tree
+        let new_address_params = address_tree_info.into_new_address_params_assigned_packed(address_seed, Some(0));
+
+        // Initialize the compressed vesting position
+        let mut compressed_position =
+            LightAccount::<CompressedVestingPosition>::new_init(&crate::ID, Some(address), output_tree_index);
+
+        // Set position data with stealth address as beneficiary
+        compressed_position.owner = ctx.accounts.admin.key();
+        compressed_position.organization = org_key;
+        compressed_position.schedule = schedule_key;
+        compressed_position.position_id = position_id;
+        compressed_position.beneficiary_commitment = beneficiary_commitment;
+        compressed_position.encrypted_total_amount = encrypted_total_amount;
+        compressed_position.encrypted_claimed_amount = [0u8; 32];
+        compressed_position.nonce = nonce;
+        compressed_position.start_timestamp = clock.unix_timestamp;
+        compressed_position.is_active = 1;
+        compressed_position.is_fully_claimed = 0;
+        compressed_position.is_stealth = 1;
+        compressed_position.metadata_commitment = metadata_commitment;
+
+        // Execute Light Protocol CPI to create the compressed account
+        LightSystemProgramCpi::new_cpi(crate::LIGHT_CPI_SIGNER, proof)
+            .with_new_addresses(&[new_address_params])
+            .with_light_account(compressed_position)?
+            .invoke(cpi_accounts)
+            .map_err(|_| ShadowVestError::LightCpiInvokeFailed)?;
+
+        // Update organization counter
+        ctx.accounts.organization.compressed_position_count = ctx
+            .accounts
+            .organization
+            .compressed_position_count
+            .checked_add(1)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+
+        // Update schedule counter
+        ctx.accounts.schedule.compressed_position_count = ctx
+            .accounts
+            .schedule
+            .compressed_position_count
+            .checked_add(1)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+
+        // Emit event for indexing
+        emit!(CompressedPositionCreated {
+            organization: org_key,
+            schedule: schedule_key,
+            position_id,
+            address,
+            beneficiary_commitment,
+            start_timestamp: clock.unix_timestamp,
+            metadata_commitment,
+        });
+
+        // Emit stealth payment event for employee scanning
+        emit!(StealthPaymentEvent {
+            organization: org_key,
+            stealth_address,
+            ephemeral_pubkey,
+            view_tag,
+            encrypted_payload,
+            auditor_encrypted_payload,
+            position_id,
+            token_mint,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Preview the address create_compressed_vesting_position would derive (read-only).
+    ///
+    /// Takes the same address tree info, output tree index and Light accounts in
+    /// remaining_accounts, but stops after derive_address and emits
+    /// CompressedAddressPreview instead of invoking the Light system program, so
+    /// clients can check their accounts and next position_id before paying for a
+    /// validity proof that may be stale by the time it lands.
+    pub fn validate_compressed_address(
+        ctx: Context<ValidateCompressedAddress>,
+        address_tree_info_bytes: Vec<u8>,
+        output_tree_index: u8,
+    ) -> Result<()> {
+        let address_tree_info: PackedAddressTreeInfo =
+            borsh::BorshDeserialize::try_from_slice(&address_tree_info_bytes)
+                .map_err(|_| ShadowVestError::LightAccountMetaDeserializationFailed)?;
+        require!(
+            ctx.remaining_accounts.len() > V2_SYSTEM_ACCOUNTS_COUNT + output_tree_index as usize,
+            ShadowVestError::InvalidOutputTree
+        );
+
+        let position_id = ctx.accounts.organization.compressed_position_count;
+        let (address, _) = derive_compressed_position_address(
+            ctx.remaining_accounts,
+            &address_tree_info,
+            &ctx.accounts.organization.key(),
+            position_id,
+        )?;
+
+        emit!(CompressedAddressPreview {
+            address,
+            position_id,
+        });
+
+        Ok(())
+    }
+
+    // ============================================================
+    // Compressed Position Claim & Withdraw Flow
+    // ============================================================
+
+    /// Authorize a claim from a compressed vesting position.
+    ///
+    /// Similar to authorize_claim but works with Light Protocol compressed accounts.
+    /// The compressed position data is read via Light Protocol CPI (validity proof verification).
+    /// An Ed25519 signature from the stealth keypair authorizes the claim.
+    ///
+    /// This creates a ClaimAuthorization PDA that the withdraw_compressed() can reference.
+    /// `position_id` must be the one the compressed address was derived from under
+    /// `address_tree`, and a nullifier already consumed in this organization (by any
+    /// position) is rejected with NullifierAlreadyUsed.
+    pub fn authorize_claim_compressed<'info>(
+        ctx: Context<'_, '_, '_, 'info, AuthorizeClaimCompressed<'info>>,
+        proof_bytes: Vec<u8>,
+        account_meta_bytes: Vec<u8>,
+        // Compressed position data (client fetches from Light RPC):
+        position_owner: Pubkey,
+        position_organization: Pubkey,
+        position_schedule: Pubkey,
+        position_id: u64,
+        beneficiary_commitment: [u8; 32],
+        encrypted_total_amount: [u8; 32],
+        encrypted_claimed_amount: [u8; 32],
+        position_nonce: u128,
+        position_start_timestamp: i64,
+        position_is_active: u8,
+        position_is_fully_claimed: u8,
+        position_is_stealth: u8,
+        position_metadata_commitment: [u8; 32],
+        // Claim params:
+        nullifier: [u8; 32],
+        withdrawal_destination: Pubkey,
+    ) -> Result<()> {
+        // 1. Verify organization is active
+        require!(ctx.accounts.organization.is_active, ShadowVestError::OrganizationNotActive);
+
+        // 2. Verify position is active and not fully claimed
+        require!(position_is_active == 1, ShadowVestError::PositionNotActive);
+        require!(position_is_fully_claimed == 0, ShadowVestError::PositionFullyClaimed);
+
+        // 3. Verify position belongs to this organization
+        require!(
+            position_organization == ctx.accounts.organization.key(),
+            ShadowVestError::InvalidPositionOrganization
+        );
+
+        // 4. Deserialize Light Protocol types
+        let proof: ValidityProof = borsh::BorshDeserialize::try_from_slice(&proof_bytes)
+            .map_err(|_| ShadowVestError::LightProofDeserializationFailed)?;
+        let account_meta: CompressedAccountMeta =
+            borsh::BorshDeserialize::try_from_slice(&account_meta_bytes)
+                .map_err(|_| ShadowVestError::LightAccountMetaDeserializationFailed)?;
+
+        // 5. Initialize CPI accounts for Light Protocol
+        let cpi_accounts = CpiAccounts::new(
+            ctx.accounts.fee_payer.as_ref(),
+            ctx.remaining_accounts,
+            crate::LIGHT_CPI_SIGNER,
+        );
+
+        // 6. Get the address from account_meta (set during creation) and check it was
+        //    derived from position_id, so the ClaimAuthorization seeds match the position
+        let address = account_meta.address;
+        let (expected_address, _) = derive_address(
+            &[
+                CompressedVestingPosition::SEED_PREFIX,
+                ctx.accounts.organization.key().as_ref(),
+                &position_id.to_le_bytes(),
+            ],
+            &ctx.accounts.address_tree.key(),
+            &crate::ID,
+        );
+        require!(
+            address == expected_address,
+            ShadowVestError::CompressedPositionIdMismatch
+        );
+
+        // 7. Load the existing compressed position via Light Protocol
+        //    This verifies the data matches what's in the Merkle tree
+        let compressed_position = LightAccount::<CompressedVestingPosition>::new_mut(
+            &crate::ID,
+            &account_meta,
+            CompressedVestingPosition {
+                owner: position_owner,
+                organization: position_organization,
+                schedule: position_schedule,
+                position_id,
+                beneficiary_commitment,
+                encrypted_total_amount,
+                encrypted_claimed_amount,
+                nonce: position_nonce,
+                start_timestamp: position_start_timestamp,
+                is_active: position_is_active,
+                is_fully_claimed: position_is_fully_claimed,
+                is_stealth: position_is_stealth,
+                metadata_commitment: position_metadata_commitment,
+            },
+        ).map_err(|_| ShadowVestError::LightAddressDerivationFailed)?;
+
+        // 8. Verify Ed25519 signature over position_id || nullifier || withdrawal_destination
+        let mut expected_msg = [0u8; 72];
+        expected_msg[..8].copy_from_slice(&position_id.to_le_bytes());
+        expected_msg[8..40].copy_from_slice(&nullifier);
+        expected_msg[40..72].copy_from_slice(withdrawal_destination.as_ref());
+        ed25519::verify_ed25519_claim_signature(
+            &ctx.accounts.instructions_sysvar,
+            &beneficiary_commitment,
+            &expected_msg,
+        )?;
+
+        // 9. Verify compressed position exists via Light Protocol CPI
+        //    We pass the same data as output (no state change here).
+        LightSystemProgramCpi::new_cpi(crate::LIGHT_CPI_SIGNER, proof)
+            .with_light_account(compressed_position)?
+            .invoke(cpi_accounts)
+            .map_err(|_| ShadowVestError::LightCpiInvokeFailed)?;
+
+        // 10. Initialize ClaimAuthorization
+        let clock = Clock::get()?;
+        let stealth_address = if position_is_stealth == 1 {
+            Pubkey::new_from_array(beneficiary_commitment)
+        } else {
+            Pubkey::default()
+        };
+        ctx.accounts.claim_authorization.set_inner(ClaimAuthorization::init(
+            Pubkey::new_from_array(address),
+            nullifier,
+            withdrawal_destination,
+            clock.unix_timestamp,
+            0,
+            ctx.accounts.fee_payer.key(),
+            stealth_address,
+            ctx.bumps.claim_authorization,
+        ));
+
+        // 11. Initialize NullifierRecord. Created here rather than with `init` so a
+        //     nullifier already used by another position in this organization fails
+        //     with NullifierAlreadyUsed instead of an opaque system program error.
+        let nullifier_info = ctx.accounts.nullifier_record.to_account_info();
+        require!(nullifier_info.data_is_empty(), ShadowVestError::NullifierAlreadyUsed);
+
+        let org_key = ctx.accounts.organization.key();
+        let namespace = ctx.accounts.organization.nullifier_namespace;
+        let bump = ctx.bumps.nullifier_record;
+        let signer_seeds: &[&[u8]] = &[
+            NullifierRecord::SEED_PREFIX,
+            org_key.as_ref(),
+            namespace.as_ref(),
+            nullifier.as_ref(),
+            std::slice::from_ref(&bump),
+        ];
+        anchor_lang::system_program::create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::CreateAccount {
+                    from: ctx.accounts.fee_payer.to_account_info(),
+                    to: nullifier_info.clone(),
+                },
+                &[signer_seeds],
+            ),
+            Rent::get()?.minimum_balance(NullifierRecord::SIZE),
+            NullifierRecord::SIZE as u64,
+            &crate::ID,
+        )?;
+
+        store_program_account(
+            &nullifier_info,
+            &NullifierRecord::init(
+                nullifier,
+                Pubkey::new_from_array(address),
+                clock.unix_timestamp,
+                namespace,
+                bump,
+            ),
+        )?;
+
+        emit!(ClaimAuthorized {
+            organization: org_key,
+            position: Pubkey::new_from_array(address),
+            position_id,
+            nullifier,
+            withdrawal_destination,
+        });
+
+        Ok(())
+    }
+
+    /// Queue MPC computation for a compressed position claim.
+    /// Computes vesting_numerator on-chain from Clock + schedule parameters.
+    /// The caller passes the position's total and claimed amounts encrypted together
+    /// for `pubkey`/`nonce`; the callback keeps the re-encrypted amounts and new nonce
+    /// on the authorization, and update_compressed_position_claimed stores them on
+    /// the position.
+    pub fn queue_process_claim_compressed(
+        ctx: Context<QueueProcessClaimCompressed>,
+        computation_offset: u64,
+        position_id: u64,
+        encrypted_total_amount: [u8; 32],
+        encrypted_claimed_amount: [u8; 32],
+        claim_amount: u64,
+        start_timestamp: i64,
+        pubkey: [u8; 32],
+        nonce: u128,
+    ) -> Result<()> {
+        require!(ctx.accounts.claim_authorization.is_authorized, ShadowVestError::ClaimNotAuthorized);
+        require!(!ctx.accounts.claim_authorization.is_processed, ShadowVestError::ClaimNotProcessed);
+        require!(
+            !ctx.accounts.pending_computation.is_pending,
+            ShadowVestError::ComputationPending
+        );
+
+        // Capture position key before mutable borrow
+        let claim_position = ctx.accounts.claim_authorization.position;
+
+        let schedule = &ctx.accounts.schedule;
+        require!(!schedule.claims_paused, ShadowVestError::ScheduleClaimsPaused);
+        // Compressed claims take the schedule account on trust; reject a zero interval
+        schedule.validate()?;
+
+        // Compute vesting_numerator on-chain
+        let clock = Clock::get()?;
+        let vesting_numerator = vesting::compute_vesting_numerator(
+            clock.unix_timestamp,
+            start_timestamp,
+            schedule.cliff_duration,
+            schedule.total_duration,
+            schedule.vesting_interval,
+            schedule.vesting_mode,
+            schedule.precision,
+        );
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(pubkey)
+            .plaintext_u128(nonce)
+            .encrypted_u64(encrypted_total_amount)
+            .encrypted_u64(encrypted_claimed_amount)
+            .plaintext_u64(vesting_numerator)
+            .plaintext_u64(claim_amount)
+            .plaintext_u64(schedule.precision)
+            .build();
+
+        let position_callback_account = CallbackAccount {
+            pubkey: ctx.accounts.position.key(),
+            is_writable: true,
+        };
+        let claim_auth_callback_account = CallbackAccount {
+            pubkey: ctx.accounts.claim_authorization.key(),
+            is_writable: true,
+        };
+        let pending_callback_account = CallbackAccount {
+            pubkey: ctx.accounts.pending_computation.key(),
+            is_writable: true,
+        };
+        let organization_callback_account = CallbackAccount {
+            pubkey: ctx.accounts.organization.key(),
+            is_writable: true,
+        };
+
+        let callback_ix = ProcessClaimV2Callback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                position_callback_account,
+                claim_auth_callback_account,
+                pending_callback_account,
+                organization_callback_account,
+            ],
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![callback_ix],
+            1,
+            0,
+        )?;
+
+        let claim_auth_mut = &mut ctx.accounts.claim_authorization;
+        claim_auth_mut.claim_amount = claim_amount;
+
+        // Reserve the claim against the vault so queued claims cannot over-commit it
+        ctx.accounts
+            .organization
+            .reserve_claim(claim_auth_mut, &ctx.accounts.vault.mint, ctx.accounts.vault.amount)?;
+
+        // Track the in-flight computation so it can be expired if the callback never lands
+        let claim_auth_key = claim_auth_mut.key();
+        ctx.accounts.pending_computation.start(
+            claim_auth_key,
+            ctx.accounts.computation_account.key(),
+            clock.unix_timestamp,
+            ctx.bumps.pending_computation,
+        );
+
+        emit!(ClaimProcessQueued {
+            position: claim_position,
+            position_id,
+            claim_amount,
+            computation_offset,
+            vesting_numerator,
+            priority_fee: 0,
+        });
+
+        Ok(())
+    }
+
+    /// Update the encrypted amounts on a compressed vesting position.
+    ///
+    /// Called after process_claim_v2_compressed_callback() confirms the claim is valid.
+    /// This updates the Light Protocol Merkle tree with the amounts and nonce the MPC
+    /// re-encrypted for this claim (recorded on the claim authorization by the callback).
+    /// new_is_fully_claimed must equal the flag the MPC revealed for this claim
+    /// (claim_authorization.compressed_is_fully_claimed).
+    ///
+    /// Can only be called when the associated ClaimAuthorization is_processed=true
+    /// and is_withdrawn=false (prevents unauthorized updates).
+    pub fn update_compressed_position_claimed<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateCompressedPositionClaimed<'info>>,
+        proof_bytes: Vec<u8>,
+        account_meta_bytes: Vec<u8>,
+        // Current compressed position data:
+        position_owner: Pubkey,
+        position_organization: Pubkey,
+        position_schedule: Pubkey,
+        position_id: u64,
+        beneficiary_commitment: [u8; 32],
+        encrypted_total_amount: [u8; 32],
+        encrypted_claimed_amount: [u8; 32],
+        position_nonce: u128,
+        position_start_timestamp: i64,
+        position_is_active: u8,
+        position_is_fully_claimed: u8,
+        position_is_stealth: u8,
+        position_metadata_commitment: [u8; 32],
+        // New values:
+        new_is_fully_claimed: u8,
+    ) -> Result<()> {
+        let claim_auth = &ctx.accounts.claim_authorization;
+        require!(claim_auth.is_authorized, ShadowVestError::ClaimNotAuthorized);
+        require!(claim_auth.is_processed, ShadowVestError::ClaimNotProcessed);
+        require!(!claim_auth.is_withdrawn, ShadowVestError::AlreadyWithdrawn);
+        claim_auth.check_fully_claimed_flag(new_is_fully_claimed)?;
+
+        // Verify position belongs to organization
+        require!(
+            position_organization == ctx.accounts.organization.key(),
+            ShadowVestError::InvalidPositionOrganization
+        );
+
+        // Deserialize Light Protocol types
+        let proof: ValidityProof = borsh::BorshDeserialize::try_from_slice(&proof_bytes)
+            .map_err(|_| ShadowVestError::LightProofDeserializationFailed)?;
+        let account_meta: CompressedAccountMeta =
+            borsh::BorshDeserialize::try_from_slice(&account_meta_bytes)
+                .map_err(|_| ShadowVestError::LightAccountMetaDeserializationFailed)?;
+
+        let cpi_accounts = CpiAccounts::new(
+            ctx.accounts.fee_payer.as_ref(),
+            ctx.remaining_accounts,
+            crate::LIGHT_CPI_SIGNER,
+        );
+
+        // Get the address from account_meta
+        let address = account_meta.address;
+
+        // Load existing compressed position for mutation
+        let mut compressed_position = LightAccount::<CompressedVestingPosition>::new_mut(
+            &crate::ID,
+            &account_meta,
+            CompressedVestingPosition {
+                owner: position_owner,
+                organization: position_organization,
+                schedule: position_schedule,
+                position_id,
+                beneficiary_commitment,
+                encrypted_total_amount,
+                encrypted_claimed_amount,
+                nonce: position_nonce,
+                start_timestamp: position_start_timestamp,
+                is_active: position_is_active,
+                is_fully_claimed: position_is_fully_claimed,
+                is_stealth: position_is_stealth,
+                metadata_commitment: position_metadata_commitment,
+            },
+        ).map_err(|_| ShadowVestError::LightAddressDerivationFailed)?;
+
+        // Store the re-encrypted amounts with their nonce, and the fully_claimed flag
+        let new_encrypted_claimed_amount = claim_auth.compressed_new_claimed_amount;
+        compressed_position.encrypted_total_amount = claim_auth.compressed_new_total_amount;
+        compressed_position.encrypted_claimed_amount = new_encrypted_claimed_amount;
+        compressed_position.nonce = claim_auth.compressed_new_nonce;
+        compressed_position.is_fully_claimed = new_is_fully_claimed;
+        ctx.accounts
+            .organization
+            .record_claim_progress(position_is_fully_claimed == 1, new_is_fully_claimed == 1)?;
+
+        // Execute Light Protocol CPI to commit the state transition
+        LightSystemProgramCpi::new_cpi(crate::LIGHT_CPI_SIGNER, proof)
+            .with_light_account(compressed_position)?
+            .invoke(cpi_accounts)
+            .map_err(|_| ShadowVestError::LightCpiInvokeFailed)?;
+
+        // Record the write so withdraw_compressed can check it against the position
+        let claim_auth = &mut ctx.accounts.claim_authorization;
+        claim_auth.compressed_claimed_amount = new_encrypted_claimed_amount;
+        claim_auth.compressed_is_fully_claimed = new_is_fully_claimed;
+
+        emit!(CompressedPositionClaimUpdated {
+            organization: ctx.accounts.organization.key(),
+            position_id,
+            address,
+            new_is_fully_claimed: new_is_fully_claimed == 1,
+        });
+
+        Ok(())
+    }
+
+    /// Revoke a compressed vesting position (admin only).
+    ///
+    /// Loads the position through Light Protocol like update_compressed_position_claimed
+    /// and commits it with is_active = 0, so authorize_claim_compressed rejects any
+    /// further claim. The revocation time is recorded in CompressedPositionRevoked.
+    pub fn revoke_compressed_position<'info>(
+        ctx: Context<'_, '_, '_, 'info, RevokeCompressedPosition<'info>>,
+        proof_bytes: Vec<u8>,
+        account_meta_bytes: Vec<u8>,
+        // Current compressed position data:
+        position_owner: Pubkey,
+        position_organization: Pubkey,
+        position_schedule: Pubkey,
+        position_id: u64,
+        beneficiary_commitment: [u8; 32],
+        encrypted_total_amount: [u8; 32],
+        encrypted_claimed_amount: [u8; 32],
+        position_nonce: u128,
+        position_start_timestamp: i64,
+        position_is_active: u8,
+        position_is_fully_claimed: u8,
+        position_is_stealth: u8,
+        position_metadata_commitment: [u8; 32],
+    ) -> Result<()> {
+        require!(
+            position_organization == ctx.accounts.organization.key(),
+            ShadowVestError::InvalidPositionOrganization
+        );
+        require!(position_is_active == 1, ShadowVestError::PositionAlreadyRevoked);
+
+        let address = deactivate_compressed_position(
+            ctx.accounts.admin.as_ref(),
+            ctx.remaining_accounts,
+            &proof_bytes,
+            &account_meta_bytes,
+            CompressedVestingPosition {
+                owner: position_owner,
+                organization: position_organization,
+                schedule: position_schedule,
+                position_id,
+                beneficiary_commitment,
+                encrypted_total_amount,
+                encrypted_claimed_amount,
+                nonce: position_nonce,
+                start_timestamp: position_start_timestamp,
+                is_active: position_is_active,
+                is_fully_claimed: position_is_fully_claimed,
+                is_stealth: position_is_stealth,
+                metadata_commitment: position_metadata_commitment,
+            },
+        )?;
+
+        emit!(CompressedPositionRevoked {
+            organization: ctx.accounts.organization.key(),
+            position_id,
+            address,
+            revoked_at: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Revoke a compressed vesting position and refund its unvested tokens (admin only).
+    ///
+    /// Deactivates the position like revoke_compressed_position and, in the same
+    /// instruction, transfers `amount` from the vault to the organization treasury.
+    /// Compressed amounts are encrypted, so the admin supplies the cleartext unvested
+    /// amount; it is bounded by the vault balance not reserved for processed claims.
+    pub fn revoke_compressed_and_refund<'info>(
+        ctx: Context<'_, '_, '_, 'info, RevokeCompressedAndRefund<'info>>,
+        proof_bytes: Vec<u8>,
+        account_meta_bytes: Vec<u8>,
+        // Current compressed position data:
+        position_owner: Pubkey,
+        position_organization: Pubkey,
+        position_schedule: Pubkey,
+        position_id: u64,
+        beneficiary_commitment: [u8; 32],
+        encrypted_total_amount: [u8; 32],
+        encrypted_claimed_amount: [u8; 32],
+        position_nonce: u128,
+        position_start_timestamp: i64,
+        position_is_active: u8,
+        position_is_fully_claimed: u8,
+        position_is_stealth: u8,
+        position_metadata_commitment: [u8; 32],
+        amount: u64,
+    ) -> Result<()> {
+        require!(
+            position_organization == ctx.accounts.organization.key(),
+            ShadowVestError::InvalidPositionOrganization
+        );
+        require!(
+            position_schedule == ctx.accounts.schedule.key(),
+            ShadowVestError::InvalidScheduleParams
+        );
+        require!(position_is_active == 1, ShadowVestError::PositionAlreadyRevoked);
+        ctx.accounts
+            .organization
+            .check_unreserved(ctx.accounts.vault.amount, &ctx.accounts.vault.mint, amount)?;
+
+        let address = deactivate_compressed_position(
+            ctx.accounts.admin.as_ref(),
+            ctx.remaining_accounts,
+            &proof_bytes,
+            &account_meta_bytes,
+            CompressedVestingPosition {
+                owner: position_owner,
+                organization: position_organization,
+                schedule: position_schedule,
+                position_id,
+                beneficiary_commitment,
+                encrypted_total_amount,
+                encrypted_claimed_amount,
+                nonce: position_nonce,
+                start_timestamp: position_start_timestamp,
+                is_active: position_is_active,
+                is_fully_claimed: position_is_fully_claimed,
+                is_stealth: position_is_stealth,
+                metadata_commitment: position_metadata_commitment,
+            },
+        )?;
+
+        let org_key = ctx.accounts.organization.key();
+        let bump = ctx.bumps.vault_authority;
+        let vault_authority_seeds: &[&[u8]] = &[
+            b"vault_authority",
+            org_key.as_ref(),
+            std::slice::from_ref(&bump),
+        ];
+        let signer_seeds = &[vault_authority_seeds];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.treasury_token_account.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::transfer(transfer_ctx, amount)?;
+
+        emit!(CompressedPositionRevokedAndRefunded {
+            organization: org_key,
+            position_id,
+            address,
+            treasury_token_account: ctx.accounts.treasury_token_account.key(),
+            amount,
+            revoked_at: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw tokens from the organization vault for a compressed position claim.
+    ///
+    /// Similar to withdraw() but uses ClaimAuthorization derived from compressed position seeds
+    /// (organization + position_id + nullifier) instead of regular position account key.
+    ///
+    /// With `verify_light_state` set, the compressed position is loaded via Light Protocol
+    /// (validity proof + Light accounts in remaining_accounts, `position_bytes` is the
+    /// account's raw data) and must hold what update_compressed_position_claimed wrote for
+    /// this claim. Without it the proof arguments are ignored and may be empty.
+    pub fn withdraw_compressed<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawCompressed<'info>>,
+        position_id: u64,
+        _nullifier: [u8; 32],
+        verify_light_state: bool,
+        proof_bytes: Vec<u8>,
+        account_meta_bytes: Vec<u8>,
+        position_bytes: Vec<u8>,
+    ) -> Result<()> {
+        require!(!ctx.accounts.organization.paused, ShadowVestError::OrganizationPaused);
+
+        let claim_auth = &ctx.accounts.claim_authorization;
+        require!(claim_auth.is_authorized, ShadowVestError::ClaimNotAuthorized);
+        require!(!claim_auth.is_revoked, ShadowVestError::ClaimRevoked);
+        require!(claim_auth.is_processed, ShadowVestError::ClaimNotProcessed);
+        require!(!claim_auth.is_withdrawn, ShadowVestError::AlreadyWithdrawn);
+        claim_auth.check_challenge_period(
+            ctx.accounts.organization.challenge_period,
+            Clock::get()?.unix_timestamp,
+        )?;
+        ctx.accounts.organization.check_guardian(
+            claim_auth.claim_amount,
+            ctx.accounts.guardian.as_ref().map(|guardian| guardian.key()),
+        )?;
+
+        if verify_light_state {
+            let proof: ValidityProof = borsh::BorshDeserialize::try_from_slice(&proof_bytes)
+                .map_err(|_| ShadowVestError::LightProofDeserializationFailed)?;
+            let account_meta: CompressedAccountMeta =
+                borsh::BorshDeserialize::try_from_slice(&account_meta_bytes)
+                    .map_err(|_| ShadowVestError::LightAccountMetaDeserializationFailed)?;
+            let position: CompressedVestingPosition =
+                borsh::BorshDeserialize::try_from_slice(&position_bytes)
+                    .map_err(|_| ShadowVestError::LightAccountMetaDeserializationFailed)?;
+
+            // authorize_claim_compressed recorded the position's address on the claim
+            require!(
+                Pubkey::new_from_array(account_meta.address) == claim_auth.position
+                    && position.organization == ctx.accounts.organization.key()
+                    && position.position_id == position_id,
+                ShadowVestError::InvalidPositionOrganization
+            );
+            claim_auth.check_compressed_state(
+                &position.encrypted_claimed_amount,
+                position.is_fully_claimed,
+            )?;
+
+            // Prove the position data is the current leaf (same data as output, no state change)
+            let cpi_accounts = CpiAccounts::new(
+                ctx.accounts.payer.as_ref(),
+                ctx.remaining_accounts,
+                crate::LIGHT_CPI_SIGNER,
+            );
+            let compressed_position =
+                LightAccount::<CompressedVestingPosition>::new_mut(&crate::ID, &account_meta, position)
+                    .map_err(|_| ShadowVestError::LightAddressDerivationFailed)?;
+            LightSystemProgramCpi::new_cpi(crate::LIGHT_CPI_SIGNER, proof)
+                .with_light_account(compressed_position)?
+                .invoke(cpi_accounts)
+                .map_err(|_| ShadowVestError::LightCpiInvokeFailed)?;
+        }
+        require!(
+            ctx.accounts.destination.key() == claim_auth.withdrawal_destination,
+            ShadowVestError::InvalidWithdrawalDestination
+        );
+        ctx.accounts.organization.check_withdrawal_destination(
+            &ctx.accounts.destination.key(),
+            &ctx.accounts.destination.owner,
+            &ctx.accounts.destination.mint,
+        )?;
+
+        let amount = claim_auth.remaining_amount();
+        ctx.accounts
+            .organization
+            .check_reserve(ctx.accounts.vault.amount, amount)?;
+
+        // Protocol fee (zero unless configured with set_withdrawal_fee)
+        let (fee, net_amount) = ctx.accounts.organization.split_withdrawal_fee(amount)?;
+
+        let org_key = ctx.accounts.organization.key();
+        let bump = ctx.bumps.vault_authority;
+        let vault_authority_seeds: &[&[u8]] = &[
+            b"vault_authority",
+            org_key.as_ref(),
+            std::slice::from_ref(&bump),
+        ];
+        let signer_seeds = &[vault_authority_seeds];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::transfer(transfer_ctx, net_amount)?;
+
+        transfer_withdrawal_fee(
+            &ctx.accounts.organization,
+            fee,
+            ctx.accounts.fee_destination.as_ref(),
+            &ctx.accounts.vault,
+            &ctx.accounts.vault_authority,
+            &ctx.accounts.token_program,
+            signer_seeds,
+        )?;
+
+        ctx.accounts.vault.reload()?;
+
+        let claim_auth_mut = &mut ctx.accounts.claim_authorization;
+        claim_auth_mut.withdrawn_so_far = claim_auth_mut.claim_amount;
+        claim_auth_mut.is_withdrawn = true;
+        ctx.accounts.organization.release_claim(claim_auth_mut, amount);
+        ctx.accounts.organization.record_withdrawal()?;
+
+        emit!(ClaimWithdrawn {
+            position: claim_auth_mut.position,
+            destination: claim_auth_mut.withdrawal_destination,
+            amount,
+            fee,
+            remaining_balance: ctx.accounts.vault.amount,
+            total_withdrawals: ctx.accounts.organization.total_withdrawals,
+            total_claimed_positions: ctx.accounts.organization.total_claimed_positions,
+            token_mint: ctx.accounts.vault.mint,
+        });
+
+        // Compressed positions are not loaded here; authorize_claim_compressed
+        // recorded the stealth address on the authorization
+        if claim_auth_mut.stealth_address != Pubkey::default() {
+            emit!(StealthWithdrawalEvent {
+                stealth_address: claim_auth_mut.stealth_address,
+                destination: claim_auth_mut.withdrawal_destination,
+                amount,
+                token_mint: ctx.accounts.vault.mint,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+        }
+
+        Ok(())
+    }
+
+    // ============================================================
+    // Stealth Address Management
+    // ============================================================
+
+    /// Register stealth meta-address (S, V) for an employee.
+    /// Employees call this to publish their public stealth keys.
+    /// Employers fetch these to derive one-time stealth addresses for payments.
+    /// `label` selects one of the owner's meta-addresses (e.g. one per employer);
+    /// the zero label is the default address.
+    pub fn register_stealth_meta(
+        ctx: Context<RegisterStealthMeta>,
+        label: [u8; 16],
+        spend_pubkey: [u8; 32],
+        view_pubkey: [u8; 32],
+    ) -> Result<()> {
+        let meta = &mut ctx.accounts.stealth_meta;
+        let clock = Clock::get()?;
+
+        meta.owner = ctx.accounts.owner.key();
+        meta.spend_pubkey = spend_pubkey;
+        meta.view_pubkey = view_pubkey;
+        meta.is_active = true;
+        meta.registered_at = clock.unix_timestamp;
+        meta.delegate = None;
+        meta.bump = ctx.bumps.stealth_meta;
+
+        emit!(StealthMetaRegistered {
+            owner: meta.owner,
+            label,
+            spend_pubkey,
+            view_pubkey,
+            registered_at: meta.registered_at,
+        });
+
+        Ok(())
+    }
+
+    /// Update stealth meta-address keys.
+    /// Allows employee (or their delegate) to rotate their stealth keys.
+    pub fn update_stealth_meta(
+        ctx: Context<UpdateStealthMeta>,
+        label: [u8; 16],
+        spend_pubkey: [u8; 32],
+        view_pubkey: [u8; 32],
+    ) -> Result<()> {
+        let meta = &mut ctx.accounts.stealth_meta;
+
+        require!(meta.is_active, ShadowVestError::StealthMetaNotActive);
+
+        meta.spend_pubkey = spend_pubkey;
+        meta.view_pubkey = view_pubkey;
+
+        emit!(StealthMetaUpdated {
+            owner: meta.owner,
+            label,
+            spend_pubkey,
+            view_pubkey,
+        });
+
+        Ok(())
+    }
+
+    /// Deactivate stealth meta-address.
+    /// Employee (or their delegate) can deactivate to stop receiving stealth payments.
+    pub fn deactivate_stealth_meta(ctx: Context<DeactivateStealthMeta>, label: [u8; 16]) -> Result<()> {
+        let meta = &mut ctx.accounts.stealth_meta;
+
+        require!(meta.is_active, ShadowVestError::StealthMetaNotActive);
+
+        meta.is_active = false;
+
+        emit!(StealthMetaDeactivated {
+            owner: meta.owner,
+            label,
+        });
+
+        Ok(())
+    }
+
+    /// Record that an employer looked up a meta-address to build a stealth payment.
+    /// Makes no state change: it requires the meta-address to be active and emits
+    /// the (S, V) keys it holds, giving the employer a verifiable on-chain record
+    /// of which key version they paid to.
+    pub fn quote_stealth_payment(ctx: Context<QuoteStealthPayment>, label: [u8; 16]) -> Result<()> {
+        let meta = &ctx.accounts.stealth_meta;
+
+        require!(meta.is_active, ShadowVestError::StealthMetaNotActive);
+
+        emit!(StealthMetaQuoted {
+            owner: meta.owner,
+            label,
+            spend_pubkey: meta.spend_pubkey,
+            view_pubkey: meta.view_pubkey,
+            quoted_at: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Set or clear the delegate allowed to rotate and deactivate a meta-address.
+    /// Lets an employee keep the meta-address owned by their primary identity
+    /// while a custodian wallet manages the keys. Only the owner can call this,
+    /// so a delegate cannot replace itself.
+    pub fn set_stealth_delegate(
+        ctx: Context<SetStealthDelegate>,
+        label: [u8; 16],
+        delegate: Option<Pubkey>,
+    ) -> Result<()> {
+        let meta = &mut ctx.accounts.stealth_meta;
+        meta.delegate = delegate;
+
+        emit!(StealthDelegateSet {
+            owner: meta.owner,
+            label,
+            delegate,
+        });
+
+        Ok(())
+    }
+
+    // ============================================================
+    // MPC Meta-Keys Vault (Optional Secure Storage)
+    // ============================================================
+
+    /// Initialize MPC computation definition for store_meta_keys
+    pub fn init_store_meta_keys_comp_def(ctx: Context<InitStoreMetaKeysCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://wajsatfcmlfkijmawyuq.supabase.co/storage/v1/object/public/init_position/store_meta_keys.arcis".to_string(),
+                hash: circuit_hash!("store_meta_keys"),
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    /// Initialize MPC computation definition for fetch_meta_keys
+    pub fn init_fetch_meta_keys_comp_def(ctx: Context<InitFetchMetaKeysCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://wajsatfcmlfkijmawyuq.supabase.co/storage/v1/object/public/init_position/fetch_meta_keys.arcis".to_string(),
+                hash: circuit_hash!("fetch_meta_keys"),
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    /// Store meta-keys in MPC vault.
+    /// Employee's private keys are encrypted via Arcium MPC.
+    /// Only the MPC cluster can decrypt; re-encrypts specifically for user on read.
+    pub fn write_meta_keys_to_vault(
+        ctx: Context<WriteMetaKeysToVault>,
+        computation_offset: u64,
+        encrypted_spend_lo: [u8; 32],
+        encrypted_spend_hi: [u8; 32],
+        encrypted_view_lo: [u8; 32],
+        encrypted_view_hi: [u8; 32],
+        pubkey: [u8; 32],
+        nonce: u128,
+        mxe_nonce: u128,
+    ) -> Result<()> {
+        // Refuse to overwrite a vault whose previous write hasn't settled
+        ctx.accounts.meta_keys_vault.check_writable()?;
+
+        // Capture keys first before mutable borrow
+        let owner_key = ctx.accounts.owner.key();
+        let vault_key = ctx.accounts.meta_keys_vault.key();
+
+        // Initialize vault
+        {
+            let vault = &mut ctx.accounts.meta_keys_vault;
+            vault.owner = owner_key;
+            vault.ciphertexts = [
+                encrypted_spend_lo,
+                encrypted_spend_hi,
+                encrypted_view_lo,
+                encrypted_view_hi,
+            ];
+            vault.nonce = nonce;
+            vault.is_initialized = false; // Will be set true in callback
+            vault.pending_write = true; // Cleared in callback
+            vault.bump = ctx.bumps.meta_keys_vault;
+        }
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        // Queue MPC computation to re-encrypt with MXE key
+        // Circuit: write_meta_keys(user_input: Enc<Shared, MetaKeys>, mxe: Mxe) -> Enc<Mxe, MetaKeys>
+        let args = ArgBuilder::new()
+            // Enc<Shared, MetaKeys> - user's encrypted keys
+            .x25519_pubkey(pubkey)
+            .plaintext_u128(nonce)
+            .encrypted_u128(encrypted_spend_lo)
+            .encrypted_u128(encrypted_spend_hi)
+            .encrypted_u128(encrypted_view_lo)
+            .encrypted_u128(encrypted_view_hi)
+            // Mxe parameter - nonce for MXE re-encryption
+            .plaintext_u128(mxe_nonce)
+            .build();
+
+        let vault_callback_account = CallbackAccount {
+            pubkey: vault_key,
+            is_writable: true,
+        };
+
+        let callback_ix = StoreMetaKeysCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[vault_callback_account],
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![callback_ix],
+            1,
+            0,
+        )?;
+
+        emit!(MetaKeysVaultCreated {
+            owner: owner_key,
+            vault: vault_key,
+        });
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "store_meta_keys")]
+    pub fn store_meta_keys_callback(
+        ctx: Context<StoreMetaKeysCallback>,
+        output: SignedComputationOutputs<StoreMetaKeysOutput>,
+    ) -> Result<()> {
+        let verified = output
+            .verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account)
+            .map_err(|_| ErrorCode::AbortedComputation)?;
+        mpc_output::require_ciphertexts(&verified.field_0.ciphertexts, 4)?;
+
+        let vault = &mut ctx.accounts.meta_keys_vault;
+
+        // Store MXE-encrypted ciphertexts and nonce
+        vault.ciphertexts[0] = verified.field_0.ciphertexts[0];
+        vault.ciphertexts[1] = verified.field_0.ciphertexts[1];
+        vault.ciphertexts[2] = verified.field_0.ciphertexts[2];
+        vault.ciphertexts[3] = verified.field_0.ciphertexts[3];
+        vault.nonce = verified.field_0.nonce;
+        vault.is_initialized = true;
+        vault.pending_write = false;
+        vault.version = vault
+            .version
+            .checked_add(1)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+
+        emit!(MetaKeysVaultInitialized {
+            owner: vault.owner,
+            vault: vault.key(),
+            version: vault.version,
+        });
+
+        Ok(())
+    }
+
+    /// Read meta-keys from MPC vault.
+    /// MPC re-encrypts stored keys specifically for the requesting user.
+    /// If `expected_version` is set, the read fails with MetaKeysVersionMismatch
+    /// when the keys were re-stored since the caller last saw them.
+    pub fn read_meta_keys_from_vault(
+        ctx: Context<ReadMetaKeysFromVault>,
+        computation_offset: u64,
+        pubkey: [u8; 32],
+        nonce: u128,
+        expected_version: Option<u32>,
+    ) -> Result<()> {
+        let vault_key = ctx.accounts.meta_keys_vault.key();
+        let vault = MetaKeysVault::load(&ctx.accounts.meta_keys_vault)?;
+
+        require!(
+            vault.owner == ctx.accounts.owner.key(),
+            ShadowVestError::UnauthorizedOwner
+        );
+        vault.check_readable()?;
+        vault.check_version(expected_version)?;
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        // Queue MPC computation to re-encrypt for user
+        // Circuit: read_meta_keys(requester: Shared, stored_keys: Enc<Mxe, MetaKeys>) -> Enc<Shared, MetaKeys>
+        let args = ArgBuilder::new()
+            // Shared - requester's x25519 pubkey and nonce
+            .x25519_pubkey(pubkey)
+            .plaintext_u128(nonce)
+            // Enc<Mxe, MetaKeys> - nonce + account data
+            // Account layout: 8 (discriminator) + 32 (owner) + 128 (ciphertexts)
+            .plaintext_u128(vault.nonce)
+            .account(vault_key, 40, 128) // Skip discriminator + owner, read 4 x 32 bytes ciphertexts
+            .build();
+
+        let vault_callback_account = CallbackAccount {
+            pubkey: vault_key,
+            is_writable: false,
+        };
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![FetchMetaKeysCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[vault_callback_account],
+            )?],
+            1,
+            0,
+        )?;
+
+        emit!(MetaKeysReadRequested {
+            owner: vault.owner,
+            vault: vault_key,
+            computation_offset,
+        });
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "fetch_meta_keys")]
+    pub fn fetch_meta_keys_callback(
+        ctx: Context<FetchMetaKeysCallback>,
+        output: SignedComputationOutputs<FetchMetaKeysOutput>,
+    ) -> Result<()> {
+        let verified = output
+            .verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account)
+            .map_err(|_| ErrorCode::AbortedComputation)?;
+        mpc_output::require_ciphertexts(&verified.field_0.ciphertexts, 4)?;
+
+        let vault = &ctx.accounts.meta_keys_vault;
+
+        // Emit event with re-encrypted keys for the user to decrypt
+        emit!(MetaKeysRetrieved {
+            owner: vault.owner,
+            vault: vault.key(),
+            encrypted_spend_lo: verified.field_0.ciphertexts[0],
+            encrypted_spend_hi: verified.field_0.ciphertexts[1],
+            encrypted_view_lo: verified.field_0.ciphertexts[2],
+            encrypted_view_hi: verified.field_0.ciphertexts[3],
+            nonce: verified.field_0.nonce.to_le_bytes(),
+        });
+
+        Ok(())
+    }
+
+    /// Close the owner's meta-keys vault, e.g. after rotating stealth keys.
+    /// The stored ciphertexts are wiped before the PDA is closed and its rent
+    /// refunded to the owner.
+    pub fn close_meta_keys_vault(ctx: Context<CloseMetaKeysVault>) -> Result<()> {
+        let vault = &mut ctx.accounts.meta_keys_vault;
+        vault.ciphertexts = [[0u8; 32]; 4];
+        vault.nonce = 0;
+        vault.is_initialized = false;
+        vault.pending_write = false;
+
+        emit!(MetaKeysVaultClosed {
+            owner: vault.owner,
+            vault: vault.key(),
+        });
+
+        Ok(())
+    }
+
+    // ============================================================
+    // Groth16 ZK Proof Verification (Noir Circuits)
+    // ============================================================
+
+    /// Store a verification key on-chain for a specific Noir circuit.
+    ///
+    /// Only the program's upgrade authority can store VKs, so the circuit ids
+    /// claims rely on (e.g. sha256("eligibility")) cannot be taken by anyone else.
+    /// The VK is derived from the circuit's trusted setup and contains
+    /// the parameters needed for Groth16 proof verification.
+    ///
+    /// Each circuit type (withdrawal_proof, identity_proof, eligibility)
+    /// has a unique circuit_id derived as sha256(circuit_name).
+    ///
+    /// # Arguments
+    /// * `circuit_id` - 32-byte identifier for the circuit
+    /// * `proof_type` - Circuit type the VK verifies; the only type proofs
+    ///   against it can be recorded as. The VK must take that type's public inputs.
+    /// * `vk_data` - Serialized VerificationKey bytes
+    pub fn store_verification_key(
+        ctx: Context<StoreVerificationKey>,
+        circuit_id: [u8; 32],
+        proof_type: ProofType,
+        vk_data: Vec<u8>,
+    ) -> Result<()> {
+        require!(
+            vk_data.len() <= VerificationKeyAccount::MAX_VK_DATA_SIZE,
+            ShadowVestError::InvalidVerificationKeyData
+        );
+
+        // Validate the VK data can be deserialized and fits the circuit type
+        let vk: VerificationKey = AnchorDeserialize::try_from_slice(&vk_data)
+            .map_err(|_| ShadowVestError::InvalidVerificationKeyData)?;
+        VerificationKeyAccount::check_arity(&vk, &proof_type)?;
+
+        let vk_account = &mut ctx.accounts.vk_account;
+        vk_account.authority = ctx.accounts.authority.key();
+        vk_account.circuit_id = circuit_id;
+        vk_account.vk_data = vk_data;
+        vk_account.is_active = true;
+        vk_account.proof_type = proof_type;
+        vk_account.bump = ctx.bumps.vk_account;
+
+        emit!(VerificationKeyStored {
+            authority: vk_account.authority,
+            circuit_id,
+            vk_account: vk_account.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Update a verification key (e.g., after a new trusted setup).
+    ///
+    /// Only the original authority can update. This allows key rotation
+    /// without changing the circuit_id PDA.
+    pub fn update_verification_key(
+        ctx: Context<UpdateVerificationKey>,
+        vk_data: Vec<u8>,
+    ) -> Result<()> {
+        require!(
+            vk_data.len() <= VerificationKeyAccount::MAX_VK_DATA_SIZE,
+            ShadowVestError::InvalidVerificationKeyData
+        );
+
+        // Validate the VK data can be deserialized
+        let _vk: VerificationKey = AnchorDeserialize::try_from_slice(&vk_data)
+            .map_err(|_| ShadowVestError::InvalidVerificationKeyData)?;
+
+        let vk_account = &mut ctx.accounts.vk_account;
+        vk_account.vk_data = vk_data;
+
+        emit!(VerificationKeyUpdated {
+            circuit_id: vk_account.circuit_id,
+            vk_account: vk_account.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Activate or deactivate a verification key.
+    ///
+    /// Only the original authority can toggle. Deactivating disables a
+    /// compromised trusted-setup VK without deleting it: every verify
+    /// instruction requires an active key.
+    pub fn set_vk_active(ctx: Context<SetVkActive>, active: bool) -> Result<()> {
+        let vk_account = &mut ctx.accounts.vk_account;
+        vk_account.is_active = active;
+
+        emit!(VerificationKeyActiveChanged {
+            circuit_id: vk_account.circuit_id,
+            vk_account: vk_account.key(),
+            is_active: active,
+        });
+
+        Ok(())
+    }
+
+    /// Emit a stored verification key in structured form.
+    ///
+    /// Read-only: clients simulate this to fetch the VK components (and a
+    /// sha256 of the raw vk_data) for off-chain pre-verification, without
+    /// re-implementing the vk_data serialization format.
+    pub fn export_verification_key(ctx: Context<ExportVerificationKey>) -> Result<()> {
+        let vk_account = &ctx.accounts.vk_account;
+        let vk = vk_account.deserialize_vk()?;
+
+        emit!(VerificationKeyExported {
+            circuit_id: vk_account.circuit_id,
+            vk_account: vk_account.key(),
+            is_active: vk_account.is_active,
+            vk_hash: hash(&vk_account.vk_data).to_bytes(),
+            alpha_g1: vk.alpha_g1,
+            beta_g2: vk.beta_g2,
+            gamma_g2: vk.gamma_g2,
+            delta_g2: vk.delta_g2,
+            ic: vk.ic,
+        });
+
+        Ok(())
+    }
+
+    /// Verify a withdrawal proof on-chain.
+    ///
+    /// Performs Groth16 verification using the stored VK for the withdrawal circuit.
+    /// On success, creates a ProofRecord PDA that other instructions can reference.
+    ///
+    /// The withdrawal proof demonstrates:
+    /// - Knowledge of a valid vesting position in the state tree
+    /// - The position is in the correct epoch
+    /// - The nullifier has not been used before
+    /// - The prover is entitled to the withdrawal
+    ///
+    /// `ttl_seconds` bounds how long the record attests the proof
+    /// (proof_valid_until = now + ttl_seconds; 0 never expires). The same
+    /// argument applies to every verify instruction.
+    ///
+    /// Requires ~1,400,000 compute units (pairing is expensive).
+    pub fn verify_withdrawal_proof(
+        ctx: Context<VerifyWithdrawalProof>,
+        proof: Groth16Proof,
+        public_inputs: WithdrawalPublicInputs,
+        ttl_seconds: u32,
+    ) -> Result<()> {
+        verify_and_record_proof(
+            &ctx.accounts.vk_account,
+            &mut ctx.accounts.proof_record,
+            ctx.accounts.verifier.key(),
+            ctx.bumps.proof_record,
+            ProofType::Withdrawal,
+            &proof,
+            &public_inputs.to_scalars(),
+            ttl_seconds,
+        )
+    }
+
+    /// Verify any proof type through one instruction.
+    ///
+    /// `public_inputs` are the circuit's raw 32-byte scalars in circuit order;
+    /// `proof_type` selects which of them is the nullifier keying the
+    /// ProofRecord (see ProofType::nullifier_index) and must be the type the
+    /// VK was stored for, so one proof cannot be recorded under several of its
+    /// inputs. verify_withdrawal_proof,
+    /// verify_identity_proof and verify_eligibility_proof are typed wrappers
+    /// over the same path.
+    pub fn verify_proof(
+        ctx: Context<VerifyProof>,
+        proof_type: ProofType,
+        proof: Groth16Proof,
+        public_inputs: Vec<[u8; 32]>,
+        ttl_seconds: u32,
+    ) -> Result<()> {
+        verify_and_record_proof(
+            &ctx.accounts.vk_account,
+            &mut ctx.accounts.proof_record,
+            ctx.accounts.verifier.key(),
+            ctx.bumps.proof_record,
+            proof_type,
+            &proof,
+            &public_inputs,
+            ttl_seconds,
+        )
+    }
+
+    /// Verify up to MAX_BATCH_PROOFS withdrawal proofs with a single aggregated
+    /// pairing check (see groth16_verifier::verify_groth16_batch).
+    ///
+    /// ProofRecord PDAs are passed in remaining_accounts in the same order as the
+    /// proofs, keyed by [b"proof_record", verifier, nullifier], and are created
+    /// here. If any proof in the batch is invalid the whole batch fails and no
+    /// record is created. The VK must be a withdrawal-circuit key.
+    pub fn verify_proofs_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, VerifyProofsBatch<'info>>,
+        proofs: Vec<Groth16Proof>,
+        inputs: Vec<WithdrawalPublicInputs>,
+        ttl_seconds: u32,
+    ) -> Result<()> {
+        let vk_account = &ctx.accounts.vk_account;
+        require!(vk_account.is_active, ShadowVestError::VerificationKeyNotActive);
+        vk_account.check_proof_type(&ProofType::Withdrawal)?;
+        require!(
+            ctx.remaining_accounts.len() == proofs.len(),
+            ShadowVestError::InvalidBatchSize
+        );
+
+        let vk = vk_account.deserialize_vk()?;
+        let scalars: Vec<Vec<[u8; 32]>> = inputs.iter().map(|i| i.to_scalars()).collect();
+
+        let is_valid = groth16_verifier::verify_groth16_batch(&vk, &proofs, &scalars)?;
+        require!(is_valid, ShadowVestError::ProofVerificationFailed);
+
+        let clock = Clock::get()?;
+        let rent = Rent::get()?;
+        let verifier_key = ctx.accounts.verifier.key();
+        let proof_valid_until = ProofRecord::valid_until(clock.unix_timestamp, ttl_seconds)?;
+
+        for (public_inputs, record_info) in inputs.iter().zip(ctx.remaining_accounts.iter()) {
+            let (expected_record, bump) = Pubkey::find_program_address(
+                &[ProofRecord::SEED_PREFIX, verifier_key.as_ref(), public_inputs.nullifier.as_ref()],
+                &crate::ID,
+            );
+            require!(
+                record_info.key() == expected_record,
+                ShadowVestError::InvalidBatchProofRecordAccount
+            );
+
+            let signer_seeds: &[&[u8]] = &[
+                ProofRecord::SEED_PREFIX,
+                verifier_key.as_ref(),
+                public_inputs.nullifier.as_ref(),
+                std::slice::from_ref(&bump),
+            ];
+            anchor_lang::system_program::create_account(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::CreateAccount {
+                        from: ctx.accounts.verifier.to_account_info(),
+                        to: record_info.clone(),
+                    },
+                    &[signer_seeds],
+                ),
+                rent.minimum_balance(ProofRecord::SIZE),
+                ProofRecord::SIZE as u64,
+                &crate::ID,
+            )?;
+
+            let proof_record = ProofRecord {
+                verifier: verifier_key,
+                circuit_id: vk_account.circuit_id,
+                nullifier: public_inputs.nullifier,
+                verified_at: clock.unix_timestamp,
+                is_valid: true,
+                proof_valid_until,
+                position_id: [0u8; 32],
+                beneficiary_commitment: [0u8; 32],
+                withdrawal_destination: [0u8; 32],
+                bump,
+            };
+            {
+                let mut data = record_info.try_borrow_mut_data()?;
+                let mut writer: &mut [u8] = &mut data;
+                proof_record.try_serialize(&mut writer)?;
+            }
+
+            emit!(ProofVerified {
+                verifier: verifier_key,
+                circuit_id: vk_account.circuit_id,
+                nullifier: public_inputs.nullifier,
+                proof_type: ProofType::Withdrawal,
+                verified_at: clock.unix_timestamp,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Verify an identity proof on-chain.
+    ///
+    /// The identity proof demonstrates knowledge of the secret behind
+    /// a position commitment, without revealing the secret itself.
+    /// This is used to prove ownership of a vesting position.
+    ///
+    /// Creates a ProofRecord keyed by position_commitment as the nullifier.
+    pub fn verify_identity_proof(
+        ctx: Context<VerifyIdentityProof>,
+        proof: Groth16Proof,
+        public_inputs: IdentityPublicInputs,
+        ttl_seconds: u32,
+    ) -> Result<()> {
+        verify_and_record_proof(
+            &ctx.accounts.vk_account,
+            &mut ctx.accounts.proof_record,
+            ctx.accounts.verifier.key(),
+            ctx.bumps.proof_record,
+            ProofType::Identity,
+            &proof,
+            &public_inputs.to_scalars(),
+            ttl_seconds,
+        )
+    }
+
+    /// Verify an eligibility proof on-chain.
+    ///
+    /// The eligibility proof demonstrates:
+    /// - The prover is the designated beneficiary of a position
+    /// - The nullifier has not been used (prevents double-claim)
+    /// - The prover knows the opening to the position commitment
+    ///
+    /// This is the primary proof used in the claim-withdraw flow,
+    /// replacing or augmenting the Ed25519 signature verification.
+    pub fn verify_eligibility_proof(
+        ctx: Context<VerifyEligibilityProof>,
+        proof: Groth16Proof,
+        public_inputs: EligibilityPublicInputs,
+        ttl_seconds: u32,
+    ) -> Result<()> {
+        verify_and_record_proof(
+            &ctx.accounts.vk_account,
+            &mut ctx.accounts.proof_record,
+            ctx.accounts.verifier.key(),
+            ctx.bumps.proof_record,
+            ProofType::Eligibility,
+            &proof,
+            &public_inputs.to_scalars(),
+            ttl_seconds,
+        )
+    }
+
+    /// Close a ProofRecord and return its rent to the verifier who created it.
+    ///
+    /// Records are keyed by [verifier, nullifier], so closing one also lets the
+    /// verifier verify the same nullifier again later.
+    pub fn close_proof_record(ctx: Context<CloseProofRecord>) -> Result<()> {
+        let proof_record = &ctx.accounts.proof_record;
+
+        emit!(ProofRecordClosed {
+            proof_record: proof_record.key(),
+            verifier: proof_record.verifier,
+            nullifier: proof_record.nullifier,
+            closed_by: ctx.accounts.verifier.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Close a ProofRecord attested by a deactivated verification key.
+    ///
+    /// Lets the VK authority clean up records of an invalidated circuit. Rent
+    /// still goes back to the verifier who paid for the record.
+    pub fn close_invalidated_proof_record(ctx: Context<CloseInvalidatedProofRecord>) -> Result<()> {
+        require!(
+            !ctx.accounts.vk_account.is_active,
+            ShadowVestError::VerificationKeyStillActive
+        );
+        let proof_record = &ctx.accounts.proof_record;
+
+        emit!(ProofRecordClosed {
+            proof_record: proof_record.key(),
+            verifier: proof_record.verifier,
+            nullifier: proof_record.nullifier,
+            closed_by: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+}
+
+// ============================================================
+// Proof Verification
+// ============================================================
+
+/// Verify a Groth16 proof against a stored VK and fill in its ProofRecord.
+/// Shared by verify_proof and the per-circuit verify instructions. The proof
+/// type must be the one the VK was stored for.
+#[allow(clippy::too_many_arguments)]
+fn verify_and_record_proof(
+    vk_account: &VerificationKeyAccount,
+    proof_record: &mut ProofRecord,
+    verifier: Pubkey,
+    bump: u8,
+    proof_type: ProofType,
+    proof: &Groth16Proof,
+    scalars: &[[u8; 32]],
+    ttl_seconds: u32,
+) -> Result<()> {
+    require!(vk_account.is_active, ShadowVestError::VerificationKeyNotActive);
+    vk_account.check_proof_type(&proof_type)?;
+
+    let nullifier = *scalars
+        .get(proof_type.nullifier_index())
+        .ok_or(ShadowVestError::InvalidPublicInputCount)?;
+
+    // Deserialize the verification key and perform Groth16 verification
+    let vk = vk_account.deserialize_vk()?;
+    let is_valid = groth16_verifier::verify_groth16(&vk, proof, scalars)?;
+    require!(is_valid, ShadowVestError::ProofVerificationFailed);
+
+    // Eligibility records keep the inputs authorize_claim_with_proof binds to
+    let (position_id, beneficiary_commitment, withdrawal_destination) = match proof_type {
+        ProofType::Eligibility => (scalars[2], scalars[0], scalars[4]),
+        _ => ([0u8; 32], [0u8; 32], [0u8; 32]),
+    };
+
+    // Create proof record
+    let clock = Clock::get()?;
+    proof_record.verifier = verifier;
+    proof_record.circuit_id = vk_account.circuit_id;
+    proof_record.nullifier = nullifier;
+    proof_record.verified_at = clock.unix_timestamp;
+    proof_record.is_valid = true;
+    proof_record.proof_valid_until = ProofRecord::valid_until(clock.unix_timestamp, ttl_seconds)?;
+    proof_record.position_id = position_id;
+    proof_record.beneficiary_commitment = beneficiary_commitment;
+    proof_record.withdrawal_destination = withdrawal_destination;
+    proof_record.bump = bump;
+
+    emit!(ProofVerified {
+        verifier,
+        circuit_id: proof_record.circuit_id,
+        nullifier,
+        proof_type,
+        verified_at: proof_record.verified_at,
+    });
+
+    Ok(())
+}
+
+// ============================================================
+// Claim Signature Verification
+// ============================================================
+
+/// Verify that the instruction immediately before the current one is a signature
+/// precompile check of `expected_message` by the position's beneficiary, using
+/// the Ed25519 or Secp256k1 precompile according to position.sig_scheme.
+fn verify_beneficiary_signature(
+    ix_sysvar: &AccountInfo,
+    position: &VestingPosition,
+    expected_message: &[u8],
+) -> Result<()> {
+    if position.sig_scheme == VestingPosition::SIG_SCHEME_SECP256K1 {
+        // Ethereum-style beneficiary: verify the preceding Secp256k1 instruction
+        let mut eth_address = [0u8; secp256k1::ETH_ADDRESS_SIZE];
+        eth_address.copy_from_slice(&position.beneficiary_commitment[..secp256k1::ETH_ADDRESS_SIZE]);
+        secp256k1::verify_secp256k1_claim_signature(ix_sysvar, &eth_address, expected_message)?;
+    } else {
+        // Stealth-address beneficiary: verify the preceding Ed25519 instruction
+        ed25519::verify_ed25519_claim_signature(
+            ix_sysvar,
+            &position.beneficiary_commitment,
+            expected_message,
+        )?;
+    }
+
+    Ok(())
+}
+
+// ============================================================
+// Withdrawal Fee
+// ============================================================
+
+/// Transfer the protocol fee portion of a withdrawal from the vault to the
+/// organization's fee destination. No-op when `fee` is zero, so organizations
+/// without a fee need not pass a fee_destination account.
+fn transfer_withdrawal_fee<'info>(
+    organization: &Organization,
+    fee: u64,
+    fee_destination: Option<&Account<'info, TokenAccount>>,
+    vault: &Account<'info, TokenAccount>,
+    vault_authority: &AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if fee == 0 {
+        return Ok(());
+    }
+
+    let fee_destination = fee_destination.ok_or(ShadowVestError::InvalidFeeDestination)?;
+    require!(
+        fee_destination.key() == organization.fee_destination,
+        ShadowVestError::InvalidFeeDestination
+    );
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: vault.to_account_info(),
+                to: fee_destination.to_account_info(),
+                authority: vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        fee,
+    )
+}
+
+// ============================================================
+// Withdrawal
+// ============================================================
+
+/// Checks every withdraw variant makes before paying out a claim: the organization
+/// is not paused, the claim is authorized, processed, not yet withdrawn and past its
+/// challenge period, the guardian co-signed if the claim needs it, and `destination`
+/// is the authorized withdrawal destination.
+fn check_withdrawable_claim(
+    organization: &Organization,
+    claim_auth: &ClaimAuthorization,
+    guardian: Option<Pubkey>,
+    destination: &Pubkey,
+) -> Result<()> {
+    require!(!organization.paused, ShadowVestError::OrganizationPaused);
+    require!(claim_auth.is_authorized, ShadowVestError::ClaimNotAuthorized);
+    require!(!claim_auth.is_revoked, ShadowVestError::ClaimRevoked);
+    require!(claim_auth.is_processed, ShadowVestError::ClaimNotProcessed);
+    require!(!claim_auth.is_withdrawn, ShadowVestError::AlreadyWithdrawn);
+    claim_auth.check_challenge_period(
+        organization.challenge_period,
+        Clock::get()?.unix_timestamp,
+    )?;
+    organization.check_guardian(claim_auth.claim_amount, guardian)?;
+    require!(
+        *destination == claim_auth.withdrawal_destination,
+        ShadowVestError::InvalidWithdrawalDestination
+    );
+    Ok(())
+}
+
+/// Move `amount` out of the vault without breaching the reserve floor: the protocol
+/// fee (zero unless configured with set_withdrawal_fee) to the fee destination and
+/// the rest to `to`. Reloads the vault afterwards. Returns (fee, net_amount).
+#[allow(clippy::too_many_arguments)]
+fn pay_out_withdrawal<'info>(
+    organization: &Organization,
+    amount: u64,
+    vault: &mut Account<'info, TokenAccount>,
+    to: AccountInfo<'info>,
+    fee_destination: Option<&Account<'info, TokenAccount>>,
+    vault_authority: &AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<(u64, u64)> {
+    organization.check_reserve(vault.amount, amount)?;
+    let (fee, net_amount) = organization.split_withdrawal_fee(amount)?;
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: vault.to_account_info(),
+                to,
+                authority: vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        net_amount,
+    )?;
+
+    transfer_withdrawal_fee(
+        organization,
+        fee,
+        fee_destination,
+        vault,
+        vault_authority,
+        token_program,
+        signer_seeds,
+    )?;
+
+    // Balance after the transfer and fee, reported in ClaimWithdrawn
+    vault.reload()?;
+    Ok((fee, net_amount))
+}
+
+/// Book a withdrawal of `amount` from a claim already marked with it: append it to
+/// the beneficiary's claim history, release it from the claim's and position's
+/// reservations, count it in the organization's statistics, settle the claim once
+/// fully withdrawn, and emit ClaimWithdrawn (plus StealthWithdrawalEvent, which lets
+/// the employee reconcile which stealth payments have been swept).
+#[allow(clippy::too_many_arguments)]
+fn record_claim_withdrawal(
+    organization: &mut Organization,
+    position: &mut Account<VestingPosition>,
+    claim_auth: &mut ClaimAuthorization,
+    claim_history: &mut BeneficiaryClaimHistory,
+    claim_history_bump: u8,
+    amount: u64,
+    fee: u64,
+    vault: &TokenAccount,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    claim_history.beneficiary_commitment = position.beneficiary_commitment;
+    claim_history.bump = claim_history_bump;
+    claim_history.record(position.key(), amount, clock.unix_timestamp);
+
+    organization.release_claim(claim_auth, amount);
+    organization.record_withdrawal()?;
+    organization.release_position_reserve(position, &vault.mint, amount);
+    if claim_auth.is_withdrawn {
+        position.settle_claim();
+    }
+
+    emit!(ClaimWithdrawn {
+        position: claim_auth.position,
+        destination: claim_auth.withdrawal_destination,
+        amount,
+        fee,
+        remaining_balance: vault.amount,
+        total_withdrawals: organization.total_withdrawals,
+        total_claimed_positions: organization.total_claimed_positions,
+        token_mint: vault.mint,
+    });
+
+    if position.is_stealth {
+        emit!(StealthWithdrawalEvent {
+            stealth_address: Pubkey::new_from_array(position.beneficiary_commitment),
+            destination: claim_auth.withdrawal_destination,
+            amount,
+            token_mint: vault.mint,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    Ok(())
+}
+
+// ============================================================
+// Claim Processing
+// ============================================================
+
+/// Checks queue_process_claim and queue_process_claims_batch apply before a
+/// regular position's claim is sent to the MPC. Returns the vesting numerator,
+/// computed on-chain from the position's vesting time (clamped to revoked_at so
+/// revoked positions stop accruing).
+fn check_queueable_claim(
+    position: &VestingPosition,
+    schedule: &VestingSchedule,
+    claim_auth: &ClaimAuthorization,
+    computation_pending: bool,
+    now: i64,
+) -> Result<u64> {
+    require!(claim_auth.is_authorized, ShadowVestError::ClaimNotAuthorized);
+    require!(!claim_auth.is_processed, ShadowVestError::ClaimNotProcessed);
+    require!(!computation_pending, ShadowVestError::ComputationPending);
+
+    require!(!position.is_paused, ShadowVestError::PositionPaused);
+    require!(position.is_claimable(), ShadowVestError::PositionNotActive);
+    require!(!schedule.claims_paused, ShadowVestError::ScheduleClaimsPaused);
+    schedule.validate()?;
+    position.check_claim_interval(now, schedule.min_claim_interval)?;
+
+    Ok(vesting::compute_vesting_numerator(
+        position.vesting_time(now),
+        position.start_timestamp,
+        schedule.cliff_duration,
+        schedule.total_duration,
+        schedule.vesting_interval,
+        schedule.vesting_mode,
+        schedule.precision,
+    ))
+}
+
+/// Apply one claim's process_claim_v2 or process_claims_batch result.
+///
+/// A valid claim marks the authorization processed and stores the re-encrypted
+/// amounts with their nonce: on the position, or on the authorization for a
+/// compressed claim, whose callback target is only a scratch position. An invalid
+/// claim (more than the claimable amount) leaves the amounts untouched, revokes
+/// the authorization so it can never be withdrawn and releases its reservation.
+#[allow(clippy::too_many_arguments)]
+fn apply_claim_result(
+    position_key: Pubkey,
+    position: &mut VestingPosition,
+    claim_auth: &mut ClaimAuthorization,
+    organization: &mut Organization,
+    amounts: &[[u8; 32]],
+    nonce: u128,
+    is_valid: bool,
+    fully_claimed: bool,
+) -> Result<()> {
+    let is_regular = claim_auth.position == position_key;
+
+    if !is_valid {
+        if is_regular && claim_auth.is_open() {
+            position.settle_claim();
+        }
+        claim_auth.is_authorized = false;
+        organization.release_claim(claim_auth, u64::MAX);
+        emit!(ClaimRejected {
+            position: position_key,
+            position_id: position.position_id,
+        });
+        return Ok(());
+    }
+
+    claim_auth.is_processed = true;
+
+    // Both amounts come back re-encrypted under a fresh nonce, so all three are
+    // stored together. The first processed claim activates the position and the
+    // one reaching the total fully claims it.
+    if is_regular {
+        position.encrypted_total_amount = amounts[0];
+        position.encrypted_claimed_amount = amounts[1];
+        position.nonce = nonce;
+        position.last_claim_at = Clock::get()?.unix_timestamp;
+        if position.apply_processed_claim(fully_claimed)? {
+            organization.record_claim_progress(false, true)?;
+        }
+    } else {
+        claim_auth.compressed_new_total_amount = amounts[0];
+        claim_auth.compressed_new_claimed_amount = amounts[1];
+        claim_auth.compressed_new_nonce = nonce;
+        claim_auth.compressed_is_fully_claimed = fully_claimed as u8;
+    }
+
+    emit!(ClaimProcessed {
+        position: position_key,
+        position_id: position.position_id,
+        claim_amount: claim_auth.claim_amount,
+        fully_claimed,
+    });
+
+    Ok(())
+}
+
+/// Deserialize a program-owned account passed without a typed wrapper (batch claims
+/// in remaining_accounts, legacy schedules in migrate_vesting_schedule)
+fn load_program_account<T: AccountDeserialize>(account: &AccountInfo) -> Result<T> {
+    require!(
+        account.owner == &crate::ID,
+        ShadowVestError::InvalidPositionOrganization
+    );
+    let data = account.try_borrow_data()?;
+    T::try_deserialize(&mut &data[..])
+}
+
+/// Write back an account read with load_program_account
+fn store_program_account<T: AccountSerialize>(account: &AccountInfo, value: &T) -> Result<()> {
+    let mut data = account.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data;
+    value.try_serialize(&mut writer)
+}
+
+// ============================================================
+// Claim Settlement
+// ============================================================
+
+/// Stop counting `claim_authorization` against its position's open claims before
+/// it is revoked or closed. Compressed positions have no account and no count; a
+/// regular position's claim must pass its position, or its merges would stay blocked.
+fn settle_open_claim(
+    claim_authorization: &Account<ClaimAuthorization>,
+    position: Option<&mut Account<VestingPosition>>,
+) -> Result<()> {
+    if !claim_authorization.is_open()
+        || !claim_authorization.is_regular(&claim_authorization.key())
+    {
+        return Ok(());
+    }
+    let position = position.ok_or(ShadowVestError::ClaimPositionRequired)?;
+    require_keys_eq!(
+        position.key(),
+        claim_authorization.position,
+        ShadowVestError::InvalidPositionOrganization
+    );
+    position.settle_claim();
+    Ok(())
+}
+
+// ============================================================
+// Compressed Position Address
+// ============================================================
+
+/// For the V2 API, Light system accounts are at indices 0-5 of remaining_accounts;
+/// tree accounts start at index 6 and packed tree indices are relative to them
+const V2_SYSTEM_ACCOUNTS_COUNT: usize = 6;
+
+/// Derive the Light Protocol address (and its seed) of compressed position
+/// `position_id`, using the address tree that `address_tree_info` points to.
+/// Seeds: [prefix, organization, position_id]
+fn derive_compressed_position_address(
+    remaining_accounts: &[AccountInfo],
+    address_tree_info: &PackedAddressTreeInfo,
+    organization: &Pubkey,
+    position_id: u64,
+) -> Result<([u8; 32], [u8; 32])> {
+    let tree_idx =
+        V2_SYSTEM_ACCOUNTS_COUNT + address_tree_info.address_merkle_tree_pubkey_index as usize;
+    let address_tree_pubkey = remaining_accounts
+        .get(tree_idx)
+        .ok_or(ShadowVestError::InvalidAddressTree)?
+        .key();
+
+    Ok(derive_address(
+        &[
+            CompressedVestingPosition::SEED_PREFIX,
+            organization.as_ref(),
+            &position_id.to_le_bytes(),
+        ],
+        &address_tree_pubkey,
+        &crate::ID,
+    ))
+}
+
+// ============================================================
+// Compressed Position Revocation
+// ============================================================
+
+/// Load a compressed position through Light Protocol and commit it with
+/// is_active = 0. Shared by revoke_compressed_position and
+/// revoke_compressed_and_refund; returns the position's address.
+fn deactivate_compressed_position<'info>(
+    fee_payer: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    proof_bytes: &[u8],
+    account_meta_bytes: &[u8],
+    position: CompressedVestingPosition,
+) -> Result<[u8; 32]> {
+    let proof: ValidityProof = borsh::BorshDeserialize::try_from_slice(proof_bytes)
+        .map_err(|_| ShadowVestError::LightProofDeserializationFailed)?;
+    let account_meta: CompressedAccountMeta =
+        borsh::BorshDeserialize::try_from_slice(account_meta_bytes)
+            .map_err(|_| ShadowVestError::LightAccountMetaDeserializationFailed)?;
+
+    let cpi_accounts = CpiAccounts::new(fee_payer, remaining_accounts, crate::LIGHT_CPI_SIGNER);
+
+    let address = account_meta.address;
+
+    let mut compressed_position =
+        LightAccount::<CompressedVestingPosition>::new_mut(&crate::ID, &account_meta, position)
+            .map_err(|_| ShadowVestError::LightAddressDerivationFailed)?;
+
+    compressed_position.deactivate();
+
+    LightSystemProgramCpi::new_cpi(crate::LIGHT_CPI_SIGNER, proof)
+        .with_light_account(compressed_position)?
+        .invoke(cpi_accounts)
+        .map_err(|_| ShadowVestError::LightCpiInvokeFailed)?;
+
+    Ok(address)
+}
+
+// ============================================================
+// Account Contexts - Position Creation
+// ============================================================
+
+#[queue_computation_accounts("init_position", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, beneficiary_commitment: [u8; 32])]
+pub struct CreateVestingPosition<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+    #[account(
+        mut,
+        seeds = [VestingSchedule::SEED_PREFIX, organization.key().as_ref(), schedule.schedule_id.to_le_bytes().as_ref()],
+        bump = schedule.bump,
+        constraint = schedule.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
+    )]
+    pub schedule: Account<'info, VestingSchedule>,
+    #[account(
+        init,
+        payer = payer,
+        space = VestingPosition::SIZE,
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), organization.position_count.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub position: Account<'info, VestingPosition>,
+    /// Rolling index of the organization's recent positions
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = PositionIndex::SIZE,
+        seeds = [PositionIndex::SEED_PREFIX, organization.key().as_ref()],
+        bump,
+    )]
+    pub position_index: Box<Account<'info, PositionIndex>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [b"ArciumSignerAccount"],
+        bump,
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_POSITION))]
+    pub comp_def_account: Box<Account<'info, ComputationDefinitionAccount>>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Box<Account<'info, Cluster>>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Box<Account<'info, FeePool>>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Box<Account<'info, ClockAccount>>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[queue_computation_accounts("init_position", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CreateVestingPositionCosigned<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// Beneficiary co-signing creation; its key must equal beneficiary_commitment
+    pub beneficiary: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+    #[account(
+        mut,
+        seeds = [VestingSchedule::SEED_PREFIX, organization.key().as_ref(), schedule.schedule_id.to_le_bytes().as_ref()],
+        bump = schedule.bump,
+        constraint = schedule.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
+    )]
+    pub schedule: Account<'info, VestingSchedule>,
+    #[account(
+        init,
+        payer = payer,
+        space = VestingPosition::SIZE,
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), organization.position_count.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub position: Account<'info, VestingPosition>,
+    /// Rolling index of the organization's recent positions
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = PositionIndex::SIZE,
+        seeds = [PositionIndex::SEED_PREFIX, organization.key().as_ref()],
+        bump,
+    )]
+    pub position_index: Box<Account<'info, PositionIndex>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [b"ArciumSignerAccount"],
+        bump,
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_POSITION))]
+    pub comp_def_account: Box<Account<'info, ComputationDefinitionAccount>>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Box<Account<'info, Cluster>>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Box<Account<'info, FeePool>>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Box<Account<'info, ClockAccount>>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[queue_computation_accounts("init_position", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CreateScheduleWithPosition<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Box<Account<'info, Organization>>,
+    #[account(
+        init,
+        payer = payer,
+        space = VestingSchedule::SIZE,
+        seeds = [VestingSchedule::SEED_PREFIX, organization.key().as_ref(), organization.schedule_count.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub schedule: Box<Account<'info, VestingSchedule>>,
+    #[account(
+        init,
+        payer = payer,
+        space = VestingPosition::SIZE,
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), organization.position_count.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub position: Box<Account<'info, VestingPosition>>,
+    /// Rolling index of the organization's recent positions
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = PositionIndex::SIZE,
+        seeds = [PositionIndex::SEED_PREFIX, organization.key().as_ref()],
+        bump,
+    )]
+    pub position_index: Box<Account<'info, PositionIndex>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [b"ArciumSignerAccount"],
+        bump,
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_POSITION))]
+    pub comp_def_account: Box<Account<'info, ComputationDefinitionAccount>>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Box<Account<'info, Cluster>>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Box<Account<'info, FeePool>>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Box<Account<'info, ClockAccount>>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[derive(Accounts)]
+pub struct BatchCreateVestingPositions<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+    #[account(
+        mut,
+        seeds = [VestingSchedule::SEED_PREFIX, organization.key().as_ref(), schedule.schedule_id.to_le_bytes().as_ref()],
+        bump = schedule.bump,
+        constraint = schedule.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
+    )]
+    pub schedule: Account<'info, VestingSchedule>,
+    /// Rolling index of the organization's recent positions
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = PositionIndex::SIZE,
+        seeds = [PositionIndex::SEED_PREFIX, organization.key().as_ref()],
+        bump,
+    )]
+    pub position_index: Box<Account<'info, PositionIndex>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("init_position", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct QueueInitPosition<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
+        bump = organization.bump,
+    )]
+    pub organization: Box<Account<'info, Organization>>,
+    #[account(
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position.position_id.to_le_bytes().as_ref()],
+        bump = position.bump,
+        constraint = position.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
+    )]
+    pub position: Box<Account<'info, VestingPosition>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [b"ArciumSignerAccount"],
+        bump,
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_POSITION))]
+    pub comp_def_account: Box<Account<'info, ComputationDefinitionAccount>>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Box<Account<'info, Cluster>>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Box<Account<'info, FeePool>>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Box<Account<'info, ClockAccount>>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("init_position")]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct InitPositionCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_POSITION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub position: Account<'info, VestingPosition>,
+}
+
+#[queue_computation_accounts("top_up_position", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct TopUpPosition<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+    #[account(
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position.position_id.to_le_bytes().as_ref()],
+        bump = position.bump,
+        constraint = position.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
+    )]
+    pub position: Account<'info, VestingPosition>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [b"ArciumSignerAccount"],
+        bump,
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_TOP_UP_POSITION))]
+    pub comp_def_account: Box<Account<'info, ComputationDefinitionAccount>>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Box<Account<'info, Cluster>>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Box<Account<'info, FeePool>>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Box<Account<'info, ClockAccount>>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("top_up_position")]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct TopUpPositionCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_TOP_UP_POSITION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub position: Account<'info, VestingPosition>,
+}
+
+#[queue_computation_accounts("compare_totals", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CompareTotals<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Box<Account<'info, Organization>>,
+    #[account(
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position_a.position_id.to_le_bytes().as_ref()],
+        bump = position_a.bump,
+        constraint = position_a.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
+    )]
+    pub position_a: Box<Account<'info, VestingPosition>>,
+    #[account(
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position_b.position_id.to_le_bytes().as_ref()],
+        bump = position_b.bump,
+        constraint = position_b.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
+    )]
+    pub position_b: Box<Account<'info, VestingPosition>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [b"ArciumSignerAccount"],
+        bump,
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMPARE_TOTALS))]
+    pub comp_def_account: Box<Account<'info, ComputationDefinitionAccount>>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Box<Account<'info, Cluster>>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Box<Account<'info, FeePool>>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Box<Account<'info, ClockAccount>>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("compare_totals")]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CompareTotalsCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMPARE_TOTALS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub position_a: Account<'info, VestingPosition>,
+    pub position_b: Account<'info, VestingPosition>,
+}
+
+#[queue_computation_accounts("check_solvency", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CheckSolvency<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Box<Account<'info, Organization>>,
+    #[account(
+        seeds = [b"vault", organization.key().as_ref(), vault.mint.as_ref()],
+        bump,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [b"ArciumSignerAccount"],
+        bump,
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CHECK_SOLVENCY))]
+    pub comp_def_account: Box<Account<'info, ComputationDefinitionAccount>>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Box<Account<'info, Cluster>>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Box<Account<'info, FeePool>>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Box<Account<'info, ClockAccount>>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("check_solvency")]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CheckSolvencyCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CHECK_SOLVENCY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub organization: Account<'info, Organization>,
+    pub vault: Account<'info, TokenAccount>,
+}
+
+#[queue_computation_accounts("merge_positions", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct MergePositions<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub admin: Signer<'info>,
+    /// Beneficiary of both positions (must match their Ed25519 commitment)
+    pub beneficiary: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Box<Account<'info, Organization>>,
+    #[account(
+        mut,
+        seeds = [VestingSchedule::SEED_PREFIX, organization.key().as_ref(), schedule.schedule_id.to_le_bytes().as_ref()],
+        bump = schedule.bump,
+        constraint = schedule.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
+    )]
+    pub schedule: Box<Account<'info, VestingSchedule>>,
+    #[account(
+        mut,
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position_a.position_id.to_le_bytes().as_ref()],
+        bump = position_a.bump,
+        constraint = position_a.schedule == schedule.key() @ ShadowVestError::InvalidMergePositions,
+    )]
+    pub position_a: Box<Account<'info, VestingPosition>>,
+    #[account(
+        mut,
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position_b.position_id.to_le_bytes().as_ref()],
+        bump = position_b.bump,
+        constraint = position_b.schedule == schedule.key() @ ShadowVestError::InvalidMergePositions,
+        constraint = position_b.key() != position_a.key() @ ShadowVestError::InvalidMergePositions,
+    )]
+    pub position_b: Box<Account<'info, VestingPosition>>,
+    #[account(
+        init,
+        payer = payer,
+        space = VestingPosition::SIZE,
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), organization.position_count.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub merged_position: Box<Account<'info, VestingPosition>>,
+    /// Rolling index of the organization's recent positions
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = PositionIndex::SIZE,
+        seeds = [PositionIndex::SEED_PREFIX, organization.key().as_ref()],
+        bump,
+    )]
+    pub position_index: Box<Account<'info, PositionIndex>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [b"ArciumSignerAccount"],
+        bump,
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MERGE_POSITIONS))]
+    pub comp_def_account: Box<Account<'info, ComputationDefinitionAccount>>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Box<Account<'info, Cluster>>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Box<Account<'info, FeePool>>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Box<Account<'info, ClockAccount>>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("merge_positions")]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct MergePositionsCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MERGE_POSITIONS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub position_a: Account<'info, VestingPosition>,
+    #[account(mut)]
+    pub position_b: Account<'info, VestingPosition>,
+    #[account(mut)]
+    pub merged_position: Account<'info, VestingPosition>,
+}
+
+#[queue_computation_accounts("reencrypt_position", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ReencryptPosition<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Box<Account<'info, Organization>>,
+    #[account(
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position.position_id.to_le_bytes().as_ref()],
+        bump = position.bump,
+        constraint = position.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
+    )]
+    pub position: Box<Account<'info, VestingPosition>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [b"ArciumSignerAccount"],
+        bump,
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REENCRYPT_POSITION))]
+    pub comp_def_account: Box<Account<'info, ComputationDefinitionAccount>>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Box<Account<'info, Cluster>>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Box<Account<'info, FeePool>>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Box<Account<'info, ClockAccount>>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("reencrypt_position")]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ReencryptPositionCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REENCRYPT_POSITION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub position: Account<'info, VestingPosition>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    #[account(
+        mut,
+        seeds = [VestingSchedule::SEED_PREFIX, organization.key().as_ref(), schedule.schedule_id.to_le_bytes().as_ref()],
+        bump = schedule.bump,
+        constraint = schedule.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
+    )]
+    pub schedule: Account<'info, VestingSchedule>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position.position_id.to_le_bytes().as_ref()],
+        bump = position.bump,
+        constraint = position.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
+        constraint = position.schedule == schedule.key() @ ShadowVestError::InvalidScheduleParams,
+    )]
+    pub position: Account<'info, VestingPosition>,
+}
+
+#[derive(Accounts)]
+pub struct RevokePosition<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    #[account(
+        mut,
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position.position_id.to_le_bytes().as_ref()],
+        bump = position.bump,
+        constraint = position.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
+    )]
+    pub position: Account<'info, VestingPosition>,
+}
+
+#[derive(Accounts)]
+pub struct RotatePositionBeneficiary<'info> {
+    #[account(
+        seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
+        bump = organization.bump,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    #[account(
+        mut,
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position.position_id.to_le_bytes().as_ref()],
+        bump = position.bump,
+        constraint = position.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
+    )]
+    pub position: Account<'info, VestingPosition>,
+
+    /// CHECK: Instructions sysvar for reading the signature verification instruction
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+// ============================================================
+// Account Contexts - Vesting Calculation
+// ============================================================
+
+#[queue_computation_accounts("calculate_vested", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CalculateVestedAmount<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()], bump = organization.bump)]
+    pub organization: Account<'info, Organization>,
+    #[account(
+        seeds = [VestingSchedule::SEED_PREFIX, organization.key().as_ref(), schedule.schedule_id.to_le_bytes().as_ref()],
+        bump = schedule.bump,
+        constraint = schedule.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
+    )]
+    pub schedule: Account<'info, VestingSchedule>,
+    #[account(
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position.position_id.to_le_bytes().as_ref()],
+        bump = position.bump,
+        constraint = position.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
+        constraint = position.schedule == schedule.key() @ ShadowVestError::InvalidScheduleParams,
+    )]
+    pub position: Account<'info, VestingPosition>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = VestedSnapshot::SIZE,
+        seeds = [VestedSnapshot::SEED_PREFIX, position.key().as_ref()],
+        bump,
+    )]
+    pub vested_snapshot: Box<Account<'info, VestedSnapshot>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [b"ArciumSignerAccount"],
+        bump,
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CALCULATE_VESTED))]
+    pub comp_def_account: Box<Account<'info, ComputationDefinitionAccount>>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Box<Account<'info, Cluster>>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Box<Account<'info, FeePool>>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Box<Account<'info, ClockAccount>>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("calculate_vested")]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CalculateVestedCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CALCULATE_VESTED))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub position: Account<'info, VestingPosition>,
+    #[account(
+        mut,
+        seeds = [VestedSnapshot::SEED_PREFIX, position.key().as_ref()],
+        bump = vested_snapshot.bump,
+    )]
+    pub vested_snapshot: Account<'info, VestedSnapshot>,
+}
+
+// ============================================================
+// Computation Definition Init Accounts
+// ============================================================
+
+#[init_computation_definition_accounts("init_position", payer)]
+#[derive(Accounts)]
+pub struct InitInitPositionCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: address_lookup_table
+    pub address_lookup_table: UncheckedAccount<'info>,
+    /// CHECK: lut_program
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("calculate_vested", payer)]
+#[derive(Accounts)]
+pub struct InitCalculateVestedCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: address_lookup_table
+    pub address_lookup_table: UncheckedAccount<'info>,
+    /// CHECK: lut_program
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("process_claim", payer)]
+#[derive(Accounts)]
+pub struct InitProcessClaimCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: address_lookup_table
+    pub address_lookup_table: UncheckedAccount<'info>,
+    /// CHECK: lut_program
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("process_claim_v2", payer)]
+#[derive(Accounts)]
+pub struct InitProcessClaimV2CompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: address_lookup_table
+    pub address_lookup_table: UncheckedAccount<'info>,
+    /// CHECK: lut_program
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("top_up_position", payer)]
+#[derive(Accounts)]
+pub struct InitTopUpPositionCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: address_lookup_table
+    pub address_lookup_table: UncheckedAccount<'info>,
+    /// CHECK: lut_program
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("compare_totals", payer)]
+#[derive(Accounts)]
+pub struct InitCompareTotalsCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: address_lookup_table
+    pub address_lookup_table: UncheckedAccount<'info>,
+    /// CHECK: lut_program
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("merge_positions", payer)]
+#[derive(Accounts)]
+pub struct InitMergePositionsCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: address_lookup_table
+    pub address_lookup_table: UncheckedAccount<'info>,
+    /// CHECK: lut_program
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("check_solvency", payer)]
+#[derive(Accounts)]
+pub struct InitCheckSolvencyCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: address_lookup_table
+    pub address_lookup_table: UncheckedAccount<'info>,
+    /// CHECK: lut_program
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("reencrypt_position", payer)]
+#[derive(Accounts)]
+pub struct InitReencryptPositionCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: address_lookup_table
+    pub address_lookup_table: UncheckedAccount<'info>,
+    /// CHECK: lut_program
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("process_claims_batch", payer)]
+#[derive(Accounts)]
+pub struct InitProcessClaimsBatchCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: address_lookup_table
+    pub address_lookup_table: UncheckedAccount<'info>,
+    /// CHECK: lut_program
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================================
+// Account Contexts - Organization & Schedule (Non-MPC)
+// ============================================================
+
+#[derive(Accounts)]
+pub struct CreateOrganization<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Organization::SIZE,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    /// Optional global registry; when provided the organization is recorded for discovery
+    #[account(
+        mut,
+        seeds = [OrgRegistry::SEED_PREFIX],
+        bump = org_registry.bump,
+    )]
+    pub org_registry: Option<Account<'info, OrgRegistry>>,
+
+    /// Current registry page (required when org_registry is provided)
+    #[account(mut)]
+    pub registry_page: Option<Account<'info, OrgRegistryPage>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTreasury<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+}
+
+#[derive(Accounts)]
+pub struct RevealOrganizationName<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+}
+
+#[derive(Accounts)]
+pub struct SetMinReserve<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+}
+
+#[derive(Accounts)]
+pub struct SetDefaultSchedule<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    #[account(
+        seeds = [VestingSchedule::SEED_PREFIX, organization.key().as_ref(), schedule.schedule_id.to_le_bytes().as_ref()],
+        bump = schedule.bump,
+        constraint = schedule.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
+    )]
+    pub schedule: Account<'info, VestingSchedule>,
+}
+
+#[derive(Accounts)]
+pub struct SetComputationTimeout<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+}
+
+#[derive(Accounts)]
+pub struct SetCompressedOnly<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+}
+
+#[derive(Accounts)]
+pub struct SetAuditorKey<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+}
+
+#[derive(Accounts)]
+pub struct SetWithdrawalFee<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+}
+
+#[derive(Accounts)]
+pub struct SetAtaOnlyWithdrawals<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+}
+
+#[derive(Accounts)]
+#[instruction(destination: Pubkey)]
+pub struct AddAllowedDestination<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = AllowedDestination::SIZE,
+        seeds = [AllowedDestination::SEED_PREFIX, organization.key().as_ref(), destination.as_ref()],
+        bump,
+    )]
+    pub allowed_destination: Account<'info, AllowedDestination>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetDestinationAllowlistEnforced<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+}
+
+#[derive(Accounts)]
+pub struct SetChallengePeriod<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+}
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+}
+
+#[derive(Accounts)]
+pub struct SetLazyInit<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+}
+
+#[derive(Accounts)]
+pub struct SetPriorityFeeAccount<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+}
+
+#[derive(Accounts)]
+pub struct SetNullifierNamespace<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+}
+
+#[derive(Accounts)]
+pub struct InitOrgRegistry<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = OrgRegistry::SIZE,
+        seeds = [OrgRegistry::SEED_PREFIX],
+        bump,
+    )]
+    pub org_registry: Account<'info, OrgRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(page_index: u64)]
+pub struct CreateOrgRegistryPage<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [OrgRegistry::SEED_PREFIX],
+        bump = org_registry.bump,
+        constraint = page_index == org_registry.page_count @ ShadowVestError::InvalidRegistryPage,
+    )]
+    pub org_registry: Account<'info, OrgRegistry>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = OrgRegistryPage::SIZE,
+        seeds = [OrgRegistryPage::SEED_PREFIX, page_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub registry_page: Account<'info, OrgRegistryPage>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateVestingSchedule<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = VestingSchedule::SIZE,
+        seeds = [
+            VestingSchedule::SEED_PREFIX,
+            organization.key().as_ref(),
+            organization.schedule_count.to_le_bytes().as_ref()
+        ],
+        bump,
+    )]
+    pub schedule: Account<'info, VestingSchedule>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Validate the parameters and initialize the next schedule of the organization.
+/// Shared by create_vesting_schedule, create_schedule_from_template and
+/// create_schedule_with_position.
+#[allow(clippy::too_many_arguments)]
+fn create_schedule<'info>(
+    organization: &mut Account<'info, Organization>,
+    schedule: &mut Account<'info, VestingSchedule>,
+    bump: u8,
+    cliff_duration: u64,
+    total_duration: u64,
+    vesting_interval: u64,
+    vesting_mode: u8,
+    token_mint: Pubkey,
+) -> Result<()> {
+    VestingSchedule::validate_params(cliff_duration, total_duration, vesting_interval)?;
+    VestingSchedule::validate_mode(vesting_mode)?;
+    require!(
+        organization.is_active,
+        ShadowVestError::OrganizationNotActive
+    );
+
+    let schedule_id = organization.schedule_count;
+
+    schedule.organization = organization.key();
+    schedule.schedule_id = schedule_id;
+    schedule.cliff_duration = cliff_duration;
+    schedule.total_duration = total_duration;
+    schedule.vesting_interval = vesting_interval;
+    schedule.token_mint = token_mint;
+    schedule.is_active = true;
+    schedule.position_count = 0;
+    schedule.compressed_position_count = 0;
+    schedule.vesting_mode = vesting_mode;
+    schedule.min_claim_interval = 0;
+    schedule.claims_paused = false;
+    schedule.beneficiary_root = [0u8; 32];
+    schedule.precision = vesting::PRECISION;
+    schedule.bump = bump;
+
+    organization.schedule_count = organization
+        .schedule_count
+        .checked_add(1)
+        .ok_or(ShadowVestError::ArithmeticOverflow)?;
+
+    emit!(VestingScheduleCreated {
+        organization: organization.key(),
+        schedule: schedule.key(),
+        schedule_id,
+        cliff_duration,
+        total_duration,
+        vesting_interval,
+        vesting_mode,
+        token_mint,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateVestingSchedule<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    #[account(
+        mut,
+        seeds = [VestingSchedule::SEED_PREFIX, organization.key().as_ref(), schedule.schedule_id.to_le_bytes().as_ref()],
+        bump = schedule.bump,
+        constraint = schedule.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
+    )]
+    pub schedule: Account<'info, VestingSchedule>,
+}
+
+#[derive(Accounts)]
+#[instruction(schedule_id: u64)]
+pub struct MigrateVestingSchedule<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    /// CHECK: legacy-layout VestingSchedule, too short to deserialize until grown;
+    /// ownership, discriminator and organization are checked in the handler
+    #[account(
+        mut,
+        seeds = [VestingSchedule::SEED_PREFIX, organization.key().as_ref(), schedule_id.to_le_bytes().as_ref()],
+        bump,
+        owner = crate::ID @ ShadowVestError::InvalidScheduleParams,
+    )]
+    pub schedule: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetScheduleActive<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    #[account(
+        mut,
+        seeds = [VestingSchedule::SEED_PREFIX, organization.key().as_ref(), schedule.schedule_id.to_le_bytes().as_ref()],
+        bump = schedule.bump,
+        constraint = schedule.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
+    )]
+    pub schedule: Account<'info, VestingSchedule>,
+}
+
+#[derive(Accounts)]
+pub struct SetMinClaimInterval<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    #[account(
+        mut,
+        seeds = [VestingSchedule::SEED_PREFIX, organization.key().as_ref(), schedule.schedule_id.to_le_bytes().as_ref()],
+        bump = schedule.bump,
+        constraint = schedule.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
+    )]
+    pub schedule: Account<'info, VestingSchedule>,
+}
+
+#[derive(Accounts)]
+pub struct SetScheduleClaimsPaused<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    #[account(
+        mut,
+        seeds = [VestingSchedule::SEED_PREFIX, organization.key().as_ref(), schedule.schedule_id.to_le_bytes().as_ref()],
+        bump = schedule.bump,
+        constraint = schedule.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
+    )]
+    pub schedule: Account<'info, VestingSchedule>,
+}
+
+#[derive(Accounts)]
+pub struct SetScheduleBeneficiaryRoot<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    #[account(
+        mut,
+        seeds = [VestingSchedule::SEED_PREFIX, organization.key().as_ref(), schedule.schedule_id.to_le_bytes().as_ref()],
+        bump = schedule.bump,
+        constraint = schedule.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
+    )]
+    pub schedule: Account<'info, VestingSchedule>,
+}
+
+// ============================================================
+// Account Contexts - Compressed Vesting Positions (Light Protocol)
+// ============================================================
+
+/// Account context for creating compressed vesting positions.
+/// Uses Light Protocol CPI for 5000x cost reduction.
+///
+/// Note: Light Protocol accounts are passed via `remaining_accounts`:
+/// - light_system_program
+/// - account_compression_program
+/// - registered_program_pda
+/// - noop_program
+/// - cpi_authority_pda
+/// - state_merkle_tree
+/// - address_merkle_tree
+/// - address_queue
+#[derive(Accounts)]
+pub struct CreateCompressedVestingPosition<'info> {
+    /// Fee payer for the Light Protocol CPI transaction
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+
+    /// Organization admin who can create positions
+    pub admin: Signer<'info>,
+
+    /// Organization account (mutable for counter update)
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
         has_one = admin @ ShadowVestError::UnauthorizedAdmin,
     )]
     pub organization: Account<'info, Organization>,
+
+    /// Vesting schedule for this position
+    #[account(
+        mut,
+        seeds = [
+            VestingSchedule::SEED_PREFIX,
+            organization.key().as_ref(),
+            schedule.schedule_id.to_le_bytes().as_ref()
+        ],
+        bump = schedule.bump,
+        constraint = schedule.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
+        // withdraw_compressed pays out of the default-mint vault only
+        constraint = schedule.token_mint == organization.token_mint @ ShadowVestError::InvalidTokenMint,
+    )]
+    pub schedule: Account<'info, VestingSchedule>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+    // Remaining accounts are provided dynamically for Light Protocol CPI
+}
+
+#[derive(Accounts)]
+pub struct ValidateCompressedAddress<'info> {
+    #[account(
+        seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
+        bump = organization.bump,
+    )]
+    pub organization: Account<'info, Organization>,
+    // Remaining accounts: the same Light Protocol accounts as create_compressed_vesting_position
+}
+
+// ============================================================
+// Account Contexts - Stealth Meta-Address
+// ============================================================
+
+#[derive(Accounts)]
+#[instruction(label: [u8; 16])]
+pub struct RegisterStealthMeta<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = StealthMetaAddress::SIZE,
+        seeds = [StealthMetaAddress::SEED_PREFIX, owner.key().as_ref(), StealthMetaAddress::label_seed(&label)],
+        bump,
+    )]
+    pub stealth_meta: Account<'info, StealthMetaAddress>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(label: [u8; 16])]
+pub struct UpdateStealthMeta<'info> {
+    /// The owner or the meta-address's delegate
+    pub authority: Signer<'info>,
+
+    /// CHECK: Meta-address owner, only used to derive the PDA (bound by has_one)
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [StealthMetaAddress::SEED_PREFIX, owner.key().as_ref(), StealthMetaAddress::label_seed(&label)],
+        bump = stealth_meta.bump,
+        has_one = owner @ ShadowVestError::UnauthorizedOwner,
+        constraint = stealth_meta.is_authority(&authority.key()) @ ShadowVestError::UnauthorizedOwner,
+    )]
+    pub stealth_meta: Account<'info, StealthMetaAddress>,
+}
+
+#[derive(Accounts)]
+#[instruction(label: [u8; 16])]
+pub struct DeactivateStealthMeta<'info> {
+    /// The owner or the meta-address's delegate
+    pub authority: Signer<'info>,
+
+    /// CHECK: Meta-address owner, only used to derive the PDA (bound by has_one)
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [StealthMetaAddress::SEED_PREFIX, owner.key().as_ref(), StealthMetaAddress::label_seed(&label)],
+        bump = stealth_meta.bump,
+        has_one = owner @ ShadowVestError::UnauthorizedOwner,
+        constraint = stealth_meta.is_authority(&authority.key()) @ ShadowVestError::UnauthorizedOwner,
+    )]
+    pub stealth_meta: Account<'info, StealthMetaAddress>,
+}
+
+#[derive(Accounts)]
+#[instruction(label: [u8; 16])]
+pub struct QuoteStealthPayment<'info> {
+    /// Employer requesting the quote
+    pub payer: Signer<'info>,
+
+    /// CHECK: Meta-address owner, only used to derive the PDA (bound by has_one)
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [StealthMetaAddress::SEED_PREFIX, owner.key().as_ref(), StealthMetaAddress::label_seed(&label)],
+        bump = stealth_meta.bump,
+        has_one = owner @ ShadowVestError::UnauthorizedOwner,
+    )]
+    pub stealth_meta: Account<'info, StealthMetaAddress>,
+}
+
+#[derive(Accounts)]
+#[instruction(label: [u8; 16])]
+pub struct SetStealthDelegate<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [StealthMetaAddress::SEED_PREFIX, owner.key().as_ref(), StealthMetaAddress::label_seed(&label)],
+        bump = stealth_meta.bump,
+        has_one = owner @ ShadowVestError::UnauthorizedOwner,
+    )]
+    pub stealth_meta: Account<'info, StealthMetaAddress>,
+}
+
+// ============================================================
+// Account Contexts - Claim Authorization & Withdrawal
+// ============================================================
+
+#[derive(Accounts)]
+#[instruction(nullifier: [u8; 32])]
+pub struct AuthorizeClaim<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
+        bump = organization.bump,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    #[account(
+        mut,
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position.position_id.to_le_bytes().as_ref()],
+        bump = position.bump,
+        constraint = position.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
+    )]
+    pub position: Account<'info, VestingPosition>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = ClaimAuthorization::SIZE,
+        seeds = [ClaimAuthorization::SEED_PREFIX, position.key().as_ref(), nullifier.as_ref()],
+        bump,
+    )]
+    pub claim_authorization: Account<'info, ClaimAuthorization>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = NullifierRecord::SIZE,
+        seeds = [
+            NullifierRecord::SEED_PREFIX,
+            organization.key().as_ref(),
+            organization.nullifier_namespace.as_ref(),
+            nullifier.as_ref(),
+        ],
+        bump,
+    )]
+    pub nullifier_record: Account<'info, NullifierRecord>,
+
+    /// Approval for the withdrawal destination, required when the organization
+    /// enforces its destination allowlist
+    pub allowed_destination: Option<Account<'info, AllowedDestination>>,
+
+    /// CHECK: Instructions sysvar for reading Ed25519 instruction
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(nullifier: [u8; 32])]
+pub struct AuthorizeClaimWithProof<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
+        bump = organization.bump,
+    )]
+    pub organization: Account<'info, Organization>,
+
     #[account(
         mut,
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position.position_id.to_le_bytes().as_ref()],
+        bump = position.bump,
+        constraint = position.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
+    )]
+    pub position: Account<'info, VestingPosition>,
+
+    /// Eligibility proof record verified by the payer for this nullifier
+    #[account(
+        seeds = [ProofRecord::SEED_PREFIX, payer.key().as_ref(), nullifier.as_ref()],
+        bump = proof_record.bump,
+    )]
+    pub proof_record: Account<'info, ProofRecord>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = ClaimAuthorization::SIZE,
+        seeds = [ClaimAuthorization::SEED_PREFIX, position.key().as_ref(), nullifier.as_ref()],
+        bump,
+    )]
+    pub claim_authorization: Account<'info, ClaimAuthorization>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = NullifierRecord::SIZE,
+        seeds = [
+            NullifierRecord::SEED_PREFIX,
+            organization.key().as_ref(),
+            organization.nullifier_namespace.as_ref(),
+            nullifier.as_ref(),
+        ],
+        bump,
+    )]
+    pub nullifier_record: Account<'info, NullifierRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimWithMerkleProof<'info> {
+    /// The position's beneficiary, named in the Merkle leaf (pays for the new accounts)
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+
+    #[account(
+        seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
+        bump = organization.bump,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    #[account(
         seeds = [VestingSchedule::SEED_PREFIX, organization.key().as_ref(), schedule.schedule_id.to_le_bytes().as_ref()],
         bump = schedule.bump,
         constraint = schedule.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
     )]
     pub schedule: Account<'info, VestingSchedule>,
+
+    #[account(
+        mut,
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position.position_id.to_le_bytes().as_ref()],
+        bump = position.bump,
+        constraint = position.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
+        constraint = position.schedule == schedule.key() @ ShadowVestError::InvalidScheduleParams,
+        constraint = position.beneficiary_commitment == beneficiary.key().to_bytes() @ ShadowVestError::SignerMismatch,
+    )]
+    pub position: Account<'info, VestingPosition>,
+
+    /// CHECK: Withdrawal destination bound by the Merkle leaf; validated as a
+    /// token account at withdraw time
+    pub withdrawal_destination: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = beneficiary,
+        space = ClaimAuthorization::SIZE,
+        seeds = [
+            ClaimAuthorization::SEED_PREFIX,
+            position.key().as_ref(),
+            position.next_merkle_claim_nullifier(beneficiary.key, withdrawal_destination.key).as_ref(),
+        ],
+        bump,
+    )]
+    pub claim_authorization: Account<'info, ClaimAuthorization>,
+
+    #[account(
+        init,
+        payer = beneficiary,
+        space = NullifierRecord::SIZE,
+        seeds = [
+            NullifierRecord::SEED_PREFIX,
+            organization.key().as_ref(),
+            organization.nullifier_namespace.as_ref(),
+            position.next_merkle_claim_nullifier(beneficiary.key, withdrawal_destination.key).as_ref(),
+        ],
+        bump,
+    )]
+    pub nullifier_record: Account<'info, NullifierRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateClaimDestination<'info> {
+    #[account(
+        seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
+        bump = organization.bump,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    #[account(
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position.position_id.to_le_bytes().as_ref()],
+        bump = position.bump,
+        constraint = position.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
+    )]
+    pub position: Account<'info, VestingPosition>,
+
+    #[account(
+        mut,
+        seeds = [ClaimAuthorization::SEED_PREFIX, position.key().as_ref(), claim_authorization.nullifier.as_ref()],
+        bump = claim_authorization.bump,
+        constraint = claim_authorization.position == position.key() @ ShadowVestError::InvalidPositionOrganization,
+    )]
+    pub claim_authorization: Account<'info, ClaimAuthorization>,
+
+    /// CHECK: Instructions sysvar for reading the signature precompile instruction
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+#[queue_computation_accounts("process_claim_v2", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct QueueProcessClaim<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
+        bump = organization.bump,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    #[account(
+        seeds = [VestingSchedule::SEED_PREFIX, organization.key().as_ref(), schedule.schedule_id.to_le_bytes().as_ref()],
+        bump = schedule.bump,
+        constraint = schedule.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
+    )]
+    pub schedule: Box<Account<'info, VestingSchedule>>,
+
+    #[account(
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position.position_id.to_le_bytes().as_ref()],
+        bump = position.bump,
+        constraint = position.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
+        constraint = position.schedule == schedule.key() @ ShadowVestError::InvalidScheduleParams,
+    )]
+    pub position: Box<Account<'info, VestingPosition>>,
+
+    #[account(
+        mut,
+        seeds = [ClaimAuthorization::SEED_PREFIX, position.key().as_ref(), claim_authorization.nullifier.as_ref()],
+        bump = claim_authorization.bump,
+        constraint = claim_authorization.position == position.key() @ ShadowVestError::InvalidPositionOrganization,
+    )]
+    pub claim_authorization: Account<'info, ClaimAuthorization>,
+
+    /// Vault backing the claim; its balance bounds the claims reserved against it
+    #[account(
+        seeds = [b"vault", organization.key().as_ref(), schedule.token_mint.as_ref()],
+        bump,
+        token::mint = schedule.token_mint,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    /// Tracks the in-flight computation so a lost callback can be expired and re-queued
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = PendingComputation::SIZE,
+        seeds = [PendingComputation::SEED_PREFIX, claim_authorization.key().as_ref()],
+        bump,
+    )]
+    pub pending_computation: Box<Account<'info, PendingComputation>>,
+
+    /// Receives the priority fee; required when priority_fee > 0
+    #[account(mut)]
+    /// CHECK: must equal organization.priority_fee_account (checked in handler)
+    pub priority_fee_account: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [b"ArciumSignerAccount"],
+        bump,
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROCESS_CLAIM_V2))]
+    pub comp_def_account: Box<Account<'info, ComputationDefinitionAccount>>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Box<Account<'info, Cluster>>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Box<Account<'info, FeePool>>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Box<Account<'info, ClockAccount>>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("process_claim_v2")]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ProcessClaimV2Callback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROCESS_CLAIM_V2))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub position: Account<'info, VestingPosition>,
+    #[account(mut)]
+    pub claim_authorization: Account<'info, ClaimAuthorization>,
+    #[account(
+        mut,
+        seeds = [PendingComputation::SEED_PREFIX, claim_authorization.key().as_ref()],
+        bump = pending_computation.bump,
+    )]
+    pub pending_computation: Account<'info, PendingComputation>,
+    /// Releases the claim's vault reservation if the MPC rejects it and counts
+    /// positions the claim fully claims
+    #[account(mut)]
+    pub organization: Account<'info, Organization>,
+}
+
+#[queue_computation_accounts("process_claims_batch", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct QueueProcessClaimsBatch<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
+        bump = organization.bump,
+    )]
+    pub organization: Box<Account<'info, Organization>>,
+
+    /// Schedule of every position in the batch
+    #[account(
+        seeds = [VestingSchedule::SEED_PREFIX, organization.key().as_ref(), schedule.schedule_id.to_le_bytes().as_ref()],
+        bump = schedule.bump,
+        constraint = schedule.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
+    )]
+    pub schedule: Box<Account<'info, VestingSchedule>>,
+
+    /// Vault backing the claims; its balance bounds the claims reserved against it
     #[account(
-        init,
-        payer = payer,
-        space = VestingPosition::SIZE,
-        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), organization.position_count.to_le_bytes().as_ref()],
+        seeds = [b"vault", organization.key().as_ref(), schedule.token_mint.as_ref()],
         bump,
+        token::mint = schedule.token_mint,
     )]
-    pub position: Account<'info, VestingPosition>,
+    pub vault: Box<Account<'info, TokenAccount>>,
+
     #[account(
         init_if_needed,
         space = 9,
@@ -2128,7 +8407,7 @@ pub struct CreateVestingPosition<'info> {
     #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
     /// CHECK: computation_account
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_POSITION))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROCESS_CLAIMS_BATCH))]
     pub comp_def_account: Box<Account<'info, ComputationDefinitionAccount>>,
     #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
     pub cluster_account: Box<Account<'info, Cluster>>,
@@ -2140,12 +8419,14 @@ pub struct CreateVestingPosition<'info> {
     pub arcium_program: Program<'info, Arcium>,
 }
 
-#[callback_accounts("init_position")]
+/// The batch's (position, claim_authorization, pending_computation) triples follow
+/// the declared accounts (see queue_process_claims_batch).
+#[callback_accounts("process_claims_batch")]
 #[derive(Accounts)]
 #[instruction(computation_offset: u64)]
-pub struct InitPositionCallback<'info> {
+pub struct ProcessClaimsBatchCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_POSITION))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROCESS_CLAIMS_BATCH))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(address = derive_mxe_pda!())]
     pub mxe_account: Account<'info, MXEAccount>,
@@ -2156,521 +8437,834 @@ pub struct InitPositionCallback<'info> {
     #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
     /// CHECK: instructions_sysvar
     pub instructions_sysvar: AccountInfo<'info>,
+    /// Releases rejected claims' vault reservations and counts positions the
+    /// batch fully claims
+    #[account(mut)]
+    pub organization: Account<'info, Organization>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeVault<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    /// CHECK: Vault authority PDA - used as token account authority
+    #[account(
+        seeds = [b"vault_authority", organization.key().as_ref()],
+        bump,
+    )]
+    pub vault_authority: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        token::mint = token_mint,
+        token::authority = vault_authority,
+        seeds = [b"vault", organization.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub token_mint: Account<'info, token::Mint>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct DepositToVault<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    #[account(
+        mut,
+        token::mint = admin_token_account.mint,
+        seeds = [b"vault", organization.key().as_ref(), admin_token_account.mint.as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::authority = admin,
+    )]
+    pub admin_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimVaultSurplus<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    /// CHECK: Vault authority PDA
+    #[account(
+        seeds = [b"vault_authority", organization.key().as_ref()],
+        bump,
+    )]
+    pub vault_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        token::mint = admin_token_account.mint,
+        token::authority = vault_authority,
+        seeds = [b"vault", organization.key().as_ref(), admin_token_account.mint.as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::authority = admin,
+    )]
+    pub admin_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetPositionReservedAmount<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    #[account(
+        mut,
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position.position_id.to_le_bytes().as_ref()],
+        bump = position.bump,
+        constraint = position.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
+    )]
+    pub position: Account<'info, VestingPosition>,
+
+    /// Schedule of the position; provides the vault's token mint
+    #[account(address = position.schedule @ ShadowVestError::InvalidScheduleParams)]
+    pub schedule: Account<'info, VestingSchedule>,
+
+    /// Vault the reserve is earmarked in; caps the reserves of the positions it pays
+    #[account(
+        seeds = [b"vault", organization.key().as_ref(), schedule.token_mint.as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SweepUnclaimedPosition<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    #[account(
+        mut,
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position.position_id.to_le_bytes().as_ref()],
+        bump = position.bump,
+        constraint = position.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
+    )]
+    pub position: Account<'info, VestingPosition>,
+
+    /// Schedule of the position; provides the vesting end and the vault's token mint
+    #[account(address = position.schedule @ ShadowVestError::InvalidScheduleParams)]
+    pub schedule: Account<'info, VestingSchedule>,
+
+    /// CHECK: Vault authority PDA
+    #[account(
+        seeds = [b"vault_authority", organization.key().as_ref()],
+        bump,
+    )]
+    pub vault_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        token::mint = schedule.token_mint,
+        token::authority = vault_authority,
+        seeds = [b"vault", organization.key().as_ref(), schedule.token_mint.as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = schedule.token_mint,
+        constraint = treasury_token_account.owner == organization.treasury @ ShadowVestError::InvalidTreasuryAccount,
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
     #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
+        bump = organization.bump,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    #[account(
+        mut,
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position.position_id.to_le_bytes().as_ref()],
+        bump = position.bump,
+        constraint = position.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
+    )]
     pub position: Account<'info, VestingPosition>,
+
+    #[account(
+        mut,
+        seeds = [ClaimAuthorization::SEED_PREFIX, position.key().as_ref(), claim_authorization.nullifier.as_ref()],
+        bump = claim_authorization.bump,
+        constraint = claim_authorization.position == position.key() @ ShadowVestError::InvalidPositionOrganization,
+    )]
+    pub claim_authorization: Account<'info, ClaimAuthorization>,
+
+    /// Schedule of the position; selects the vault for its token mint
+    #[account(address = position.schedule @ ShadowVestError::InvalidScheduleParams)]
+    pub schedule: Account<'info, VestingSchedule>,
+
+    /// CHECK: Vault authority PDA
+    #[account(
+        seeds = [b"vault_authority", organization.key().as_ref()],
+        bump,
+    )]
+    pub vault_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", organization.key().as_ref(), schedule.token_mint.as_ref()],
+        bump,
+        token::mint = schedule.token_mint,
+        token::authority = vault_authority,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+
+    /// Receives the protocol fee; required when organization.fee_bps > 0
+    #[account(mut)]
+    pub fee_destination: Option<Account<'info, TokenAccount>>,
+
+    /// Organization guardian; must co-sign claims of at least guardian_threshold
+    pub guardian: Option<Signer<'info>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = BeneficiaryClaimHistory::SIZE,
+        seeds = [BeneficiaryClaimHistory::SEED_PREFIX, position.beneficiary_commitment.as_ref()],
+        bump,
+    )]
+    pub claim_history: Box<Account<'info, BeneficiaryClaimHistory>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Withdraw<'info> {
+    /// Pay out the rest of an authorized, processed claim (shared by withdraw and
+    /// execute_queued_withdraw). Returns the amount withdrawn before fees.
+    fn withdraw_remaining(
+        &mut self,
+        vault_authority_bump: u8,
+        claim_history_bump: u8,
+    ) -> Result<u64> {
+        // Verify destination matches what was authorized
+        check_withdrawable_claim(
+            &self.organization,
+            &self.claim_authorization,
+            self.guardian.as_ref().map(|guardian| guardian.key()),
+            &self.destination.key(),
+        )?;
+        self.organization.check_withdrawal_destination(
+            &self.destination.key(),
+            &self.destination.owner,
+            &self.destination.mint,
+        )?;
+
+        // Anything already paid out through withdraw_partial is excluded
+        let amount = self.claim_authorization.remaining_amount();
+
+        let org_key = self.organization.key();
+        let vault_authority_seeds: &[&[u8]] = &[
+            b"vault_authority",
+            org_key.as_ref(),
+            std::slice::from_ref(&vault_authority_bump),
+        ];
+        let signer_seeds = &[vault_authority_seeds];
+
+        let (fee, _) = pay_out_withdrawal(
+            &self.organization,
+            amount,
+            &mut self.vault,
+            self.destination.to_account_info(),
+            self.fee_destination.as_ref(),
+            &self.vault_authority,
+            &self.token_program,
+            signer_seeds,
+        )?;
+
+        // Mark as withdrawn
+        let claim_auth = &mut self.claim_authorization;
+        claim_auth.withdrawn_so_far = claim_auth.claim_amount;
+        claim_auth.is_withdrawn = true;
+
+        record_claim_withdrawal(
+            &mut self.organization,
+            &mut self.position,
+            &mut self.claim_authorization,
+            &mut self.claim_history,
+            claim_history_bump,
+            amount,
+            fee,
+            &self.vault,
+        )?;
+
+        Ok(amount)
+    }
 }
 
-// ============================================================
-// Account Contexts - Vesting Calculation
-// ============================================================
-
-#[queue_computation_accounts("calculate_vested", payer)]
 #[derive(Accounts)]
-#[instruction(computation_offset: u64)]
-pub struct CalculateVestedAmount<'info> {
+pub struct WithdrawToAta<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
-    #[account(seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()], bump = organization.bump)]
-    pub organization: Account<'info, Organization>,
+
     #[account(
-        seeds = [VestingSchedule::SEED_PREFIX, organization.key().as_ref(), schedule.schedule_id.to_le_bytes().as_ref()],
-        bump = schedule.bump,
-        constraint = schedule.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
+        mut,
+        seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
+        bump = organization.bump,
     )]
-    pub schedule: Account<'info, VestingSchedule>,
+    pub organization: Account<'info, Organization>,
+
     #[account(
+        mut,
         seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position.position_id.to_le_bytes().as_ref()],
         bump = position.bump,
-        constraint = position.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
-        constraint = position.schedule == schedule.key() @ ShadowVestError::InvalidScheduleParams,
+        constraint = position.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
     )]
     pub position: Account<'info, VestingPosition>,
+
     #[account(
-        init_if_needed,
-        space = 9,
-        payer = payer,
-        seeds = [b"ArciumSignerAccount"],
-        bump,
+        mut,
+        seeds = [ClaimAuthorization::SEED_PREFIX, position.key().as_ref(), claim_authorization.nullifier.as_ref()],
+        bump = claim_authorization.bump,
+        constraint = claim_authorization.position == position.key() @ ShadowVestError::InvalidPositionOrganization,
     )]
-    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
-    #[account(address = derive_mxe_pda!())]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: mempool_account
-    pub mempool_account: UncheckedAccount<'info>,
-    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: executing_pool
-    pub executing_pool: UncheckedAccount<'info>,
-    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: computation_account
-    pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CALCULATE_VESTED))]
-    pub comp_def_account: Box<Account<'info, ComputationDefinitionAccount>>,
-    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    pub cluster_account: Box<Account<'info, Cluster>>,
-    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
-    pub pool_account: Box<Account<'info, FeePool>>,
-    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
-    pub clock_account: Box<Account<'info, ClockAccount>>,
-    pub system_program: Program<'info, System>,
-    pub arcium_program: Program<'info, Arcium>,
-}
-
-#[callback_accounts("calculate_vested")]
-#[derive(Accounts)]
-#[instruction(computation_offset: u64)]
-pub struct CalculateVestedCallback<'info> {
-    pub arcium_program: Program<'info, Arcium>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CALCULATE_VESTED))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
-    #[account(address = derive_mxe_pda!())]
-    pub mxe_account: Account<'info, MXEAccount>,
-    /// CHECK: computation_account
-    pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    pub cluster_account: Account<'info, Cluster>,
-    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
-    /// CHECK: instructions_sysvar
-    pub instructions_sysvar: AccountInfo<'info>,
-    pub position: Account<'info, VestingPosition>,
-}
+    pub claim_authorization: Account<'info, ClaimAuthorization>,
 
-// ============================================================
-// Computation Definition Init Accounts
-// ============================================================
+    /// Schedule of the position; selects the vault for its token mint
+    #[account(address = position.schedule @ ShadowVestError::InvalidScheduleParams)]
+    pub schedule: Account<'info, VestingSchedule>,
 
-#[init_computation_definition_accounts("init_position", payer)]
-#[derive(Accounts)]
-pub struct InitInitPositionCompDef<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(mut, address = derive_mxe_pda!())]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(mut)]
-    /// CHECK: comp_def_account
-    pub comp_def_account: UncheckedAccount<'info>,
-    #[account(mut)]
-    /// CHECK: address_lookup_table
-    pub address_lookup_table: UncheckedAccount<'info>,
-    /// CHECK: lut_program
-    pub lut_program: UncheckedAccount<'info>,
-    pub arcium_program: Program<'info, Arcium>,
-    pub system_program: Program<'info, System>,
-}
+    /// CHECK: Vault authority PDA
+    #[account(
+        seeds = [b"vault_authority", organization.key().as_ref()],
+        bump,
+    )]
+    pub vault_authority: AccountInfo<'info>,
 
-#[init_computation_definition_accounts("calculate_vested", payer)]
-#[derive(Accounts)]
-pub struct InitCalculateVestedCompDef<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(mut, address = derive_mxe_pda!())]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(mut)]
-    /// CHECK: comp_def_account
-    pub comp_def_account: UncheckedAccount<'info>,
-    #[account(mut)]
-    /// CHECK: address_lookup_table
-    pub address_lookup_table: UncheckedAccount<'info>,
-    /// CHECK: lut_program
-    pub lut_program: UncheckedAccount<'info>,
-    pub arcium_program: Program<'info, Arcium>,
-    pub system_program: Program<'info, System>,
-}
+    #[account(
+        mut,
+        seeds = [b"vault", organization.key().as_ref(), schedule.token_mint.as_ref()],
+        bump,
+        token::mint = schedule.token_mint,
+        token::authority = vault_authority,
+    )]
+    pub vault: Account<'info, TokenAccount>,
 
-#[init_computation_definition_accounts("process_claim", payer)]
-#[derive(Accounts)]
-pub struct InitProcessClaimCompDef<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(mut, address = derive_mxe_pda!())]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(mut)]
-    /// CHECK: comp_def_account
-    pub comp_def_account: UncheckedAccount<'info>,
-    #[account(mut)]
-    /// CHECK: address_lookup_table
-    pub address_lookup_table: UncheckedAccount<'info>,
-    /// CHECK: lut_program
-    pub lut_program: UncheckedAccount<'info>,
-    pub arcium_program: Program<'info, Arcium>,
-    pub system_program: Program<'info, System>,
-}
+    #[account(address = schedule.token_mint @ ShadowVestError::InvalidTokenMint)]
+    pub token_mint: Account<'info, token::Mint>,
 
-#[init_computation_definition_accounts("process_claim_v2", payer)]
-#[derive(Accounts)]
-pub struct InitProcessClaimV2CompDef<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(mut, address = derive_mxe_pda!())]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(mut)]
-    /// CHECK: comp_def_account
-    pub comp_def_account: UncheckedAccount<'info>,
-    #[account(mut)]
-    /// CHECK: address_lookup_table
-    pub address_lookup_table: UncheckedAccount<'info>,
-    /// CHECK: lut_program
-    pub lut_program: UncheckedAccount<'info>,
-    pub arcium_program: Program<'info, Arcium>,
-    pub system_program: Program<'info, System>,
-}
+    /// CHECK: Wallet owning the destination ATA; only used to derive it
+    pub beneficiary_wallet: UncheckedAccount<'info>,
 
-// ============================================================
-// Account Contexts - Organization & Schedule (Non-MPC)
-// ============================================================
+    /// Beneficiary wallet's ATA for the vault mint, created if missing
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = token_mint,
+        associated_token::authority = beneficiary_wallet,
+    )]
+    pub destination: Box<Account<'info, TokenAccount>>,
 
-#[derive(Accounts)]
-pub struct CreateOrganization<'info> {
+    /// Receives the protocol fee; required when organization.fee_bps > 0
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub fee_destination: Option<Account<'info, TokenAccount>>,
+
+    /// Organization guardian; must co-sign claims of at least guardian_threshold
+    pub guardian: Option<Signer<'info>>,
 
     #[account(
-        init,
-        payer = admin,
-        space = Organization::SIZE,
-        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        init_if_needed,
+        payer = payer,
+        space = BeneficiaryClaimHistory::SIZE,
+        seeds = [BeneficiaryClaimHistory::SEED_PREFIX, position.beneficiary_commitment.as_ref()],
         bump,
     )]
-    pub organization: Account<'info, Organization>,
+    pub claim_history: Box<Account<'info, BeneficiaryClaimHistory>>,
 
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct CreateVestingSchedule<'info> {
+pub struct WithdrawAndUnwrap<'info> {
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub payer: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
         bump = organization.bump,
-        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
     )]
     pub organization: Account<'info, Organization>,
 
+    #[account(
+        mut,
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position.position_id.to_le_bytes().as_ref()],
+        bump = position.bump,
+        constraint = position.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
+    )]
+    pub position: Account<'info, VestingPosition>,
+
+    #[account(
+        mut,
+        seeds = [ClaimAuthorization::SEED_PREFIX, position.key().as_ref(), claim_authorization.nullifier.as_ref()],
+        bump = claim_authorization.bump,
+        constraint = claim_authorization.position == position.key() @ ShadowVestError::InvalidPositionOrganization,
+    )]
+    pub claim_authorization: Account<'info, ClaimAuthorization>,
+
+    /// Schedule of the position; selects the vault for its token mint
+    #[account(address = position.schedule @ ShadowVestError::InvalidScheduleParams)]
+    pub schedule: Account<'info, VestingSchedule>,
+
+    /// CHECK: Vault authority PDA
+    #[account(
+        seeds = [b"vault_authority", organization.key().as_ref()],
+        bump,
+    )]
+    pub vault_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", organization.key().as_ref(), schedule.token_mint.as_ref()],
+        bump,
+        token::mint = schedule.token_mint,
+        token::authority = vault_authority,
+        constraint = vault.mint == token::spl_token::native_mint::ID @ ShadowVestError::InvalidTokenMint,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    /// Temporary wSOL account, closed before the instruction returns
     #[account(
         init,
-        payer = admin,
-        space = VestingSchedule::SIZE,
-        seeds = [
-            VestingSchedule::SEED_PREFIX,
-            organization.key().as_ref(),
-            organization.schedule_count.to_le_bytes().as_ref()
-        ],
+        payer = payer,
+        seeds = [b"unwrap", claim_authorization.key().as_ref()],
         bump,
+        token::mint = native_mint,
+        token::authority = vault_authority,
     )]
-    pub schedule: Account<'info, VestingSchedule>,
+    pub unwrap_account: Account<'info, TokenAccount>,
 
-    pub system_program: Program<'info, System>,
-}
+    #[account(address = token::spl_token::native_mint::ID @ ShadowVestError::InvalidTokenMint)]
+    pub native_mint: Account<'info, token::Mint>,
 
-// ============================================================
-// Account Contexts - Compressed Vesting Positions (Light Protocol)
-// ============================================================
+    /// wSOL account receiving the protocol fee; required when organization.fee_bps > 0
+    #[account(mut)]
+    pub fee_destination: Option<Account<'info, TokenAccount>>,
 
-/// Account context for creating compressed vesting positions.
-/// Uses Light Protocol CPI for 5000x cost reduction.
-///
-/// Note: Light Protocol accounts are passed via `remaining_accounts`:
-/// - light_system_program
-/// - account_compression_program
-/// - registered_program_pda
-/// - noop_program
-/// - cpi_authority_pda
-/// - state_merkle_tree
-/// - address_merkle_tree
-/// - address_queue
-#[derive(Accounts)]
-pub struct CreateCompressedVestingPosition<'info> {
-    /// Fee payer for the Light Protocol CPI transaction
+    /// Organization guardian; must co-sign claims of at least guardian_threshold
+    pub guardian: Option<Signer<'info>>,
+
+    /// CHECK: Receives the unwrapped lamports; must match the authorized destination
     #[account(mut)]
-    pub fee_payer: Signer<'info>,
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = BeneficiaryClaimHistory::SIZE,
+        seeds = [BeneficiaryClaimHistory::SEED_PREFIX, position.beneficiary_commitment.as_ref()],
+        bump,
+    )]
+    pub claim_history: Box<Account<'info, BeneficiaryClaimHistory>>,
 
-    /// Organization admin who can create positions
-    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireClaimAuthorization<'info> {
+    /// CHECK: Rent refund destination, must be the original payer
+    #[account(mut, address = claim_authorization.payer)]
+    pub payer: UncheckedAccount<'info>,
 
-    /// Organization account (mutable for counter update)
     #[account(
         mut,
-        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
         bump = organization.bump,
-        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
     )]
     pub organization: Account<'info, Organization>,
 
-    /// Vesting schedule for this position
     #[account(
         mut,
+        close = payer,
         seeds = [
-            VestingSchedule::SEED_PREFIX,
+            NullifierRecord::SEED_PREFIX,
             organization.key().as_ref(),
-            schedule.schedule_id.to_le_bytes().as_ref()
+            nullifier_record.namespace.as_ref(),
+            claim_authorization.nullifier.as_ref(),
         ],
-        bump = schedule.bump,
-        constraint = schedule.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
+        bump = nullifier_record.bump,
+        constraint = nullifier_record.position == claim_authorization.position @ ShadowVestError::InvalidPositionOrganization,
     )]
-    pub schedule: Account<'info, VestingSchedule>,
+    pub nullifier_record: Account<'info, NullifierRecord>,
 
-    /// System program for account creation
-    pub system_program: Program<'info, System>,
-    // Remaining accounts are provided dynamically for Light Protocol CPI
-}
+    #[account(mut, close = payer)]
+    pub claim_authorization: Account<'info, ClaimAuthorization>,
 
-// ============================================================
-// Account Contexts - Stealth Meta-Address
-// ============================================================
+    /// The claim's position; required to settle an open claim of a regular
+    /// (non-compressed) position, omitted for compressed positions
+    #[account(mut, address = claim_authorization.position @ ShadowVestError::InvalidPositionOrganization)]
+    pub position: Option<Account<'info, VestingPosition>>,
+}
 
 #[derive(Accounts)]
-pub struct RegisterStealthMeta<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
+pub struct CloseSpentClaim<'info> {
+    /// CHECK: Rent refund destination, must be the original payer
+    #[account(mut, address = claim_authorization.payer)]
+    pub payer: UncheckedAccount<'info>,
 
     #[account(
-        init,
-        payer = owner,
-        space = StealthMetaAddress::SIZE,
-        seeds = [StealthMetaAddress::SEED_PREFIX, owner.key().as_ref()],
-        bump,
+        mut,
+        close = payer,
+        constraint = claim_authorization.is_withdrawn @ ShadowVestError::ClaimNotWithdrawn,
     )]
-    pub stealth_meta: Account<'info, StealthMetaAddress>,
-
-    pub system_program: Program<'info, System>,
+    pub claim_authorization: Account<'info, ClaimAuthorization>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateStealthMeta<'info> {
-    pub owner: Signer<'info>,
+pub struct ClaimFlowStatus<'info> {
+    pub claim_authorization: Account<'info, ClaimAuthorization>,
 
     #[account(
-        mut,
-        seeds = [StealthMetaAddress::SEED_PREFIX, owner.key().as_ref()],
-        bump = stealth_meta.bump,
-        has_one = owner @ ShadowVestError::UnauthorizedOwner,
+        seeds = [PendingComputation::SEED_PREFIX, claim_authorization.key().as_ref()],
+        bump = pending_computation.bump,
     )]
-    pub stealth_meta: Account<'info, StealthMetaAddress>,
+    pub pending_computation: Option<Account<'info, PendingComputation>>,
 }
 
 #[derive(Accounts)]
-pub struct DeactivateStealthMeta<'info> {
-    pub owner: Signer<'info>,
+pub struct ResolveClaimAuthorization<'info> {
+    #[account(
+        seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
+        bump = organization.bump,
+    )]
+    pub organization: Account<'info, Organization>,
+}
 
+#[derive(Accounts)]
+pub struct CheckCliff<'info> {
     #[account(
-        mut,
-        seeds = [StealthMetaAddress::SEED_PREFIX, owner.key().as_ref()],
-        bump = stealth_meta.bump,
-        has_one = owner @ ShadowVestError::UnauthorizedOwner,
+        seeds = [VestingPosition::SEED_PREFIX, position.organization.as_ref(), position.position_id.to_le_bytes().as_ref()],
+        bump = position.bump,
     )]
-    pub stealth_meta: Account<'info, StealthMetaAddress>,
+    pub position: Account<'info, VestingPosition>,
+
+    #[account(
+        constraint = position.schedule == schedule.key() @ ShadowVestError::InvalidScheduleParams,
+    )]
+    pub schedule: Account<'info, VestingSchedule>,
 }
 
-// ============================================================
-// Account Contexts - Claim Authorization & Withdrawal
-// ============================================================
+#[derive(Accounts)]
+pub struct IsPositionRevoked<'info> {
+    #[account(
+        seeds = [VestingPosition::SEED_PREFIX, position.organization.as_ref(), position.position_id.to_le_bytes().as_ref()],
+        bump = position.bump,
+    )]
+    pub position: Account<'info, VestingPosition>,
+}
 
 #[derive(Accounts)]
-#[instruction(nullifier: [u8; 32])]
-pub struct AuthorizeClaim<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
+pub struct RevealPositionTotal<'info> {
+    pub admin: Signer<'info>,
 
     #[account(
-        seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
         bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
     )]
     pub organization: Account<'info, Organization>,
 
     #[account(
+        mut,
         seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position.position_id.to_le_bytes().as_ref()],
         bump = position.bump,
         constraint = position.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
     )]
     pub position: Account<'info, VestingPosition>,
+}
+
+#[derive(Accounts)]
+pub struct SetPositionPaused<'info> {
+    pub admin: Signer<'info>,
 
     #[account(
-        init,
-        payer = payer,
-        space = ClaimAuthorization::SIZE,
-        seeds = [ClaimAuthorization::SEED_PREFIX, position.key().as_ref(), nullifier.as_ref()],
-        bump,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
     )]
-    pub claim_authorization: Account<'info, ClaimAuthorization>,
+    pub organization: Account<'info, Organization>,
 
     #[account(
-        init,
-        payer = payer,
-        space = NullifierRecord::SIZE,
-        seeds = [NullifierRecord::SEED_PREFIX, organization.key().as_ref(), nullifier.as_ref()],
-        bump,
+        mut,
+        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position.position_id.to_le_bytes().as_ref()],
+        bump = position.bump,
+        constraint = position.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
     )]
-    pub nullifier_record: Account<'info, NullifierRecord>,
-
-    /// CHECK: Instructions sysvar for reading Ed25519 instruction
-    #[account(address = sysvar_instructions::ID)]
-    pub instructions_sysvar: AccountInfo<'info>,
-
-    pub system_program: Program<'info, System>,
+    pub position: Account<'info, VestingPosition>,
 }
 
-#[queue_computation_accounts("process_claim_v2", payer)]
 #[derive(Accounts)]
-#[instruction(computation_offset: u64)]
-pub struct QueueProcessClaim<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
+pub struct SetFreezeVestingOnPause<'info> {
+    pub admin: Signer<'info>,
 
     #[account(
-        seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
         bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
     )]
     pub organization: Account<'info, Organization>,
+}
 
+#[derive(Accounts)]
+pub struct CalculateVestedPlaintext<'info> {
     #[account(
-        seeds = [VestingSchedule::SEED_PREFIX, organization.key().as_ref(), schedule.schedule_id.to_le_bytes().as_ref()],
-        bump = schedule.bump,
-        constraint = schedule.organization == organization.key() @ ShadowVestError::InvalidScheduleParams,
+        seeds = [VestingPosition::SEED_PREFIX, position.organization.as_ref(), position.position_id.to_le_bytes().as_ref()],
+        bump = position.bump,
     )]
-    pub schedule: Box<Account<'info, VestingSchedule>>,
+    pub position: Account<'info, VestingPosition>,
 
     #[account(
-        seeds = [VestingPosition::SEED_PREFIX, organization.key().as_ref(), position.position_id.to_le_bytes().as_ref()],
-        bump = position.bump,
-        constraint = position.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
         constraint = position.schedule == schedule.key() @ ShadowVestError::InvalidScheduleParams,
     )]
-    pub position: Box<Account<'info, VestingPosition>>,
+    pub schedule: Account<'info, VestingSchedule>,
+}
 
+#[derive(Accounts)]
+pub struct ExpireComputation<'info> {
     #[account(
         mut,
-        seeds = [ClaimAuthorization::SEED_PREFIX, position.key().as_ref(), claim_authorization.nullifier.as_ref()],
-        bump = claim_authorization.bump,
-        constraint = claim_authorization.position == position.key() @ ShadowVestError::InvalidPositionOrganization,
+        seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
+        bump = organization.bump,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    /// Nullifier record binds the authorization to this organization
+    #[account(
+        seeds = [
+            NullifierRecord::SEED_PREFIX,
+            organization.key().as_ref(),
+            nullifier_record.namespace.as_ref(),
+            claim_authorization.nullifier.as_ref(),
+        ],
+        bump = nullifier_record.bump,
+        constraint = nullifier_record.position == claim_authorization.position @ ShadowVestError::InvalidPositionOrganization,
     )]
+    pub nullifier_record: Account<'info, NullifierRecord>,
+
+    #[account(mut)]
     pub claim_authorization: Account<'info, ClaimAuthorization>,
 
     #[account(
-        init_if_needed,
-        space = 9,
-        payer = payer,
-        seeds = [b"ArciumSignerAccount"],
-        bump,
+        mut,
+        seeds = [PendingComputation::SEED_PREFIX, claim_authorization.key().as_ref()],
+        bump = pending_computation.bump,
     )]
-    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
-    #[account(address = derive_mxe_pda!())]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: mempool_account
-    pub mempool_account: UncheckedAccount<'info>,
-    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: executing_pool
-    pub executing_pool: UncheckedAccount<'info>,
-    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: computation_account
-    pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROCESS_CLAIM_V2))]
-    pub comp_def_account: Box<Account<'info, ComputationDefinitionAccount>>,
-    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    pub cluster_account: Box<Account<'info, Cluster>>,
-    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
-    pub pool_account: Box<Account<'info, FeePool>>,
-    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
-    pub clock_account: Box<Account<'info, ClockAccount>>,
-    pub system_program: Program<'info, System>,
-    pub arcium_program: Program<'info, Arcium>,
+    pub pending_computation: Account<'info, PendingComputation>,
 }
 
-#[callback_accounts("process_claim_v2")]
 #[derive(Accounts)]
-#[instruction(computation_offset: u64)]
-pub struct ProcessClaimV2Callback<'info> {
-    pub arcium_program: Program<'info, Arcium>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROCESS_CLAIM_V2))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
-    #[account(address = derive_mxe_pda!())]
-    pub mxe_account: Account<'info, MXEAccount>,
-    /// CHECK: computation_account
-    pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    pub cluster_account: Account<'info, Cluster>,
-    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
-    /// CHECK: instructions_sysvar
-    pub instructions_sysvar: AccountInfo<'info>,
-    #[account(mut)]
-    pub position: Account<'info, VestingPosition>,
+pub struct RevokeAuthorization<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    /// Nullifier record binds the authorization to this organization
+    /// (works for both regular and compressed position claims)
+    #[account(
+        seeds = [
+            NullifierRecord::SEED_PREFIX,
+            organization.key().as_ref(),
+            nullifier_record.namespace.as_ref(),
+            claim_authorization.nullifier.as_ref(),
+        ],
+        bump = nullifier_record.bump,
+        constraint = nullifier_record.position == claim_authorization.position @ ShadowVestError::InvalidPositionOrganization,
+    )]
+    pub nullifier_record: Account<'info, NullifierRecord>,
+
     #[account(mut)]
     pub claim_authorization: Account<'info, ClaimAuthorization>,
+
+    /// The claim's position; required to settle an open claim of a regular
+    /// (non-compressed) position, omitted for compressed positions
+    #[account(mut, address = claim_authorization.position @ ShadowVestError::InvalidPositionOrganization)]
+    pub position: Option<Account<'info, VestingPosition>>,
 }
 
 #[derive(Accounts)]
-pub struct InitializeVault<'info> {
+pub struct QueueWithdraw<'info> {
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
+        bump = organization.bump,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    /// Nullifier record binds the authorization to this organization
+    /// (works for both regular and compressed position claims)
+    #[account(
+        seeds = [
+            NullifierRecord::SEED_PREFIX,
+            organization.key().as_ref(),
+            nullifier_record.namespace.as_ref(),
+            claim_authorization.nullifier.as_ref(),
+        ],
+        bump = nullifier_record.bump,
+        constraint = nullifier_record.position == claim_authorization.position @ ShadowVestError::InvalidPositionOrganization,
+    )]
+    pub nullifier_record: Account<'info, NullifierRecord>,
+
     #[account(mut)]
+    pub claim_authorization: Account<'info, ClaimAuthorization>,
+}
+
+#[derive(Accounts)]
+pub struct CancelWithdraw<'info> {
     pub admin: Signer<'info>,
 
     #[account(
+        mut,
         seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
         bump = organization.bump,
         has_one = admin @ ShadowVestError::UnauthorizedAdmin,
     )]
     pub organization: Account<'info, Organization>,
 
-    /// CHECK: Vault authority PDA - used as token account authority
-    #[account(
-        seeds = [b"vault_authority", organization.key().as_ref()],
-        bump,
-    )]
-    pub vault_authority: AccountInfo<'info>,
-
     #[account(
-        init,
-        payer = admin,
-        token::mint = token_mint,
-        token::authority = vault_authority,
-        seeds = [b"vault", organization.key().as_ref()],
-        bump,
+        seeds = [
+            NullifierRecord::SEED_PREFIX,
+            organization.key().as_ref(),
+            nullifier_record.namespace.as_ref(),
+            claim_authorization.nullifier.as_ref(),
+        ],
+        bump = nullifier_record.bump,
+        constraint = nullifier_record.position == claim_authorization.position @ ShadowVestError::InvalidPositionOrganization,
     )]
-    pub vault: Account<'info, TokenAccount>,
+    pub nullifier_record: Account<'info, NullifierRecord>,
 
-    pub token_mint: Account<'info, token::Mint>,
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+    #[account(mut)]
+    pub claim_authorization: Account<'info, ClaimAuthorization>,
+
+    /// The claim's position; required to settle an open claim of a regular
+    /// (non-compressed) position, omitted for compressed positions
+    #[account(mut, address = claim_authorization.position @ ShadowVestError::InvalidPositionOrganization)]
+    pub position: Option<Account<'info, VestingPosition>>,
 }
 
 #[derive(Accounts)]
-pub struct DepositToVault<'info> {
-    #[account(mut)]
+#[instruction(nullifier: [u8; 32])]
+pub struct InvalidateNullifier<'info> {
     pub admin: Signer<'info>,
 
     #[account(
+        mut,
         seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
         bump = organization.bump,
         has_one = admin @ ShadowVestError::UnauthorizedAdmin,
     )]
     pub organization: Account<'info, Organization>,
 
+    /// CHECK: Rent refund destination, must be the original payer
+    #[account(mut, address = claim_authorization.payer)]
+    pub payer: UncheckedAccount<'info>,
+
     #[account(
         mut,
-        token::mint = organization.token_mint,
-        seeds = [b"vault", organization.key().as_ref()],
-        bump,
+        close = payer,
+        seeds = [
+            NullifierRecord::SEED_PREFIX,
+            organization.key().as_ref(),
+            nullifier_record.namespace.as_ref(),
+            nullifier.as_ref(),
+        ],
+        bump = nullifier_record.bump,
+        constraint = nullifier_record.position == claim_authorization.position @ ShadowVestError::InvalidPositionOrganization,
     )]
-    pub vault: Account<'info, TokenAccount>,
+    pub nullifier_record: Account<'info, NullifierRecord>,
 
     #[account(
         mut,
-        token::mint = organization.token_mint,
-        token::authority = admin,
+        close = payer,
+        constraint = claim_authorization.nullifier == nullifier @ ShadowVestError::NullifierMismatch,
     )]
-    pub admin_token_account: Account<'info, TokenAccount>,
+    pub claim_authorization: Account<'info, ClaimAuthorization>,
 
-    pub token_program: Program<'info, Token>,
+    /// The claim's position; required to settle an open claim of a regular
+    /// (non-compressed) position, omitted for compressed positions
+    #[account(mut, address = claim_authorization.position @ ShadowVestError::InvalidPositionOrganization)]
+    pub position: Option<Account<'info, VestingPosition>>,
 }
 
 #[derive(Accounts)]
-pub struct Withdraw<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-
+pub struct ReplayPositionEvents<'info> {
     #[account(
         seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
         bump = organization.bump,
@@ -2683,34 +9277,6 @@ pub struct Withdraw<'info> {
         constraint = position.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
     )]
     pub position: Account<'info, VestingPosition>,
-
-    #[account(
-        mut,
-        seeds = [ClaimAuthorization::SEED_PREFIX, position.key().as_ref(), claim_authorization.nullifier.as_ref()],
-        bump = claim_authorization.bump,
-        constraint = claim_authorization.position == position.key() @ ShadowVestError::InvalidPositionOrganization,
-    )]
-    pub claim_authorization: Account<'info, ClaimAuthorization>,
-
-    /// CHECK: Vault authority PDA
-    #[account(
-        seeds = [b"vault_authority", organization.key().as_ref()],
-        bump,
-    )]
-    pub vault_authority: AccountInfo<'info>,
-
-    #[account(
-        mut,
-        seeds = [b"vault", organization.key().as_ref()],
-        bump,
-        token::authority = vault_authority,
-    )]
-    pub vault: Account<'info, TokenAccount>,
-
-    #[account(mut)]
-    pub destination: Account<'info, TokenAccount>,
-
-    pub token_program: Program<'info, Token>,
 }
 
 // ============================================================
@@ -2732,6 +9298,8 @@ pub struct Withdraw<'info> {
     position_start_timestamp: i64,
     position_is_active: u8,
     position_is_fully_claimed: u8,
+    position_is_stealth: u8,
+    position_metadata_commitment: [u8; 32],
     nullifier: [u8; 32],
     withdrawal_destination: Pubkey,
 )]
@@ -2759,14 +9327,21 @@ pub struct AuthorizeClaimCompressed<'info> {
     )]
     pub claim_authorization: Account<'info, ClaimAuthorization>,
 
+    /// CHECK: NullifierRecord PDA, created in the handler; must not exist yet
     #[account(
-        init,
-        payer = fee_payer,
-        space = NullifierRecord::SIZE,
-        seeds = [NullifierRecord::SEED_PREFIX, organization.key().as_ref(), nullifier.as_ref()],
+        mut,
+        seeds = [
+            NullifierRecord::SEED_PREFIX,
+            organization.key().as_ref(),
+            organization.nullifier_namespace.as_ref(),
+            nullifier.as_ref(),
+        ],
         bump,
     )]
-    pub nullifier_record: Account<'info, NullifierRecord>,
+    pub nullifier_record: UncheckedAccount<'info>,
+
+    /// CHECK: Light address tree the compressed position's address was derived under
+    pub address_tree: UncheckedAccount<'info>,
 
     /// CHECK: Instructions sysvar for Ed25519 verification
     #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
@@ -2783,6 +9358,7 @@ pub struct QueueProcessClaimCompressed<'info> {
     pub payer: Signer<'info>,
 
     #[account(
+        mut,
         seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
         bump = organization.bump,
     )]
@@ -2794,9 +9370,9 @@ pub struct QueueProcessClaimCompressed<'info> {
     )]
     pub schedule: Account<'info, VestingSchedule>,
 
-    /// Scratch position account used as a callback target.
-    /// For compressed positions, the callback writes encrypted_claimed_amount here
-    /// (the real state lives in Light Protocol and is updated via update_compressed_position_claimed).
+    /// Scratch position account used as a callback target; the callback leaves it
+    /// untouched for compressed claims (the real state lives in Light Protocol and is
+    /// updated via update_compressed_position_claimed).
     #[account(mut)]
     pub position: Account<'info, VestingPosition>,
 
@@ -2806,6 +9382,23 @@ pub struct QueueProcessClaimCompressed<'info> {
     )]
     pub claim_authorization: Account<'info, ClaimAuthorization>,
 
+    /// Compressed positions always draw from the organization's default-mint vault
+    #[account(
+        seeds = [b"vault", organization.key().as_ref(), organization.token_mint.as_ref()],
+        bump,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    /// Tracks the in-flight computation so a lost callback can be expired and re-queued
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = PendingComputation::SIZE,
+        seeds = [PendingComputation::SEED_PREFIX, claim_authorization.key().as_ref()],
+        bump,
+    )]
+    pub pending_computation: Box<Account<'info, PendingComputation>>,
+
     #[account(
         init_if_needed,
         space = 9,
@@ -2843,12 +9436,14 @@ pub struct UpdateCompressedPositionClaimed<'info> {
     pub fee_payer: Signer<'info>,
 
     #[account(
+        mut,
         seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
         bump = organization.bump,
     )]
     pub organization: Account<'info, Organization>,
 
     #[account(
+        mut,
         constraint = claim_authorization.is_processed @ ShadowVestError::ClaimNotProcessed,
         constraint = !claim_authorization.is_withdrawn @ ShadowVestError::AlreadyWithdrawn,
     )]
@@ -2857,6 +9452,66 @@ pub struct UpdateCompressedPositionClaimed<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct RevokeCompressedPosition<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeCompressedAndRefund<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Organization::SEED_PREFIX, admin.key().as_ref()],
+        bump = organization.bump,
+        has_one = admin @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub organization: Account<'info, Organization>,
+
+    /// Schedule of the revoked position; provides the vault's token mint
+    #[account(
+        constraint = schedule.organization == organization.key() @ ShadowVestError::InvalidPositionOrganization,
+    )]
+    pub schedule: Account<'info, VestingSchedule>,
+
+    /// CHECK: Vault authority PDA
+    #[account(
+        seeds = [b"vault_authority", organization.key().as_ref()],
+        bump,
+    )]
+    pub vault_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        token::mint = schedule.token_mint,
+        token::authority = vault_authority,
+        seeds = [b"vault", organization.key().as_ref(), schedule.token_mint.as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = schedule.token_mint,
+        constraint = treasury_token_account.owner == organization.treasury @ ShadowVestError::InvalidTreasuryAccount,
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 #[instruction(position_id: u64, nullifier: [u8; 32])]
 pub struct WithdrawCompressed<'info> {
@@ -2864,6 +9519,7 @@ pub struct WithdrawCompressed<'info> {
     pub payer: Signer<'info>,
 
     #[account(
+        mut,
         seeds = [Organization::SEED_PREFIX, organization.admin.as_ref()],
         bump = organization.bump,
     )]
@@ -2888,10 +9544,11 @@ pub struct WithdrawCompressed<'info> {
     )]
     pub vault_authority: AccountInfo<'info>,
 
+    /// Compressed positions always use the organization's default-mint vault
     #[account(
         mut,
         token::authority = vault_authority,
-        seeds = [b"vault", organization.key().as_ref()],
+        seeds = [b"vault", organization.key().as_ref(), organization.token_mint.as_ref()],
         bump,
     )]
     pub vault: Account<'info, TokenAccount>,
@@ -2899,6 +9556,13 @@ pub struct WithdrawCompressed<'info> {
     #[account(mut)]
     pub destination: Account<'info, TokenAccount>,
 
+    /// Receives the protocol fee; required when organization.fee_bps > 0
+    #[account(mut)]
+    pub fee_destination: Option<Account<'info, TokenAccount>>,
+
+    /// Organization guardian; must co-sign claims of at least guardian_threshold
+    pub guardian: Option<Signer<'info>>,
+
     pub token_program: Program<'info, Token>,
 }
 
@@ -3018,12 +9682,13 @@ pub struct ReadMetaKeysFromVault<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     pub owner: Signer<'info>,
+    /// CHECK: deserialized by MetaKeysVault::load so a closed vault reports
+    /// MetaKeysVaultNotInitialized
     #[account(
         seeds = [MetaKeysVault::SEED_PREFIX, owner.key().as_ref()],
-        bump = meta_keys_vault.bump,
-        has_one = owner @ ShadowVestError::UnauthorizedOwner,
+        bump,
     )]
-    pub meta_keys_vault: Account<'info, MetaKeysVault>,
+    pub meta_keys_vault: UncheckedAccount<'info>,
     #[account(
         init_if_needed,
         space = 9,
@@ -3074,66 +9739,229 @@ pub struct FetchMetaKeysCallback<'info> {
     pub meta_keys_vault: Account<'info, MetaKeysVault>,
 }
 
+#[derive(Accounts)]
+pub struct CloseMetaKeysVault<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [MetaKeysVault::SEED_PREFIX, owner.key().as_ref()],
+        bump = meta_keys_vault.bump,
+        has_one = owner @ ShadowVestError::UnauthorizedOwner,
+        close = owner,
+    )]
+    pub meta_keys_vault: Account<'info, MetaKeysVault>,
+}
+
 // ============================================================
 // Account Contexts - Groth16 Proof Verification
 // ============================================================
 
 /// Context for storing a verification key on-chain.
-/// Only the authority (admin) can store VKs for circuit verification.
+/// Only the program's upgrade authority can store VKs for circuit verification.
 #[derive(Accounts)]
-#[instruction(circuit_id: [u8; 32], vk_data: Vec<u8>)]
+#[instruction(circuit_id: [u8; 32], proof_type: ProofType, vk_data: Vec<u8>)]
 pub struct StoreVerificationKey<'info> {
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub authority: Signer<'info>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::Contract>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = VerificationKeyAccount::size_with_vk_data(vk_data.len()),
+        seeds = [VerificationKeyAccount::SEED_PREFIX, circuit_id.as_ref()],
+        bump,
+    )]
+    pub vk_account: Account<'info, VerificationKeyAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for updating an existing verification key.
+#[derive(Accounts)]
+pub struct UpdateVerificationKey<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VerificationKeyAccount::SEED_PREFIX, vk_account.circuit_id.as_ref()],
+        bump = vk_account.bump,
+        has_one = authority @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub vk_account: Account<'info, VerificationKeyAccount>,
+}
+
+/// Context for activating or deactivating a verification key.
+#[derive(Accounts)]
+pub struct SetVkActive<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VerificationKeyAccount::SEED_PREFIX, vk_account.circuit_id.as_ref()],
+        bump = vk_account.bump,
+        has_one = authority @ ShadowVestError::UnauthorizedAdmin,
+    )]
+    pub vk_account: Account<'info, VerificationKeyAccount>,
+}
+
+/// Context for exporting a stored verification key (read-only).
+#[derive(Accounts)]
+pub struct ExportVerificationKey<'info> {
+    #[account(
+        seeds = [VerificationKeyAccount::SEED_PREFIX, vk_account.circuit_id.as_ref()],
+        bump = vk_account.bump,
+    )]
+    pub vk_account: Account<'info, VerificationKeyAccount>,
+}
+
+/// Context for verifying a withdrawal proof.
+/// Creates a ProofRecord PDA keyed by [b"proof_record", verifier, nullifier].
+#[derive(Accounts)]
+#[instruction(proof: Groth16Proof, public_inputs: WithdrawalPublicInputs)]
+pub struct VerifyWithdrawalProof<'info> {
+    #[account(mut)]
+    pub verifier: Signer<'info>,
+
+    /// The verification key account for the withdrawal circuit
+    #[account(
+        seeds = [VerificationKeyAccount::SEED_PREFIX, vk_account.circuit_id.as_ref()],
+        bump = vk_account.bump,
+    )]
+    pub vk_account: Account<'info, VerificationKeyAccount>,
+
+    /// Proof record PDA - proves this verification happened on-chain.
+    /// Keyed by verifier + nullifier to prevent duplicate records.
+    #[account(
+        init,
+        payer = verifier,
+        space = ProofRecord::SIZE,
+        seeds = [ProofRecord::SEED_PREFIX, verifier.key().as_ref(), public_inputs.nullifier.as_ref()],
+        bump,
+    )]
+    pub proof_record: Account<'info, ProofRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for verifying a proof of any type.
+/// Creates a ProofRecord PDA keyed by [b"proof_record", verifier, nullifier],
+/// where the nullifier is the public input selected by `proof_type`.
+#[derive(Accounts)]
+#[instruction(proof_type: ProofType, proof: Groth16Proof, public_inputs: Vec<[u8; 32]>)]
+pub struct VerifyProof<'info> {
+    #[account(mut)]
+    pub verifier: Signer<'info>,
+
+    /// The verification key account for the proof's circuit
+    #[account(
+        seeds = [VerificationKeyAccount::SEED_PREFIX, vk_account.circuit_id.as_ref()],
+        bump = vk_account.bump,
+    )]
+    pub vk_account: Account<'info, VerificationKeyAccount>,
+
+    /// Proof record PDA keyed by the proof's nullifier
+    #[account(
+        init,
+        payer = verifier,
+        space = ProofRecord::SIZE,
+        seeds = [ProofRecord::SEED_PREFIX, verifier.key().as_ref(), proof_type.nullifier_seed(&public_inputs)],
+        bump,
+    )]
+    pub proof_record: Account<'info, ProofRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for verifying a batch of withdrawal proofs.
+/// ProofRecord PDAs (one per nullifier) are passed in remaining_accounts.
+#[derive(Accounts)]
+pub struct VerifyProofsBatch<'info> {
+    #[account(mut)]
+    pub verifier: Signer<'info>,
 
+    /// The verification key account for the withdrawal circuit
     #[account(
-        init,
-        payer = authority,
-        space = VerificationKeyAccount::size_with_vk_data(vk_data.len()),
-        seeds = [VerificationKeyAccount::SEED_PREFIX, circuit_id.as_ref()],
-        bump,
+        seeds = [VerificationKeyAccount::SEED_PREFIX, vk_account.circuit_id.as_ref()],
+        bump = vk_account.bump,
     )]
     pub vk_account: Account<'info, VerificationKeyAccount>,
 
     pub system_program: Program<'info, System>,
 }
 
-/// Context for updating an existing verification key.
+/// Context for closing a proof record as the verifier who created it.
 #[derive(Accounts)]
-pub struct UpdateVerificationKey<'info> {
-    pub authority: Signer<'info>,
+pub struct CloseProofRecord<'info> {
+    #[account(mut)]
+    pub verifier: Signer<'info>,
 
     #[account(
         mut,
+        seeds = [ProofRecord::SEED_PREFIX, verifier.key().as_ref(), proof_record.nullifier.as_ref()],
+        bump = proof_record.bump,
+        has_one = verifier @ ShadowVestError::UnauthorizedOwner,
+        close = verifier,
+    )]
+    pub proof_record: Account<'info, ProofRecord>,
+}
+
+/// Context for closing a proof record of a deactivated verification key.
+#[derive(Accounts)]
+pub struct CloseInvalidatedProofRecord<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
         seeds = [VerificationKeyAccount::SEED_PREFIX, vk_account.circuit_id.as_ref()],
         bump = vk_account.bump,
         has_one = authority @ ShadowVestError::UnauthorizedAdmin,
     )]
     pub vk_account: Account<'info, VerificationKeyAccount>,
+
+    /// CHECK: Receives the record's rent; bound to proof_record.verifier
+    #[account(mut, address = proof_record.verifier)]
+    pub verifier: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [ProofRecord::SEED_PREFIX, proof_record.verifier.as_ref(), proof_record.nullifier.as_ref()],
+        bump = proof_record.bump,
+        constraint = proof_record.circuit_id == vk_account.circuit_id @ ShadowVestError::CircuitIdMismatch,
+        close = verifier,
+    )]
+    pub proof_record: Account<'info, ProofRecord>,
 }
 
-/// Context for verifying a withdrawal proof.
-/// Creates a ProofRecord PDA keyed by [b"proof_record", verifier, nullifier].
+/// Context for verifying an identity proof.
+/// Creates a ProofRecord keyed by [b"proof_record", verifier, position_commitment].
 #[derive(Accounts)]
-#[instruction(proof: Groth16Proof, public_inputs: WithdrawalPublicInputs)]
-pub struct VerifyWithdrawalProof<'info> {
+#[instruction(proof: Groth16Proof, public_inputs: IdentityPublicInputs)]
+pub struct VerifyIdentityProof<'info> {
     #[account(mut)]
     pub verifier: Signer<'info>,
 
-    /// The verification key account for the withdrawal circuit
+    /// The verification key account for the identity circuit
     #[account(
         seeds = [VerificationKeyAccount::SEED_PREFIX, vk_account.circuit_id.as_ref()],
         bump = vk_account.bump,
     )]
     pub vk_account: Account<'info, VerificationKeyAccount>,
 
-    /// Proof record PDA - proves this verification happened on-chain.
-    /// Keyed by verifier + nullifier to prevent duplicate records.
+    /// Proof record PDA keyed by position_commitment (used as nullifier for identity proofs)
     #[account(
         init,
         payer = verifier,
         space = ProofRecord::SIZE,
-        seeds = [ProofRecord::SEED_PREFIX, verifier.key().as_ref(), public_inputs.nullifier.as_ref()],
+        seeds = [ProofRecord::SEED_PREFIX, verifier.key().as_ref(), public_inputs.position_commitment.as_ref()],
         bump,
     )]
     pub proof_record: Account<'info, ProofRecord>,
@@ -3141,98 +9969,399 @@ pub struct VerifyWithdrawalProof<'info> {
     pub system_program: Program<'info, System>,
 }
 
-/// Context for verifying an identity proof.
-/// Creates a ProofRecord keyed by [b"proof_record", verifier, position_commitment].
+/// Context for verifying an eligibility proof.
+/// Creates a ProofRecord keyed by [b"proof_record", verifier, nullifier].
 #[derive(Accounts)]
-#[instruction(proof: Groth16Proof, public_inputs: IdentityPublicInputs)]
-pub struct VerifyIdentityProof<'info> {
+#[instruction(proof: Groth16Proof, public_inputs: EligibilityPublicInputs)]
+pub struct VerifyEligibilityProof<'info> {
     #[account(mut)]
     pub verifier: Signer<'info>,
 
-    /// The verification key account for the identity circuit
+    /// The verification key account for the eligibility circuit
     #[account(
         seeds = [VerificationKeyAccount::SEED_PREFIX, vk_account.circuit_id.as_ref()],
         bump = vk_account.bump,
     )]
     pub vk_account: Account<'info, VerificationKeyAccount>,
 
-    /// Proof record PDA keyed by position_commitment (used as nullifier for identity proofs)
+    /// Proof record PDA keyed by nullifier (prevents double-verification)
     #[account(
         init,
         payer = verifier,
         space = ProofRecord::SIZE,
-        seeds = [ProofRecord::SEED_PREFIX, verifier.key().as_ref(), public_inputs.position_commitment.as_ref()],
+        seeds = [ProofRecord::SEED_PREFIX, verifier.key().as_ref(), public_inputs.nullifier.as_ref()],
         bump,
     )]
     pub proof_record: Account<'info, ProofRecord>,
 
-    pub system_program: Program<'info, System>,
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================================
+// Events
+// ============================================================
+
+#[event]
+pub struct OrganizationCreated {
+    pub organization: Pubkey,
+    pub admin: Pubkey,
+    pub name_hash: [u8; 32],
+    pub token_mint: Pubkey,
+}
+
+#[event]
+pub struct OrganizationRegistered {
+    pub organization: Pubkey,
+    pub name_hash: [u8; 32],
+    /// Position of this organization in the registry (0-based)
+    pub sequence: u64,
+    pub page_index: u64,
+}
+
+#[event]
+pub struct TreasuryUpdated {
+    pub organization: Pubkey,
+    pub old_treasury: Pubkey,
+    pub new_treasury: Pubkey,
+}
+
+#[event]
+pub struct OrganizationNameRevealed {
+    pub organization: Pubkey,
+    pub name: String,
+}
+
+#[event]
+pub struct MinReserveUpdated {
+    pub organization: Pubkey,
+    pub min_reserve: u64,
+}
+
+#[event]
+pub struct DefaultScheduleUpdated {
+    pub organization: Pubkey,
+    pub schedule: Pubkey,
+}
+
+#[event]
+pub struct ComputationTimeoutUpdated {
+    pub organization: Pubkey,
+    pub computation_timeout: u64,
+}
+
+#[event]
+pub struct CompressedOnlyUpdated {
+    pub organization: Pubkey,
+    pub compressed_only: bool,
+}
+
+#[event]
+pub struct OrganizationPausedUpdated {
+    pub organization: Pubkey,
+    pub paused: bool,
+}
+
+#[event]
+pub struct AuditorKeyUpdated {
+    pub organization: Pubkey,
+    pub auditor_pubkey: [u8; 32],
+}
+
+#[event]
+pub struct WithdrawalFeeUpdated {
+    pub organization: Pubkey,
+    pub fee_bps: u16,
+    pub fee_destination: Pubkey,
+}
+
+#[event]
+pub struct AtaOnlyWithdrawalsUpdated {
+    pub organization: Pubkey,
+    pub ata_only_withdrawals: bool,
+}
+
+#[event]
+pub struct ChallengePeriodUpdated {
+    pub organization: Pubkey,
+    pub challenge_period: u64,
+}
+
+#[event]
+pub struct GuardianUpdated {
+    pub organization: Pubkey,
+    pub guardian: Option<Pubkey>,
+    pub threshold: u64,
+}
+
+#[event]
+pub struct WithdrawalQueued {
+    pub organization: Pubkey,
+    pub claim_authorization: Pubkey,
+    pub position: Pubkey,
+    pub requested_at: i64,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct WithdrawalExecuted {
+    pub organization: Pubkey,
+    pub claim_authorization: Pubkey,
+    pub position: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct WithdrawalCancelled {
+    pub organization: Pubkey,
+    pub claim_authorization: Pubkey,
+    pub position: Pubkey,
+    pub nullifier: [u8; 32],
+}
+
+#[event]
+pub struct AllowedDestinationAdded {
+    pub organization: Pubkey,
+    pub destination: Pubkey,
+    pub allowed_destination: Pubkey,
+}
+
+#[event]
+pub struct DestinationAllowlistUpdated {
+    pub organization: Pubkey,
+    pub enforced: bool,
+}
+
+#[event]
+pub struct LazyInitUpdated {
+    pub organization: Pubkey,
+    pub lazy_init: bool,
+}
+
+#[event]
+pub struct PriorityFeeAccountUpdated {
+    pub organization: Pubkey,
+    pub priority_fee_account: Pubkey,
+}
+
+#[event]
+pub struct NullifierNamespaceUpdated {
+    pub organization: Pubkey,
+    pub namespace: [u8; 8],
+}
+
+#[event]
+pub struct VestingScheduleCreated {
+    pub organization: Pubkey,
+    pub schedule: Pubkey,
+    pub schedule_id: u64,
+    pub cliff_duration: u64,
+    pub total_duration: u64,
+    pub vesting_interval: u64,
+    pub vesting_mode: u8,
+    pub token_mint: Pubkey,
+}
+
+#[event]
+pub struct VestingScheduleUpdated {
+    pub organization: Pubkey,
+    pub schedule: Pubkey,
+    pub schedule_id: u64,
+    pub cliff_duration: u64,
+    pub total_duration: u64,
+    pub vesting_interval: u64,
+}
+
+#[event]
+pub struct VestingScheduleMigrated {
+    pub organization: Pubkey,
+    pub schedule: Pubkey,
+    pub schedule_id: u64,
+}
+
+#[event]
+pub struct ScheduleActiveChanged {
+    pub schedule: Pubkey,
+    pub is_active: bool,
+}
+
+#[event]
+pub struct MinClaimIntervalChanged {
+    pub schedule: Pubkey,
+    pub min_claim_interval: u64,
+}
+
+#[event]
+pub struct SchedulePrecisionUpdated {
+    pub schedule: Pubkey,
+    pub precision: u64,
+}
+
+#[event]
+pub struct PositionPausedChanged {
+    pub position: Pubkey,
+    pub position_id: u64,
+    pub is_paused: bool,
+}
+
+#[event]
+pub struct FreezeVestingOnPauseUpdated {
+    pub organization: Pubkey,
+    pub freeze_vesting_on_pause: bool,
+}
+
+#[event]
+pub struct PositionResumed {
+    pub position: Pubkey,
+    pub total_paused_seconds: u64,
+}
+
+#[event]
+pub struct ScheduleClaimsPausedChanged {
+    pub schedule: Pubkey,
+    pub claims_paused: bool,
+}
+
+#[event]
+pub struct ScheduleBeneficiaryRootSet {
+    pub schedule: Pubkey,
+    pub beneficiary_root: [u8; 32],
+}
+
+#[event]
+pub struct VestingPositionCreated {
+    pub organization: Pubkey,
+    pub schedule: Pubkey,
+    pub position: Pubkey,
+    pub position_id: u64,
+    pub beneficiary_commitment: [u8; 32],
+    pub start_timestamp: i64,
+    pub metadata_commitment: [u8; 32],
+}
+
+#[event]
+pub struct VestingPositionInitialized {
+    pub position: Pubkey,
+    pub position_id: u64,
+}
+
+#[event]
+pub struct PositionTopUpQueued {
+    pub position: Pubkey,
+    pub position_id: u64,
+    pub computation_offset: u64,
+}
+
+#[event]
+pub struct PositionToppedUp {
+    pub position: Pubkey,
+    pub position_id: u64,
+    pub encrypted_total_amount: [u8; 32],
+}
+
+#[event]
+pub struct PositionTopUpRejected {
+    pub position: Pubkey,
+    pub position_id: u64,
+}
+
+#[event]
+pub struct TotalsComparisonQueued {
+    pub position_a: Pubkey,
+    pub position_b: Pubkey,
+    pub auditor_pubkey: [u8; 32],
+    pub computation_offset: u64,
 }
 
-/// Context for verifying an eligibility proof.
-/// Creates a ProofRecord keyed by [b"proof_record", verifier, nullifier].
-#[derive(Accounts)]
-#[instruction(proof: Groth16Proof, public_inputs: EligibilityPublicInputs)]
-pub struct VerifyEligibilityProof<'info> {
-    #[account(mut)]
-    pub verifier: Signer<'info>,
+#[event]
+pub struct TotalsCompared {
+    pub position_a: Pubkey,
+    pub position_b: Pubkey,
+    pub encrypted_is_equal: [u8; 32],
+    pub nonce: [u8; 16],
+}
 
-    /// The verification key account for the eligibility circuit
-    #[account(
-        seeds = [VerificationKeyAccount::SEED_PREFIX, vk_account.circuit_id.as_ref()],
-        bump = vk_account.bump,
-    )]
-    pub vk_account: Account<'info, VerificationKeyAccount>,
+#[event]
+pub struct SolvencyCheckQueued {
+    pub organization: Pubkey,
+    pub vault: Pubkey,
+    pub vault_balance: u64,
+    pub position_count: u8,
+    pub computation_offset: u64,
+}
 
-    /// Proof record PDA keyed by nullifier (prevents double-verification)
-    #[account(
-        init,
-        payer = verifier,
-        space = ProofRecord::SIZE,
-        seeds = [ProofRecord::SEED_PREFIX, verifier.key().as_ref(), public_inputs.nullifier.as_ref()],
-        bump,
-    )]
-    pub proof_record: Account<'info, ProofRecord>,
+#[event]
+pub struct SolvencyChecked {
+    pub organization: Pubkey,
+    pub vault: Pubkey,
+    pub is_solvent: bool,
+}
 
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct PositionMergeQueued {
+    pub organization: Pubkey,
+    pub merged_position: Pubkey,
+    pub merged_position_id: u64,
+    pub position_a: Pubkey,
+    pub position_b: Pubkey,
+    pub computation_offset: u64,
 }
 
-// ============================================================
-// Events
-// ============================================================
+#[event]
+pub struct PositionMergeRejected {
+    pub merged_position: Pubkey,
+    pub merged_position_id: u64,
+    pub position_a: Pubkey,
+    pub position_b: Pubkey,
+}
 
 #[event]
-pub struct OrganizationCreated {
-    pub organization: Pubkey,
-    pub admin: Pubkey,
-    pub name_hash: [u8; 32],
-    pub token_mint: Pubkey,
+pub struct PositionsMerged {
+    pub merged_position: Pubkey,
+    pub merged_position_id: u64,
+    pub position_a: Pubkey,
+    pub position_b: Pubkey,
+    pub encrypted_total_amount: [u8; 32],
+    pub encrypted_claimed_amount: [u8; 32],
+    pub nonce: [u8; 16],
 }
 
 #[event]
-pub struct VestingScheduleCreated {
-    pub organization: Pubkey,
-    pub schedule: Pubkey,
-    pub schedule_id: u64,
-    pub cliff_duration: u64,
-    pub total_duration: u64,
-    pub vesting_interval: u64,
+pub struct PositionReencryptionQueued {
+    pub position: Pubkey,
+    pub position_id: u64,
+    pub new_pubkey: [u8; 32],
+    pub computation_offset: u64,
 }
 
 #[event]
-pub struct VestingPositionCreated {
+pub struct PositionReencrypted {
+    pub position: Pubkey,
+    pub position_id: u64,
+    pub encrypted_total_amount: [u8; 32],
+    pub encrypted_claimed_amount: [u8; 32],
+    pub nonce: [u8; 16],
+}
+
+#[event]
+pub struct PositionClosed {
+    pub position: Pubkey,
+    pub position_id: u64,
+}
+
+#[event]
+pub struct VestingPositionRevoked {
     pub organization: Pubkey,
-    pub schedule: Pubkey,
     pub position: Pubkey,
     pub position_id: u64,
-    pub beneficiary_commitment: [u8; 32],
-    pub start_timestamp: i64,
+    pub revoked_at: i64,
 }
 
 #[event]
-pub struct VestingPositionInitialized {
+pub struct PositionBeneficiaryRotated {
     pub position: Pubkey,
     pub position_id: u64,
+    pub old_commitment: [u8; 32],
+    pub new_commitment: [u8; 32],
 }
 
 #[event]
@@ -3242,6 +10371,22 @@ pub struct VestedAmountCalculationQueued {
     pub computation_offset: u64,
 }
 
+#[event]
+pub struct PositionCosigned {
+    pub organization: Pubkey,
+    pub position: Pubkey,
+    pub position_id: u64,
+    pub admin: Pubkey,
+    /// Beneficiary key that co-signed creation (equals the beneficiary commitment)
+    pub beneficiary: Pubkey,
+}
+
+#[event]
+pub struct PositionInitDeferred {
+    pub position: Pubkey,
+    pub position_id: u64,
+}
+
 #[event]
 pub struct VestedAmountCalculated {
     pub position: Pubkey,
@@ -3262,6 +10407,14 @@ pub struct CompressedPositionCreated {
     pub address: [u8; 32],
     pub beneficiary_commitment: [u8; 32],
     pub start_timestamp: i64,
+    pub metadata_commitment: [u8; 32],
+}
+
+#[event]
+pub struct CompressedAddressPreview {
+    /// Address create_compressed_vesting_position would derive for position_id
+    pub address: [u8; 32],
+    pub position_id: u64,
 }
 
 #[event]
@@ -3278,6 +10431,31 @@ pub struct VaultDeposited {
     pub vault: Pubkey,
     pub depositor: Pubkey,
     pub amount: u64,
+    /// Vault balance after the deposit
+    pub new_balance: u64,
+}
+
+#[event]
+pub struct VaultSurplusReclaimed {
+    pub organization: Pubkey,
+    pub vault: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PositionReservedAmountSet {
+    pub position: Pubkey,
+    pub position_id: u64,
+    pub reserved_amount: u64,
+}
+
+#[event]
+pub struct PositionSwept {
+    pub organization: Pubkey,
+    pub position: Pubkey,
+    pub position_id: u64,
+    pub treasury_token_account: Pubkey,
+    pub amount: u64,
 }
 
 #[event]
@@ -3296,11 +10474,30 @@ pub struct CompressedPositionClaimUpdated {
     pub new_is_fully_claimed: bool,
 }
 
+#[event]
+pub struct CompressedPositionRevoked {
+    pub organization: Pubkey,
+    pub position_id: u64,
+    pub address: [u8; 32],
+    pub revoked_at: i64,
+}
+
+#[event]
+pub struct CompressedPositionRevokedAndRefunded {
+    pub organization: Pubkey,
+    pub position_id: u64,
+    pub address: [u8; 32],
+    pub treasury_token_account: Pubkey,
+    pub amount: u64,
+    pub revoked_at: i64,
+}
+
 // Phase 4: Events for stealth addresses
 
 #[event]
 pub struct StealthMetaRegistered {
     pub owner: Pubkey,
+    pub label: [u8; 16],
     pub spend_pubkey: [u8; 32],
     pub view_pubkey: [u8; 32],
     pub registered_at: i64,
@@ -3309,6 +10506,7 @@ pub struct StealthMetaRegistered {
 #[event]
 pub struct StealthMetaUpdated {
     pub owner: Pubkey,
+    pub label: [u8; 16],
     pub spend_pubkey: [u8; 32],
     pub view_pubkey: [u8; 32],
 }
@@ -3316,6 +10514,23 @@ pub struct StealthMetaUpdated {
 #[event]
 pub struct StealthMetaDeactivated {
     pub owner: Pubkey,
+    pub label: [u8; 16],
+}
+
+#[event]
+pub struct StealthMetaQuoted {
+    pub owner: Pubkey,
+    pub label: [u8; 16],
+    pub spend_pubkey: [u8; 32],
+    pub view_pubkey: [u8; 32],
+    pub quoted_at: i64,
+}
+
+#[event]
+pub struct StealthDelegateSet {
+    pub owner: Pubkey,
+    pub label: [u8; 16],
+    pub delegate: Option<Pubkey>,
 }
 
 #[event]
@@ -3328,6 +10543,7 @@ pub struct MetaKeysVaultCreated {
 pub struct MetaKeysVaultInitialized {
     pub owner: Pubkey,
     pub vault: Pubkey,
+    pub version: u32,
 }
 
 #[event]
@@ -3348,15 +10564,31 @@ pub struct MetaKeysRetrieved {
     pub nonce: [u8; 16],
 }
 
+#[event]
+pub struct MetaKeysVaultClosed {
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+}
+
 // Phase 5: Claim & Withdrawal Events
 
 #[event]
 pub struct ClaimAuthorized {
+    pub organization: Pubkey,
     pub position: Pubkey,
+    pub position_id: u64,
     pub nullifier: [u8; 32],
     pub withdrawal_destination: Pubkey,
 }
 
+#[event]
+pub struct ClaimDestinationUpdated {
+    pub position: Pubkey,
+    pub nullifier: [u8; 32],
+    pub old_destination: Pubkey,
+    pub new_destination: Pubkey,
+}
+
 #[event]
 pub struct ClaimProcessQueued {
     pub position: Pubkey,
@@ -3364,6 +10596,7 @@ pub struct ClaimProcessQueued {
     pub claim_amount: u64,
     pub computation_offset: u64,
     pub vesting_numerator: u64,
+    pub priority_fee: u64,
 }
 
 #[event]
@@ -3371,6 +10604,30 @@ pub struct ClaimProcessed {
     pub position: Pubkey,
     pub position_id: u64,
     pub claim_amount: u64,
+    /// Whether the new claimed amount reaches the total (revealed by the MPC)
+    pub fully_claimed: bool,
+}
+
+#[event]
+pub struct ClaimRejected {
+    pub position: Pubkey,
+    pub position_id: u64,
+}
+
+#[event]
+pub struct ClaimsBatchQueued {
+    pub organization: Pubkey,
+    pub positions: Vec<Pubkey>,
+    pub computation_offset: u64,
+}
+
+#[event]
+pub struct ClaimsBatchProcessed {
+    pub organization: Pubkey,
+    /// Claims' positions applied by the callback (expired claims are left out)
+    pub positions: Vec<Pubkey>,
+    /// Whether each applied claim was within its claimable amount
+    pub is_valid: Vec<bool>,
 }
 
 #[event]
@@ -3381,14 +10638,140 @@ pub struct VaultInitialized {
     pub token_mint: Pubkey,
 }
 
+#[event]
+pub struct ClaimFlowStatusReported {
+    pub claim_authorization: Pubkey,
+    pub position: Pubkey,
+    pub step: ClaimFlowStep,
+    pub is_revoked: bool,
+    pub claim_amount: u64,
+    pub withdrawn_so_far: u64,
+}
+
+#[event]
+pub struct CliffStatus {
+    pub position: Pubkey,
+    pub cliff_reached: bool,
+    /// Seconds until the cliff ends (0 once reached)
+    pub seconds_until_cliff: i64,
+}
+
+#[event]
+pub struct PositionRevocationStatus {
+    pub position: Pubkey,
+    pub is_revoked: bool,
+    /// Revocation timestamp (0 if never revoked)
+    pub revoked_at: i64,
+}
+
+#[event]
+pub struct PositionTotalRevealed {
+    pub position: Pubkey,
+    pub position_id: u64,
+    pub total_amount: u64,
+}
+
+#[event]
+pub struct PlaintextVestedCalculated {
+    pub position: Pubkey,
+    pub total_amount: u64,
+    /// Vested fraction scaled by `precision`
+    pub vesting_numerator: u64,
+    /// The schedule's numerator precision
+    pub precision: u64,
+    pub vested_amount: u64,
+}
+
+#[event]
+pub struct ClaimAuthorizationResolved {
+    pub organization: Pubkey,
+    pub is_compressed: bool,
+    pub claim_authorization: Pubkey,
+    pub bump: u8,
+}
+
 #[event]
 pub struct ClaimWithdrawn {
     pub position: Pubkey,
     pub destination: Pubkey,
+    /// Total debited from the vault, including the fee
     pub amount: u64,
+    /// Portion of amount sent to the organization's fee destination
+    pub fee: u64,
+    /// Vault balance after the withdrawal
+    pub remaining_balance: u64,
+    /// Organization's completed withdrawals, including this one
+    pub total_withdrawals: u64,
+    /// Organization's positions that have reached fully claimed
+    pub total_claimed_positions: u64,
     pub token_mint: Pubkey,
 }
 
+#[event]
+pub struct PositionReplayed {
+    pub organization: Pubkey,
+    pub schedule: Pubkey,
+    pub position: Pubkey,
+    pub position_id: u64,
+    pub beneficiary_commitment: [u8; 32],
+    pub start_timestamp: i64,
+    pub state: PositionState,
+    pub revoked_at: i64,
+    pub encrypted_total_amount: [u8; 32],
+    pub encrypted_claimed_amount: [u8; 32],
+}
+
+#[event]
+pub struct ClaimReplayed {
+    pub position: Pubkey,
+    pub claim_authorization: Pubkey,
+    pub nullifier: [u8; 32],
+    pub withdrawal_destination: Pubkey,
+    pub claim_amount: u64,
+    pub withdrawn_so_far: u64,
+    pub authorized_at: i64,
+    pub is_processed: bool,
+    pub is_withdrawn: bool,
+    pub is_revoked: bool,
+}
+
+#[event]
+pub struct ClaimAuthorizationClosed {
+    pub claim_authorization: Pubkey,
+    pub position: Pubkey,
+    pub nullifier: [u8; 32],
+}
+
+#[event]
+pub struct ClaimAuthorizationExpired {
+    pub organization: Pubkey,
+    pub position: Pubkey,
+    pub nullifier: [u8; 32],
+}
+
+#[event]
+pub struct ComputationExpired {
+    pub organization: Pubkey,
+    pub claim_authorization: Pubkey,
+    pub computation_account: Pubkey,
+    pub queued_at: i64,
+}
+
+#[event]
+pub struct ClaimAuthorizationRevoked {
+    pub organization: Pubkey,
+    pub position: Pubkey,
+    pub nullifier: [u8; 32],
+}
+
+#[event]
+pub struct NullifierInvalidated {
+    pub organization: Pubkey,
+    pub position: Pubkey,
+    pub claim_authorization: Pubkey,
+    pub nullifier: [u8; 32],
+}
+
 // Phase 6: Groth16 Proof Verification Events
 
 #[event]
@@ -3404,6 +10787,27 @@ pub struct VerificationKeyUpdated {
     pub vk_account: Pubkey,
 }
 
+#[event]
+pub struct VerificationKeyActiveChanged {
+    pub circuit_id: [u8; 32],
+    pub vk_account: Pubkey,
+    pub is_active: bool,
+}
+
+#[event]
+pub struct VerificationKeyExported {
+    pub circuit_id: [u8; 32],
+    pub vk_account: Pubkey,
+    pub is_active: bool,
+    /// sha256 of the raw vk_data
+    pub vk_hash: [u8; 32],
+    pub alpha_g1: [u8; 64],
+    pub beta_g2: [u8; 128],
+    pub gamma_g2: [u8; 128],
+    pub delta_g2: [u8; 128],
+    pub ic: Vec<[u8; 64]>,
+}
+
 #[event]
 pub struct ProofVerified {
     pub verifier: Pubkey,
@@ -3413,6 +10817,15 @@ pub struct ProofVerified {
     pub verified_at: i64,
 }
 
+#[event]
+pub struct ProofRecordClosed {
+    pub proof_record: Pubkey,
+    pub verifier: Pubkey,
+    pub nullifier: [u8; 32],
+    /// The verifier, or the VK authority for an invalidated circuit
+    pub closed_by: Pubkey,
+}
+
 /// Type of ZK proof being verified.
 /// Used in events and for circuit identification.
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
@@ -3425,6 +10838,37 @@ pub enum ProofType {
     Eligibility,
 }
 
+impl ProofType {
+    /// Index of the public input that keys the ProofRecord: the nullifier for
+    /// withdrawal and eligibility proofs, the position_commitment for identity
+    /// proofs (matching the order of each circuit's to_scalars).
+    pub fn nullifier_index(&self) -> usize {
+        match self {
+            ProofType::Withdrawal => 2,
+            ProofType::Identity => 0,
+            ProofType::Eligibility => 1,
+        }
+    }
+
+    /// Number of public inputs of the circuit (matching each to_scalars)
+    pub fn num_public_inputs(&self) -> usize {
+        match self {
+            ProofType::Withdrawal => WithdrawalPublicInputs::NUM_PUBLIC_INPUTS,
+            ProofType::Identity => IdentityPublicInputs::NUM_PUBLIC_INPUTS,
+            ProofType::Eligibility => EligibilityPublicInputs::NUM_PUBLIC_INPUTS,
+        }
+    }
+
+    /// ProofRecord seed for a raw public input vector. Too few inputs yield an
+    /// empty seed; verify_proof then rejects the call before writing the record.
+    pub fn nullifier_seed<'a>(&self, public_inputs: &'a [[u8; 32]]) -> &'a [u8] {
+        public_inputs
+            .get(self.nullifier_index())
+            .map(|n| n.as_ref())
+            .unwrap_or(&[])
+    }
+}
+
 // ============================================================
 // Error Codes
 // ============================================================
@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::errors::ShadowVestError;
+
+// ============================================================
+// Merkle Beneficiary Lists
+//
+// A schedule can commit to an off-chain beneficiary list through a single
+// sha256 Merkle root instead of one PDA per recipient. Leaves and inner
+// nodes are domain-separated (0x00 / 0x01 prefix) so an inner node can never
+// be presented as a leaf, and each pair is hashed in sorted order so proofs
+// don't need to carry left/right flags. Claims spend claim_nullifier(leaf, n)
+// (0x02 prefix) for the position's n-th list claim, so one entry can back
+// several claims without its nullifiers colliding.
+// ============================================================
+
+/// Deepest proof accepted (2^32 leaves)
+pub const MAX_PROOF_DEPTH: usize = 32;
+
+const LEAF_PREFIX: &[u8] = &[0x00];
+const NODE_PREFIX: &[u8] = &[0x01];
+const CLAIM_PREFIX: &[u8] = &[0x02];
+
+/// Leaf committing `beneficiary` to claim position `position_id` into `withdrawal_destination`:
+/// sha256(0x00 || position_id (le) || beneficiary || withdrawal_destination)
+pub fn beneficiary_leaf(
+    position_id: u64,
+    beneficiary: &Pubkey,
+    withdrawal_destination: &Pubkey,
+) -> [u8; 32] {
+    hashv(&[
+        LEAF_PREFIX,
+        &position_id.to_le_bytes(),
+        beneficiary.as_ref(),
+        withdrawal_destination.as_ref(),
+    ])
+    .to_bytes()
+}
+
+/// Nullifier of the claim with index `claim_index` made through `leaf`:
+/// sha256(0x02 || leaf || claim_index (le))
+pub fn claim_nullifier(leaf: &[u8; 32], claim_index: u64) -> [u8; 32] {
+    hashv(&[CLAIM_PREFIX, leaf, &claim_index.to_le_bytes()]).to_bytes()
+}
+
+/// Parent of two nodes: sha256(0x01 || min(a, b) || max(a, b))
+pub fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[NODE_PREFIX, left, right]).to_bytes()
+}
+
+/// Check that `proof` leads from `leaf` to `root`
+pub fn verify_proof(root: &[u8; 32], leaf: &[u8; 32], proof: &[[u8; 32]]) -> Result<()> {
+    require!(
+        proof.len() <= MAX_PROOF_DEPTH,
+        ShadowVestError::InvalidMerkleProof
+    );
+    let computed = proof
+        .iter()
+        .fold(*leaf, |node, sibling| hash_pair(&node, sibling));
+    require!(computed == *root, ShadowVestError::InvalidMerkleProof);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Four-leaf tree: returns (root, leaves, proof for leaf 2)
+    fn tree() -> ([u8; 32], Vec<[u8; 32]>, Vec<[u8; 32]>) {
+        let destination = Pubkey::new_unique();
+        let leaves: Vec<[u8; 32]> = (0..4)
+            .map(|id| beneficiary_leaf(id, &Pubkey::new_unique(), &destination))
+            .collect();
+        let left = hash_pair(&leaves[0], &leaves[1]);
+        let right = hash_pair(&leaves[2], &leaves[3]);
+        let proof = vec![leaves[3], left];
+        (hash_pair(&left, &right), leaves, proof)
+    }
+
+    #[test]
+    fn test_valid_proof() {
+        let (root, leaves, proof) = tree();
+        assert!(verify_proof(&root, &leaves[2], &proof).is_ok());
+    }
+
+    #[test]
+    fn test_single_leaf_tree() {
+        let leaf = beneficiary_leaf(0, &Pubkey::new_unique(), &Pubkey::new_unique());
+        assert!(verify_proof(&leaf, &leaf, &[]).is_ok());
+    }
+
+    #[test]
+    fn test_wrong_leaf_rejected() {
+        let (root, leaves, proof) = tree();
+        for leaf in [leaves[0], leaves[1], [0u8; 32]] {
+            assert_eq!(
+                verify_proof(&root, &leaf, &proof).unwrap_err(),
+                ShadowVestError::InvalidMerkleProof.into()
+            );
+        }
+    }
+
+    #[test]
+    fn test_tampered_proof_rejected() {
+        let (root, leaves, mut proof) = tree();
+        proof[1][0] ^= 1;
+        assert!(verify_proof(&root, &leaves[2], &proof).is_err());
+
+        // Dropping or appending a level also fails
+        let (root, leaves, proof) = tree();
+        assert!(verify_proof(&root, &leaves[2], &proof[..1]).is_err());
+        let mut longer = proof.clone();
+        longer.push([0u8; 32]);
+        assert!(verify_proof(&root, &leaves[2], &longer).is_err());
+    }
+
+    #[test]
+    fn test_claim_nullifiers_are_distinct() {
+        let (_, leaves, _) = tree();
+        let first = claim_nullifier(&leaves[0], 0);
+        assert_ne!(first, leaves[0]);
+        assert_ne!(first, claim_nullifier(&leaves[0], 1));
+        assert_ne!(first, claim_nullifier(&leaves[1], 0));
+    }
+
+    #[test]
+    fn test_proof_depth_limit() {
+        let leaf = [1u8; 32];
+        let proof = vec![[2u8; 32]; MAX_PROOF_DEPTH + 1];
+        let root = proof.iter().fold(leaf, |node, sibling| hash_pair(&node, sibling));
+        assert!(verify_proof(&root, &leaf, &proof).is_err());
+    }
+}
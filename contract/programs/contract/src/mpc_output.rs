@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ShadowVestError;
+
+// ============================================================
+// MPC Output Shape Checks
+//
+// Callbacks copy ciphertexts out of verified MPC outputs by index. A
+// misbehaving or upgraded circuit could return fewer ciphertexts than the
+// callback expects; these checks turn that into a clean
+// OutputVerificationFailed error instead of an index-out-of-bounds panic.
+// ============================================================
+
+/// Require that an MPC output carries at least `expected` ciphertexts.
+pub fn require_ciphertexts(ciphertexts: &[[u8; 32]], expected: usize) -> Result<()> {
+    require!(
+        ciphertexts.len() >= expected,
+        ShadowVestError::OutputVerificationFailed
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stand-in for an MPC output struct returned by an unexpected circuit shape
+    struct MockSharedOutput<const N: usize> {
+        ciphertexts: [[u8; 32]; N],
+    }
+
+    #[test]
+    fn test_exact_length_passes() {
+        let output = MockSharedOutput { ciphertexts: [[1u8; 32]; 2] };
+        assert!(require_ciphertexts(&output.ciphertexts, 2).is_ok());
+    }
+
+    #[test]
+    fn test_longer_output_passes() {
+        let output = MockSharedOutput { ciphertexts: [[1u8; 32]; 8] };
+        assert!(require_ciphertexts(&output.ciphertexts, 4).is_ok());
+    }
+
+    #[test]
+    fn test_short_output_rejected() {
+        let output = MockSharedOutput { ciphertexts: [[1u8; 32]; 1] };
+        let result = require_ciphertexts(&output.ciphertexts, 2);
+        assert_eq!(
+            result.unwrap_err(),
+            ShadowVestError::OutputVerificationFailed.into()
+        );
+    }
+
+    #[test]
+    fn test_empty_output_rejected() {
+        let output = MockSharedOutput { ciphertexts: [] };
+        assert!(require_ciphertexts(&output.ciphertexts, 1).is_err());
+    }
+}
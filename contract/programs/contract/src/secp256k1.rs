@@ -0,0 +1,219 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
+
+use crate::errors::ShadowVestError;
+
+// ============================================================
+// secp256k1 (Ethereum-style) Claim Signature Verification
+//
+// Beneficiaries holding secp256k1 keys authorize claims through Solana's
+// Secp256k1 precompile. The precompile recovers the signer's Ethereum
+// address and fails the transaction if the signature is invalid, so the
+// program only has to check that the preceding precompile instruction
+// verified the expected address over the expected message.
+// ============================================================
+
+/// Secp256k1 signature verification program ID
+pub const SECP256K1_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("KeccakSecp256k11111111111111111111111111111");
+
+/// Size of an Ethereum address (last 20 bytes of keccak256(pubkey))
+pub const ETH_ADDRESS_SIZE: usize = 20;
+
+/// Offsets table starts right after the num_signatures byte
+const SIGNATURE_OFFSETS_START: usize = 1;
+
+/// Serialized size of one SecpSignatureOffsets entry:
+/// signature_offset(u16), signature_ix(u8), eth_address_offset(u16), eth_address_ix(u8),
+/// message_offset(u16), message_size(u16), message_ix(u8)
+const SIGNATURE_OFFSETS_SIZE: usize = 11;
+
+/// Verify that the instruction immediately before the current one is a Secp256k1
+/// precompile instruction that checked a signature from `expected_eth_address`
+/// over `expected_message`.
+pub fn verify_secp256k1_claim_signature(
+    ix_sysvar: &AccountInfo,
+    expected_eth_address: &[u8; ETH_ADDRESS_SIZE],
+    expected_message: &[u8],
+) -> Result<()> {
+    let current_ix_index = sysvar_instructions::load_current_index_checked(ix_sysvar)
+        .map_err(|_| ShadowVestError::InvalidEligibilitySignature)?;
+
+    // The Secp256k1 instruction must be the one immediately before this instruction
+    require!(
+        current_ix_index > 0,
+        ShadowVestError::InvalidEligibilitySignature
+    );
+    let secp_ix_index = current_ix_index - 1;
+
+    let secp_ix = sysvar_instructions::load_instruction_at_checked(secp_ix_index as usize, ix_sysvar)
+        .map_err(|_| ShadowVestError::InvalidEligibilitySignature)?;
+
+    require!(
+        secp_ix.program_id == SECP256K1_PROGRAM_ID,
+        ShadowVestError::InvalidEligibilitySignature
+    );
+
+    // Instruction indexes in the offsets table are u8 for the secp256k1 precompile
+    let secp_ix_index =
+        u8::try_from(secp_ix_index).map_err(|_| ShadowVestError::InvalidEligibilitySignature)?;
+
+    check_secp256k1_instruction_data(
+        &secp_ix.data,
+        secp_ix_index,
+        expected_eth_address,
+        expected_message,
+    )
+}
+
+/// Check the data of a Secp256k1 precompile instruction located at `own_index`.
+///
+/// All offsets must reference data inside the precompile instruction itself,
+/// otherwise the precompile could have verified different bytes than the ones
+/// read here.
+pub fn check_secp256k1_instruction_data(
+    data: &[u8],
+    own_index: u8,
+    expected_eth_address: &[u8; ETH_ADDRESS_SIZE],
+    expected_message: &[u8],
+) -> Result<()> {
+    require!(
+        data.len() >= SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE,
+        ShadowVestError::InvalidEligibilitySignature
+    );
+
+    let num_signatures = data[0];
+    require!(
+        num_signatures == 1,
+        ShadowVestError::InvalidEligibilitySignature
+    );
+
+    let offsets = &data[SIGNATURE_OFFSETS_START..SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE];
+    let signature_ix = offsets[2];
+    let eth_address_offset = u16::from_le_bytes([offsets[3], offsets[4]]) as usize;
+    let eth_address_ix = offsets[5];
+    let message_data_offset = u16::from_le_bytes([offsets[6], offsets[7]]) as usize;
+    let message_data_size = u16::from_le_bytes([offsets[8], offsets[9]]) as usize;
+    let message_ix = offsets[10];
+
+    require!(
+        signature_ix == own_index && eth_address_ix == own_index && message_ix == own_index,
+        ShadowVestError::InvalidEligibilitySignature
+    );
+
+    // Verify the recovered address matches the beneficiary
+    require!(
+        data.len() >= eth_address_offset + ETH_ADDRESS_SIZE,
+        ShadowVestError::InvalidEligibilitySignature
+    );
+    let signer_address = &data[eth_address_offset..eth_address_offset + ETH_ADDRESS_SIZE];
+    require!(
+        signer_address == expected_eth_address,
+        ShadowVestError::SignerMismatch
+    );
+
+    // Verify the signed message
+    require!(
+        data.len() >= message_data_offset + message_data_size,
+        ShadowVestError::InvalidEligibilitySignature
+    );
+    let signed_message = &data[message_data_offset..message_data_offset + message_data_size];
+    require!(
+        signed_message == expected_message,
+        ShadowVestError::InvalidEligibilitySignature
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ETH_ADDRESS: [u8; ETH_ADDRESS_SIZE] = [0xAB; ETH_ADDRESS_SIZE];
+
+    /// Build Secp256k1 instruction data in the same layout as
+    /// `Secp256k1Program.createInstructionWithPublicKey` from web3.js:
+    /// header | eth_address | signature | recovery_id | message
+    fn build_secp_ix_data(ix_index: u8, eth_address: &[u8; 20], message: &[u8]) -> Vec<u8> {
+        let data_start = SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE;
+        let eth_address_offset = data_start as u16;
+        let signature_offset = eth_address_offset + ETH_ADDRESS_SIZE as u16;
+        let message_offset = signature_offset + 64 + 1;
+
+        let mut data = vec![1u8];
+        data.extend_from_slice(&signature_offset.to_le_bytes());
+        data.push(ix_index);
+        data.extend_from_slice(&eth_address_offset.to_le_bytes());
+        data.push(ix_index);
+        data.extend_from_slice(&message_offset.to_le_bytes());
+        data.extend_from_slice(&(message.len() as u16).to_le_bytes());
+        data.push(ix_index);
+        data.extend_from_slice(eth_address);
+        data.extend_from_slice(&[0x11; 64]); // signature (checked by the precompile)
+        data.push(0); // recovery id
+        data.extend_from_slice(message);
+        data
+    }
+
+    fn claim_message() -> [u8; 72] {
+        let mut msg = [0u8; 72];
+        msg[..8].copy_from_slice(&7u64.to_le_bytes());
+        msg[8..40].copy_from_slice(&[0x22; 32]);
+        msg[40..72].copy_from_slice(&[0x33; 32]);
+        msg
+    }
+
+    #[test]
+    fn test_secp256k1_valid_claim_signature() {
+        let msg = claim_message();
+        let data = build_secp_ix_data(0, &ETH_ADDRESS, &msg);
+        assert!(check_secp256k1_instruction_data(&data, 0, &ETH_ADDRESS, &msg).is_ok());
+    }
+
+    #[test]
+    fn test_secp256k1_signer_mismatch() {
+        let msg = claim_message();
+        let data = build_secp_ix_data(0, &[0xCD; 20], &msg);
+        let result = check_secp256k1_instruction_data(&data, 0, &ETH_ADDRESS, &msg);
+        assert_eq!(result.unwrap_err(), ShadowVestError::SignerMismatch.into());
+    }
+
+    #[test]
+    fn test_secp256k1_message_mismatch() {
+        let msg = claim_message();
+        let data = build_secp_ix_data(0, &ETH_ADDRESS, &msg);
+        let mut other = msg;
+        other[0] ^= 1;
+        let result = check_secp256k1_instruction_data(&data, 0, &ETH_ADDRESS, &other);
+        assert_eq!(
+            result.unwrap_err(),
+            ShadowVestError::InvalidEligibilitySignature.into()
+        );
+    }
+
+    #[test]
+    fn test_secp256k1_rejects_foreign_instruction_index() {
+        // Offsets pointing at another instruction would let the precompile verify
+        // different bytes than the ones checked here
+        let msg = claim_message();
+        let data = build_secp_ix_data(0, &ETH_ADDRESS, &msg);
+        assert!(check_secp256k1_instruction_data(&data, 1, &ETH_ADDRESS, &msg).is_err());
+    }
+
+    #[test]
+    fn test_secp256k1_rejects_multiple_signatures() {
+        let msg = claim_message();
+        let mut data = build_secp_ix_data(0, &ETH_ADDRESS, &msg);
+        data[0] = 2;
+        assert!(check_secp256k1_instruction_data(&data, 0, &ETH_ADDRESS, &msg).is_err());
+    }
+
+    #[test]
+    fn test_secp256k1_rejects_truncated_data() {
+        let msg = claim_message();
+        let data = build_secp_ix_data(0, &ETH_ADDRESS, &msg);
+        assert!(check_secp256k1_instruction_data(&data[..40], 0, &ETH_ADDRESS, &msg).is_err());
+        assert!(check_secp256k1_instruction_data(&data[..5], 0, &ETH_ADDRESS, &msg).is_err());
+    }
+}
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+/// Withdrawal destination approved by an organization's admin.
+/// Only consulted while organization.enforce_destination_allowlist is set.
+/// Seeds: [b"allowed_dest", organization, destination]
+#[account]
+pub struct AllowedDestination {
+    /// Organization that approved the destination
+    pub organization: Pubkey,
+    /// Approved withdrawal destination (token account)
+    pub destination: Pubkey,
+    /// Timestamp when the destination was approved
+    pub added_at: i64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl AllowedDestination {
+    pub const SIZE: usize = 8 +  // discriminator
+        32 + // organization
+        32 + // destination
+        8 +  // added_at
+        1;   // bump
+    // Total: 81 bytes
+
+    pub const SEED_PREFIX: &'static [u8] = b"allowed_dest";
+}
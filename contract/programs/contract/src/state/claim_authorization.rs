@@ -1,8 +1,26 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::ShadowVestError;
+
+/// Step of the authorize -> queue -> process -> withdraw claim flow.
+/// Reported by claim_flow_status so clients can resume an interrupted claim.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClaimFlowStep {
+    /// Signature verified; next: queue_process_claim
+    Authorized,
+    /// MPC computation queued, callback not received yet; next: wait or expire_computation
+    Queued,
+    /// MPC callback applied the claim; next: withdraw
+    Processed,
+    /// Tokens fully withdrawn; nothing left to do
+    Withdrawn,
+}
+
 /// Authorization record for a claim against a vesting position.
 /// Created by authorize_claim after Ed25519 signature verification.
 /// Seeds: [b"claim_auth", position.key(), nullifier]
+/// Compressed positions have no account key, so their authorizations use
+/// [b"claim_auth", organization.key(), position_id.to_le_bytes(), nullifier]
 #[account]
 pub struct ClaimAuthorization {
     /// The vesting position being claimed against
@@ -17,10 +35,42 @@ pub struct ClaimAuthorization {
     pub is_authorized: bool,
     /// Whether MPC processing is complete
     pub is_processed: bool,
-    /// Whether tokens have been withdrawn
+    /// Whether tokens have been withdrawn (set once withdrawn_so_far reaches claim_amount)
     pub is_withdrawn: bool,
     /// Timestamp of authorization
     pub authorized_at: i64,
+    /// Whether the organization revoked this authorization (blocks withdrawal)
+    pub is_revoked: bool,
+    /// Amount withdrawn so far via partial withdrawals
+    pub withdrawn_so_far: u64,
+    /// Seconds after authorized_at before an unprocessed authorization can be expired (0 = never)
+    pub claim_expiry_seconds: u64,
+    /// Account that paid rent for this authorization and its nullifier record
+    pub payer: Pubkey,
+    /// Stealth address of the position's beneficiary (default when the
+    /// beneficiary is not a stealth address); reported on withdrawal
+    pub stealth_address: Pubkey,
+    /// Encrypted claimed amount update_compressed_position_claimed wrote to the
+    /// compressed position (all zeros until then; unused for regular positions)
+    pub compressed_claimed_amount: [u8; 32],
+    /// Fully-claimed flag revealed by process_claim_v2 (set by its callback);
+    /// update_compressed_position_claimed must write exactly this value
+    pub compressed_is_fully_claimed: u8,
+    /// Total amount process_claim_v2 re-encrypted for a compressed position
+    /// (set by its callback, written to the position by update_compressed_position_claimed)
+    pub compressed_new_total_amount: [u8; 32],
+    /// Claimed amount process_claim_v2 re-encrypted for a compressed position
+    pub compressed_new_claimed_amount: [u8; 32],
+    /// Nonce of the re-encrypted compressed amounts
+    pub compressed_new_nonce: u128,
+    /// Part of claim_amount still reserved against the vault for reserved_mint
+    pub reserved_amount: u64,
+    /// When queue_withdraw was called (0 = not queued)
+    pub withdraw_requested_at: i64,
+    /// Organization challenge period captured by queue_withdraw
+    pub challenge_period: u64,
+    /// Token mint of the vault the claim is reserved against (set by queue_process_claim)
+    pub reserved_mint: Pubkey,
     /// PDA bump seed
     pub bump: u8,
 }
@@ -35,15 +85,217 @@ impl ClaimAuthorization {
         1 +  // is_processed
         1 +  // is_withdrawn
         8 +  // authorized_at
+        1 +  // is_revoked
+        8 +  // withdrawn_so_far
+        8 +  // claim_expiry_seconds
+        32 + // payer
+        32 + // stealth_address
+        32 + // compressed_claimed_amount
+        1 +  // compressed_is_fully_claimed
+        32 + // compressed_new_total_amount
+        32 + // compressed_new_claimed_amount
+        16 + // compressed_new_nonce
+        8 +  // reserved_amount
+        8 +  // withdraw_requested_at
+        8 +  // challenge_period
+        32 + // reserved_mint
         1;   // bump
-    // Total: 124 bytes
+    // Total: 374 bytes
 
     pub const SEED_PREFIX: &'static [u8] = b"claim_auth";
+
+    /// PDA of a regular position's claim authorization
+    pub fn find_pda(position: &Pubkey, nullifier: &[u8; 32]) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[Self::SEED_PREFIX, position.as_ref(), nullifier.as_ref()],
+            &crate::ID,
+        )
+    }
+
+    /// PDA of a compressed position's claim authorization
+    pub fn find_compressed_pda(
+        organization: &Pubkey,
+        position_id: u64,
+        nullifier: &[u8; 32],
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                Self::SEED_PREFIX,
+                organization.as_ref(),
+                &position_id.to_le_bytes(),
+                nullifier.as_ref(),
+            ],
+            &crate::ID,
+        )
+    }
+
+    /// A fresh authorization as created by authorize_claim and its variants:
+    /// authorized at `authorized_at`, nothing queued, processed, reserved or withdrawn
+    #[allow(clippy::too_many_arguments)]
+    pub fn init(
+        position: Pubkey,
+        nullifier: [u8; 32],
+        withdrawal_destination: Pubkey,
+        authorized_at: i64,
+        claim_expiry_seconds: u64,
+        payer: Pubkey,
+        stealth_address: Pubkey,
+        bump: u8,
+    ) -> Self {
+        Self {
+            position,
+            nullifier,
+            withdrawal_destination,
+            claim_amount: 0,
+            is_authorized: true,
+            is_processed: false,
+            is_withdrawn: false,
+            authorized_at,
+            is_revoked: false,
+            withdrawn_so_far: 0,
+            claim_expiry_seconds,
+            payer,
+            stealth_address,
+            compressed_claimed_amount: [0u8; 32],
+            compressed_is_fully_claimed: 0,
+            compressed_new_total_amount: [0u8; 32],
+            compressed_new_claimed_amount: [0u8; 32],
+            compressed_new_nonce: 0,
+            reserved_amount: 0,
+            withdraw_requested_at: 0,
+            challenge_period: 0,
+            reserved_mint: Pubkey::default(),
+            bump,
+        }
+    }
+
+    /// Whether the authorization has passed its expiry and was never processed.
+    /// Processed claims have already been applied to the position, so they never expire.
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.claim_expiry_seconds > 0
+            && !self.is_processed
+            && !self.is_withdrawn
+            && now > self
+                .authorized_at
+                .saturating_add(i64::try_from(self.claim_expiry_seconds).unwrap_or(i64::MAX))
+    }
+
+    /// Whether the claim still counts against its position's open_claim_count:
+    /// authorized and neither withdrawn nor revoked
+    pub fn is_open(&self) -> bool {
+        self.is_authorized && !self.is_withdrawn && !self.is_revoked
+    }
+
+    /// Whether this is a regular position's authorization (rather than a
+    /// compressed position's), given the account's own address
+    pub fn is_regular(&self, key: &Pubkey) -> bool {
+        Self::find_pda(&self.position, &self.nullifier).0 == *key
+    }
+
+    /// Current step of the claim flow. `computation_pending` is the
+    /// PendingComputation flag for this authorization (false if none exists).
+    pub fn flow_step(&self, computation_pending: bool) -> ClaimFlowStep {
+        if self.is_withdrawn {
+            ClaimFlowStep::Withdrawn
+        } else if self.is_processed {
+            ClaimFlowStep::Processed
+        } else if computation_pending {
+            ClaimFlowStep::Queued
+        } else {
+            ClaimFlowStep::Authorized
+        }
+    }
+
+    /// Whether update_compressed_position_claimed has recorded its write
+    pub fn has_compressed_update(&self) -> bool {
+        self.compressed_claimed_amount != [0u8; 32]
+    }
+
+    /// Check a compressed position's current state against what
+    /// update_compressed_position_claimed wrote for this claim, so a withdrawal
+    /// cannot pay out a claim that was never applied to the position.
+    pub fn check_compressed_state(
+        &self,
+        encrypted_claimed_amount: &[u8; 32],
+        is_fully_claimed: u8,
+    ) -> Result<()> {
+        require!(
+            self.has_compressed_update(),
+            ShadowVestError::CompressedPositionNotUpdated
+        );
+        require!(
+            *encrypted_claimed_amount == self.compressed_claimed_amount
+                && is_fully_claimed == self.compressed_is_fully_claimed,
+            ShadowVestError::CompressedPositionStateMismatch
+        );
+        Ok(())
+    }
+
+    /// Check a client-supplied is_fully_claimed against the flag the MPC revealed
+    pub fn check_fully_claimed_flag(&self, is_fully_claimed: u8) -> Result<()> {
+        require!(
+            is_fully_claimed == self.compressed_is_fully_claimed,
+            ShadowVestError::FullyClaimedFlagMismatch
+        );
+        Ok(())
+    }
+
+    /// Whether queue_withdraw has been called for this claim
+    pub fn is_withdrawal_queued(&self) -> bool {
+        self.withdraw_requested_at != 0
+    }
+
+    /// Earliest time a queued withdrawal can execute
+    pub fn withdrawal_executable_at(&self) -> i64 {
+        self.withdraw_requested_at
+            .saturating_add(i64::try_from(self.challenge_period).unwrap_or(i64::MAX))
+    }
+
+    /// Check that tokens may leave the vault for this claim at `now`. Without a
+    /// queued withdrawal that requires the organization to have no challenge
+    /// period; a queued withdrawal must have waited out the period captured
+    /// when it was queued.
+    pub fn check_challenge_period(&self, org_challenge_period: u64, now: i64) -> Result<()> {
+        if !self.is_withdrawal_queued() {
+            require!(
+                org_challenge_period == 0,
+                ShadowVestError::WithdrawalNotQueued
+            );
+            return Ok(());
+        }
+        require!(
+            now >= self.withdrawal_executable_at(),
+            ShadowVestError::ChallengePeriodActive
+        );
+        Ok(())
+    }
+
+    /// Amount authorized but not yet withdrawn
+    pub fn remaining_amount(&self) -> u64 {
+        self.claim_amount.saturating_sub(self.withdrawn_so_far)
+    }
+
+    /// Record a withdrawal of `amount`, marking the claim withdrawn once the
+    /// running total reaches claim_amount
+    pub fn record_withdrawal(&mut self, amount: u64) -> Result<()> {
+        require!(
+            amount > 0 && amount <= self.remaining_amount(),
+            ShadowVestError::InvalidClaimAmount
+        );
+        self.withdrawn_so_far = self
+            .withdrawn_so_far
+            .checked_add(amount)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+        if self.withdrawn_so_far == self.claim_amount {
+            self.is_withdrawn = true;
+        }
+        Ok(())
+    }
 }
 
 /// Record that a nullifier has been used, preventing double-claims.
 /// Uses init constraint - existence means used. Second init with same seeds fails.
-/// Seeds: [b"nullifier", organization.key(), nullifier]
+/// Seeds: [b"nullifier", organization.key(), organization.nullifier_namespace, nullifier]
 #[account]
 pub struct NullifierRecord {
     /// The nullifier value
@@ -52,6 +304,8 @@ pub struct NullifierRecord {
     pub position: Pubkey,
     /// Timestamp when nullifier was consumed
     pub used_at: i64,
+    /// Organization nullifier namespace the record was created under
+    pub namespace: [u8; 8],
     /// PDA bump seed
     pub bump: u8,
 }
@@ -61,8 +315,261 @@ impl NullifierRecord {
         32 + // nullifier
         32 + // position
         8 +  // used_at
+        8 +  // namespace
         1;   // bump
-    // Total: 81 bytes
+    // Total: 89 bytes
 
     pub const SEED_PREFIX: &'static [u8] = b"nullifier";
+
+    /// Record of `nullifier` spent on `position` at `used_at`
+    pub fn init(
+        nullifier: [u8; 32],
+        position: Pubkey,
+        used_at: i64,
+        namespace: [u8; 8],
+        bump: u8,
+    ) -> Self {
+        Self {
+            nullifier,
+            position,
+            used_at,
+            namespace,
+            bump,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claim_auth(claim_amount: u64) -> ClaimAuthorization {
+        ClaimAuthorization {
+            position: Pubkey::default(),
+            nullifier: [0u8; 32],
+            withdrawal_destination: Pubkey::default(),
+            claim_amount,
+            is_authorized: true,
+            is_processed: true,
+            is_withdrawn: false,
+            authorized_at: 0,
+            is_revoked: false,
+            withdrawn_so_far: 0,
+            claim_expiry_seconds: 0,
+            payer: Pubkey::default(),
+            stealth_address: Pubkey::default(),
+            compressed_claimed_amount: [0u8; 32],
+            compressed_is_fully_claimed: 0,
+            compressed_new_total_amount: [0u8; 32],
+            compressed_new_claimed_amount: [0u8; 32],
+            compressed_new_nonce: 0,
+            reserved_amount: 0,
+            withdraw_requested_at: 0,
+            challenge_period: 0,
+            reserved_mint: Pubkey::default(),
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_init_starts_authorized_and_unreserved() {
+        let auth = ClaimAuthorization::init(
+            Pubkey::new_unique(),
+            [1u8; 32],
+            Pubkey::new_unique(),
+            1_000,
+            60,
+            Pubkey::new_unique(),
+            Pubkey::default(),
+            255,
+        );
+        assert_eq!(auth.flow_step(false), ClaimFlowStep::Authorized);
+        assert!(auth.is_open());
+        assert!(!auth.is_revoked);
+        assert!(!auth.has_compressed_update());
+        assert!(!auth.is_withdrawal_queued());
+        assert_eq!(auth.claim_amount, 0);
+        assert_eq!(auth.reserved_amount, 0);
+        assert_eq!(auth.reserved_mint, Pubkey::default());
+        assert!(!auth.is_expired(1_060));
+        assert!(auth.is_expired(1_061));
+    }
+
+    #[test]
+    fn test_two_partial_withdrawals_sum_to_claim_amount() {
+        let mut auth = claim_auth(100);
+        auth.record_withdrawal(40).unwrap();
+        assert!(!auth.is_withdrawn);
+        assert_eq!(auth.remaining_amount(), 60);
+
+        auth.record_withdrawal(60).unwrap();
+        assert!(auth.is_withdrawn);
+        assert_eq!(auth.remaining_amount(), 0);
+        assert_eq!(auth.withdrawn_so_far, 100);
+    }
+
+    #[test]
+    fn test_over_withdrawal_rejected() {
+        let mut auth = claim_auth(100);
+        auth.record_withdrawal(70).unwrap();
+        let result = auth.record_withdrawal(31);
+        assert_eq!(result.unwrap_err(), ShadowVestError::InvalidClaimAmount.into());
+        assert_eq!(auth.withdrawn_so_far, 70);
+        assert!(!auth.is_withdrawn);
+    }
+
+    #[test]
+    fn test_open_until_withdrawn_revoked_or_rejected() {
+        let mut auth = claim_auth(100);
+        assert!(auth.is_open());
+
+        auth.record_withdrawal(100).unwrap();
+        assert!(!auth.is_open());
+
+        let mut auth = claim_auth(100);
+        auth.is_revoked = true;
+        assert!(!auth.is_open());
+
+        let mut auth = claim_auth(100);
+        auth.is_authorized = false;
+        assert!(!auth.is_open());
+    }
+
+    #[test]
+    fn test_expiry_window() {
+        let mut auth = claim_auth(100);
+        auth.is_processed = false;
+        auth.authorized_at = 1_000;
+        auth.claim_expiry_seconds = 3_600;
+        assert!(!auth.is_expired(4_600));
+        assert!(auth.is_expired(4_601));
+
+        // Processed claims are already reflected in the position
+        auth.is_processed = true;
+        assert!(!auth.is_expired(10_000));
+
+        // Zero expiry never expires
+        auth.is_processed = false;
+        auth.claim_expiry_seconds = 0;
+        assert!(!auth.is_expired(i64::MAX));
+    }
+
+    #[test]
+    fn test_challenge_period() {
+        let mut auth = claim_auth(100);
+
+        // No challenge period: withdraw directly
+        assert!(auth.check_challenge_period(0, 1_000).is_ok());
+        assert_eq!(
+            auth.check_challenge_period(3_600, 1_000).unwrap_err(),
+            ShadowVestError::WithdrawalNotQueued.into()
+        );
+
+        auth.withdraw_requested_at = 1_000;
+        auth.challenge_period = 3_600;
+        assert_eq!(
+            auth.check_challenge_period(3_600, 4_599).unwrap_err(),
+            ShadowVestError::ChallengePeriodActive.into()
+        );
+        assert!(auth.check_challenge_period(3_600, 4_600).is_ok());
+
+        // The period captured at queue time applies even if the organization changes it
+        assert!(auth.check_challenge_period(86_400, 4_600).is_ok());
+        assert!(auth.check_challenge_period(0, 4_599).is_err());
+    }
+
+    #[test]
+    fn test_compressed_state_check() {
+        let mut auth = claim_auth(100);
+
+        // Processed but update_compressed_position_claimed never ran
+        assert_eq!(
+            auth.check_compressed_state(&[0u8; 32], 0).unwrap_err(),
+            ShadowVestError::CompressedPositionNotUpdated.into()
+        );
+
+        auth.compressed_claimed_amount = [7u8; 32];
+        auth.compressed_is_fully_claimed = 1;
+        assert!(auth.check_compressed_state(&[7u8; 32], 1).is_ok());
+
+        // Position holds a different ciphertext or fully-claimed flag
+        assert_eq!(
+            auth.check_compressed_state(&[8u8; 32], 1).unwrap_err(),
+            ShadowVestError::CompressedPositionStateMismatch.into()
+        );
+        assert_eq!(
+            auth.check_compressed_state(&[7u8; 32], 0).unwrap_err(),
+            ShadowVestError::CompressedPositionStateMismatch.into()
+        );
+    }
+
+    #[test]
+    fn test_fully_claimed_flag_must_match_mpc_result() {
+        let mut auth = claim_auth(100);
+        assert!(auth.check_fully_claimed_flag(0).is_ok());
+        assert_eq!(
+            auth.check_fully_claimed_flag(1).unwrap_err(),
+            ShadowVestError::FullyClaimedFlagMismatch.into()
+        );
+
+        // Callback revealed the position as fully claimed
+        auth.compressed_is_fully_claimed = 1;
+        assert!(auth.check_fully_claimed_flag(1).is_ok());
+        assert_eq!(
+            auth.check_fully_claimed_flag(0).unwrap_err(),
+            ShadowVestError::FullyClaimedFlagMismatch.into()
+        );
+    }
+
+    #[test]
+    fn test_zero_withdrawal_rejected() {
+        let mut auth = claim_auth(100);
+        assert!(auth.record_withdrawal(0).is_err());
+    }
+
+    #[test]
+    fn test_flow_step_follows_claim_lifecycle() {
+        let mut auth = claim_auth(100);
+        auth.is_processed = false;
+        assert_eq!(auth.flow_step(false), ClaimFlowStep::Authorized);
+        assert_eq!(auth.flow_step(true), ClaimFlowStep::Queued);
+
+        auth.is_processed = true;
+        assert_eq!(auth.flow_step(false), ClaimFlowStep::Processed);
+
+        // Partially withdrawn claims still have tokens to withdraw
+        auth.record_withdrawal(40).unwrap();
+        assert_eq!(auth.flow_step(false), ClaimFlowStep::Processed);
+
+        auth.record_withdrawal(60).unwrap();
+        assert_eq!(auth.flow_step(false), ClaimFlowStep::Withdrawn);
+    }
+
+    #[test]
+    fn test_regular_and_compressed_seed_schemes_differ() {
+        let organization = Pubkey::new_unique();
+        let position = Pubkey::new_unique();
+        let nullifier = [7u8; 32];
+
+        let (regular, _) = ClaimAuthorization::find_pda(&position, &nullifier);
+        let (compressed, _) = ClaimAuthorization::find_compressed_pda(&organization, 3, &nullifier);
+        assert_ne!(regular, compressed);
+
+        // Same seeds as the Withdraw / WithdrawCompressed account constraints
+        let (expected, _) = Pubkey::find_program_address(
+            &[
+                ClaimAuthorization::SEED_PREFIX,
+                organization.as_ref(),
+                &3u64.to_le_bytes(),
+                &nullifier,
+            ],
+            &crate::ID,
+        );
+        assert_eq!(compressed, expected);
+        let (expected, _) = Pubkey::find_program_address(
+            &[ClaimAuthorization::SEED_PREFIX, position.as_ref(), &nullifier],
+            &crate::ID,
+        );
+        assert_eq!(regular, expected);
+    }
 }
@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+
+/// A single withdrawal recorded in a beneficiary's claim history.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ClaimRecord {
+    /// Position the tokens were withdrawn from
+    pub position: Pubkey,
+    /// Amount transferred to the withdrawal destination
+    pub amount: u64,
+    /// Timestamp of the withdrawal
+    pub timestamp: i64,
+}
+
+impl ClaimRecord {
+    pub const SIZE: usize = 32 + // position
+        8 +  // amount
+        8;   // timestamp
+    // Total: 48 bytes
+}
+
+/// Rolling history of the most recent withdrawals for one beneficiary.
+/// Created on the beneficiary's first withdrawal; once full, the oldest
+/// record is overwritten.
+/// Seeds: [b"claim_history", beneficiary_commitment]
+#[account]
+pub struct BeneficiaryClaimHistory {
+    /// Beneficiary commitment this history belongs to
+    pub beneficiary_commitment: [u8; 32],
+    /// Total number of withdrawals ever recorded (next slot = total_claims % MAX_RECORDS)
+    pub total_claims: u64,
+    /// Ring buffer of the last MAX_RECORDS withdrawals
+    pub records: [ClaimRecord; BeneficiaryClaimHistory::MAX_RECORDS],
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl BeneficiaryClaimHistory {
+    /// Number of withdrawals kept before the oldest is overwritten
+    pub const MAX_RECORDS: usize = 16;
+
+    pub const SIZE: usize = 8 +  // discriminator
+        32 + // beneficiary_commitment
+        8 +  // total_claims
+        ClaimRecord::SIZE * Self::MAX_RECORDS + // records
+        1;   // bump
+    // Total: 817 bytes
+
+    pub const SEED_PREFIX: &'static [u8] = b"claim_history";
+
+    /// Append a withdrawal, overwriting the oldest record once the buffer is full
+    pub fn record(&mut self, position: Pubkey, amount: u64, timestamp: i64) {
+        let slot = (self.total_claims % Self::MAX_RECORDS as u64) as usize;
+        self.records[slot] = ClaimRecord {
+            position,
+            amount,
+            timestamp,
+        };
+        self.total_claims = self.total_claims.saturating_add(1);
+    }
+
+    /// Recorded withdrawals, oldest first
+    pub fn recent(&self) -> Vec<ClaimRecord> {
+        let count = self.total_claims.min(Self::MAX_RECORDS as u64) as usize;
+        let start = if self.total_claims > Self::MAX_RECORDS as u64 {
+            (self.total_claims % Self::MAX_RECORDS as u64) as usize
+        } else {
+            0
+        };
+        (0..count)
+            .map(|i| self.records[(start + i) % Self::MAX_RECORDS])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history() -> BeneficiaryClaimHistory {
+        BeneficiaryClaimHistory {
+            beneficiary_commitment: [0u8; 32],
+            total_claims: 0,
+            records: [ClaimRecord::default(); BeneficiaryClaimHistory::MAX_RECORDS],
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_records_in_order() {
+        let mut h = history();
+        h.record(Pubkey::default(), 10, 1_000);
+        h.record(Pubkey::default(), 20, 2_000);
+
+        let recent = h.recent();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].amount, 10);
+        assert_eq!(recent[1].amount, 20);
+        assert_eq!(h.total_claims, 2);
+    }
+
+    #[test]
+    fn test_rotation_overwrites_oldest() {
+        let mut h = history();
+        let n = BeneficiaryClaimHistory::MAX_RECORDS as u64;
+        for i in 0..n + 3 {
+            h.record(Pubkey::default(), i, i as i64);
+        }
+
+        let recent = h.recent();
+        assert_eq!(recent.len(), BeneficiaryClaimHistory::MAX_RECORDS);
+        assert_eq!(recent[0].amount, 3);
+        assert_eq!(recent.last().unwrap().amount, n + 2);
+        assert_eq!(h.total_claims, n + 3);
+    }
+}
@@ -53,6 +53,12 @@ pub struct CompressedVestingPosition {
     /// Whether all tokens have been claimed
     #[hash]
     pub is_fully_claimed: u8, // 1 = fully claimed, 0 = not
+    /// Whether beneficiary_commitment is a one-time stealth address
+    #[hash]
+    pub is_stealth: u8, // 1 = stealth, 0 = not
+    /// Commitment to off-chain position metadata (grant agreement, board approval, ...)
+    #[hash]
+    pub metadata_commitment: [u8; 32],
 }
 
 impl CompressedVestingPosition {
@@ -69,6 +75,7 @@ impl CompressedVestingPosition {
         encrypted_total_amount: [u8; 32],
         nonce: u128,
         start_timestamp: i64,
+        metadata_commitment: [u8; 32],
     ) -> Self {
         Self {
             owner,
@@ -82,6 +89,8 @@ impl CompressedVestingPosition {
             start_timestamp,
             is_active: 1,
             is_fully_claimed: 0,
+            is_stealth: 0,
+            metadata_commitment,
         }
     }
 
@@ -95,6 +104,11 @@ impl CompressedVestingPosition {
         self.is_fully_claimed == 1
     }
 
+    /// Check if the beneficiary is a stealth address
+    pub fn is_stealth(&self) -> bool {
+        self.is_stealth == 1
+    }
+
     /// Mark position as fully claimed
     pub fn mark_fully_claimed(&mut self) {
         self.is_fully_claimed = 1;
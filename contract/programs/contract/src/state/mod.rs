@@ -1,15 +1,27 @@
+pub mod allowed_destination;
 pub mod claim_authorization;
+pub mod claim_history;
 pub mod compressed_position;
+pub mod org_registry;
 pub mod organization;
+pub mod pending_computation;
 pub mod position;
+pub mod position_index;
 pub mod schedule;
 pub mod stealth_meta;
 pub mod verification_key;
+pub mod vested_snapshot;
 
+pub use allowed_destination::*;
 pub use claim_authorization::*;
+pub use claim_history::*;
 pub use compressed_position::*;
+pub use org_registry::*;
 pub use organization::*;
+pub use pending_computation::*;
 pub use position::*;
+pub use position_index::*;
 pub use schedule::*;
 pub use stealth_meta::*;
 pub use verification_key::*;
+pub use vested_snapshot::*;
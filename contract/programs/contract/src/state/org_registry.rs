@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+/// Global registry of organizations for client-side discovery.
+/// Singleton account; entries are stored in a chain of fixed-size pages.
+/// Seeds: [b"org_registry"]
+#[account]
+pub struct OrgRegistry {
+    /// Number of organizations registered so far (also the next sequence number)
+    pub org_count: u64,
+    /// Number of registry pages created
+    pub page_count: u64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl OrgRegistry {
+    pub const SIZE: usize = 8 + // discriminator
+        8 +  // org_count
+        8 +  // page_count
+        1;   // bump
+    // Total: 25 bytes
+
+    pub const SEED_PREFIX: &'static [u8] = b"org_registry";
+
+    /// Index of the page that receives the next registered organization
+    pub fn current_page_index(&self) -> u64 {
+        self.org_count / OrgRegistryPage::ENTRIES_PER_PAGE as u64
+    }
+}
+
+/// A single organization entry in the registry.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OrgRegistryEntry {
+    /// Organization account address
+    pub organization: Pubkey,
+    /// Hash of the organization name (same as Organization::name_hash)
+    pub name_hash: [u8; 32],
+}
+
+impl OrgRegistryEntry {
+    pub const SIZE: usize = 32 + // organization
+        32; // name_hash
+    // Total: 64 bytes
+}
+
+/// One page of the organization registry chain.
+/// Pages are filled in order; a new page must be created once the current one is full.
+/// Seeds: [b"org_registry_page", page_index.to_le_bytes()]
+#[account]
+pub struct OrgRegistryPage {
+    /// Index of this page in the chain
+    pub page_index: u64,
+    /// Registered organizations, in sequence order
+    pub entries: Vec<OrgRegistryEntry>,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl OrgRegistryPage {
+    /// Maximum number of entries stored in a single page
+    pub const ENTRIES_PER_PAGE: usize = 32;
+
+    pub const SIZE: usize = 8 + // discriminator
+        8 +  // page_index
+        4 + Self::ENTRIES_PER_PAGE * OrgRegistryEntry::SIZE + // entries (vec prefix + 32 entries)
+        1;   // bump
+    // Total: 2069 bytes
+
+    pub const SEED_PREFIX: &'static [u8] = b"org_registry_page";
+}
@@ -1,4 +1,35 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::errors::ShadowVestError;
+use crate::state::{AllowedDestination, ClaimAuthorization, VestingPosition};
+
+/// SPL Associated Token Account program ID
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// Claim and position reservations held against one of the organization's vaults.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VaultReserve {
+    /// Token mint of the vault (default pubkey = unused slot)
+    pub mint: Pubkey,
+    /// Total of the vault's queued claims not yet withdrawn, expired or revoked
+    pub reserved_amount: u64,
+    /// Sum of the reserved_amount of positions paid from the vault
+    pub position_reserved_amount: u64,
+}
+
+impl VaultReserve {
+    pub const SIZE: usize = 32 + // mint
+        8 +  // reserved_amount
+        8;   // position_reserved_amount
+    // Total: 48 bytes
+
+    /// Whether nothing is reserved against the vault, so the slot can be reused
+    pub fn is_empty(&self) -> bool {
+        self.reserved_amount == 0 && self.position_reserved_amount == 0
+    }
+}
 
 /// Organization account that manages vesting schedules and positions.
 /// Seeds: [b"organization", admin.key()]
@@ -10,7 +41,8 @@ pub struct Organization {
     pub name_hash: [u8; 32],
     /// Number of vesting schedules created
     pub schedule_count: u64,
-    /// Number of vesting positions created (regular Solana accounts)
+    /// Number of vesting positions created (regular Solana accounts).
+    /// Also the next position_id seed, so it never decreases.
     pub position_count: u64,
     /// Number of compressed vesting positions (Light Protocol)
     pub compressed_position_count: u64,
@@ -20,6 +52,59 @@ pub struct Organization {
     pub token_mint: Pubkey,
     /// Whether the organization is active
     pub is_active: bool,
+    /// Number of regular positions closed via close_position
+    /// (open positions = position_count - closed_position_count)
+    pub closed_position_count: u64,
+    /// Vault balance that claim withdrawals may not dip below (operational buffer)
+    pub min_reserve: u64,
+    /// Seconds after which a pending MPC computation can be expired (0 = never)
+    pub computation_timeout: u64,
+    /// Whether new positions must use compressed storage (Light Protocol)
+    pub compressed_only: bool,
+    /// Emergency switch that blocks all withdrawals from the vault
+    pub paused: bool,
+    /// X25519 key stealth payments are additionally encrypted to for auditing
+    /// (all zeros = auditing disabled)
+    pub auditor_pubkey: [u8; 32],
+    /// Protocol fee taken from each withdrawal, in basis points (0 = no fee)
+    pub fee_bps: u16,
+    /// Token account receiving withdrawal fees
+    pub fee_destination: Pubkey,
+    /// Extra seed mixed into NullifierRecord PDAs, so the same nullifier can be
+    /// used once per namespace (e.g. separate deployments sharing beneficiaries)
+    pub nullifier_namespace: [u8; 8],
+    /// Whether withdrawals may only go to associated token accounts
+    pub ata_only_withdrawals: bool,
+    /// Whether position creation defers the init_position computation until
+    /// the position is first used (queue_init_position)
+    pub lazy_init: bool,
+    /// Account receiving claim priority fees paid to MPC operators
+    /// (default pubkey = priority fees disabled)
+    pub priority_fee_account: Pubkey,
+    /// Total of queued claims not yet withdrawn, expired or revoked, summed
+    /// across all of the organization's vaults (see vault_reserves)
+    pub reserved_amount: u64,
+    /// Schedule used by create_vesting_position_default (None = not set)
+    pub default_schedule: Option<Pubkey>,
+    /// Number of positions that have reached the fully-claimed state
+    pub total_claimed_positions: u64,
+    /// Number of completed withdrawals from the organization's vaults
+    pub total_withdrawals: u64,
+    /// Whether claims may only be authorized to destinations with an AllowedDestination
+    pub enforce_destination_allowlist: bool,
+    /// Seconds a queued withdrawal waits before it can execute (0 = withdraw directly)
+    pub challenge_period: u64,
+    /// Co-signer required on withdrawals of large claims (None = not required)
+    pub guardian: Option<Pubkey>,
+    /// Smallest claim_amount that needs the guardian's signature
+    pub guardian_threshold: u64,
+    /// Sum of the positions' reserved_amount across all of the organization's
+    /// vaults (each vault's share never exceeds its balance at the time)
+    pub position_reserved_amount: u64,
+    /// Claim and position reservations of each vault, checked against that vault's balance
+    pub vault_reserves: [VaultReserve; Organization::MAX_VAULTS],
+    /// Whether position pauses also suspend vesting accrual (default: only claims stop)
+    pub freeze_vesting_on_pause: bool,
     /// PDA bump seed
     pub bump: u8,
 }
@@ -34,8 +119,790 @@ impl Organization {
         32 + // treasury
         32 + // token_mint
         1 +  // is_active
+        8 +  // closed_position_count
+        8 +  // min_reserve
+        8 +  // computation_timeout
+        1 +  // compressed_only
+        1 +  // paused
+        32 + // auditor_pubkey
+        2 +  // fee_bps
+        32 + // fee_destination
+        8 +  // nullifier_namespace
+        1 +  // ata_only_withdrawals
+        1 +  // lazy_init
+        32 + // priority_fee_account
+        8 +  // reserved_amount
+        33 + // default_schedule
+        8 +  // total_claimed_positions
+        8 +  // total_withdrawals
+        1 +  // enforce_destination_allowlist
+        8 +  // challenge_period
+        33 + // guardian
+        8 +  // guardian_threshold
+        8 +  // position_reserved_amount
+        VaultReserve::SIZE * Organization::MAX_VAULTS + // vault_reserves
+        1 +  // freeze_vesting_on_pause
         1;   // bump
-    // Total: 162 bytes
+    // Total: 796 bytes
 
     pub const SEED_PREFIX: &'static [u8] = b"organization";
+
+    /// Upper bound for fee_bps (100%)
+    pub const MAX_FEE_BPS: u16 = 10_000;
+
+    /// Vaults (token mints) with reservations tracked at the same time
+    pub const MAX_VAULTS: usize = 8;
+
+    /// Check that withdrawing `amount` from a vault holding `vault_balance`
+    /// leaves at least min_reserve in the vault
+    pub fn check_reserve(&self, vault_balance: u64, amount: u64) -> Result<()> {
+        let remaining = vault_balance
+            .checked_sub(amount)
+            .ok_or(ShadowVestError::InsufficientVaultBalance)?;
+        require!(
+            remaining >= self.min_reserve,
+            ShadowVestError::ReserveFloorBreached
+        );
+        Ok(())
+    }
+
+    /// Claims reserved against the vault for `mint`
+    pub fn vault_reserved_amount(&self, mint: &Pubkey) -> u64 {
+        self.vault_reserves
+            .iter()
+            .find(|reserve| reserve.mint == *mint)
+            .map_or(0, |reserve| reserve.reserved_amount)
+    }
+
+    /// Reservations of the vault for `mint`, taking a free slot on first use
+    fn vault_reserve_mut(&mut self, mint: &Pubkey) -> Result<&mut VaultReserve> {
+        let index = self
+            .vault_reserves
+            .iter()
+            .position(|reserve| reserve.mint == *mint)
+            .or_else(|| {
+                self.vault_reserves
+                    .iter()
+                    .position(|reserve| reserve.mint == Pubkey::default())
+            })
+            .ok_or(ShadowVestError::TooManyVaults)?;
+        let reserve = &mut self.vault_reserves[index];
+        reserve.mint = *mint;
+        Ok(reserve)
+    }
+
+    /// Check that `amount` (non-zero) can leave the vault for `mint` holding
+    /// `vault_balance` without touching the balance reserved for its processed claims
+    pub fn check_unreserved(&self, vault_balance: u64, mint: &Pubkey, amount: u64) -> Result<()> {
+        require!(amount > 0, ShadowVestError::InvalidClaimAmount);
+        require!(
+            amount <= vault_balance.saturating_sub(self.vault_reserved_amount(mint)),
+            ShadowVestError::InsufficientVaultBalance
+        );
+        Ok(())
+    }
+
+    /// Reserve a queued claim's claim_amount against the vault for `mint` holding
+    /// `vault_balance`, so concurrent claims cannot commit more than that vault holds.
+    /// A re-queued claim replaces its earlier reservation.
+    pub fn reserve_claim(
+        &mut self,
+        claim: &mut ClaimAuthorization,
+        mint: &Pubkey,
+        vault_balance: u64,
+    ) -> Result<()> {
+        self.release_claim(claim, u64::MAX);
+
+        let reserve = self.vault_reserve_mut(mint)?;
+        let reserved_amount = reserve
+            .reserved_amount
+            .checked_add(claim.claim_amount)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+        require!(
+            vault_balance >= reserved_amount,
+            ShadowVestError::InsufficientVaultBalance
+        );
+        reserve.reserved_amount = reserved_amount;
+
+        self.reserved_amount = self
+            .reserved_amount
+            .checked_add(claim.claim_amount)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+        claim.reserved_amount = claim.claim_amount;
+        claim.reserved_mint = *mint;
+        Ok(())
+    }
+
+    /// Release up to `amount` of a claim's reservation (u64::MAX releases all of it)
+    pub fn release_claim(&mut self, claim: &mut ClaimAuthorization, amount: u64) {
+        let released = amount.min(claim.reserved_amount);
+        if released == 0 {
+            return;
+        }
+        claim.reserved_amount -= released;
+        self.reserved_amount = self.reserved_amount.saturating_sub(released);
+        if let Some(reserve) = self
+            .vault_reserves
+            .iter_mut()
+            .find(|reserve| reserve.mint == claim.reserved_mint)
+        {
+            reserve.reserved_amount = reserve.reserved_amount.saturating_sub(released);
+            if reserve.is_empty() {
+                *reserve = VaultReserve::default();
+            }
+        }
+    }
+
+    /// Earmark `amount` of the vault for `mint` holding `vault_balance` for a
+    /// position. Each position's reserve is set once, and the reserves of all
+    /// positions paid from the vault together cannot exceed it.
+    pub fn reserve_position(
+        &mut self,
+        position: &mut VestingPosition,
+        mint: &Pubkey,
+        amount: u64,
+        vault_balance: u64,
+    ) -> Result<()> {
+        require!(
+            !position.is_reserve_set,
+            ShadowVestError::ReservedAmountAlreadySet
+        );
+        let reserve = self.vault_reserve_mut(mint)?;
+        let position_reserved_amount = reserve
+            .position_reserved_amount
+            .checked_add(amount)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+        require!(
+            vault_balance >= position_reserved_amount,
+            ShadowVestError::InsufficientVaultBalance
+        );
+        reserve.position_reserved_amount = position_reserved_amount;
+
+        self.position_reserved_amount = self
+            .position_reserved_amount
+            .checked_add(amount)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+        position.reserved_amount = amount;
+        position.is_reserve_set = true;
+        Ok(())
+    }
+
+    /// Release up to `amount` of a position's reserve in the vault for `mint`
+    /// (u64::MAX releases all of it)
+    pub fn release_position_reserve(
+        &mut self,
+        position: &mut VestingPosition,
+        mint: &Pubkey,
+        amount: u64,
+    ) {
+        let released = amount.min(position.reserved_amount);
+        if released == 0 {
+            return;
+        }
+        position.reserved_amount -= released;
+        self.position_reserved_amount = self.position_reserved_amount.saturating_sub(released);
+        if let Some(reserve) = self
+            .vault_reserves
+            .iter_mut()
+            .find(|reserve| reserve.mint == *mint)
+        {
+            reserve.position_reserved_amount =
+                reserve.position_reserved_amount.saturating_sub(released);
+            if reserve.is_empty() {
+                *reserve = VaultReserve::default();
+            }
+        }
+    }
+
+    /// Count a completed withdrawal
+    pub fn record_withdrawal(&mut self) -> Result<()> {
+        self.total_withdrawals = self
+            .total_withdrawals
+            .checked_add(1)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Count a position moving to fully claimed; `was_fully_claimed` keeps a
+    /// repeated update of an already fully-claimed position from counting twice
+    pub fn record_claim_progress(&mut self, was_fully_claimed: bool, is_fully_claimed: bool) -> Result<()> {
+        if is_fully_claimed && !was_fully_claimed {
+            self.total_claimed_positions = self
+                .total_claimed_positions
+                .checked_add(1)
+                .ok_or(ShadowVestError::ArithmeticOverflow)?;
+        }
+        Ok(())
+    }
+
+    /// Split a withdrawal of `amount` into (fee, amount sent to the destination).
+    /// The fee rounds down, so tiny withdrawals may carry no fee.
+    pub fn split_withdrawal_fee(&self, amount: u64) -> Result<(u64, u64)> {
+        let fee = (amount as u128 * self.fee_bps as u128 / Self::MAX_FEE_BPS as u128) as u64;
+        let net = amount
+            .checked_sub(fee)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+        Ok((fee, net))
+    }
+
+    /// In ATA-only mode, check that `destination` is the associated token
+    /// account of its `owner` for `mint`
+    pub fn check_withdrawal_destination(
+        &self,
+        destination: &Pubkey,
+        owner: &Pubkey,
+        mint: &Pubkey,
+    ) -> Result<()> {
+        if self.ata_only_withdrawals {
+            let (ata, _) = Pubkey::find_program_address(
+                &[owner.as_ref(), anchor_spl::token::ID.as_ref(), mint.as_ref()],
+                &ASSOCIATED_TOKEN_PROGRAM_ID,
+            );
+            require!(*destination == ata, ShadowVestError::NonAtaDestination);
+        }
+        Ok(())
+    }
+
+    /// Check that `name` and `salt` open name_hash, i.e. sha256(name || salt) == name_hash
+    pub fn check_name_opening(&self, name: &str, salt: &[u8; 32]) -> Result<()> {
+        require!(
+            hashv(&[name.as_bytes(), salt]).to_bytes() == self.name_hash,
+            ShadowVestError::NameHashMismatch
+        );
+        Ok(())
+    }
+
+    /// With the allowlist enforced, require `allowed` to approve `destination` for
+    /// the organization at `organization`
+    pub fn check_destination_allowlist(
+        &self,
+        organization: &Pubkey,
+        allowed: Option<&AllowedDestination>,
+        destination: &Pubkey,
+    ) -> Result<()> {
+        if self.enforce_destination_allowlist {
+            let allowed = allowed.ok_or(ShadowVestError::DestinationNotAllowed)?;
+            require!(
+                allowed.organization == *organization && allowed.destination == *destination,
+                ShadowVestError::DestinationNotAllowed
+            );
+        }
+        Ok(())
+    }
+
+    /// With a guardian set, require `guardian_signer` to be the guardian for claims
+    /// of at least guardian_threshold
+    pub fn check_guardian(&self, claim_amount: u64, guardian_signer: Option<Pubkey>) -> Result<()> {
+        if let Some(guardian) = self.guardian {
+            if claim_amount >= self.guardian_threshold {
+                require!(
+                    guardian_signer == Some(guardian),
+                    ShadowVestError::GuardianSignatureRequired
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether stealth payments must carry a payload encrypted to the auditor
+    pub fn has_auditor(&self) -> bool {
+        self.auditor_pubkey != [0u8; 32]
+    }
+
+    /// Check that an auditor payload is present exactly when the organization
+    /// has an auditor configured
+    pub fn check_auditor_payload(&self, auditor_encrypted_payload: &[u8; 128]) -> Result<()> {
+        let has_payload = auditor_encrypted_payload.iter().any(|b| *b != 0);
+        require!(
+            has_payload == self.has_auditor(),
+            ShadowVestError::InvalidAuditorPayload
+        );
+        Ok(())
+    }
+
+    /// Number of regular positions that have not been closed
+    pub fn open_position_count(&self) -> u64 {
+        self.position_count.saturating_sub(self.closed_position_count)
+    }
+
+    /// Check that no beneficiary can still claim from the vault: every regular
+    /// position has been fully claimed and closed, and no compressed positions
+    /// exist (their claim state is not tracked on the organization)
+    pub fn check_no_open_positions(&self) -> Result<()> {
+        require!(
+            self.open_position_count() == 0 && self.compressed_position_count == 0,
+            ShadowVestError::PositionsStillOpen
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::PositionState;
+
+    const MINT: Pubkey = Pubkey::new_from_array([1u8; 32]);
+
+    fn organization(min_reserve: u64) -> Organization {
+        Organization {
+            admin: Pubkey::default(),
+            name_hash: [0u8; 32],
+            schedule_count: 0,
+            position_count: 0,
+            compressed_position_count: 0,
+            treasury: Pubkey::default(),
+            token_mint: Pubkey::default(),
+            is_active: true,
+            closed_position_count: 0,
+            min_reserve,
+            computation_timeout: 0,
+            compressed_only: false,
+            paused: false,
+            auditor_pubkey: [0u8; 32],
+            fee_bps: 0,
+            fee_destination: Pubkey::default(),
+            nullifier_namespace: [0u8; 8],
+            ata_only_withdrawals: false,
+            lazy_init: false,
+            priority_fee_account: Pubkey::default(),
+            reserved_amount: 0,
+            default_schedule: None,
+            total_claimed_positions: 0,
+            total_withdrawals: 0,
+            enforce_destination_allowlist: false,
+            challenge_period: 0,
+            guardian: None,
+            guardian_threshold: 0,
+            position_reserved_amount: 0,
+            vault_reserves: [VaultReserve::default(); Organization::MAX_VAULTS],
+            freeze_vesting_on_pause: false,
+            bump: 0,
+        }
+    }
+
+    fn queued_claim(claim_amount: u64) -> ClaimAuthorization {
+        ClaimAuthorization {
+            position: Pubkey::default(),
+            nullifier: [0u8; 32],
+            withdrawal_destination: Pubkey::default(),
+            claim_amount,
+            is_authorized: true,
+            is_processed: false,
+            is_withdrawn: false,
+            authorized_at: 0,
+            is_revoked: false,
+            withdrawn_so_far: 0,
+            claim_expiry_seconds: 0,
+            payer: Pubkey::default(),
+            stealth_address: Pubkey::default(),
+            compressed_claimed_amount: [0u8; 32],
+            compressed_is_fully_claimed: 0,
+            compressed_new_total_amount: [0u8; 32],
+            compressed_new_claimed_amount: [0u8; 32],
+            compressed_new_nonce: 0,
+            reserved_amount: 0,
+            withdraw_requested_at: 0,
+            challenge_period: 0,
+            reserved_mint: Pubkey::default(),
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_second_claim_over_vault_balance_rejected() {
+        let mut org = organization(0);
+        let mut first = queued_claim(600);
+        let mut second = queued_claim(500);
+
+        org.reserve_claim(&mut first, &MINT, 1_000).unwrap();
+        assert_eq!(org.reserved_amount, 600);
+
+        let result = org.reserve_claim(&mut second, &MINT, 1_000);
+        assert_eq!(
+            result.unwrap_err(),
+            ShadowVestError::InsufficientVaultBalance.into()
+        );
+        assert_eq!(org.reserved_amount, 600);
+        assert_eq!(second.reserved_amount, 0);
+    }
+
+    #[test]
+    fn test_release_claim_frees_reservation() {
+        let mut org = organization(0);
+        let mut first = queued_claim(600);
+        let mut second = queued_claim(500);
+        org.reserve_claim(&mut first, &MINT, 1_000).unwrap();
+
+        // Partial withdrawal, then the rest (over-release is capped)
+        org.release_claim(&mut first, 200);
+        assert_eq!((org.reserved_amount, first.reserved_amount), (400, 400));
+        org.release_claim(&mut first, u64::MAX);
+        assert_eq!((org.reserved_amount, first.reserved_amount), (0, 0));
+        org.release_claim(&mut first, u64::MAX);
+        assert_eq!(org.reserved_amount, 0);
+
+        assert!(org.reserve_claim(&mut second, &MINT, 1_000).is_ok());
+    }
+
+    #[test]
+    fn test_claim_reservations_are_per_vault() {
+        let other_mint = Pubkey::new_from_array([2u8; 32]);
+        let mut org = organization(0);
+        let mut first = queued_claim(600);
+        let mut second = queued_claim(500);
+
+        // Each claim only needs its own vault to cover it
+        org.reserve_claim(&mut first, &MINT, 1_000).unwrap();
+        org.reserve_claim(&mut second, &other_mint, 500).unwrap();
+        assert_eq!(org.vault_reserved_amount(&MINT), 600);
+        assert_eq!(org.vault_reserved_amount(&other_mint), 500);
+        assert_eq!(org.reserved_amount, 1_100);
+        assert!(org.check_unreserved(1_000, &MINT, 400).is_ok());
+        assert_eq!(
+            org.check_unreserved(500, &other_mint, 1).unwrap_err(),
+            ShadowVestError::InsufficientVaultBalance.into()
+        );
+
+        // Releasing goes back to the claim's vault and frees its slot once empty
+        org.release_claim(&mut second, u64::MAX);
+        assert_eq!(org.vault_reserved_amount(&other_mint), 0);
+        assert_eq!(org.vault_reserved_amount(&MINT), 600);
+        assert_eq!(
+            org.vault_reserves.iter().filter(|reserve| reserve.mint != Pubkey::default()).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_vault_reserve_slots_are_bounded() {
+        let mut org = organization(0);
+        for i in 0..Organization::MAX_VAULTS {
+            let mut claim = queued_claim(1);
+            let mint = Pubkey::new_from_array([i as u8 + 1; 32]);
+            org.reserve_claim(&mut claim, &mint, 1).unwrap();
+        }
+
+        let mut claim = queued_claim(1);
+        assert_eq!(
+            org.reserve_claim(&mut claim, &Pubkey::new_unique(), 1).unwrap_err(),
+            ShadowVestError::TooManyVaults.into()
+        );
+    }
+
+    fn unreserved_position() -> VestingPosition {
+        VestingPosition {
+            organization: Pubkey::default(),
+            schedule: Pubkey::default(),
+            position_id: 0,
+            beneficiary_commitment: [0u8; 32],
+            encrypted_total_amount: [0u8; 32],
+            encrypted_claimed_amount: [0u8; 32],
+            nonce: 0,
+            encryption_pubkey: [0u8; 32],
+            start_timestamp: 0,
+            state: PositionState::Active,
+            sig_scheme: VestingPosition::SIG_SCHEME_ED25519,
+            revoked_at: 0,
+            is_stealth: false,
+            last_claim_at: 0,
+            metadata_commitment: [0u8; 32],
+            privacy_mode: true,
+            plaintext_total_amount: None,
+            is_paused: false,
+            paused_at: 0,
+            total_paused_seconds: 0,
+            reserved_amount: 0,
+            merkle_claim_count: 0,
+            open_claim_count: 0,
+            is_reserve_set: false,
+            pause_freezes_vesting: false,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_position_reserve_set_once_and_capped_by_vault() {
+        let mut org = organization(0);
+        let mut first = unreserved_position();
+        let mut second = unreserved_position();
+
+        org.reserve_position(&mut first, &MINT, 600, 1_000).unwrap();
+        assert_eq!((org.position_reserved_amount, first.reserved_amount), (600, 600));
+        assert_eq!(
+            org.reserve_position(&mut first, &MINT, 100, 1_000).unwrap_err(),
+            ShadowVestError::ReservedAmountAlreadySet.into()
+        );
+
+        // Together the reserves cannot exceed the vault
+        assert_eq!(
+            org.reserve_position(&mut second, &MINT, 500, 1_000).unwrap_err(),
+            ShadowVestError::InsufficientVaultBalance.into()
+        );
+        assert!(!second.is_reserve_set);
+        org.reserve_position(&mut second, &MINT, 400, 1_000).unwrap();
+        assert_eq!(org.position_reserved_amount, 1_000);
+    }
+
+    #[test]
+    fn test_position_reserves_capped_per_vault() {
+        let other_mint = Pubkey::new_from_array([2u8; 32]);
+        let mut org = organization(0);
+        let mut first = unreserved_position();
+        let mut second = unreserved_position();
+
+        // A full first vault does not block reserving in another one
+        org.reserve_position(&mut first, &MINT, 1_000, 1_000).unwrap();
+        org.reserve_position(&mut second, &other_mint, 300, 300).unwrap();
+        assert_eq!(org.position_reserved_amount, 1_300);
+
+        // Releases go back to the vault the reserve was made in
+        org.release_position_reserve(&mut second, &other_mint, u64::MAX);
+        assert_eq!(org.position_reserved_amount, 1_000);
+        assert_eq!(
+            org.vault_reserves.iter().filter(|reserve| reserve.mint != Pubkey::default()).count(),
+            1
+        );
+        let mut third = unreserved_position();
+        assert_eq!(
+            org.reserve_position(&mut third, &MINT, 1, 1_000).unwrap_err(),
+            ShadowVestError::InsufficientVaultBalance.into()
+        );
+    }
+
+    #[test]
+    fn test_release_position_reserve_lowers_sum() {
+        let mut org = organization(0);
+        let mut position = unreserved_position();
+        org.reserve_position(&mut position, &MINT, 600, 1_000).unwrap();
+
+        org.release_position_reserve(&mut position, &MINT, 200);
+        assert_eq!((org.position_reserved_amount, position.reserved_amount), (400, 400));
+        org.release_position_reserve(&mut position, &MINT, u64::MAX);
+        assert_eq!((org.position_reserved_amount, position.reserved_amount), (0, 0));
+        // Releasing does not allow setting the reserve again
+        assert!(position.is_reserve_set);
+    }
+
+    #[test]
+    fn test_unreserved_amount_excludes_processed_claims() {
+        let mut org = organization(0);
+        let mut claim = queued_claim(600);
+        org.reserve_claim(&mut claim, &MINT, 1_000).unwrap();
+
+        assert!(org.check_unreserved(1_000, &MINT, 400).is_ok());
+        assert_eq!(
+            org.check_unreserved(1_000, &MINT, 401).unwrap_err(),
+            ShadowVestError::InsufficientVaultBalance.into()
+        );
+        assert_eq!(
+            org.check_unreserved(1_000, &MINT, 0).unwrap_err(),
+            ShadowVestError::InvalidClaimAmount.into()
+        );
+    }
+
+    #[test]
+    fn test_claim_statistics_count_only_full_claims() {
+        let mut org = organization(0);
+
+        // Two partial claims, then the final one
+        org.record_claim_progress(false, false).unwrap();
+        org.record_withdrawal().unwrap();
+        org.record_claim_progress(false, false).unwrap();
+        org.record_withdrawal().unwrap();
+        assert_eq!((org.total_withdrawals, org.total_claimed_positions), (2, 0));
+
+        org.record_claim_progress(false, true).unwrap();
+        org.record_withdrawal().unwrap();
+        assert_eq!((org.total_withdrawals, org.total_claimed_positions), (3, 1));
+
+        // Re-writing an already fully-claimed position does not count again
+        org.record_claim_progress(true, true).unwrap();
+        assert_eq!(org.total_claimed_positions, 1);
+    }
+
+    #[test]
+    fn test_name_opening() {
+        let salt = [7u8; 32];
+        let mut org = organization(0);
+        org.name_hash = hashv(&[b"Acme Labs".as_ref(), salt.as_ref()]).to_bytes();
+
+        assert!(org.check_name_opening("Acme Labs", &salt).is_ok());
+        assert_eq!(
+            org.check_name_opening("Acme Labs", &[8u8; 32]).unwrap_err(),
+            ShadowVestError::NameHashMismatch.into()
+        );
+        assert!(org.check_name_opening("Acme Lab", &salt).is_err());
+    }
+
+    #[test]
+    fn test_destination_allowlist_enforced() {
+        let org_key = Pubkey::new_unique();
+        let destination = Pubkey::new_unique();
+        let allowed = AllowedDestination {
+            organization: org_key,
+            destination,
+            added_at: 0,
+            bump: 0,
+        };
+
+        let mut org = organization(0);
+        let other = Pubkey::new_unique();
+        assert!(org.check_destination_allowlist(&org_key, None, &other).is_ok());
+
+        org.enforce_destination_allowlist = true;
+        assert!(org
+            .check_destination_allowlist(&org_key, Some(&allowed), &destination)
+            .is_ok());
+        for (approval, dest) in [(None, &destination), (Some(&allowed), &other)] {
+            assert_eq!(
+                org.check_destination_allowlist(&org_key, approval, dest).unwrap_err(),
+                ShadowVestError::DestinationNotAllowed.into()
+            );
+        }
+        // Another organization's approval does not count
+        assert!(org
+            .check_destination_allowlist(&Pubkey::new_unique(), Some(&allowed), &destination)
+            .is_err());
+    }
+
+    #[test]
+    fn test_check_reserve_allows_withdrawal_down_to_floor() {
+        let org = organization(100);
+        assert!(org.check_reserve(1_000, 900).is_ok());
+        assert!(organization(0).check_reserve(1_000, 1_000).is_ok());
+    }
+
+    #[test]
+    fn test_check_reserve_rejects_breach() {
+        let org = organization(100);
+        let result = org.check_reserve(1_000, 901);
+        assert_eq!(result.unwrap_err(), ShadowVestError::ReserveFloorBreached.into());
+    }
+
+    #[test]
+    fn test_check_reserve_rejects_insufficient_balance() {
+        let result = organization(0).check_reserve(1_000, 1_001);
+        assert_eq!(
+            result.unwrap_err(),
+            ShadowVestError::InsufficientVaultBalance.into()
+        );
+    }
+
+    #[test]
+    fn test_auditor_payload_required_when_auditor_set() {
+        let mut org = organization(0);
+        org.auditor_pubkey = [7u8; 32];
+        assert!(org.check_auditor_payload(&[1u8; 128]).is_ok());
+        let result = org.check_auditor_payload(&[0u8; 128]);
+        assert_eq!(result.unwrap_err(), ShadowVestError::InvalidAuditorPayload.into());
+    }
+
+    #[test]
+    fn test_auditor_payload_rejected_when_auditing_disabled() {
+        let org = organization(0);
+        assert!(org.check_auditor_payload(&[0u8; 128]).is_ok());
+        let result = org.check_auditor_payload(&[1u8; 128]);
+        assert_eq!(result.unwrap_err(), ShadowVestError::InvalidAuditorPayload.into());
+    }
+
+    #[test]
+    fn test_zero_fee_sends_everything_to_destination() {
+        let org = organization(0);
+        assert_eq!(org.split_withdrawal_fee(1_000_000).unwrap(), (0, 1_000_000));
+    }
+
+    #[test]
+    fn test_fee_of_two_and_a_half_percent() {
+        let mut org = organization(0);
+        org.fee_bps = 250;
+        assert_eq!(org.split_withdrawal_fee(1_000_000).unwrap(), (25_000, 975_000));
+        assert_eq!(org.split_withdrawal_fee(u64::MAX).unwrap().0, u64::MAX / 40);
+    }
+
+    #[test]
+    fn test_fee_rounds_down_on_tiny_amounts() {
+        let mut org = organization(0);
+        org.fee_bps = 250;
+        // 39 * 250 / 10_000 = 0.975 -> 0
+        assert_eq!(org.split_withdrawal_fee(39).unwrap(), (0, 39));
+        assert_eq!(org.split_withdrawal_fee(40).unwrap(), (1, 39));
+        assert_eq!(org.split_withdrawal_fee(1).unwrap(), (0, 1));
+    }
+
+    #[test]
+    fn test_ata_only_accepts_associated_token_account() {
+        let mut org = organization(0);
+        org.ata_only_withdrawals = true;
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let (ata, _) = Pubkey::find_program_address(
+            &[owner.as_ref(), anchor_spl::token::ID.as_ref(), mint.as_ref()],
+            &ASSOCIATED_TOKEN_PROGRAM_ID,
+        );
+        assert!(org.check_withdrawal_destination(&ata, &owner, &mint).is_ok());
+    }
+
+    #[test]
+    fn test_ata_only_rejects_other_token_accounts() {
+        let mut org = organization(0);
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        assert!(org.check_withdrawal_destination(&other, &owner, &mint).is_ok());
+
+        org.ata_only_withdrawals = true;
+        let result = org.check_withdrawal_destination(&other, &owner, &mint);
+        assert_eq!(result.unwrap_err(), ShadowVestError::NonAtaDestination.into());
+    }
+
+    #[test]
+    fn test_no_open_positions_once_all_closed() {
+        let mut org = organization(0);
+        assert!(org.check_no_open_positions().is_ok());
+
+        org.position_count = 3;
+        org.closed_position_count = 2;
+        let result = org.check_no_open_positions();
+        assert_eq!(result.unwrap_err(), ShadowVestError::PositionsStillOpen.into());
+
+        org.closed_position_count = 3;
+        assert!(org.check_no_open_positions().is_ok());
+    }
+
+    #[test]
+    fn test_compressed_positions_count_as_open() {
+        let mut org = organization(0);
+        org.compressed_position_count = 1;
+        let result = org.check_no_open_positions();
+        assert_eq!(result.unwrap_err(), ShadowVestError::PositionsStillOpen.into());
+    }
+
+    #[test]
+    fn test_guardian_required_only_at_or_above_threshold() {
+        let guardian = Pubkey::new_unique();
+        let mut org = organization(0);
+
+        // No guardian configured: nothing to check
+        assert!(org.check_guardian(u64::MAX, None).is_ok());
+
+        org.guardian = Some(guardian);
+        org.guardian_threshold = 1_000;
+
+        // Below the threshold no guardian is needed
+        assert!(org.check_guardian(999, None).is_ok());
+
+        // At or above it the guardian must sign
+        for amount in [1_000, u64::MAX] {
+            assert_eq!(
+                org.check_guardian(amount, None).unwrap_err(),
+                ShadowVestError::GuardianSignatureRequired.into()
+            );
+            assert_eq!(
+                org.check_guardian(amount, Some(Pubkey::new_unique())).unwrap_err(),
+                ShadowVestError::GuardianSignatureRequired.into()
+            );
+            assert!(org.check_guardian(amount, Some(guardian)).is_ok());
+        }
+    }
 }
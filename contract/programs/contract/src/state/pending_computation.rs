@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+/// Tracks an in-flight MPC computation for a claim authorization.
+/// Prevents queuing a second computation while one is pending, and lets anyone
+/// reset a computation the cluster never answered once the org's timeout passes.
+/// Seeds: [b"pending_computation", claim_authorization.key()]
+#[account]
+pub struct PendingComputation {
+    /// Claim authorization the computation was queued for
+    pub claim_authorization: Pubkey,
+    /// Arcium computation account of the latest queued computation
+    pub computation_account: Pubkey,
+    /// Timestamp when the computation was queued
+    pub queued_at: i64,
+    /// Whether a computation is queued and its callback has not run yet
+    pub is_pending: bool,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl PendingComputation {
+    pub const SIZE: usize = 8 +  // discriminator
+        32 + // claim_authorization
+        32 + // computation_account
+        8 +  // queued_at
+        1 +  // is_pending
+        1;   // bump
+    // Total: 82 bytes
+
+    pub const SEED_PREFIX: &'static [u8] = b"pending_computation";
+
+    /// Record `computation_account` as the in-flight computation for `claim_authorization`
+    pub fn start(
+        &mut self,
+        claim_authorization: Pubkey,
+        computation_account: Pubkey,
+        now: i64,
+        bump: u8,
+    ) {
+        self.claim_authorization = claim_authorization;
+        self.computation_account = computation_account;
+        self.queued_at = now;
+        self.is_pending = true;
+        self.bump = bump;
+    }
+
+    /// Whether the pending computation has exceeded `timeout` seconds (0 = never expires)
+    pub fn is_expired(&self, now: i64, timeout: u64) -> bool {
+        self.is_pending
+            && timeout > 0
+            && now > self
+                .queued_at
+                .saturating_add(i64::try_from(timeout).unwrap_or(i64::MAX))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(is_pending: bool) -> PendingComputation {
+        PendingComputation {
+            claim_authorization: Pubkey::default(),
+            computation_account: Pubkey::default(),
+            queued_at: 1_000,
+            is_pending,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_expires_after_timeout() {
+        let p = pending(true);
+        assert!(!p.is_expired(1_600, 600));
+        assert!(p.is_expired(1_601, 600));
+    }
+
+    #[test]
+    fn test_start_tracks_the_new_computation() {
+        let mut p = pending(false);
+        let computation = Pubkey::new_unique();
+        p.start(Pubkey::default(), computation, 2_000, 7);
+        assert!(p.is_pending);
+        assert_eq!(p.computation_account, computation);
+        assert_eq!(p.queued_at, 2_000);
+        assert_eq!(p.bump, 7);
+        assert!(p.is_expired(2_601, 600));
+    }
+
+    #[test]
+    fn test_completed_or_disabled_never_expires() {
+        assert!(!pending(false).is_expired(i64::MAX, 600));
+        assert!(!pending(true).is_expired(i64::MAX, 0));
+    }
+}
@@ -1,4 +1,72 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::errors::ShadowVestError;
+use crate::merkle;
+
+/// Lifecycle state of a vesting position.
+///
+/// Created -> Initialized (init_position callback) -> Active (first processed claim).
+/// Live positions can be Frozen, Revoked or Deactivated; FullyClaimed, Revoked and
+/// Deactivated are terminal apart from Revoked -> FullyClaimed and Revoked -> Deactivated
+/// (a swept position, see sweep_unclaimed_position).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PositionState {
+    /// Account created, init_position MPC computation not yet finalized
+    Created,
+    /// Encrypted amounts initialized by MPC, no claims processed yet
+    Initialized,
+    /// At least one claim processed
+    Active,
+    /// Claims blocked while the position is merged; reported as PositionStatus::Paused
+    Frozen,
+    /// Everything vested has been claimed
+    FullyClaimed,
+    /// Revoked by the organization; amount vested before revoked_at stays claimable
+    Revoked,
+    /// Closed by the organization; no further claims
+    Deactivated,
+}
+
+impl PositionState {
+    /// Whether moving from `self` to `next` is a legal lifecycle transition
+    pub fn can_transition_to(self, next: PositionState) -> bool {
+        use PositionState::*;
+        matches!(
+            (self, next),
+            (Created, Initialized)
+                | (Initialized, Active)
+                | (Initialized | Active, Frozen)
+                | (Frozen, Active)
+                | (Active | Revoked, FullyClaimed)
+                | (Initialized | Active | Frozen, Revoked)
+                | (Created | Initialized | Active | Frozen | Revoked, Deactivated)
+        )
+    }
+
+    /// Whether the position is vesting normally (initialized and not frozen, revoked or closed)
+    pub fn is_vesting(self) -> bool {
+        matches!(self, PositionState::Initialized | PositionState::Active)
+    }
+}
+
+/// Claim-facing status of a position, derived from its lifecycle state and the admin
+/// pause flag so a paused position can't read as claimable.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PositionStatus {
+    /// init_position MPC computation not yet finalized
+    Pending,
+    /// Vesting and accepting claims
+    Active,
+    /// Claims blocked by an admin pause or an in-flight merge
+    Paused,
+    /// Revoked; the amount vested before revoked_at stays claimable
+    Revoked,
+    /// Everything vested has been claimed
+    FullyClaimed,
+    /// Closed by the organization
+    Deactivated,
+}
 
 /// Vesting position with encrypted amounts for privacy.
 /// Seeds: [b"vesting_position", organization.key(), position_id.to_le_bytes()]
@@ -18,12 +86,52 @@ pub struct VestingPosition {
     pub encrypted_claimed_amount: [u8; 32],
     /// Nonce for encryption
     pub nonce: u128,
+    /// Client x25519 public key the amounts were encrypted with
+    /// (needed to queue init_position after the account is created)
+    pub encryption_pubkey: [u8; 32],
     /// Start timestamp for vesting
     pub start_timestamp: i64,
-    /// Whether this position is active
-    pub is_active: bool,
-    /// Whether all tokens have been claimed
-    pub is_fully_claimed: bool,
+    /// Lifecycle state (see PositionState for allowed transitions)
+    pub state: PositionState,
+    /// Signature scheme the beneficiary uses to authorize claims
+    /// (0 = Ed25519, 1 = secp256k1 with the Ethereum address in beneficiary_commitment[..20])
+    pub sig_scheme: u8,
+    /// Timestamp when the position was revoked by the organization (0 = not revoked).
+    /// Vesting stops accruing at this time; the amount vested before it stays claimable.
+    pub revoked_at: i64,
+    /// Whether beneficiary_commitment is a one-time stealth address
+    /// (set by create_stealth_vesting_position; withdrawals emit StealthWithdrawalEvent)
+    pub is_stealth: bool,
+    /// Timestamp of the last processed claim (0 = never claimed)
+    pub last_claim_at: i64,
+    /// Commitment to off-chain position metadata (grant agreement, board approval, ...)
+    pub metadata_commitment: [u8; 32],
+    /// Whether the amounts are only available encrypted (default). Cleared when the
+    /// organization reveals the total with reveal_position_total.
+    pub privacy_mode: bool,
+    /// Publicly revealed total amount (None while privacy_mode is set)
+    pub plaintext_total_amount: Option<u64>,
+    /// Claims temporarily paused by the admin (e.g. dispute); vesting keeps accruing
+    /// unless pause_freezes_vesting is set
+    pub is_paused: bool,
+    /// When the current pause started (0 while not paused)
+    pub paused_at: i64,
+    /// Seconds spent in completed pauses that froze vesting; excluded from vesting time
+    pub total_paused_seconds: u64,
+    /// Cleartext vault balance earmarked for this position (set once by the admin, reduced
+    /// by withdrawals). Claim amounts are encrypted, so this is what a sweep can recover.
+    pub reserved_amount: u64,
+    /// Claims authorized through the schedule's Merkle beneficiary list; indexes
+    /// the next claim's nullifier (see merkle::claim_nullifier)
+    pub merkle_claim_count: u64,
+    /// Claims not yet settled: authorizations that are neither withdrawn, revoked nor
+    /// rejected. Blocks merging.
+    pub open_claim_count: u64,
+    /// Whether the admin has set reserved_amount (it can only be set once)
+    pub is_reserve_set: bool,
+    /// Whether the current pause suspends vesting accrual (the organization's
+    /// freeze_vesting_on_pause when the pause started)
+    pub pause_freezes_vesting: bool,
     /// PDA bump seed
     pub bump: u8,
 }
@@ -37,11 +145,707 @@ impl VestingPosition {
         32 + // encrypted_total_amount
         32 + // encrypted_claimed_amount
         16 + // nonce
+        32 + // encryption_pubkey
         8 +  // start_timestamp
-        1 +  // is_active
-        1 +  // is_fully_claimed
+        1 +  // state
+        1 +  // sig_scheme
+        8 +  // revoked_at
+        1 +  // is_stealth
+        8 +  // last_claim_at
+        32 + // metadata_commitment
+        1 +  // privacy_mode
+        9 +  // plaintext_total_amount
+        1 +  // is_paused
+        8 +  // paused_at
+        8 +  // total_paused_seconds
+        8 +  // reserved_amount
+        8 +  // merkle_claim_count
+        8 +  // open_claim_count
+        1 +  // is_reserve_set
+        1 +  // pause_freezes_vesting
         1;   // bump
-    // Total: 203 bytes
+    // Total: 337 bytes
 
     pub const SEED_PREFIX: &'static [u8] = b"vesting_position";
+
+    /// Domain separator of the message signed by rotate_position_beneficiary
+    pub const ROTATION_PREFIX: &'static [u8] = b"rotate";
+
+    /// Maximum positions created by one batch_create_vesting_positions call
+    /// (bounded by the compute budget for PDA derivation and account creation)
+    pub const MAX_BATCH_POSITIONS: usize = 8;
+
+    /// Time after the vesting end before an unclaimed position can be swept (1 year)
+    pub const SWEEP_GRACE_PERIOD: i64 = 365 * 24 * 60 * 60;
+
+    /// Positions covered by one check_solvency computation (fixed circuit input size)
+    pub const MAX_SOLVENCY_POSITIONS: usize = 4;
+
+    /// Claims covered by one process_claims_batch computation (fixed circuit input size)
+    pub const MAX_BATCH_CLAIMS: usize = 4;
+
+    /// Claims are authorized with an Ed25519 signature from beneficiary_commitment
+    pub const SIG_SCHEME_ED25519: u8 = 0;
+    /// Claims are authorized with a secp256k1 signature from the Ethereum address
+    /// stored in the first 20 bytes of beneficiary_commitment
+    pub const SIG_SCHEME_SECP256K1: u8 = 1;
+
+    /// Move the position to `next`, rejecting illegal lifecycle transitions
+    pub fn transition_to(&mut self, next: PositionState) -> Result<()> {
+        require!(
+            self.state.can_transition_to(next),
+            ShadowVestError::InvalidPositionStateTransition
+        );
+        self.state = next;
+        Ok(())
+    }
+
+    /// Apply the lifecycle effect of a processed claim: the first one activates the
+    /// position, and one the MPC reports as reaching the total fully claims it
+    /// (unless the position was frozen meanwhile). Returns whether the position
+    /// just became fully claimed.
+    pub fn apply_processed_claim(&mut self, fully_claimed: bool) -> Result<bool> {
+        if self.state == PositionState::Initialized {
+            self.transition_to(PositionState::Active)?;
+        }
+        if fully_claimed && self.state.can_transition_to(PositionState::FullyClaimed) {
+            self.transition_to(PositionState::FullyClaimed)?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Stealth address the beneficiary claims to (default when not a stealth position)
+    pub fn stealth_address(&self) -> Pubkey {
+        if self.is_stealth {
+            Pubkey::new_from_array(self.beneficiary_commitment)
+        } else {
+            Pubkey::default()
+        }
+    }
+
+    /// Whether the organization revoked this position
+    pub fn is_revoked(&self) -> bool {
+        self.state == PositionState::Revoked
+    }
+
+    /// Claim-facing status; a pause overrides every non-terminal state
+    pub fn status(&self) -> PositionStatus {
+        match self.state {
+            PositionState::Created => PositionStatus::Pending,
+            PositionState::FullyClaimed => PositionStatus::FullyClaimed,
+            PositionState::Deactivated => PositionStatus::Deactivated,
+            PositionState::Frozen => PositionStatus::Paused,
+            _ if self.is_paused => PositionStatus::Paused,
+            PositionState::Revoked => PositionStatus::Revoked,
+            PositionState::Initialized | PositionState::Active => PositionStatus::Active,
+        }
+    }
+
+    /// Whether the position is active (or revoked with vested funds left), not paused
+    /// and not fully claimed. Revoked positions remain claimable up to the amount
+    /// vested at revocation.
+    pub fn is_claimable(&self) -> bool {
+        matches!(
+            self.status(),
+            PositionStatus::Active | PositionStatus::Revoked
+        )
+    }
+
+    /// Whether claims may still be made against this position
+    pub fn accepts_claims(&self) -> bool {
+        self.is_claimable()
+    }
+
+    /// Whether the position was created without MPC init in a lazy-init organization.
+    /// Such positions may be used before init_position has run; the client queues
+    /// it (queue_init_position) alongside the first interaction.
+    pub fn is_pending_lazy_init(&self, lazy_init: bool) -> bool {
+        lazy_init && self.state == PositionState::Created
+    }
+
+    /// Reject a claim queued less than `min_claim_interval` seconds after the last one
+    pub fn check_claim_interval(&self, current_time: i64, min_claim_interval: u64) -> Result<()> {
+        if self.last_claim_at == 0 || min_claim_interval == 0 {
+            return Ok(());
+        }
+        let next_claim_at = self
+            .last_claim_at
+            .saturating_add(min_claim_interval.min(i64::MAX as u64) as i64);
+        require!(current_time >= next_claim_at, ShadowVestError::ClaimTooSoon);
+        Ok(())
+    }
+
+    /// Earliest time sweep_unclaimed_position may recover the position's reserve:
+    /// the vesting end (shifted by any pauses) plus SWEEP_GRACE_PERIOD
+    pub fn sweepable_at(&self, total_duration: u64) -> i64 {
+        self.start_timestamp
+            .saturating_add(total_duration.min(i64::MAX as u64) as i64)
+            .saturating_add(self.total_paused_seconds.min(i64::MAX as u64) as i64)
+            .saturating_add(Self::SWEEP_GRACE_PERIOD)
+    }
+
+    /// Reject sweeping a fully claimed, paused or still-vesting position
+    pub fn check_sweepable(&self, total_duration: u64, current_time: i64) -> Result<()> {
+        require!(
+            self.state != PositionState::FullyClaimed,
+            ShadowVestError::PositionFullyClaimed
+        );
+        require!(!self.is_paused, ShadowVestError::PositionPaused);
+        require!(
+            current_time >= self.sweepable_at(total_duration),
+            ShadowVestError::SweepTooEarly
+        );
+        Ok(())
+    }
+
+    /// Nullifier of the next claim `beneficiary` makes through the schedule's
+    /// Merkle list into `withdrawal_destination`
+    pub fn next_merkle_claim_nullifier(
+        &self,
+        beneficiary: &Pubkey,
+        withdrawal_destination: &Pubkey,
+    ) -> [u8; 32] {
+        let leaf = merkle::beneficiary_leaf(self.position_id, beneficiary, withdrawal_destination);
+        merkle::claim_nullifier(&leaf, self.merkle_claim_count)
+    }
+
+    /// Count a claim authorized against the position
+    pub fn open_claim(&mut self) -> Result<()> {
+        self.open_claim_count = self
+            .open_claim_count
+            .checked_add(1)
+            .ok_or(ShadowVestError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Stop counting a claim once it is withdrawn, revoked, rejected or closed
+    pub fn settle_claim(&mut self) {
+        self.open_claim_count = self.open_claim_count.saturating_sub(1);
+    }
+
+    /// Check that no claim against the position is still unsettled, so its stored
+    /// claimed amount is final and no callback will write to it
+    pub fn check_no_open_claims(&self) -> Result<()> {
+        require!(self.open_claim_count == 0, ShadowVestError::PositionHasOpenClaims);
+        Ok(())
+    }
+
+    /// Revealed total amount, for the MPC-free calculate_vested_plaintext path.
+    /// Fails for positions still in privacy mode.
+    pub fn plaintext_total(&self) -> Result<u64> {
+        require!(!self.privacy_mode, ShadowVestError::PositionIsPrivate);
+        self.plaintext_total_amount
+            .ok_or_else(|| error!(ShadowVestError::PositionIsPrivate))
+    }
+
+    /// Time used for vesting calculations: clamped to the revocation time if revoked,
+    /// minus every second spent in pauses that freeze vesting, so vesting is
+    /// suspended during them
+    pub fn vesting_time(&self, current_time: i64) -> i64 {
+        let now = self.clamp_to_revocation(current_time);
+        let ongoing_pause = if self.is_paused && self.pause_freezes_vesting {
+            now.saturating_sub(self.paused_at).max(0)
+        } else {
+            0
+        };
+        now.saturating_sub(i64::try_from(self.total_paused_seconds).unwrap_or(i64::MAX))
+            .saturating_sub(ongoing_pause)
+    }
+
+    fn clamp_to_revocation(&self, current_time: i64) -> i64 {
+        if self.is_revoked() {
+            current_time.min(self.revoked_at)
+        } else {
+            current_time
+        }
+    }
+
+    /// Start a pause at `now` that also suspends vesting if `freeze_vesting`
+    /// (no-op if already paused)
+    pub fn pause(&mut self, now: i64, freeze_vesting: bool) {
+        if !self.is_paused {
+            self.is_paused = true;
+            self.paused_at = now;
+            self.pause_freezes_vesting = freeze_vesting;
+        }
+    }
+
+    /// End the current pause at `now`. A pause that froze vesting adds its length
+    /// to total_paused_seconds; time after a revocation never vested anyway, so it
+    /// is not counted. No-op if not paused.
+    pub fn resume(&mut self, now: i64) {
+        if self.is_paused {
+            if self.pause_freezes_vesting {
+                let paused = self.clamp_to_revocation(now).saturating_sub(self.paused_at).max(0);
+                self.total_paused_seconds = self.total_paused_seconds.saturating_add(paused as u64);
+            }
+            self.is_paused = false;
+            self.paused_at = 0;
+            self.pause_freezes_vesting = false;
+        }
+    }
+
+    /// Message the current beneficiary signs to re-point the position to
+    /// `new_commitment`:
+    /// sha256("rotate" || position_id (le) || old_commitment || new_commitment).
+    /// Binding the current commitment keeps a signature from being replayed
+    /// after the position has been rotated away and back.
+    pub fn rotation_message(&self, new_commitment: &[u8; 32]) -> [u8; 32] {
+        hashv(&[
+            Self::ROTATION_PREFIX,
+            &self.position_id.to_le_bytes(),
+            &self.beneficiary_commitment,
+            new_commitment,
+        ])
+        .to_bytes()
+    }
+
+    /// Replace beneficiary_commitment after a key rotation. Fully claimed and
+    /// closed positions have nothing left to protect and are rejected.
+    pub fn rotate_beneficiary(&mut self, new_commitment: [u8; 32]) -> Result<()> {
+        require!(
+            !matches!(
+                self.state,
+                PositionState::FullyClaimed | PositionState::Deactivated
+            ),
+            ShadowVestError::PositionNotActive
+        );
+        Self::validate_sig_scheme(self.sig_scheme, &new_commitment)?;
+        self.beneficiary_commitment = new_commitment;
+        Ok(())
+    }
+
+    /// Validate a signature scheme selector against the beneficiary commitment.
+    /// secp256k1 commitments carry a 20-byte address, so the remaining bytes must be zero.
+    pub fn validate_sig_scheme(sig_scheme: u8, beneficiary_commitment: &[u8; 32]) -> Result<()> {
+        match sig_scheme {
+            Self::SIG_SCHEME_ED25519 => Ok(()),
+            Self::SIG_SCHEME_SECP256K1 => {
+                require!(
+                    beneficiary_commitment[20..].iter().all(|b| *b == 0),
+                    ShadowVestError::InvalidBeneficiaryCommitment
+                );
+                Ok(())
+            }
+            _ => err!(ShadowVestError::UnsupportedSignatureScheme),
+        }
+    }
+}
+
+/// Parameters for one position in batch_create_vesting_positions.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PositionParams {
+    /// Commitment hash of beneficiary identity (Ed25519 stealth address)
+    pub beneficiary_commitment: [u8; 32],
+    /// Encrypted total vesting amount
+    pub encrypted_total_amount: [u8; 32],
+    /// Client x25519 public key used for encryption
+    pub pubkey: [u8; 32],
+    /// Encryption nonce
+    pub nonce: u128,
+    /// Commitment to off-chain position metadata
+    pub metadata_commitment: [u8; 32],
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(state: PositionState, revoked_at: i64) -> VestingPosition {
+        VestingPosition {
+            organization: Pubkey::default(),
+            schedule: Pubkey::default(),
+            position_id: 0,
+            beneficiary_commitment: [0u8; 32],
+            encrypted_total_amount: [0u8; 32],
+            encrypted_claimed_amount: [0u8; 32],
+            nonce: 0,
+            encryption_pubkey: [0u8; 32],
+            start_timestamp: 1_000,
+            state,
+            sig_scheme: VestingPosition::SIG_SCHEME_ED25519,
+            revoked_at,
+            is_stealth: false,
+            last_claim_at: 0,
+            metadata_commitment: [0u8; 32],
+            privacy_mode: true,
+            plaintext_total_amount: None,
+            is_paused: false,
+            paused_at: 0,
+            total_paused_seconds: 0,
+            reserved_amount: 0,
+            merkle_claim_count: 0,
+            open_claim_count: 0,
+            is_reserve_set: false,
+            pause_freezes_vesting: false,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_merkle_claim_nullifier_advances_with_count() {
+        let mut p = position(PositionState::Active, 0);
+        let (beneficiary, destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let first = p.next_merkle_claim_nullifier(&beneficiary, &destination);
+        assert_eq!(
+            first,
+            merkle::claim_nullifier(&merkle::beneficiary_leaf(0, &beneficiary, &destination), 0)
+        );
+
+        p.merkle_claim_count = 1;
+        assert_ne!(
+            p.next_merkle_claim_nullifier(&beneficiary, &destination),
+            first
+        );
+    }
+
+    #[test]
+    fn test_open_claims_block_until_settled() {
+        let mut p = position(PositionState::Active, 0);
+        assert!(p.check_no_open_claims().is_ok());
+
+        p.open_claim().unwrap();
+        p.open_claim().unwrap();
+        assert_eq!(
+            p.check_no_open_claims().unwrap_err(),
+            ShadowVestError::PositionHasOpenClaims.into()
+        );
+
+        p.settle_claim();
+        assert!(p.check_no_open_claims().is_err());
+        p.settle_claim();
+        assert!(p.check_no_open_claims().is_ok());
+
+        // Settling never underflows
+        p.settle_claim();
+        assert_eq!(p.open_claim_count, 0);
+    }
+
+    #[test]
+    fn test_plaintext_total_requires_revealed_position() {
+        let mut p = position(PositionState::Active, 0);
+        assert_eq!(
+            p.plaintext_total().unwrap_err(),
+            ShadowVestError::PositionIsPrivate.into()
+        );
+
+        // A total without clearing privacy mode is still refused
+        p.plaintext_total_amount = Some(1_000);
+        assert!(p.plaintext_total().is_err());
+
+        p.privacy_mode = false;
+        assert_eq!(p.plaintext_total().unwrap(), 1_000);
+    }
+
+    #[test]
+    fn test_vesting_time_unrevoked_follows_clock() {
+        let p = position(PositionState::Active, 0);
+        assert!(!p.is_revoked());
+        assert_eq!(p.vesting_time(1_500), 1_500);
+        assert_eq!(p.vesting_time(2_000), 2_000);
+    }
+
+    #[test]
+    fn test_vesting_time_stops_growing_after_revocation() {
+        // Revoked halfway through a 1_000s linear schedule starting at 1_000
+        let p = position(PositionState::Revoked, 1_500);
+        assert!(p.is_revoked());
+        assert!(p.accepts_claims());
+        assert_eq!(p.vesting_time(1_200), 1_200);
+        assert_eq!(p.vesting_time(1_500), 1_500);
+        assert_eq!(p.vesting_time(1_800), 1_500);
+        assert_eq!(p.vesting_time(5_000), 1_500);
+    }
+
+    #[test]
+    fn test_vesting_time_frozen_while_paused() {
+        let mut p = position(PositionState::Active, 0);
+
+        // Before the pause: follows the clock
+        assert_eq!(p.vesting_time(1_200), 1_200);
+
+        // During the pause: frozen at the pause start
+        p.pause(1_200, true);
+        assert_eq!(p.vesting_time(1_200), 1_200);
+        assert_eq!(p.vesting_time(1_500), 1_200);
+
+        // Pausing again does not move the pause start
+        p.pause(1_400, true);
+        assert_eq!(p.vesting_time(1_500), 1_200);
+
+        // After resuming: shifted by the paused interval
+        p.resume(1_500);
+        assert!(!p.is_paused);
+        assert_eq!(p.total_paused_seconds, 300);
+        assert_eq!(p.vesting_time(1_500), 1_200);
+        assert_eq!(p.vesting_time(1_600), 1_300);
+
+        // A second pause accumulates
+        p.pause(1_700, true);
+        p.resume(1_750);
+        assert_eq!(p.total_paused_seconds, 350);
+        assert_eq!(p.vesting_time(2_000), 1_650);
+    }
+
+    #[test]
+    fn test_vesting_time_accrues_through_default_pause() {
+        let mut p = position(PositionState::Active, 0);
+
+        // A pause that does not freeze vesting only stops claims
+        p.pause(1_200, false);
+        assert!(p.is_paused);
+        assert_eq!(p.vesting_time(1_500), 1_500);
+
+        p.resume(1_500);
+        assert!(!p.is_paused);
+        assert_eq!(p.total_paused_seconds, 0);
+        assert_eq!(p.vesting_time(1_600), 1_600);
+    }
+
+    #[test]
+    fn test_pause_after_revocation_is_not_counted() {
+        // Paused at 1_400, revoked at 1_500, resumed at 1_800
+        let mut p = position(PositionState::Active, 0);
+        p.pause(1_400, true);
+        p.revoked_at = 1_500;
+        p.state = PositionState::Revoked;
+        assert_eq!(p.vesting_time(1_800), 1_400);
+
+        p.resume(1_800);
+        assert_eq!(p.total_paused_seconds, 100);
+        assert_eq!(p.vesting_time(5_000), 1_400);
+    }
+
+    #[test]
+    fn test_normal_lifecycle_transitions() {
+        let mut p = position(PositionState::Created, 0);
+        assert!(p.transition_to(PositionState::Initialized).is_ok());
+        assert!(p.transition_to(PositionState::Active).is_ok());
+        assert!(p.transition_to(PositionState::Frozen).is_ok());
+        assert!(p.transition_to(PositionState::Active).is_ok());
+        assert!(p.transition_to(PositionState::Revoked).is_ok());
+        assert!(p.transition_to(PositionState::FullyClaimed).is_ok());
+        assert_eq!(p.state, PositionState::FullyClaimed);
+    }
+
+    #[test]
+    fn test_processed_claim_lifecycle() {
+        // First claim activates; a partial claim leaves the position active
+        let mut p = position(PositionState::Initialized, 0);
+        assert!(!p.apply_processed_claim(false).unwrap());
+        assert_eq!(p.state, PositionState::Active);
+
+        // The claim reaching the total fully claims it, once
+        assert!(p.apply_processed_claim(true).unwrap());
+        assert_eq!(p.state, PositionState::FullyClaimed);
+        assert!(!p.accepts_claims());
+
+        // A single claim of everything goes straight through Active
+        let mut p = position(PositionState::Initialized, 0);
+        assert!(p.apply_processed_claim(true).unwrap());
+        assert_eq!(p.state, PositionState::FullyClaimed);
+
+        // Revoked positions become fully claimed once everything vested is claimed
+        let mut p = position(PositionState::Revoked, 1_500);
+        assert!(!p.apply_processed_claim(false).unwrap());
+        assert_eq!(p.state, PositionState::Revoked);
+        assert!(p.apply_processed_claim(true).unwrap());
+        assert_eq!(p.state, PositionState::FullyClaimed);
+
+        // A position frozen while the computation ran stays frozen
+        let mut p = position(PositionState::Frozen, 0);
+        assert!(!p.apply_processed_claim(true).unwrap());
+        assert_eq!(p.state, PositionState::Frozen);
+    }
+
+    #[test]
+    fn test_created_position_rejects_claims() {
+        let mut p = position(PositionState::Created, 0);
+        assert!(!p.accepts_claims());
+        let result = p.transition_to(PositionState::Active);
+        assert_eq!(
+            result.unwrap_err(),
+            ShadowVestError::InvalidPositionStateTransition.into()
+        );
+        assert_eq!(p.state, PositionState::Created);
+    }
+
+    #[test]
+    fn test_terminal_states_reject_transitions() {
+        for terminal in [PositionState::FullyClaimed, PositionState::Deactivated] {
+            let mut p = position(terminal, 0);
+            assert!(!p.accepts_claims());
+            assert!(p.transition_to(PositionState::Active).is_err());
+            assert!(p.transition_to(PositionState::Revoked).is_err());
+        }
+        // Revoked positions can't be revived
+        let mut p = position(PositionState::Revoked, 1_500);
+        assert!(p.transition_to(PositionState::Active).is_err());
+        assert!(p.transition_to(PositionState::Initialized).is_err());
+    }
+
+    #[test]
+    fn test_rotation_message_binds_position_and_both_commitments() {
+        let mut p = position(PositionState::Active, 0);
+        p.position_id = 7;
+        p.beneficiary_commitment = [1u8; 32];
+        let message = p.rotation_message(&[9u8; 32]);
+        assert_eq!(
+            message,
+            hashv(&[b"rotate".as_ref(), &7u64.to_le_bytes(), &[1u8; 32], &[9u8; 32]]).to_bytes()
+        );
+        assert_ne!(message, p.rotation_message(&[8u8; 32]));
+
+        // A signature over one rotation does not cover the same target from another key
+        p.beneficiary_commitment = [2u8; 32];
+        assert_ne!(message, p.rotation_message(&[9u8; 32]));
+        p.beneficiary_commitment = [1u8; 32];
+        p.position_id = 8;
+        assert_ne!(message, p.rotation_message(&[9u8; 32]));
+    }
+
+    #[test]
+    fn test_rotate_beneficiary() {
+        // Revoked and frozen positions still hold claimable funds
+        for state in [PositionState::Active, PositionState::Revoked, PositionState::Frozen] {
+            let mut p = position(state, 0);
+            p.rotate_beneficiary([5u8; 32]).unwrap();
+            assert_eq!(p.beneficiary_commitment, [5u8; 32]);
+        }
+
+        for state in [PositionState::FullyClaimed, PositionState::Deactivated] {
+            assert_eq!(
+                position(state, 0).rotate_beneficiary([5u8; 32]).unwrap_err(),
+                ShadowVestError::PositionNotActive.into()
+            );
+        }
+
+        // secp256k1 commitments must stay a zero-padded 20-byte address
+        let mut p = position(PositionState::Active, 0);
+        p.sig_scheme = VestingPosition::SIG_SCHEME_SECP256K1;
+        assert_eq!(
+            p.rotate_beneficiary([5u8; 32]).unwrap_err(),
+            ShadowVestError::InvalidBeneficiaryCommitment.into()
+        );
+        assert_eq!(p.beneficiary_commitment, [0u8; 32]);
+    }
+
+    #[test]
+    fn test_premature_sweep_rejected() {
+        // Start 0, 1_000s schedule: sweepable from 1_000 + grace
+        let mut p = position(PositionState::Active, 0);
+        p.start_timestamp = 0;
+        let sweepable_at = 1_000 + VestingPosition::SWEEP_GRACE_PERIOD;
+        assert_eq!(p.sweepable_at(1_000), sweepable_at);
+
+        for now in [0, 999, 1_000, sweepable_at - 1] {
+            assert_eq!(
+                p.check_sweepable(1_000, now).unwrap_err(),
+                ShadowVestError::SweepTooEarly.into()
+            );
+        }
+        assert!(p.check_sweepable(1_000, sweepable_at).is_ok());
+
+        // Pauses push the vesting end, and the sweep window, back
+        p.total_paused_seconds = 50;
+        assert!(p.check_sweepable(1_000, sweepable_at).is_err());
+        assert!(p.check_sweepable(1_000, sweepable_at + 50).is_ok());
+    }
+
+    #[test]
+    fn test_sweep_rejects_claimed_or_paused_positions() {
+        let p = position(PositionState::FullyClaimed, 0);
+        assert_eq!(
+            p.check_sweepable(0, i64::MAX).unwrap_err(),
+            ShadowVestError::PositionFullyClaimed.into()
+        );
+
+        let mut p = position(PositionState::Active, 0);
+        p.pause(0, true);
+        assert_eq!(
+            p.check_sweepable(0, i64::MAX).unwrap_err(),
+            ShadowVestError::PositionPaused.into()
+        );
+
+        // Revoked positions can be swept (and closed) too
+        let mut p = position(PositionState::Revoked, 1_500);
+        assert!(p.check_sweepable(0, i64::MAX).is_ok());
+        assert!(p.transition_to(PositionState::Deactivated).is_ok());
+    }
+
+    #[test]
+    fn test_frozen_position_rejects_claims() {
+        let p = position(PositionState::Frozen, 0);
+        assert!(!p.accepts_claims());
+        assert!(!p.state.is_vesting());
+    }
+
+    #[test]
+    fn test_status_transitions() {
+        let mut p = position(PositionState::Created, 0);
+        assert_eq!(p.status(), PositionStatus::Pending);
+        assert!(!p.is_claimable());
+
+        p.transition_to(PositionState::Initialized).unwrap();
+        assert_eq!(p.status(), PositionStatus::Active);
+        assert!(p.is_claimable());
+
+        // An admin pause blocks claims without changing the lifecycle state
+        p.pause(1_200, false);
+        assert_eq!(p.state, PositionState::Initialized);
+        assert_eq!(p.status(), PositionStatus::Paused);
+        assert!(!p.is_claimable());
+        p.resume(1_300);
+        assert_eq!(p.status(), PositionStatus::Active);
+
+        // Merging freezes the position, reported as paused
+        p.transition_to(PositionState::Frozen).unwrap();
+        assert_eq!(p.status(), PositionStatus::Paused);
+        assert!(!p.is_claimable());
+        p.transition_to(PositionState::Active).unwrap();
+        assert_eq!(p.status(), PositionStatus::Active);
+
+        // Revoked positions stay claimable unless paused
+        p.transition_to(PositionState::Revoked).unwrap();
+        assert_eq!(p.status(), PositionStatus::Revoked);
+        assert!(p.is_claimable());
+        p.pause(1_400, false);
+        assert_eq!(p.status(), PositionStatus::Paused);
+        assert!(!p.is_claimable());
+        p.resume(1_500);
+
+        p.transition_to(PositionState::FullyClaimed).unwrap();
+        assert_eq!(p.status(), PositionStatus::FullyClaimed);
+        assert!(!p.is_claimable());
+
+        // Terminal states win over a pause
+        let mut p = position(PositionState::Deactivated, 0);
+        p.pause(1_200, false);
+        assert_eq!(p.status(), PositionStatus::Deactivated);
+        assert!(!p.is_claimable());
+    }
+
+    #[test]
+    fn test_pending_lazy_init_only_for_created_positions_in_lazy_orgs() {
+        assert!(position(PositionState::Created, 0).is_pending_lazy_init(true));
+        assert!(!position(PositionState::Created, 0).is_pending_lazy_init(false));
+        assert!(!position(PositionState::Initialized, 0).is_pending_lazy_init(true));
+    }
+
+    #[test]
+    fn test_claim_interval() {
+        let mut p = position(PositionState::Active, 0);
+        // First claim and a zero interval are never limited
+        assert!(p.check_claim_interval(1_000, 600).is_ok());
+        p.last_claim_at = 1_000;
+        assert!(p.check_claim_interval(1_001, 0).is_ok());
+
+        assert_eq!(
+            p.check_claim_interval(1_599, 600).unwrap_err(),
+            ShadowVestError::ClaimTooSoon.into()
+        );
+        assert!(p.check_claim_interval(1_600, 600).is_ok());
+        assert!(p.check_claim_interval(i64::MAX, u64::MAX).is_ok());
+    }
 }
@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+
+/// Rolling index of the most recently created positions of one organization.
+/// Gives indexers a deterministic starting point instead of probing position_id
+/// PDAs; once full, the oldest entry is overwritten.
+/// Seeds: [b"position_index", organization]
+#[account]
+pub struct PositionIndex {
+    /// Organization whose positions are indexed
+    pub organization: Pubkey,
+    /// Total number of positions ever recorded (next slot = total_positions % MAX_POSITIONS)
+    pub total_positions: u64,
+    /// Ring buffer of the last MAX_POSITIONS position pubkeys
+    pub positions: [Pubkey; PositionIndex::MAX_POSITIONS],
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl PositionIndex {
+    /// Number of positions kept before the oldest is overwritten
+    pub const MAX_POSITIONS: usize = 32;
+
+    pub const SIZE: usize = 8 +  // discriminator
+        32 + // organization
+        8 +  // total_positions
+        32 * Self::MAX_POSITIONS + // positions
+        1;   // bump
+    // Total: 1073 bytes
+
+    pub const SEED_PREFIX: &'static [u8] = b"position_index";
+
+    /// Append a newly created position, overwriting the oldest entry once the buffer is full
+    pub fn record(&mut self, organization: Pubkey, bump: u8, position: Pubkey) {
+        self.organization = organization;
+        self.bump = bump;
+        let slot = (self.total_positions % Self::MAX_POSITIONS as u64) as usize;
+        self.positions[slot] = position;
+        self.total_positions = self.total_positions.saturating_add(1);
+    }
+
+    /// Indexed positions, oldest first
+    pub fn recent(&self) -> Vec<Pubkey> {
+        let count = self.total_positions.min(Self::MAX_POSITIONS as u64) as usize;
+        let start = if self.total_positions > Self::MAX_POSITIONS as u64 {
+            (self.total_positions % Self::MAX_POSITIONS as u64) as usize
+        } else {
+            0
+        };
+        (0..count)
+            .map(|i| self.positions[(start + i) % Self::MAX_POSITIONS])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> PositionIndex {
+        PositionIndex {
+            organization: Pubkey::default(),
+            total_positions: 0,
+            positions: [Pubkey::default(); PositionIndex::MAX_POSITIONS],
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_index_grows_in_order() {
+        let mut idx = index();
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+        idx.record(Pubkey::default(), 255, first);
+        idx.record(Pubkey::default(), 255, second);
+
+        assert_eq!(idx.recent(), vec![first, second]);
+        assert_eq!(idx.total_positions, 2);
+        assert_eq!(idx.bump, 255);
+    }
+
+    #[test]
+    fn test_full_index_wraps() {
+        let mut idx = index();
+        let keys: Vec<Pubkey> = (0..PositionIndex::MAX_POSITIONS + 2)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+        for key in &keys {
+            idx.record(Pubkey::default(), 0, *key);
+        }
+
+        let recent = idx.recent();
+        assert_eq!(recent.len(), PositionIndex::MAX_POSITIONS);
+        assert_eq!(recent[0], keys[2]);
+        assert_eq!(*recent.last().unwrap(), *keys.last().unwrap());
+        assert_eq!(idx.total_positions, keys.len() as u64);
+    }
+}
@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::ShadowVestError;
+use crate::vesting::{PRECISION, VESTING_MODE_INTERVAL, VESTING_MODE_LINEAR};
+
 /// Vesting schedule defining the parameters for a vesting plan.
 /// Seeds: [b"vesting_schedule", organization.key(), schedule_id.to_le_bytes()]
 #[account]
@@ -24,6 +27,19 @@ pub struct VestingSchedule {
     pub compressed_position_count: u64,
     /// PDA bump seed
     pub bump: u8,
+    // Fields below were appended after launch; schedules created earlier are grown
+    // to this layout by migrate_vesting_schedule.
+    /// How vesting accrues between intervals (0 = interval-stepped, 1 = linear)
+    pub vesting_mode: u8,
+    /// Minimum seconds between processed claims on one position (0 = no limit)
+    pub min_claim_interval: u64,
+    /// Whether claims on this schedule's positions are frozen (checked at queue time)
+    pub claims_paused: bool,
+    /// Merkle root of an off-chain beneficiary list (all zero = none, see merkle.rs)
+    pub beneficiary_root: [u8; 32],
+    /// Scale of the vesting numerator (vesting::PRECISION by default; higher values
+    /// round less for very large totals)
+    pub precision: u64,
 }
 
 impl VestingSchedule {
@@ -37,8 +53,264 @@ impl VestingSchedule {
         1 +  // is_active
         8 +  // position_count
         8 +  // compressed_position_count
-        1;   // bump
-    // Total: 122 bytes
+        1 +  // bump
+        1 +  // vesting_mode
+        8 +  // min_claim_interval
+        1 +  // claims_paused
+        32 + // beneficiary_root
+        8;   // precision
+    // Total: 172 bytes
+
+    /// Size of schedules created before vesting_mode and the fields after it existed
+    pub const LEGACY_SIZE: usize = 122;
 
     pub const SEED_PREFIX: &'static [u8] = b"vesting_schedule";
+
+    /// Longest allowed total_duration: 100 years in seconds. Keeps
+    /// start_timestamp + total_duration far from i64 overflow in the vesting math.
+    pub const MAX_TOTAL_DURATION: u64 = 100 * 365 * 24 * 60 * 60;
+
+    /// Validate vesting parameters (shared by schedule creation and updates).
+    pub fn validate_params(
+        cliff_duration: u64,
+        total_duration: u64,
+        vesting_interval: u64,
+    ) -> Result<()> {
+        require!(
+            total_duration > 0 && vesting_interval > 0,
+            ShadowVestError::InvalidScheduleParams
+        );
+        require!(
+            cliff_duration <= total_duration,
+            ShadowVestError::InvalidScheduleParams
+        );
+        require!(
+            total_duration <= Self::MAX_TOTAL_DURATION,
+            ShadowVestError::ScheduleDurationTooLong
+        );
+        Ok(())
+    }
+
+    /// Validate a vesting mode (VESTING_MODE_INTERVAL or VESTING_MODE_LINEAR).
+    pub fn validate_mode(vesting_mode: u8) -> Result<()> {
+        require!(
+            vesting_mode == VESTING_MODE_INTERVAL || vesting_mode == VESTING_MODE_LINEAR,
+            ShadowVestError::InvalidScheduleParams
+        );
+        Ok(())
+    }
+
+    /// Validate a vesting numerator precision (any non-zero u64; the vesting
+    /// math multiplies in u128)
+    pub fn validate_precision(precision: u64) -> Result<()> {
+        require!(precision > 0, ShadowVestError::InvalidScheduleParams);
+        Ok(())
+    }
+
+    /// Re-check stored parameters before they are used in vesting math, so a
+    /// schedule with a zero interval or precision fails cleanly instead of
+    /// dividing by zero
+    pub fn validate(&self) -> Result<()> {
+        Self::validate_params(self.cliff_duration, self.total_duration, self.vesting_interval)?;
+        Self::validate_mode(self.vesting_mode)?;
+        Self::validate_precision(self.precision)
+    }
+
+    /// Default the appended fields of a grown legacy schedule: interval vesting at
+    /// vesting::PRECISION, no claim interval, claims not paused, no beneficiary root
+    pub fn apply_migration_defaults(&mut self) {
+        self.vesting_mode = VESTING_MODE_INTERVAL;
+        self.min_claim_interval = 0;
+        self.claims_paused = false;
+        self.beneficiary_root = [0u8; 32];
+        self.precision = PRECISION;
+    }
+
+    /// Whether any regular or compressed position references this schedule
+    pub fn is_in_use(&self) -> bool {
+        self.position_count > 0 || self.compressed_position_count > 0
+    }
+}
+
+/// Named schedule presets for create_schedule_from_template.
+/// Months are 30 days and years 365 days; all presets vest monthly in interval mode.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScheduleTemplate {
+    /// 4 years with a 1-year cliff
+    FourYearOneYearCliff,
+    /// 4 years, no cliff
+    FourYearNoCliff,
+    /// 3 years, no cliff
+    ThreeYearNoCliff,
+    /// 2 years with a 6-month cliff
+    TwoYearSixMonthCliff,
+    /// 1 year, no cliff
+    MonthlyOneYear,
+}
+
+impl ScheduleTemplate {
+    const MONTH: u64 = 30 * 24 * 60 * 60;
+    const YEAR: u64 = 365 * 24 * 60 * 60;
+
+    /// Concrete (cliff_duration, total_duration, vesting_interval) in seconds
+    pub fn params(self) -> (u64, u64, u64) {
+        match self {
+            Self::FourYearOneYearCliff => (Self::YEAR, 4 * Self::YEAR, Self::MONTH),
+            Self::FourYearNoCliff => (0, 4 * Self::YEAR, Self::MONTH),
+            Self::ThreeYearNoCliff => (0, 3 * Self::YEAR, Self::MONTH),
+            Self::TwoYearSixMonthCliff => (6 * Self::MONTH, 2 * Self::YEAR, Self::MONTH),
+            Self::MonthlyOneYear => (0, Self::YEAR, Self::MONTH),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_params_accepts_valid_schedule() {
+        assert!(VestingSchedule::validate_params(30, 365, 1).is_ok());
+        // Cliff equal to total duration is allowed (cliff-only vesting)
+        assert!(VestingSchedule::validate_params(365, 365, 1).is_ok());
+    }
+
+    #[test]
+    fn test_validate_params_rejects_invalid_schedule() {
+        assert!(VestingSchedule::validate_params(0, 0, 1).is_err());
+        assert!(VestingSchedule::validate_params(0, 365, 0).is_err());
+        assert!(VestingSchedule::validate_params(366, 365, 1).is_err());
+    }
+
+    #[test]
+    fn test_validate_params_rejects_overlong_duration() {
+        let max = VestingSchedule::MAX_TOTAL_DURATION;
+        assert!(VestingSchedule::validate_params(0, max, 1).is_ok());
+        assert_eq!(
+            VestingSchedule::validate_params(0, max + 1, 1).unwrap_err(),
+            ShadowVestError::ScheduleDurationTooLong.into()
+        );
+        assert_eq!(
+            VestingSchedule::validate_params(0, u64::MAX, 1).unwrap_err(),
+            ShadowVestError::ScheduleDurationTooLong.into()
+        );
+    }
+
+    #[test]
+    fn test_zeroed_interval_returns_error() {
+        let schedule = VestingSchedule {
+            organization: Pubkey::default(),
+            schedule_id: 0,
+            cliff_duration: 0,
+            total_duration: 365,
+            vesting_interval: 0,
+            token_mint: Pubkey::default(),
+            is_active: true,
+            position_count: 0,
+            compressed_position_count: 0,
+            bump: 0,
+            vesting_mode: VESTING_MODE_INTERVAL,
+            min_claim_interval: 0,
+            claims_paused: false,
+            beneficiary_root: [0u8; 32],
+            precision: PRECISION,
+        };
+        assert_eq!(
+            schedule.validate().unwrap_err(),
+            ShadowVestError::InvalidScheduleParams.into()
+        );
+    }
+
+    #[test]
+    fn test_validate_mode() {
+        assert!(VestingSchedule::validate_mode(VESTING_MODE_INTERVAL).is_ok());
+        assert!(VestingSchedule::validate_mode(VESTING_MODE_LINEAR).is_ok());
+        assert_eq!(
+            VestingSchedule::validate_mode(2).unwrap_err(),
+            ShadowVestError::InvalidScheduleParams.into()
+        );
+    }
+
+    #[test]
+    fn test_validate_precision() {
+        assert!(VestingSchedule::validate_precision(PRECISION).is_ok());
+        assert!(VestingSchedule::validate_precision(1_000_000_000).is_ok());
+        assert!(VestingSchedule::validate_precision(u64::MAX).is_ok());
+        assert_eq!(
+            VestingSchedule::validate_precision(0).unwrap_err(),
+            ShadowVestError::InvalidScheduleParams.into()
+        );
+    }
+
+    #[test]
+    fn test_legacy_schedule_migrates_to_defaults() {
+        let schedule = VestingSchedule {
+            organization: Pubkey::new_unique(),
+            schedule_id: 3,
+            cliff_duration: 30,
+            total_duration: 365,
+            vesting_interval: 1,
+            token_mint: Pubkey::new_unique(),
+            is_active: true,
+            position_count: 2,
+            compressed_position_count: 1,
+            bump: 254,
+            vesting_mode: VESTING_MODE_LINEAR,
+            min_claim_interval: 60,
+            claims_paused: true,
+            beneficiary_root: [7u8; 32],
+            precision: 1_000_000_000,
+        };
+        let mut data = Vec::new();
+        schedule.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), VestingSchedule::SIZE);
+
+        // A legacy account ends at the bump; growing it zero-fills the appended fields
+        data.truncate(VestingSchedule::LEGACY_SIZE);
+        data.resize(VestingSchedule::SIZE, 0);
+        let mut legacy = VestingSchedule::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(legacy.organization, schedule.organization);
+        assert_eq!(legacy.compressed_position_count, 1);
+        assert_eq!(legacy.bump, 254);
+        assert_eq!(legacy.precision, 0);
+        assert!(legacy.validate().is_err());
+
+        legacy.apply_migration_defaults();
+        assert_eq!(legacy.vesting_mode, VESTING_MODE_INTERVAL);
+        assert_eq!(legacy.min_claim_interval, 0);
+        assert!(!legacy.claims_paused);
+        assert_eq!(legacy.beneficiary_root, [0u8; 32]);
+        assert_eq!(legacy.precision, PRECISION);
+        assert!(legacy.validate().is_ok());
+    }
+
+    #[test]
+    fn test_templates_expand_to_documented_params() {
+        let day = 24 * 60 * 60;
+        assert_eq!(
+            ScheduleTemplate::FourYearOneYearCliff.params(),
+            (365 * day, 4 * 365 * day, 30 * day)
+        );
+        assert_eq!(ScheduleTemplate::FourYearNoCliff.params(), (0, 4 * 365 * day, 30 * day));
+        assert_eq!(ScheduleTemplate::ThreeYearNoCliff.params(), (0, 3 * 365 * day, 30 * day));
+        assert_eq!(
+            ScheduleTemplate::TwoYearSixMonthCliff.params(),
+            (180 * day, 2 * 365 * day, 30 * day)
+        );
+        assert_eq!(ScheduleTemplate::MonthlyOneYear.params(), (0, 365 * day, 30 * day));
+    }
+
+    #[test]
+    fn test_templates_pass_validation() {
+        for template in [
+            ScheduleTemplate::FourYearOneYearCliff,
+            ScheduleTemplate::FourYearNoCliff,
+            ScheduleTemplate::ThreeYearNoCliff,
+            ScheduleTemplate::TwoYearSixMonthCliff,
+            ScheduleTemplate::MonthlyOneYear,
+        ] {
+            let (cliff, total, interval) = template.params();
+            assert!(VestingSchedule::validate_params(cliff, total, interval).is_ok());
+        }
+    }
 }
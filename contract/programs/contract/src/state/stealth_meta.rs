@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::ShadowVestError;
+
 /// Stealth Meta-Address Registry
 ///
 /// Stores an employee's public stealth meta-address (S, V) on-chain.
@@ -8,7 +10,9 @@ use anchor_lang::prelude::*;
 /// The employee keeps (s, v) private keys secret and only publishes
 /// the public keys (S = s*G, V = v*G).
 ///
-/// Seeds: [b"stealth_meta", owner.key()]
+/// Seeds: [b"stealth_meta", owner.key(), label] - an owner may register one
+/// meta-address per label (e.g. one per employer). The zero label adds no seed,
+/// so it derives the original single-address PDA [b"stealth_meta", owner.key()].
 #[account]
 pub struct StealthMetaAddress {
     /// Owner who can update this meta-address
@@ -21,6 +25,9 @@ pub struct StealthMetaAddress {
     pub is_active: bool,
     /// Timestamp when registered
     pub registered_at: i64,
+    /// Optional custodian wallet that may rotate or deactivate this
+    /// meta-address on the owner's behalf (only the owner can change it)
+    pub delegate: Option<Pubkey>,
     /// PDA bump seed
     pub bump: u8,
 }
@@ -32,16 +39,36 @@ impl StealthMetaAddress {
         32 +  // view_pubkey
         1 +   // is_active
         8 +   // registered_at
+        33 +  // delegate (Option<Pubkey>)
         1;    // bump
-    // Total: 114 bytes
+    // Total: 147 bytes
 
     pub const SEED_PREFIX: &'static [u8] = b"stealth_meta";
 
+    /// Label of the owner's default meta-address
+    pub const DEFAULT_LABEL: [u8; 16] = [0u8; 16];
+
+    /// PDA seed for `label`. The default label maps to an empty seed, keeping
+    /// meta-addresses registered before labels existed at their original PDA.
+    pub fn label_seed(label: &[u8; 16]) -> &[u8] {
+        if *label == Self::DEFAULT_LABEL {
+            &[]
+        } else {
+            label
+        }
+    }
+
     /// Check if meta-address is active
     pub fn is_active(&self) -> bool {
         self.is_active
     }
 
+    /// Whether `signer` may manage this meta-address's keys: the owner or
+    /// the stored delegate
+    pub fn is_authority(&self, signer: &Pubkey) -> bool {
+        *signer == self.owner || self.delegate == Some(*signer)
+    }
+
     /// Deactivate meta-address
     pub fn deactivate(&mut self) {
         self.is_active = false;
@@ -54,6 +81,17 @@ impl StealthMetaAddress {
     }
 }
 
+/// Check that a stealth address is a valid Ed25519 point.
+///
+/// Stealth positions store the address as their beneficiary commitment and
+/// later match it against an Ed25519 signer, so an off-curve address (e.g. a
+/// PDA or random bytes) would create a position that can never be claimed.
+pub fn is_valid_stealth_address(stealth_address: &Pubkey) -> bool {
+    solana_curve25519::edwards::validate_edwards(&solana_curve25519::edwards::PodEdwardsPoint(
+        stealth_address.to_bytes(),
+    ))
+}
+
 /// Stealth Payment Event
 ///
 /// Emitted when a payment is made to a stealth address.
@@ -66,8 +104,14 @@ pub struct StealthPaymentEvent {
     pub stealth_address: Pubkey,
     /// Ephemeral public key (R = r * G) - needed for recipient to derive key
     pub ephemeral_pubkey: [u8; 32],
+    /// First byte of H(r * V). Recipients compare it against their own
+    /// H(v * R) before the full derivation, rejecting ~255/256 foreign events
+    pub view_tag: u8,
     /// Encrypted payload (contains ephemeral private key for recipient)
     pub encrypted_payload: [u8; 128],
+    /// Same payment data encrypted to the organization's auditor key
+    /// (all zeros when the organization has no auditor)
+    pub auditor_encrypted_payload: [u8; 128],
     /// Position ID (if associated with a vesting position)
     pub position_id: u64,
     /// Token mint
@@ -110,6 +154,11 @@ pub struct MetaKeysVault {
     pub nonce: u128,
     /// Whether vault is initialized
     pub is_initialized: bool,
+    /// Incremented on each successful store (0 until the first one settles)
+    pub version: u32,
+    /// Set by write_meta_keys_to_vault until store_meta_keys_callback replaces the
+    /// user-encrypted ciphertexts with MXE-encrypted ones
+    pub pending_write: bool,
     /// PDA bump seed
     pub bump: u8,
 }
@@ -120,8 +169,204 @@ impl MetaKeysVault {
         128 +  // ciphertexts (4 * 32)
         16 +   // nonce (u128)
         1 +    // is_initialized
+        4 +    // version
+        1 +    // pending_write
         1;     // bump
-    // Total: 186 bytes
+    // Total: 191 bytes
 
     pub const SEED_PREFIX: &'static [u8] = b"meta_keys_vault";
+
+    /// Check that a write may replace the vault contents. A freshly created vault
+    /// (owner unset) is always writable; an existing vault must have settled its
+    /// previous store_meta_keys computation, otherwise the pending callback and
+    /// the new write would interleave and corrupt the stored ciphertexts.
+    pub fn check_writable(&self) -> Result<()> {
+        require!(
+            self.owner == Pubkey::default() || self.is_initialized,
+            ShadowVestError::MetaKeysWriteInProgress
+        );
+        Ok(())
+    }
+
+    /// Check that the stored ciphertexts may be fed to fetch_meta_keys. While a
+    /// write is pending they are still encrypted to the user's shared key, which
+    /// the circuit would misread as MXE-encrypted data.
+    pub fn check_readable(&self) -> Result<()> {
+        require!(!self.pending_write, ShadowVestError::MetaKeysWritePending);
+        require!(
+            self.is_initialized,
+            ShadowVestError::MetaKeysVaultNotInitialized
+        );
+        Ok(())
+    }
+
+    /// Deserialize a vault passed as an unchecked account. A closed (or never
+    /// created) vault reports MetaKeysVaultNotInitialized rather than Anchor's
+    /// generic account error.
+    pub fn load(info: &AccountInfo) -> Result<Self> {
+        require!(
+            info.owner == &crate::ID && !info.data_is_empty(),
+            ShadowVestError::MetaKeysVaultNotInitialized
+        );
+        let data = info.try_borrow_data()?;
+        Self::try_deserialize(&mut &data[..])
+    }
+
+    /// Check a reader's expected version against the stored one, so a client
+    /// holding keys from an earlier store can detect that they were replaced.
+    pub fn check_version(&self, expected_version: Option<u32>) -> Result<()> {
+        if let Some(expected) = expected_version {
+            require!(
+                expected == self.version,
+                ShadowVestError::MetaKeysVersionMismatch
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vault(owner: Pubkey, is_initialized: bool) -> MetaKeysVault {
+        MetaKeysVault {
+            owner,
+            ciphertexts: [[0u8; 32]; 4],
+            nonce: 0,
+            is_initialized,
+            version: 1,
+            pending_write: false,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_new_vault_is_writable() {
+        assert!(vault(Pubkey::default(), false).check_writable().is_ok());
+    }
+
+    #[test]
+    fn test_settled_vault_can_be_overwritten() {
+        let owner = Pubkey::new_unique();
+        assert!(vault(owner, true).check_writable().is_ok());
+    }
+
+    #[test]
+    fn test_vault_mid_computation_rejects_write() {
+        let owner = Pubkey::new_unique();
+        assert_eq!(
+            vault(owner, false).check_writable().unwrap_err(),
+            ShadowVestError::MetaKeysWriteInProgress.into()
+        );
+    }
+
+    #[test]
+    fn test_read_rejected_while_write_pending() {
+        let owner = Pubkey::new_unique();
+        assert!(vault(owner, true).check_readable().is_ok());
+
+        // write_meta_keys_to_vault ran, store_meta_keys_callback has not
+        let mut pending = vault(owner, false);
+        pending.pending_write = true;
+        assert_eq!(
+            pending.check_readable().unwrap_err(),
+            ShadowVestError::MetaKeysWritePending.into()
+        );
+
+        // Closed vault: nothing pending, nothing stored
+        assert_eq!(
+            vault(owner, false).check_readable().unwrap_err(),
+            ShadowVestError::MetaKeysVaultNotInitialized.into()
+        );
+    }
+
+    #[test]
+    fn test_version_check() {
+        let v = vault(Pubkey::new_unique(), true);
+        assert!(v.check_version(None).is_ok());
+        assert!(v.check_version(Some(1)).is_ok());
+        assert_eq!(
+            v.check_version(Some(0)).unwrap_err(),
+            ShadowVestError::MetaKeysVersionMismatch.into()
+        );
+    }
+
+    #[test]
+    fn test_default_label_keeps_legacy_pda() {
+        let owner = Pubkey::new_unique();
+        let (legacy, _) = Pubkey::find_program_address(
+            &[StealthMetaAddress::SEED_PREFIX, owner.as_ref()],
+            &crate::ID,
+        );
+        let label = StealthMetaAddress::DEFAULT_LABEL;
+        let (labeled, _) = Pubkey::find_program_address(
+            &[
+                StealthMetaAddress::SEED_PREFIX,
+                owner.as_ref(),
+                StealthMetaAddress::label_seed(&label),
+            ],
+            &crate::ID,
+        );
+        assert_eq!(legacy, labeled);
+    }
+
+    #[test]
+    fn test_labels_derive_distinct_pdas() {
+        let owner = Pubkey::new_unique();
+        let derive = |label: [u8; 16]| {
+            Pubkey::find_program_address(
+                &[
+                    StealthMetaAddress::SEED_PREFIX,
+                    owner.as_ref(),
+                    StealthMetaAddress::label_seed(&label),
+                ],
+                &crate::ID,
+            )
+            .0
+        };
+        let mut employer_a = [0u8; 16];
+        employer_a[..5].copy_from_slice(b"acme!");
+        let mut employer_b = [0u8; 16];
+        employer_b[..5].copy_from_slice(b"globx");
+        assert_ne!(derive(employer_a), derive(employer_b));
+        assert_ne!(derive(employer_a), derive(StealthMetaAddress::DEFAULT_LABEL));
+    }
+
+    #[test]
+    fn test_delegate_is_authority() {
+        let owner = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
+        let mut meta = StealthMetaAddress {
+            owner,
+            spend_pubkey: [0u8; 32],
+            view_pubkey: [0u8; 32],
+            is_active: true,
+            registered_at: 0,
+            delegate: None,
+            bump: 0,
+        };
+        assert!(meta.is_authority(&owner));
+        assert!(!meta.is_authority(&delegate));
+
+        meta.delegate = Some(delegate);
+        assert!(meta.is_authority(&owner));
+        assert!(meta.is_authority(&delegate));
+        assert!(!meta.is_authority(&Pubkey::new_unique()));
+    }
+
+    #[test]
+    fn test_ed25519_point_is_valid_stealth_address() {
+        // Compressed Ed25519 base point
+        let mut base_point = [0x66u8; 32];
+        base_point[0] = 0x58;
+        assert!(is_valid_stealth_address(&Pubkey::new_from_array(base_point)));
+    }
+
+    #[test]
+    fn test_off_curve_stealth_address_rejected() {
+        // PDAs are off-curve by construction
+        let (pda, _) = Pubkey::find_program_address(&[b"stealth"], &crate::ID);
+        assert!(!is_valid_stealth_address(&pda));
+    }
 }
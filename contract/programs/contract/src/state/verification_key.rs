@@ -1,5 +1,9 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::ShadowVestError;
+use crate::groth16_verifier::{EligibilityPublicInputs, VerificationKey};
+use crate::ProofType;
+
 /// On-chain account storing a Groth16 verification key for a specific Noir circuit.
 ///
 /// Each circuit (withdrawal_proof, identity_proof, eligibility) has its own VK
@@ -21,35 +25,70 @@ pub struct VerificationKeyAccount {
     pub vk_data: Vec<u8>,
     /// Whether this VK is active and can be used for verification
     pub is_active: bool,
+    /// Circuit type this VK verifies; every proof checked against it is
+    /// recorded as this type
+    pub proof_type: ProofType,
     /// PDA bump seed
     pub bump: u8,
 }
 
 impl VerificationKeyAccount {
     /// Base size (without vk_data vector contents):
-    /// discriminator + authority + circuit_id + vec_len + is_active + bump
+    /// discriminator + authority + circuit_id + vec_len + is_active + proof_type + bump
     pub const BASE_SIZE: usize = 8 + // discriminator
         32 + // authority
         32 + // circuit_id
         4 +  // vec length prefix (u32)
         1 +  // is_active
+        1 +  // proof_type
         1;   // bump
-    // Total base: 78 bytes
+    // Total base: 79 bytes
 
     /// PDA seed prefix
     pub const SEED_PREFIX: &'static [u8] = b"vk";
 
+    /// Name of the eligibility circuit (circuit_id = sha256 of the name)
+    pub const ELIGIBILITY_CIRCUIT_NAME: &'static [u8] = b"eligibility";
+
+    /// Circuit identifier for a circuit name: sha256(name)
+    pub fn circuit_id_for(name: &[u8]) -> [u8; 32] {
+        anchor_lang::solana_program::hash::hash(name).to_bytes()
+    }
+
     /// Calculate the required account size for a given VK data length
     pub fn size_with_vk_data(vk_data_len: usize) -> usize {
         Self::BASE_SIZE + vk_data_len
     }
 
     /// Maximum supported VK data size.
-    /// With 5 IC points (4 public inputs + 1):
-    /// alpha_g1(64) + beta_g2(128) + gamma_g2(128) + delta_g2(128) + vec_len(4) + 5*ic(320)
-    /// = 772 bytes serialized
-    /// Allow headroom for larger circuits.
+    /// A VK is alpha_g1(64) + beta_g2(128) + gamma_g2(128) + delta_g2(128) + vec_len(4)
+    /// + 64 per IC point, e.g. 772 bytes for 5 IC points (4 public inputs + 1).
+    /// 2048 bytes fits circuits with up to 23 public inputs.
     pub const MAX_VK_DATA_SIZE: usize = 2048;
+
+    /// Deserialize the stored vk_data into its curve-point components
+    pub fn deserialize_vk(&self) -> Result<VerificationKey> {
+        VerificationKey::try_from_slice(&self.vk_data)
+            .map_err(|_| ShadowVestError::InvalidVerificationKeyData.into())
+    }
+
+    /// Check that `vk` takes exactly the public inputs of `proof_type`
+    pub fn check_arity(vk: &VerificationKey, proof_type: &ProofType) -> Result<()> {
+        require!(
+            vk.num_public_inputs() == Some(proof_type.num_public_inputs()),
+            ShadowVestError::InvalidVerificationKeyData
+        );
+        Ok(())
+    }
+
+    /// Check that a proof submitted as `proof_type` is the type this VK verifies
+    pub fn check_proof_type(&self, proof_type: &ProofType) -> Result<()> {
+        require!(
+            self.proof_type == *proof_type,
+            ShadowVestError::ProofTypeMismatch
+        );
+        Ok(())
+    }
 }
 
 /// Record that a proof has been verified on-chain.
@@ -71,6 +110,15 @@ pub struct ProofRecord {
     pub verified_at: i64,
     /// Whether this record is still valid (can be invalidated by admin if needed)
     pub is_valid: bool,
+    /// Unix timestamp after which the record no longer attests anything
+    /// (0 = never expires). Set from the caller-supplied TTL at verification.
+    pub proof_valid_until: i64,
+    /// Eligibility proofs only (zero otherwise): the position_id public input
+    pub position_id: [u8; 32],
+    /// Eligibility proofs only (zero otherwise): the beneficiary_commitment public input
+    pub beneficiary_commitment: [u8; 32],
+    /// Eligibility proofs only (zero otherwise): the withdrawal_destination public input
+    pub withdrawal_destination: [u8; 32],
     /// PDA bump seed
     pub bump: u8,
 }
@@ -82,8 +130,212 @@ impl ProofRecord {
         32 + // nullifier
         8 +  // verified_at
         1 +  // is_valid
+        8 +  // proof_valid_until
+        32 + // position_id
+        32 + // beneficiary_commitment
+        32 + // withdrawal_destination
         1;   // bump
-    // Total: 114 bytes
+    // Total: 218 bytes
 
     pub const SEED_PREFIX: &'static [u8] = b"proof_record";
+
+    /// Expiry timestamp for a record verified at `now` with `ttl_seconds`
+    /// (a zero TTL never expires).
+    pub fn valid_until(now: i64, ttl_seconds: u32) -> Result<i64> {
+        if ttl_seconds == 0 {
+            return Ok(0);
+        }
+        now.checked_add(ttl_seconds as i64)
+            .ok_or(ShadowVestError::ArithmeticOverflow.into())
+    }
+
+    /// Whether the record has expired at `now`
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.proof_valid_until != 0 && now > self.proof_valid_until
+    }
+
+    /// Check that the record can still be relied on at `now`
+    pub fn check_usable(&self, now: i64) -> Result<()> {
+        require!(self.is_valid, ShadowVestError::ProofRecordInvalid);
+        require!(!self.is_expired(now), ShadowVestError::ProofRecordExpired);
+        Ok(())
+    }
+
+    /// Check that an eligibility record was proven for this position and
+    /// beneficiary, paying out to `withdrawal_destination`
+    pub fn check_eligibility_binding(
+        &self,
+        position_id: u64,
+        beneficiary_commitment: &[u8; 32],
+        withdrawal_destination: &Pubkey,
+    ) -> Result<()> {
+        require!(
+            self.position_id == EligibilityPublicInputs::position_id_scalar(position_id)
+                && self.beneficiary_commitment == *beneficiary_commitment
+                && self.withdrawal_destination
+                    == EligibilityPublicInputs::destination_scalar(withdrawal_destination),
+            ShadowVestError::EligibilityBindingMismatch
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vk_account(vk_data: Vec<u8>) -> VerificationKeyAccount {
+        VerificationKeyAccount {
+            authority: Pubkey::default(),
+            circuit_id: [0u8; 32],
+            vk_data,
+            is_active: true,
+            proof_type: ProofType::Eligibility,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_deserialize_vk_round_trip() {
+        let vk = VerificationKey {
+            alpha_g1: [1u8; 64],
+            beta_g2: [2u8; 128],
+            gamma_g2: [3u8; 128],
+            delta_g2: [4u8; 128],
+            ic: vec![[5u8; 64], [6u8; 64]],
+        };
+        let account = vk_account(borsh::to_vec(&vk).unwrap());
+
+        let decoded = account.deserialize_vk().unwrap();
+        assert_eq!(decoded.alpha_g1, vk.alpha_g1);
+        assert_eq!(decoded.beta_g2, vk.beta_g2);
+        assert_eq!(decoded.gamma_g2, vk.gamma_g2);
+        assert_eq!(decoded.delta_g2, vk.delta_g2);
+        assert_eq!(decoded.ic, vk.ic);
+    }
+
+    #[test]
+    fn test_eligibility_circuit_id_is_sha256_of_name() {
+        // sha256("eligibility") = 533cbfe9...2f3b778e, matching computeCircuitId
+        // in the client library
+        let id = VerificationKeyAccount::circuit_id_for(VerificationKeyAccount::ELIGIBILITY_CIRCUIT_NAME);
+        assert_eq!(id[..4], [0x53, 0x3c, 0xbf, 0xe9]);
+        assert_eq!(id[28..], [0x2f, 0x3b, 0x77, 0x8e]);
+    }
+
+    #[test]
+    fn test_proof_type_must_match_vk() {
+        let account = vk_account(vec![]);
+        assert!(account.check_proof_type(&ProofType::Eligibility).is_ok());
+        for other in [ProofType::Withdrawal, ProofType::Identity] {
+            assert_eq!(
+                account.check_proof_type(&other).unwrap_err(),
+                ShadowVestError::ProofTypeMismatch.into()
+            );
+        }
+    }
+
+    #[test]
+    fn test_vk_arity_must_match_proof_type() {
+        let vk = VerificationKey {
+            alpha_g1: [0u8; 64],
+            beta_g2: [0u8; 128],
+            gamma_g2: [0u8; 128],
+            delta_g2: [0u8; 128],
+            ic: vec![[0u8; 64]; 2],
+        };
+        assert!(VerificationKeyAccount::check_arity(&vk, &ProofType::Identity).is_ok());
+        assert_eq!(
+            VerificationKeyAccount::check_arity(&vk, &ProofType::Withdrawal).unwrap_err(),
+            ShadowVestError::InvalidVerificationKeyData.into()
+        );
+    }
+
+    #[test]
+    fn test_deserialize_vk_rejects_truncated_data() {
+        let account = vk_account(vec![0u8; 100]);
+        assert_eq!(
+            account.deserialize_vk().unwrap_err(),
+            ShadowVestError::InvalidVerificationKeyData.into()
+        );
+    }
+
+    fn proof_record(proof_valid_until: i64) -> ProofRecord {
+        ProofRecord {
+            verifier: Pubkey::default(),
+            circuit_id: [0u8; 32],
+            nullifier: [0u8; 32],
+            verified_at: 1_000,
+            is_valid: true,
+            proof_valid_until,
+            position_id: EligibilityPublicInputs::position_id_scalar(7),
+            beneficiary_commitment: [9u8; 32],
+            withdrawal_destination: EligibilityPublicInputs::destination_scalar(&Pubkey::default()),
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_valid_until_from_ttl() {
+        assert_eq!(ProofRecord::valid_until(1_000, 0).unwrap(), 0);
+        assert_eq!(ProofRecord::valid_until(1_000, 3_600).unwrap(), 4_600);
+        assert_eq!(
+            ProofRecord::valid_until(i64::MAX, 1).unwrap_err(),
+            ShadowVestError::ArithmeticOverflow.into()
+        );
+    }
+
+    #[test]
+    fn test_record_without_ttl_never_expires() {
+        let record = proof_record(0);
+        assert!(!record.is_expired(i64::MAX));
+        assert!(record.check_usable(i64::MAX).is_ok());
+    }
+
+    #[test]
+    fn test_record_expires_after_valid_until() {
+        let record = proof_record(4_600);
+        assert!(record.check_usable(4_600).is_ok());
+        assert_eq!(
+            record.check_usable(4_601).unwrap_err(),
+            ShadowVestError::ProofRecordExpired.into()
+        );
+    }
+
+    #[test]
+    fn test_eligibility_binding_matches_position_and_destination() {
+        let record = proof_record(0);
+        assert!(record
+            .check_eligibility_binding(7, &[9u8; 32], &Pubkey::default())
+            .is_ok());
+
+        let mismatches = [
+            record.check_eligibility_binding(8, &[9u8; 32], &Pubkey::default()),
+            record.check_eligibility_binding(7, &[1u8; 32], &Pubkey::default()),
+            record.check_eligibility_binding(7, &[9u8; 32], &Pubkey::new_unique()),
+        ];
+        for result in mismatches {
+            assert_eq!(
+                result.unwrap_err(),
+                ShadowVestError::EligibilityBindingMismatch.into()
+            );
+        }
+    }
+
+    #[test]
+    fn test_destination_scalar_is_canonical() {
+        let scalar = EligibilityPublicInputs::destination_scalar(&Pubkey::new_unique());
+        assert_eq!(scalar[0], 0);
+        assert_ne!(scalar, [0u8; 32]);
+    }
+
+    #[test]
+    fn test_invalidated_record_is_unusable() {
+        let mut record = proof_record(0);
+        record.is_valid = false;
+        assert_eq!(
+            record.check_usable(1_000).unwrap_err(),
+            ShadowVestError::ProofRecordInvalid.into()
+        );
+    }
 }
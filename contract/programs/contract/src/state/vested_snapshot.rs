@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+/// Latest calculate_vested result for a position, so clients can read it
+/// without replaying VestedAmountCalculated logs.
+/// Created when the first calculation is queued and overwritten by each callback.
+/// Seeds: [b"vested_snapshot", position.key()]
+#[account]
+pub struct VestedSnapshot {
+    /// Position the calculation was made for
+    pub position: Pubkey,
+    /// Encrypted vested amount (Enc<Shared> for the requesting key)
+    pub encrypted_vested_amount: [u8; 32],
+    /// Encrypted claimable amount (vested - claimed)
+    pub encrypted_claimable_amount: [u8; 32],
+    /// Nonce of the encrypted amounts
+    pub nonce: u128,
+    /// Timestamp of the callback that wrote this snapshot (0 = not calculated yet)
+    pub calculated_at: i64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl VestedSnapshot {
+    pub const SIZE: usize = 8 +  // discriminator
+        32 + // position
+        32 + // encrypted_vested_amount
+        32 + // encrypted_claimable_amount
+        16 + // nonce
+        8 +  // calculated_at
+        1;   // bump
+    // Total: 129 bytes
+
+    pub const SEED_PREFIX: &'static [u8] = b"vested_snapshot";
+}
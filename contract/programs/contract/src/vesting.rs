@@ -0,0 +1,459 @@
+// ============================================================
+// Vesting Schedule Math
+//
+// The vesting numerator is the vested fraction of a position scaled by the
+// schedule's precision (PRECISION unless the schedule opts into a finer one).
+// It is computed on-chain from Clock + schedule parameters and
+// passed to the MPC circuits, which multiply it with the encrypted total.
+// Integrators can call compute_vesting_numerator off-chain to predict the
+// claimable amount for a given timestamp.
+// ============================================================
+
+/// Default precision of the vesting numerator (10^6 = fully vested)
+pub const PRECISION: u64 = 1_000_000;
+
+/// Vesting snaps down to whole `vesting_interval`s (default)
+pub const VESTING_MODE_INTERVAL: u8 = 0;
+/// Vesting accrues every second after the cliff, ignoring `vesting_interval`
+pub const VESTING_MODE_LINEAR: u8 = 1;
+
+/// Compute the vested fraction of a position at `current_time`, scaled by `precision`.
+///
+/// - Before `start + cliff`: 0
+/// - At or after `start + total`: `precision`
+/// - Otherwise: time since the cliff as a fraction of the post-cliff vesting
+///   duration. In VESTING_MODE_INTERVAL the elapsed time is rounded down to a
+///   whole number of `interval`s; in VESTING_MODE_LINEAR it is used as-is.
+///
+/// An `interval` of 0 is treated as 1 second (continuous linear vesting)
+/// instead of dividing by zero.
+pub fn compute_vesting_numerator(
+    current_time: i64,
+    start: i64,
+    cliff: u64,
+    total: u64,
+    interval: u64,
+    mode: u8,
+    precision: u64,
+) -> u64 {
+    let cliff_end = start.saturating_add(cliff as i64);
+    let vesting_end = start.saturating_add(total as i64);
+
+    if current_time < cliff_end {
+        return 0;
+    }
+    if current_time >= vesting_end {
+        return precision;
+    }
+
+    let vesting_duration = total.saturating_sub(cliff);
+    if vesting_duration == 0 {
+        return precision;
+    }
+
+    let elapsed = (current_time - cliff_end) as u64;
+    let vested_seconds = if mode == VESTING_MODE_LINEAR {
+        elapsed
+    } else {
+        let interval = interval.max(1);
+        (elapsed / interval) * interval
+    };
+
+    // u128 intermediate so long schedules and fine precisions can't overflow
+    (vested_seconds as u128 * precision as u128 / vesting_duration as u128) as u64
+}
+
+/// Seconds from `current_time` until the cliff at `start + cliff` ends (0 once reached).
+/// The cliff is reached exactly when compute_vesting_numerator stops returning 0
+/// because of it.
+pub fn seconds_until_cliff(current_time: i64, start: i64, cliff: u64) -> i64 {
+    let cliff_end = start.saturating_add(cliff.min(i64::MAX as u64) as i64);
+    cliff_end.saturating_sub(current_time).max(0)
+}
+
+/// Vested amount of `total` at `numerator` (scaled by `precision`), as computed by
+/// the calculate_vested and process_claim_v2 circuits. The product is taken in u128
+/// so large grants don't wrap at any u64 precision.
+pub fn compute_vested_amount(total: u64, numerator: u64, precision: u64) -> u64 {
+    (total as u128 * numerator as u128 / precision as u128) as u64
+}
+
+/// Whether `claimed` reaches `total`, as revealed by process_claim_v2 for the new
+/// claimed amount
+pub fn is_fully_claimed(total: u64, claimed: u64) -> bool {
+    claimed >= total
+}
+
+/// Plaintext reference for process_claim_v2: returns the new claimed amount and
+/// whether the claim is valid. A claim is rejected (claimed amount unchanged) if it
+/// exceeds the claimable amount or would push the claimed amount past `total`.
+pub fn apply_claim(
+    total: u64,
+    claimed: u64,
+    numerator: u64,
+    precision: u64,
+    claim: u64,
+) -> (u64, bool) {
+    let claimable = compute_vested_amount(total, numerator, precision).saturating_sub(claimed);
+    let is_valid = claim <= claimable && claimed as u128 + claim as u128 <= total as u128;
+    if is_valid {
+        (claimed + claim, true)
+    } else {
+        (claimed, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: i64 = 1_000;
+
+    #[test]
+    fn test_before_cliff_is_zero() {
+        assert_eq!(
+            compute_vesting_numerator(
+                START,
+                START,
+                100,
+                1_000,
+                10,
+                VESTING_MODE_INTERVAL,
+                PRECISION
+            ),
+            0
+        );
+        assert_eq!(
+            compute_vesting_numerator(
+                START + 99,
+                START,
+                100,
+                1_000,
+                10,
+                VESTING_MODE_INTERVAL,
+                PRECISION
+            ),
+            0
+        );
+        // Before start entirely
+        assert_eq!(
+            compute_vesting_numerator(0, START, 0, 1_000, 10, VESTING_MODE_INTERVAL, PRECISION),
+            0
+        );
+    }
+
+    #[test]
+    fn test_after_total_is_precision() {
+        assert_eq!(
+            compute_vesting_numerator(
+                START + 1_000,
+                START,
+                100,
+                1_000,
+                10,
+                VESTING_MODE_INTERVAL,
+                PRECISION
+            ),
+            PRECISION
+        );
+        assert_eq!(
+            compute_vesting_numerator(
+                i64::MAX,
+                START,
+                100,
+                1_000,
+                10,
+                VESTING_MODE_INTERVAL,
+                PRECISION
+            ),
+            PRECISION
+        );
+    }
+
+    #[test]
+    fn test_exact_interval_boundaries() {
+        // 900s post-cliff vesting in 100s steps
+        assert_eq!(
+            compute_vesting_numerator(
+                START + 100,
+                START,
+                100,
+                1_000,
+                100,
+                VESTING_MODE_INTERVAL,
+                PRECISION
+            ),
+            0
+        );
+        assert_eq!(
+            compute_vesting_numerator(
+                START + 199,
+                START,
+                100,
+                1_000,
+                100,
+                VESTING_MODE_INTERVAL,
+                PRECISION
+            ),
+            0
+        );
+        assert_eq!(
+            compute_vesting_numerator(
+                START + 200,
+                START,
+                100,
+                1_000,
+                100,
+                VESTING_MODE_INTERVAL,
+                PRECISION
+            ),
+            100 * PRECISION / 900
+        );
+        assert_eq!(
+            compute_vesting_numerator(
+                START + 550,
+                START,
+                100,
+                1_000,
+                100,
+                VESTING_MODE_INTERVAL,
+                PRECISION
+            ),
+            400 * PRECISION / 900
+        );
+    }
+
+    #[test]
+    fn test_linear_without_cliff() {
+        assert_eq!(
+            compute_vesting_numerator(
+                START + 500,
+                START,
+                0,
+                1_000,
+                1,
+                VESTING_MODE_INTERVAL,
+                PRECISION
+            ),
+            PRECISION / 2
+        );
+    }
+
+    #[test]
+    fn test_zero_interval_is_linear() {
+        assert_eq!(
+            compute_vesting_numerator(
+                START + 500,
+                START,
+                0,
+                1_000,
+                0,
+                VESTING_MODE_INTERVAL,
+                PRECISION
+            ),
+            compute_vesting_numerator(
+                START + 500,
+                START,
+                0,
+                1_000,
+                1,
+                VESTING_MODE_INTERVAL,
+                PRECISION
+            )
+        );
+    }
+
+    #[test]
+    fn test_cliff_only_schedule() {
+        // cliff == total: nothing, then everything
+        assert_eq!(
+            compute_vesting_numerator(
+                START + 999,
+                START,
+                1_000,
+                1_000,
+                1,
+                VESTING_MODE_INTERVAL,
+                PRECISION
+            ),
+            0
+        );
+        assert_eq!(
+            compute_vesting_numerator(
+                START + 1_000,
+                START,
+                1_000,
+                1_000,
+                1,
+                VESTING_MODE_INTERVAL,
+                PRECISION
+            ),
+            PRECISION
+        );
+    }
+
+    #[test]
+    fn test_linear_mode_between_intervals() {
+        // 900s post-cliff vesting in 100s steps, 150s past the cliff
+        let now = START + 250;
+        let stepped = compute_vesting_numerator(
+            now,
+            START,
+            100,
+            1_000,
+            100,
+            VESTING_MODE_INTERVAL,
+            PRECISION,
+        );
+        let linear =
+            compute_vesting_numerator(now, START, 100, 1_000, 100, VESTING_MODE_LINEAR, PRECISION);
+        assert_eq!(stepped, 100 * PRECISION / 900);
+        assert_eq!(linear, 150 * PRECISION / 900);
+        assert!(linear > stepped);
+    }
+
+    #[test]
+    fn test_modes_agree_on_boundaries() {
+        for now in [START, START + 100, START + 200, START + 1_000] {
+            assert_eq!(
+                compute_vesting_numerator(
+                    now,
+                    START,
+                    100,
+                    1_000,
+                    100,
+                    VESTING_MODE_INTERVAL,
+                    PRECISION
+                ),
+                compute_vesting_numerator(
+                    now,
+                    START,
+                    100,
+                    1_000,
+                    100,
+                    VESTING_MODE_LINEAR,
+                    PRECISION
+                )
+            );
+        }
+    }
+
+    // Test vectors shared with the calculate_vested / process_claim_v2 circuits
+
+    #[test]
+    fn test_vested_amount_large_grant_does_not_wrap() {
+        // 2e16 * PRECISION overflows u64; the u128 product does not
+        let total = 20_000_000_000_000_000u64;
+        assert_eq!(compute_vested_amount(total, PRECISION, PRECISION), total);
+        assert_eq!(
+            compute_vested_amount(total, PRECISION / 2, PRECISION),
+            total / 2
+        );
+        assert_eq!(
+            compute_vested_amount(u64::MAX, PRECISION, PRECISION),
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn test_apply_claim_within_claimable() {
+        assert_eq!(
+            apply_claim(1_000, 0, PRECISION / 2, PRECISION, 500),
+            (500, true)
+        );
+        assert_eq!(
+            apply_claim(1_000, 500, PRECISION, PRECISION, 500),
+            (1_000, true)
+        );
+    }
+
+    #[test]
+    fn test_apply_claim_rejects_over_claimable() {
+        assert_eq!(
+            apply_claim(1_000, 0, PRECISION / 2, PRECISION, 501),
+            (0, false)
+        );
+        assert_eq!(
+            apply_claim(1_000, 600, PRECISION / 2, PRECISION, 1),
+            (600, false)
+        );
+    }
+
+    #[test]
+    fn test_apply_claim_never_exceeds_total() {
+        // A numerator above PRECISION would make vested > total
+        assert_eq!(
+            apply_claim(1_000, 900, 2 * PRECISION, PRECISION, 200),
+            (900, false)
+        );
+        assert_eq!(
+            apply_claim(1_000, 900, 2 * PRECISION, PRECISION, 100),
+            (1_000, true)
+        );
+    }
+
+    #[test]
+    fn test_finer_precision_reduces_rounding() {
+        // 1e18 base units vesting linearly over 4 years, one hour in
+        const FINE: u64 = 1_000_000_000;
+        let total = 1_000_000_000_000_000_000u64;
+        let duration = 4 * 365 * 24 * 60 * 60;
+        let exact = (total as u128 * 3_600 / duration as u128) as u64;
+        let vested_at = |now: i64, precision: u64| {
+            let numerator = compute_vesting_numerator(
+                now,
+                START,
+                0,
+                duration,
+                1,
+                VESTING_MODE_LINEAR,
+                precision,
+            );
+            compute_vested_amount(total, numerator, precision)
+        };
+
+        // Both round down, each by less than total / precision
+        let coarse = vested_at(START + 3_600, PRECISION);
+        let fine = vested_at(START + 3_600, FINE);
+        assert!(coarse <= fine && fine <= exact);
+        assert!(exact - coarse < total / PRECISION);
+        assert!(exact - fine < total / FINE);
+        assert!(exact - fine < exact - coarse);
+
+        // Fully vested is exact at either precision
+        let end = START + duration as i64;
+        assert_eq!(
+            compute_vesting_numerator(end, START, 0, duration, 1, VESTING_MODE_LINEAR, FINE),
+            FINE
+        );
+        assert_eq!(vested_at(end, PRECISION), total);
+        assert_eq!(vested_at(end, FINE), total);
+    }
+
+    #[test]
+    fn test_fully_claimed_flag_follows_new_claimed_amount() {
+        // Partial claim, then the claim reaching the total
+        let (claimed, valid) = apply_claim(1_000, 0, PRECISION / 2, PRECISION, 500);
+        assert!(valid && !is_fully_claimed(1_000, claimed));
+        let (claimed, valid) = apply_claim(1_000, claimed, PRECISION, PRECISION, 500);
+        assert!(valid && is_fully_claimed(1_000, claimed));
+
+        // A rejected claim keeps the previous flag
+        let (claimed, valid) = apply_claim(1_000, 600, PRECISION / 2, PRECISION, 1);
+        assert!(!valid && !is_fully_claimed(1_000, claimed));
+
+        // A zero-total position is fully claimed from the start
+        assert!(is_fully_claimed(0, 0));
+    }
+
+    #[test]
+    fn test_seconds_until_cliff() {
+        assert_eq!(seconds_until_cliff(START, START, 100), 100);
+        assert_eq!(seconds_until_cliff(START + 99, START, 100), 1);
+        // Boundary: the cliff is reached at start + cliff
+        assert_eq!(seconds_until_cliff(START + 100, START, 100), 0);
+        assert_eq!(seconds_until_cliff(START + 5_000, START, 100), 0);
+        assert_eq!(seconds_until_cliff(START, START, 0), 0);
+        assert_eq!(seconds_until_cliff(0, START, u64::MAX), i64::MAX);
+    }
+}
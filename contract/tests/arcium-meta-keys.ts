@@ -389,7 +389,8 @@ describe("Arcium Meta-Keys Storage", () => {
         .readMetaKeysFromVault(
           computationOffset,
           Array.from(sessionPubKey) as number[],
-          new anchor.BN(deserializeLE(sessionNonce).toString())
+          new anchor.BN(deserializeLE(sessionNonce).toString()),
+          null // expected_version: read whatever is stored
         )
         .accountsPartial(accounts)
         .signers([owner])
@@ -486,6 +487,268 @@ describe("Arcium Meta-Keys Storage", () => {
       console.log("\n✅ All keys verified! MPC meta-keys storage working correctly.");
     });
   });
+
+  describe("4. Meta-Keys Versioning", () => {
+    it("Bumps the version on re-store and rejects reads of the old version", async () => {
+      const [vaultPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("meta_keys_vault"), owner.publicKey.toBuffer()],
+        program.programId
+      );
+      const signPdaAccount = PublicKey.findProgramAddressSync(
+        [Buffer.from("ArciumSignerAccount")],
+        program.programId
+      )[0];
+      const oldVersion = (await program.account.metaKeysVault.fetch(vaultPDA)).version;
+
+      // Store the same keys again under a fresh session key
+      const sessionPrivKey = x25519.utils.randomSecretKey();
+      const cipher = new RescueCipher(x25519.getSharedSecret(sessionPrivKey, mxePublicKey));
+      const [spendLo, spendHi] = splitKeyToU128(originalSpendPriv);
+      const [viewLo, viewHi] = splitKeyToU128(originalViewPriv);
+      const userNonce = randomBytes(16);
+      const ciphertext = cipher.encrypt([spendLo, spendHi, viewLo, viewHi], userNonce);
+      const writeOffset = new anchor.BN(randomBytes(8), "le");
+
+      await program.methods
+        .writeMetaKeysToVault(
+          writeOffset,
+          Array.from(ciphertext[0]) as number[],
+          Array.from(ciphertext[1]) as number[],
+          Array.from(ciphertext[2]) as number[],
+          Array.from(ciphertext[3]) as number[],
+          Array.from(x25519.getPublicKey(sessionPrivKey)) as number[],
+          new anchor.BN(deserializeLE(userNonce).toString()),
+          new anchor.BN(deserializeLE(randomBytes(16)).toString())
+        )
+        .accountsPartial({
+          payer: owner.publicKey,
+          owner: owner.publicKey,
+          metaKeysVault: vaultPDA,
+          signPdaAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          computationAccount: getComputationAccAddress(arciumEnv.arciumClusterOffset, writeOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("store_meta_keys")).readUInt32LE()
+          ),
+          clusterAccount: getClusterAccAddress(arciumEnv.arciumClusterOffset),
+          poolAccount: getFeePoolAccAddress(),
+          clockAccount: getClockAccAddress(),
+          systemProgram: anchor.web3.SystemProgram.programId,
+          arciumProgram: getArciumProgramId(),
+        })
+        .signers([owner])
+        .rpc({ commitment: "confirmed" });
+
+      await waitForAccountState(
+        provider,
+        program,
+        vaultPDA,
+        "metaKeysVault",
+        (account: any) => account.isInitialized === true && account.version > oldVersion,
+        300000,
+      );
+      const vaultAccount = await program.account.metaKeysVault.fetch(vaultPDA);
+      expect(vaultAccount.version).to.equal(oldVersion + 1);
+
+      // A reader still holding the old version is told the keys changed
+      const readOffset = new anchor.BN(randomBytes(8), "le");
+      try {
+        await program.methods
+          .readMetaKeysFromVault(
+            readOffset,
+            Array.from(x25519.getPublicKey(x25519.utils.randomSecretKey())) as number[],
+            new anchor.BN(deserializeLE(randomBytes(16)).toString()),
+            oldVersion
+          )
+          .accountsPartial({
+            payer: owner.publicKey,
+            owner: owner.publicKey,
+            metaKeysVault: vaultPDA,
+            signPdaAccount,
+            mxeAccount: getMXEAccAddress(program.programId),
+            mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+            executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+            computationAccount: getComputationAccAddress(arciumEnv.arciumClusterOffset, readOffset),
+            compDefAccount: getCompDefAccAddress(
+              program.programId,
+              Buffer.from(getCompDefAccOffset("fetch_meta_keys")).readUInt32LE()
+            ),
+            clusterAccount: getClusterAccAddress(arciumEnv.arciumClusterOffset),
+            poolAccount: getFeePoolAccAddress(),
+            clockAccount: getClockAccAddress(),
+            systemProgram: anchor.web3.SystemProgram.programId,
+            arciumProgram: getArciumProgramId(),
+          })
+          .signers([owner])
+          .rpc({ commitment: "confirmed" });
+        expect.fail("Should have thrown - stale version");
+      } catch (err: any) {
+        expect(err.message || err.toString()).to.include("MetaKeysVersionMismatch");
+      }
+    });
+
+    it("Rejects a read queued between a write and its store callback", async () => {
+      const [vaultPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("meta_keys_vault"), owner.publicKey.toBuffer()],
+        program.programId
+      );
+      const signPdaAccount = PublicKey.findProgramAddressSync(
+        [Buffer.from("ArciumSignerAccount")],
+        program.programId
+      )[0];
+      const oldVersion = (await program.account.metaKeysVault.fetch(vaultPDA)).version;
+
+      const sessionPrivKey = x25519.utils.randomSecretKey();
+      const cipher = new RescueCipher(x25519.getSharedSecret(sessionPrivKey, mxePublicKey));
+      const [spendLo, spendHi] = splitKeyToU128(originalSpendPriv);
+      const [viewLo, viewHi] = splitKeyToU128(originalViewPriv);
+      const userNonce = randomBytes(16);
+      const ciphertext = cipher.encrypt([spendLo, spendHi, viewLo, viewHi], userNonce);
+      const writeOffset = new anchor.BN(randomBytes(8), "le");
+      const readOffset = new anchor.BN(randomBytes(8), "le");
+
+      const writeIx = await program.methods
+        .writeMetaKeysToVault(
+          writeOffset,
+          Array.from(ciphertext[0]) as number[],
+          Array.from(ciphertext[1]) as number[],
+          Array.from(ciphertext[2]) as number[],
+          Array.from(ciphertext[3]) as number[],
+          Array.from(x25519.getPublicKey(sessionPrivKey)) as number[],
+          new anchor.BN(deserializeLE(userNonce).toString()),
+          new anchor.BN(deserializeLE(randomBytes(16)).toString())
+        )
+        .accountsPartial({
+          payer: owner.publicKey,
+          owner: owner.publicKey,
+          metaKeysVault: vaultPDA,
+          signPdaAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          computationAccount: getComputationAccAddress(arciumEnv.arciumClusterOffset, writeOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("store_meta_keys")).readUInt32LE()
+          ),
+          clusterAccount: getClusterAccAddress(arciumEnv.arciumClusterOffset),
+          poolAccount: getFeePoolAccAddress(),
+          clockAccount: getClockAccAddress(),
+          systemProgram: anchor.web3.SystemProgram.programId,
+          arciumProgram: getArciumProgramId(),
+        })
+        .instruction();
+
+      // Queue the read in the same transaction as the write, so it always lands
+      // before the store callback
+      try {
+        await program.methods
+          .readMetaKeysFromVault(
+            readOffset,
+            Array.from(x25519.getPublicKey(x25519.utils.randomSecretKey())) as number[],
+            new anchor.BN(deserializeLE(randomBytes(16)).toString()),
+            null
+          )
+          .accountsPartial({
+            payer: owner.publicKey,
+            owner: owner.publicKey,
+            metaKeysVault: vaultPDA,
+            signPdaAccount,
+            mxeAccount: getMXEAccAddress(program.programId),
+            mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+            executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+            computationAccount: getComputationAccAddress(arciumEnv.arciumClusterOffset, readOffset),
+            compDefAccount: getCompDefAccAddress(
+              program.programId,
+              Buffer.from(getCompDefAccOffset("fetch_meta_keys")).readUInt32LE()
+            ),
+            clusterAccount: getClusterAccAddress(arciumEnv.arciumClusterOffset),
+            poolAccount: getFeePoolAccAddress(),
+            clockAccount: getClockAccAddress(),
+            systemProgram: anchor.web3.SystemProgram.programId,
+            arciumProgram: getArciumProgramId(),
+          })
+          .preInstructions([writeIx])
+          .signers([owner])
+          .rpc({ commitment: "confirmed" });
+        expect.fail("Should have thrown - write still pending");
+      } catch (err: any) {
+        expect(err.message || err.toString()).to.include("MetaKeysWritePending");
+      }
+
+      // The whole transaction rolled back: the vault still holds the settled keys
+      const vaultAccount = await program.account.metaKeysVault.fetch(vaultPDA);
+      expect(vaultAccount.pendingWrite).to.be.false;
+      expect(vaultAccount.isInitialized).to.be.true;
+      expect(vaultAccount.version).to.equal(oldVersion);
+    });
+  });
+
+  describe("5. Close Meta-Keys Vault", () => {
+    it("Closes the vault and rejects later reads", async () => {
+      const [vaultPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("meta_keys_vault"), owner.publicKey.toBuffer()],
+        program.programId
+      );
+
+      const { events } = await program.methods
+        .closeMetaKeysVault()
+        .accountsPartial({ owner: owner.publicKey, metaKeysVault: vaultPDA })
+        .signers([owner])
+        .simulate();
+      const closed = events.find((e: any) => e.name === "metaKeysVaultClosed");
+      expect(closed, "MetaKeysVaultClosed event").to.not.be.undefined;
+      expect((closed as any).data.vault.toBase58()).to.equal(vaultPDA.toBase58());
+
+      await program.methods
+        .closeMetaKeysVault()
+        .accountsPartial({ owner: owner.publicKey, metaKeysVault: vaultPDA })
+        .signers([owner])
+        .rpc({ commitment: "confirmed" });
+      expect(await provider.connection.getAccountInfo(vaultPDA)).to.be.null;
+
+      const readOffset = new anchor.BN(randomBytes(8), "le");
+      try {
+        await program.methods
+          .readMetaKeysFromVault(
+            readOffset,
+            Array.from(x25519.getPublicKey(x25519.utils.randomSecretKey())) as number[],
+            new anchor.BN(deserializeLE(randomBytes(16)).toString()),
+            null
+          )
+          .accountsPartial({
+            payer: owner.publicKey,
+            owner: owner.publicKey,
+            metaKeysVault: vaultPDA,
+            signPdaAccount: PublicKey.findProgramAddressSync(
+              [Buffer.from("ArciumSignerAccount")],
+              program.programId
+            )[0],
+            mxeAccount: getMXEAccAddress(program.programId),
+            mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+            executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+            computationAccount: getComputationAccAddress(arciumEnv.arciumClusterOffset, readOffset),
+            compDefAccount: getCompDefAccAddress(
+              program.programId,
+              Buffer.from(getCompDefAccOffset("fetch_meta_keys")).readUInt32LE()
+            ),
+            clusterAccount: getClusterAccAddress(arciumEnv.arciumClusterOffset),
+            poolAccount: getFeePoolAccAddress(),
+            clockAccount: getClockAccAddress(),
+            systemProgram: anchor.web3.SystemProgram.programId,
+            arciumProgram: getArciumProgramId(),
+          })
+          .signers([owner])
+          .rpc({ commitment: "confirmed" });
+        expect.fail("Should have thrown - vault closed");
+      } catch (err: any) {
+        expect(err.message || err.toString()).to.include("MetaKeysVaultNotInitialized");
+      }
+    });
+  });
 });
 
 // ============================================================
@@ -109,10 +109,13 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
   let vaultPda: PublicKey;
   let vaultAuthorityPda: PublicKey;
   let adminTokenAccount: PublicKey;
+  let treasury: PublicKey;
 
   // Compressed position state
   let compressedPositionAddress: PublicKey; // Light Protocol derived address
   let positionId: number;
+  let secondPositionId: number; // Used only for cross-position nullifier reuse
+  let refundedPositionId: number; // Revoked with a treasury refund
 
   // Scratch position for MPC callback (regular VestingPosition account)
   let scratchPositionPda: PublicKey;
@@ -140,9 +143,13 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
   const nameHash = createHash("sha256")
     .update("CompressedE2E_" + Date.now())
     .digest();
+  const metadataCommitment = Array.from(
+    createHash("sha256").update("compressed-grant-agreement").digest(),
+  );
   const TOTAL_AMOUNT = BigInt(100_000_000); // 100 tokens (6 decimals)
   const CLAIM_AMOUNT = BigInt(50_000_000); // 50 tokens
   const DEPOSIT_AMOUNT = 200_000_000; // 200 tokens
+  const REFUND_AMOUNT = BigInt(20_000_000); // 20 tokens returned on revocation
 
   before(async () => {
     const payer = readKpJson(`${os.homedir()}/.config/solana/id.json`);
@@ -209,7 +216,7 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
     );
     console.log("Token mint:", tokenMint.toString());
 
-    const treasury = Keypair.generate().publicKey;
+    treasury = Keypair.generate().publicKey;
 
     await program.methods
       .createOrganization(Array.from(nameHash), treasury, tokenMint)
@@ -244,6 +251,8 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
         new anchor.BN(0),
         new anchor.BN(10),
         new anchor.BN(1),
+        0, // vesting_mode: interval-stepped
+        tokenMint,
       )
       .accountsPartial({
         admin: admin.publicKey,
@@ -263,7 +272,7 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
       program.programId,
     );
     [vaultPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("vault"), organizationPda.toBuffer()],
+      [Buffer.from("vault"), organizationPda.toBuffer(), tokenMint.toBuffer()],
       program.programId,
     );
 
@@ -300,6 +309,11 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
       DEPOSIT_AMOUNT,
     );
 
+    let deposited: any = null;
+    const listener = program.addEventListener("vaultDeposited", (e) => {
+      deposited = e;
+    });
+
     // Deposit to vault
     await program.methods
       .depositToVault(new anchor.BN(DEPOSIT_AMOUNT))
@@ -313,8 +327,13 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
       .signers([admin])
       .rpc({ commitment: "confirmed" });
 
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    await program.removeEventListener(listener);
+
     const vaultAccount = await getAccount(provider.connection, vaultPda);
     expect(Number(vaultAccount.amount)).to.equal(DEPOSIT_AMOUNT);
+    expect(deposited).to.not.be.null;
+    expect(deposited.newBalance.toString()).to.equal(vaultAccount.amount.toString());
     console.log(`Vault funded: ${DEPOSIT_AMOUNT / 1_000_000} tokens`);
   });
 
@@ -381,6 +400,152 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
   // Phase 2: Create Compressed Vesting Position (Light Protocol)
   // ============================================================
 
+  it("Rejects regular position creation when compressed storage is required", async () => {
+    await program.methods
+      .setCompressedOnly(true)
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    const org = await program.account.organization.fetch(organizationPda);
+    expect(org.compressedOnly).to.be.true;
+
+    const [positionPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vesting_position"),
+        organizationPda.toBuffer(),
+        org.positionCount.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId,
+    );
+    const [signPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ArciumSignerAccount")],
+      program.programId,
+    );
+
+    const nonce = randomBytes(16);
+    const ciphertext = cipher.encrypt([TOTAL_AMOUNT], nonce);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+
+    try {
+      await program.methods
+        .createVestingPosition(
+          computationOffset,
+          Array.from(beneficiaryCommitment) as any,
+          Array.from(ciphertext[0]) as any,
+          Array.from(publicKey) as any,
+          new anchor.BN(deserializeLE(nonce).toString()),
+          0, // sig_scheme: Ed25519
+          Array(32).fill(0),
+        )
+        .accountsPartial({
+          payer: admin.publicKey,
+          admin: admin.publicKey,
+          organization: organizationPda,
+          schedule: schedulePda,
+          position: positionPda,
+          signPdaAccount: signPda,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(
+            arciumEnv.arciumClusterOffset,
+          ),
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            computationOffset,
+          ),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_position")).readUInt32LE(),
+          ),
+          clusterAccount,
+          poolAccount: getFeePoolAccAddress(),
+          clockAccount: getClockAccAddress(),
+          systemProgram: SystemProgram.programId,
+          arciumProgram: getArciumProgramId(),
+        })
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+
+      expect.fail("Should have thrown - org requires compressed storage");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("CompressedStorageRequired");
+    }
+  });
+
+  it("Rejects regular stealth position creation when compressed storage is required", async () => {
+    const org = await program.account.organization.fetch(organizationPda);
+    expect(org.compressedOnly).to.be.true;
+
+    const [positionPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vesting_position"),
+        organizationPda.toBuffer(),
+        org.positionCount.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId,
+    );
+    const [signPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ArciumSignerAccount")],
+      program.programId,
+    );
+
+    const nonce = randomBytes(16);
+    const ciphertext = cipher.encrypt([TOTAL_AMOUNT], nonce);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+
+    try {
+      await program.methods
+        .createStealthVestingPosition(
+          computationOffset,
+          Keypair.generate().publicKey,
+          Array.from(randomBytes(32)) as any,
+          0, // view_tag
+          Array(128).fill(0),
+          Array(128).fill(0),
+          Array.from(ciphertext[0]) as any,
+          Array.from(publicKey) as any,
+          new anchor.BN(deserializeLE(nonce).toString()),
+          Array(32).fill(0),
+        )
+        .accountsPartial({
+          payer: admin.publicKey,
+          admin: admin.publicKey,
+          organization: organizationPda,
+          schedule: schedulePda,
+          position: positionPda,
+          signPdaAccount: signPda,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(
+            arciumEnv.arciumClusterOffset,
+          ),
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            computationOffset,
+          ),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_position")).readUInt32LE(),
+          ),
+          clusterAccount,
+          poolAccount: getFeePoolAccAddress(),
+          clockAccount: getClockAccAddress(),
+          systemProgram: SystemProgram.programId,
+          arciumProgram: getArciumProgramId(),
+        })
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+
+      expect.fail("Should have thrown - org requires compressed storage");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("CompressedStorageRequired");
+    }
+  });
+
   it("Creates compressed vesting position via Light Protocol", async () => {
     const org = await program.account.organization.fetch(organizationPda);
     positionId = org.compressedPositionCount.toNumber();
@@ -477,6 +642,24 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
       microLamports: 1000,
     });
 
+    // Dry run: the program derives the same address without the Light CPI
+    const { events } = await program.methods
+      .validateCompressedAddress(Buffer.from(addressTreeInfoBytes), outputStateTreeIndex)
+      .accounts({ organization: organizationPda })
+      .remainingAccounts(remainingAccounts)
+      .simulate();
+    const preview = events.find((e: any) => e.name === "compressedAddressPreview");
+    expect(preview, "CompressedAddressPreview event").to.not.be.undefined;
+    expect(Buffer.from((preview as any).data.address)).to.deep.equal(
+      compressedPositionAddress.toBuffer(),
+    );
+    expect((preview as any).data.positionId.toNumber()).to.equal(positionId);
+
+    let created: any = null;
+    const createdListener = program.addEventListener("compressedPositionCreated", (e) => {
+      created = e;
+    });
+
     try {
       await program.methods
         .createCompressedVestingPosition(
@@ -486,6 +669,7 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
           Array.from(beneficiaryCommitment) as any,
           Array.from(ciphertext[0]) as any,
           new anchor.BN(nonceAsBN.toString()),
+          metadataCommitment,
         )
         .accountsPartial({
           feePayer: admin.publicKey,
@@ -515,6 +699,14 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
       throw err;
     }
 
+    await sleep(2000);
+    await program.removeEventListener(createdListener);
+    expect(created).to.not.be.null;
+    expect(Array.from(created.metadataCommitment)).to.deep.equal(metadataCommitment);
+    expect(Buffer.from(created.address)).to.deep.equal(
+      Buffer.from((preview as any).data.address),
+    );
+
     // Verify organization counter incremented
     const updatedOrg = await program.account.organization.fetch(organizationPda);
     expect(updatedOrg.compressedPositionCount.toNumber()).to.equal(positionId + 1);
@@ -532,6 +724,23 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
     await sleep(5000);
   });
 
+  it("Disables compressed-only storage for the scratch callback position", async () => {
+    // Compressed creation above succeeded with the flag set. The claim flow
+    // below needs a regular scratch position, so lift the requirement again.
+    await program.methods
+      .setCompressedOnly(false)
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    const org = await program.account.organization.fetch(organizationPda);
+    expect(org.compressedOnly).to.be.false;
+    expect(org.compressedPositionCount.toNumber()).to.equal(positionId + 1);
+  });
+
   // ============================================================
   // Phase 3: Authorize Claim (Ed25519 + Light Protocol Proof)
   // ============================================================
@@ -565,6 +774,7 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
       [
         Buffer.from("nullifier"),
         organizationPda.toBuffer(),
+        Buffer.alloc(8), // default nullifier namespace
         nullifier,
       ],
       program.programId,
@@ -624,6 +834,7 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
 
     // Decode the compressed position data (to pass as args)
     const positionData = deserializeCompressedPosition(compressedAccount!.data!.data);
+    expect(Array.from(positionData.metadataCommitment)).to.deep.equal(metadataCommitment);
     console.log("Deserialized position data:");
     console.log("  owner:", positionData.owner.toString());
     console.log("  organization:", positionData.organization.toString());
@@ -657,6 +868,8 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
         new anchor.BN(positionData.startTimestamp),
         positionData.isActive,
         positionData.isFullyClaimed,
+        positionData.isStealth,
+        Array.from(positionData.metadataCommitment) as any,
         Array.from(nullifier) as any,
         destinationTokenAccount,
       )
@@ -665,6 +878,7 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
         organization: organizationPda,
         claimAuthorization: claimAuthPda,
         nullifierRecord: nullifierRecordPda,
+        addressTree: new PublicKey(batchAddressTree),
         instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
         systemProgram: SystemProgram.programId,
       })
@@ -686,6 +900,11 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
     const versionedTx = new VersionedTransaction(messageV0);
     versionedTx.sign([admin]);
 
+    let authorized: any = null;
+    const authorizedListener = program.addEventListener("claimAuthorized", (e) => {
+      authorized = e;
+    });
+
     // Send versioned transaction
     const txSig = await provider.connection.sendTransaction(versionedTx, {
       skipPreflight: false,
@@ -698,6 +917,13 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
     }, "confirmed");
     console.log("Authorize claim tx:", txSig);
 
+    await sleep(2000);
+    await program.removeEventListener(authorizedListener);
+    expect(authorized).to.not.be.null;
+    expect(authorized.organization.toString()).to.equal(organizationPda.toString());
+    expect(authorized.positionId.toNumber()).to.equal(positionId);
+    expect(authorized.position.toString()).to.equal(compressedPositionAddress.toString());
+
     // Verify claim authorization state
     const claimAuth = await program.account.claimAuthorization.fetch(claimAuthPda);
     expect(claimAuth.isAuthorized).to.be.true;
@@ -776,6 +1002,8 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
           new anchor.BN(positionData.startTimestamp),
           positionData.isActive,
           positionData.isFullyClaimed,
+          positionData.isStealth,
+          Array.from(positionData.metadataCommitment) as any,
           Array.from(nullifier) as any,
           destinationTokenAccount,
         )
@@ -784,6 +1012,7 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
           organization: organizationPda,
           claimAuthorization: claimAuthPda,
           nullifierRecord: nullifierRecordPda,
+          addressTree: new PublicKey(batchAddressTree),
           instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
           systemProgram: SystemProgram.programId,
         })
@@ -816,6 +1045,231 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
     }
   });
 
+  /**
+   * Authorize a claim against a compressed position, reading its current
+   * state from the indexer. `claimedPositionId` is the position_id passed to
+   * the instruction (and signed), which may differ from the account's own.
+   * `overrides` replaces the serialized proof or account meta (malformed input tests).
+   */
+  async function authorizeCompressed(
+    address: PublicKey,
+    claimedPositionId: number,
+    claimNullifier: Buffer,
+    overrides: { proofBytes?: Buffer; accountMetaBytes?: Buffer } = {},
+  ): Promise<void> {
+    const positionIdBytes = Buffer.alloc(8);
+    positionIdBytes.writeBigUInt64LE(BigInt(claimedPositionId));
+    const [authPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("claim_auth"), organizationPda.toBuffer(), positionIdBytes, claimNullifier],
+      program.programId,
+    );
+    const [recordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("nullifier"), organizationPda.toBuffer(), Buffer.alloc(8), claimNullifier],
+      program.programId,
+    );
+
+    const compressedAccount = await lightRpc.getCompressedAccount(bn(address.toBytes()));
+    const proof = await lightRpc.getValidityProofV0(
+      [
+        {
+          hash: compressedAccount!.hash,
+          tree: compressedAccount!.treeInfo.tree,
+          queue: compressedAccount!.treeInfo.queue,
+        },
+      ],
+      [],
+    );
+    const trees = defaultTestStateTreeAccounts();
+    const remainingAccounts = buildLightRemainingAccounts(
+      [trees.merkleTree, trees.nullifierQueue],
+      program.programId,
+    );
+    const accountMetaBytes = serializeCompressedAccountMeta({
+      address: Array.from(address.toBytes()),
+      merkleTreePubkeyIndex: 0,
+      queuePubkeyIndex: 1,
+      leafIndex: proof.leafIndices[0],
+      rootIndex: proof.rootIndices[0],
+    });
+    const positionData = deserializeCompressedPosition(compressedAccount!.data!.data);
+
+    const ed25519Ix = Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: stealthKeypair.secretKey,
+      message: Uint8Array.from(
+        Buffer.concat([positionIdBytes, claimNullifier, destinationTokenAccount.toBuffer()]),
+      ),
+    });
+
+    const authorizeIx = await program.methods
+      .authorizeClaimCompressed(
+        overrides.proofBytes ?? Buffer.from(serializeValidityProof(proof)),
+        overrides.accountMetaBytes ?? Buffer.from(accountMetaBytes),
+        positionData.owner,
+        positionData.organization,
+        positionData.schedule,
+        new anchor.BN(claimedPositionId),
+        Array.from(positionData.beneficiaryCommitment) as any,
+        Array.from(positionData.encryptedTotalAmount) as any,
+        Array.from(positionData.encryptedClaimedAmount) as any,
+        new anchor.BN(positionData.nonce.toString()),
+        new anchor.BN(positionData.startTimestamp),
+        positionData.isActive,
+        positionData.isFullyClaimed,
+        positionData.isStealth,
+        Array.from(positionData.metadataCommitment) as any,
+        Array.from(claimNullifier) as any,
+        destinationTokenAccount,
+      )
+      .accountsPartial({
+        feePayer: admin.publicKey,
+        organization: organizationPda,
+        claimAuthorization: authPda,
+        nullifierRecord: recordPda,
+        addressTree: new PublicKey(batchAddressTree),
+        instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(remainingAccounts)
+      .instruction();
+
+    const { blockhash } = await provider.connection.getLatestBlockhash();
+    const messageV0 = new TransactionMessage({
+      payerKey: admin.publicKey,
+      recentBlockhash: blockhash,
+      instructions: [
+        ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+        ed25519Ix,
+        authorizeIx,
+      ],
+    }).compileToV0Message([lookupTableAccount]);
+    const versionedTx = new VersionedTransaction(messageV0);
+    versionedTx.sign([admin]);
+
+    await provider.connection.sendTransaction(versionedTx, {
+      skipPreflight: false,
+      preflightCommitment: "confirmed",
+    });
+  }
+
+  it("Rejects a position_id that does not match the compressed address", async () => {
+    const otherNullifier = randomBytes(32);
+    try {
+      await authorizeCompressed(compressedPositionAddress, positionId + 1, otherNullifier);
+      expect.fail("Should have thrown - position_id does not match the address");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("CompressedPositionIdMismatch");
+    }
+  });
+
+  it("Reports malformed Light proof and account meta bytes with distinct errors", async () => {
+    // 2 is not a valid Option tag for the validity proof
+    try {
+      await authorizeCompressed(compressedPositionAddress, positionId, randomBytes(32), {
+        proofBytes: Buffer.from([2]),
+      });
+      expect.fail("Should have thrown - malformed proof bytes");
+    } catch (err: any) {
+      const message = err.message || err.toString();
+      expect(message).to.include("LightProofDeserializationFailed");
+      expect(message).to.not.include("LightAccountMetaDeserializationFailed");
+    }
+
+    // Account meta truncated to a few bytes
+    try {
+      await authorizeCompressed(compressedPositionAddress, positionId, randomBytes(32), {
+        accountMetaBytes: Buffer.alloc(3),
+      });
+      expect.fail("Should have thrown - malformed account meta bytes");
+    } catch (err: any) {
+      const message = err.message || err.toString();
+      expect(message).to.include("LightAccountMetaDeserializationFailed");
+      expect(message).to.not.include("LightProofDeserializationFailed");
+    }
+  });
+
+  /** Create another compressed position (TOTAL_AMOUNT) for the same beneficiary */
+  async function createCompressedPosition(): Promise<{ positionId: number; address: PublicKey }> {
+    const org = await program.account.organization.fetch(organizationPda);
+    const newPositionId = org.compressedPositionCount.toNumber();
+    const positionIdBytes = Buffer.alloc(8);
+    positionIdBytes.writeBigUInt64LE(BigInt(newPositionId));
+
+    const addressMerkleTree = new PublicKey(batchAddressTree);
+    const addressSeed = deriveAddressSeedV2([
+      Buffer.from("compressed_position"),
+      organizationPda.toBuffer(),
+      positionIdBytes,
+    ]);
+    const address = new PublicKey(
+      deriveAddressV2(addressSeed, addressMerkleTree, program.programId),
+    );
+
+    const proof = await lightRpc.getValidityProofV0(
+      [],
+      [
+        {
+          address: bn(address.toBytes()),
+          tree: addressMerkleTree,
+          queue: addressMerkleTree,
+        },
+      ],
+    );
+    const packedAccounts = new PackedAccounts();
+    packedAccounts.addSystemAccountsV2(SystemAccountMetaConfig.new(program.programId));
+    const outputStateTreeIndex = packedAccounts.insertOrGet(defaultTestStateTreeAccounts().merkleTree);
+    const addressMerkleTreePubkeyIndex = packedAccounts.insertOrGet(addressMerkleTree);
+    const { remainingAccounts } = packedAccounts.toAccountMetas();
+
+    const nonce = randomBytes(16);
+    const ciphertext = cipher.encrypt([TOTAL_AMOUNT], nonce);
+    const nonceAsBN = BigInt("0x" + Buffer.from(nonce).toString("hex"));
+
+    await program.methods
+      .createCompressedVestingPosition(
+        Buffer.from(serializeValidityProof(proof)),
+        Buffer.from(
+          serializePackedAddressTreeInfo({
+            rootIndex: proof.rootIndices[0],
+            addressMerkleTreePubkeyIndex,
+            addressQueuePubkeyIndex: addressMerkleTreePubkeyIndex,
+          }),
+        ),
+        outputStateTreeIndex,
+        Array.from(beneficiaryCommitment) as any,
+        Array.from(ciphertext[0]) as any,
+        new anchor.BN(nonceAsBN.toString()),
+        Array(32).fill(0),
+      )
+      .accountsPartial({
+        feePayer: admin.publicKey,
+        admin: admin.publicKey,
+        organization: organizationPda,
+        schedule: schedulePda,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(remainingAccounts)
+      .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 })])
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+    await sleep(5000);
+
+    return { positionId: newPositionId, address };
+  }
+
+  it("Rejects reusing a nullifier on a different compressed position", async () => {
+    // Create a second compressed position for the same beneficiary
+    let secondAddress: PublicKey;
+    ({ positionId: secondPositionId, address: secondAddress } = await createCompressedPosition());
+
+    // The first position's nullifier is already recorded for this organization
+    try {
+      await authorizeCompressed(secondAddress, secondPositionId, nullifier);
+      expect.fail("Should have thrown - nullifier used by another position");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("NullifierAlreadyUsed");
+    }
+  });
+
   // ============================================================
   // Phase 4: Queue MPC Process Claim (Arcium Cluster 456)
   // ============================================================
@@ -865,6 +1319,8 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
         Array.from(scratchCiphertext[0]) as any,
         Array.from(publicKey) as any,
         scratchNonceAsBN,
+        0, // sig_scheme: Ed25519
+        Array(32).fill(0),
       )
       .accountsPartial({
         payer: admin.publicKey,
@@ -912,20 +1368,18 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
     console.log("Scratch position initialized by MPC");
 
     // Now queue process_claim_v2 for the compressed position
+    // The position's total and claimed amounts, encrypted together; the vesting
+    // numerator is computed on-chain (fully vested after >10s) and the claim
+    // amount is passed in plaintext
     const claimedSoFar = BigInt(0);
-    const PRECISION = BigInt(1_000_000);
-    const vestingNumerator = PRECISION; // Fully vested (>10s elapsed)
 
     const nonce = randomBytes(16);
     const nonceAsBN = new anchor.BN(deserializeLE(nonce).toString());
 
-    const encryptedTotalAmount = cipher.encrypt([TOTAL_AMOUNT], nonce);
-    const encryptedClaimedAmount = cipher.encrypt([claimedSoFar], nonce);
-    const encryptedVestingNumerator = cipher.encrypt(
-      [vestingNumerator],
+    const [encryptedTotalAmount, encryptedClaimedAmount] = cipher.encrypt(
+      [TOTAL_AMOUNT, claimedSoFar],
       nonce,
     );
-    const encryptedClaimAmount = cipher.encrypt([CLAIM_AMOUNT], nonce);
 
     const computationOffset = new anchor.BN(randomBytes(8), "hex");
 
@@ -939,10 +1393,8 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
       .queueProcessClaimCompressed(
         computationOffset,
         new anchor.BN(positionId),
-        Array.from(encryptedTotalAmount[0]) as any,
-        Array.from(encryptedClaimedAmount[0]) as any,
-        Array.from(encryptedVestingNumerator[0]) as any,
-        Array.from(encryptedClaimAmount[0]) as any,
+        Array.from(encryptedTotalAmount) as any,
+        Array.from(encryptedClaimedAmount) as any,
         new anchor.BN(CLAIM_AMOUNT.toString()),
         new anchor.BN(positionData.startTimestamp),
         Array.from(publicKey) as any,
@@ -954,6 +1406,7 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
         schedule: schedulePda,
         position: scratchPositionPda,
         claimAuthorization: claimAuthPda,
+        vault: vaultPda,
         signPdaAccount: signPda,
         mxeAccount: getMXEAccAddress(program.programId),
         mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
@@ -1004,6 +1457,91 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
     );
   });
 
+  /**
+   * Send withdraw_compressed with verify_light_state set, passing the
+   * compressed position's current leaf and a validity proof for it.
+   */
+  async function withdrawCompressedVerified(): Promise<void> {
+    const compressedAccount = await lightRpc.getCompressedAccount(
+      bn(compressedPositionAddress.toBytes()),
+    );
+    const proof = await lightRpc.getValidityProofV0(
+      [
+        {
+          hash: compressedAccount!.hash,
+          tree: compressedAccount!.treeInfo.tree,
+          queue: compressedAccount!.treeInfo.queue,
+        },
+      ],
+      [],
+    );
+    const remainingAccounts = buildLightRemainingAccountsForUpdate(
+      new PublicKey(compressedAccount!.treeInfo.tree),
+      new PublicKey(compressedAccount!.treeInfo.queue),
+      program.programId,
+    );
+    const accountMetaBytes = serializeCompressedAccountMeta({
+      address: Array.from(compressedPositionAddress.toBytes()),
+      merkleTreePubkeyIndex: 0,
+      queuePubkeyIndex: 1,
+      leafIndex: proof.leafIndices[0],
+      rootIndex: proof.rootIndices[0],
+    });
+
+    const withdrawIx = await program.methods
+      .withdrawCompressed(
+        new anchor.BN(positionId),
+        Array.from(nullifier) as any,
+        true, // verify_light_state
+        Buffer.from(serializeValidityProof(proof)),
+        Buffer.from(accountMetaBytes),
+        Buffer.from(compressedAccount!.data!.data),
+      )
+      .accountsPartial({
+        payer: admin.publicKey,
+        organization: organizationPda,
+        claimAuthorization: claimAuthPda,
+        vaultAuthority: vaultAuthorityPda,
+        vault: vaultPda,
+        destination: destinationTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(remainingAccounts)
+      .instruction();
+
+    const { blockhash, lastValidBlockHeight } = await provider.connection.getLatestBlockhash();
+    const messageV0 = new TransactionMessage({
+      payerKey: admin.publicKey,
+      recentBlockhash: blockhash,
+      instructions: [
+        ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+        withdrawIx,
+      ],
+    }).compileToV0Message([lookupTableAccount]);
+    const versionedTx = new VersionedTransaction(messageV0);
+    versionedTx.sign([admin]);
+
+    const txSig = await provider.connection.sendTransaction(versionedTx, {
+      skipPreflight: false,
+      preflightCommitment: "confirmed",
+    });
+    await provider.connection.confirmTransaction(
+      { signature: txSig, blockhash, lastValidBlockHeight },
+      "confirmed",
+    );
+  }
+
+  it("Rejects a Light-verified withdrawal before the compressed position is updated", async () => {
+    // The claim is processed, but update_compressed_position_claimed has not run,
+    // so the compressed position does not reflect it yet
+    try {
+      await withdrawCompressedVerified();
+      expect.fail("Should have thrown - compressed position not updated");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("CompressedPositionNotUpdated");
+    }
+  });
+
   // ============================================================
   // Phase 5: Update Compressed Position Claimed (Light Protocol)
   // ============================================================
@@ -1057,14 +1595,14 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
 
     const positionData = deserializeCompressedPosition(compressedAccount!.data!.data);
 
-    // After MPC processing, get the new encrypted_claimed_amount from scratch position
-    const scratchPosition = await program.account.vestingPosition.fetch(
-      scratchPositionPda,
-    );
-    const newEncryptedClaimedAmount = scratchPosition.encryptedClaimedAmount;
-
-    // Determine if fully claimed (claim_amount == total_amount means fully claimed)
-    const newIsFullyClaimed = CLAIM_AMOUNT >= TOTAL_AMOUNT ? 1 : 0;
+    // The process_claim_v2 callback recorded the re-encrypted amounts, their nonce
+    // and the fully-claimed flag the MPC revealed; the scratch position is untouched
+    const processedAuth = await program.account.claimAuthorization.fetch(claimAuthPda);
+    expect(Buffer.from(processedAuth.compressedNewClaimedAmount).some((b) => b !== 0)).to.be
+      .true;
+    const newIsFullyClaimed = processedAuth.compressedIsFullyClaimed;
+    expect(newIsFullyClaimed).to.equal(CLAIM_AMOUNT >= TOTAL_AMOUNT ? 1 : 0);
+    const orgBefore = await program.account.organization.fetch(organizationPda);
 
     const modifyComputeUnits = ComputeBudgetProgram.setComputeUnitLimit({
       units: 1_400_000,
@@ -1073,37 +1611,55 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
       microLamports: 1000,
     });
 
-    await program.methods
-      .updateCompressedPositionClaimed(
-        Buffer.from(proofBytes),
-        Buffer.from(accountMetaBytes),
-        positionData.owner,
-        positionData.organization,
-        positionData.schedule,
-        new anchor.BN(positionData.positionId),
-        Array.from(positionData.beneficiaryCommitment) as any,
-        Array.from(positionData.encryptedTotalAmount) as any,
-        Array.from(positionData.encryptedClaimedAmount) as any,
-        new anchor.BN(positionData.nonce.toString()),
-        new anchor.BN(positionData.startTimestamp),
-        positionData.isActive,
-        positionData.isFullyClaimed,
-        Array.from(newEncryptedClaimedAmount) as any,
-        newIsFullyClaimed,
-      )
-      .accountsPartial({
-        feePayer: admin.publicKey,
-        organization: organizationPda,
-        claimAuthorization: claimAuthPda,
-        systemProgram: SystemProgram.programId,
-      })
-      .remainingAccounts(remainingAccounts)
-      .preInstructions([modifyComputeUnits, addPriorityFee])
-      .signers([admin])
-      .rpc({ commitment: "confirmed" });
+    const updateClaimed = (isFullyClaimed: number) =>
+      program.methods
+        .updateCompressedPositionClaimed(
+          Buffer.from(proofBytes),
+          Buffer.from(accountMetaBytes),
+          positionData.owner,
+          positionData.organization,
+          positionData.schedule,
+          new anchor.BN(positionData.positionId),
+          Array.from(positionData.beneficiaryCommitment) as any,
+          Array.from(positionData.encryptedTotalAmount) as any,
+          Array.from(positionData.encryptedClaimedAmount) as any,
+          new anchor.BN(positionData.nonce.toString()),
+          new anchor.BN(positionData.startTimestamp),
+          positionData.isActive,
+          positionData.isFullyClaimed,
+          positionData.isStealth,
+          Array.from(positionData.metadataCommitment) as any,
+          isFullyClaimed,
+        )
+        .accountsPartial({
+          feePayer: admin.publicKey,
+          organization: organizationPda,
+          claimAuthorization: claimAuthPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(remainingAccounts)
+        .preInstructions([modifyComputeUnits, addPriorityFee])
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+
+    // A flag contradicting the MPC result is rejected
+    try {
+      await updateClaimed(1 - newIsFullyClaimed);
+      expect.fail("Should have rejected a fully-claimed flag the MPC did not reveal");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("FullyClaimedFlagMismatch");
+    }
+
+    await updateClaimed(newIsFullyClaimed);
 
     console.log("Compressed position claimed amount updated");
 
+    // Only a transition to fully claimed advances the organization counter
+    const orgAfter = await program.account.organization.fetch(organizationPda);
+    expect(orgAfter.totalClaimedPositions.toNumber()).to.equal(
+      orgBefore.totalClaimedPositions.toNumber() + newIsFullyClaimed,
+    );
+
     // Wait for indexer to catch up
     await sleep(3000);
 
@@ -1114,6 +1670,14 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
     expect(updatedAccount).to.not.be.null;
     const updatedData = deserializeCompressedPosition(updatedAccount!.data!.data);
     expect(updatedData.isFullyClaimed).to.equal(newIsFullyClaimed);
+    // Both amounts are stored with the nonce the MPC encrypted them under
+    expect(Buffer.from(updatedData.encryptedTotalAmount)).to.deep.equal(
+      Buffer.from(processedAuth.compressedNewTotalAmount),
+    );
+    expect(Buffer.from(updatedData.encryptedClaimedAmount)).to.deep.equal(
+      Buffer.from(processedAuth.compressedNewClaimedAmount),
+    );
+    expect(updatedData.nonce.toString()).to.equal(processedAuth.compressedNewNonce.toString());
     console.log("Compressed position state verified after update");
   });
 
@@ -1127,26 +1691,16 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
       destinationTokenAccount,
     );
     expect(Number(beforeBalance.amount)).to.equal(0);
+    const orgBefore = await program.account.organization.fetch(organizationPda);
 
     const positionIdBytes = Buffer.alloc(8);
     positionIdBytes.writeBigUInt64LE(BigInt(positionId));
 
-    await program.methods
-      .withdrawCompressed(
-        new anchor.BN(positionId),
-        Array.from(nullifier) as any,
-      )
-      .accountsPartial({
-        payer: admin.publicKey,
-        organization: organizationPda,
-        claimAuthorization: claimAuthPda,
-        vaultAuthority: vaultAuthorityPda,
-        vault: vaultPda,
-        destination: destinationTokenAccount,
-        tokenProgram: TOKEN_PROGRAM_ID,
-      })
-      .signers([admin])
-      .rpc({ commitment: "confirmed" });
+    // Withdraw with the Light state check: the position must hold the update above
+    await withdrawCompressedVerified();
+
+    const orgAfter = await program.account.organization.fetch(organizationPda);
+    expect(orgAfter.totalWithdrawals.toNumber()).to.equal(orgBefore.totalWithdrawals.toNumber() + 1);
 
     // Verify tokens received
     const afterBalance = await getAccount(
@@ -1166,12 +1720,32 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
     console.log("ClaimAuthorization: withdrawn=true");
   });
 
+  it("Resolves the compressed claim authorization PDA used by withdraw_compressed", async () => {
+    const { events } = await program.methods
+      .resolveClaimAuthorization(
+        true,
+        PublicKey.default, // ignored for compressed positions
+        new anchor.BN(positionId),
+        Array.from(nullifier) as any,
+      )
+      .accounts({ organization: organizationPda })
+      .simulate();
+
+    const resolved = events.find((e: any) => e.name === "claimAuthorizationResolved");
+    expect(resolved, "ClaimAuthorizationResolved event").to.not.be.undefined;
+    expect((resolved as any).data.claimAuthorization.toString()).to.equal(claimAuthPda.toString());
+  });
+
   it("Rejects double-withdrawal", async () => {
     try {
       await program.methods
         .withdrawCompressed(
           new anchor.BN(positionId),
           Array.from(nullifier) as any,
+          false, // verify_light_state
+          Buffer.alloc(0),
+          Buffer.alloc(0),
+          Buffer.alloc(0),
         )
         .accountsPartial({
           payer: admin.publicKey,
@@ -1192,15 +1766,196 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
     }
   });
 
+  /** Fetch a compressed position with the validity proof and accounts to update it */
+  async function loadCompressedPositionForUpdate(address: PublicKey) {
+    const compressedAccount = await lightRpc.getCompressedAccount(bn(address.toBytes()));
+    expect(compressedAccount).to.not.be.null;
+    const proof = await lightRpc.getValidityProofV0(
+      [
+        {
+          hash: compressedAccount!.hash,
+          tree: compressedAccount!.treeInfo.tree,
+          queue: compressedAccount!.treeInfo.queue,
+        },
+      ],
+      [],
+    );
+    const remainingAccounts = buildLightRemainingAccountsForUpdate(
+      new PublicKey(compressedAccount!.treeInfo.tree),
+      new PublicKey(compressedAccount!.treeInfo.queue),
+      program.programId,
+    );
+    const accountMetaBytes = serializeCompressedAccountMeta({
+      address: Array.from(address.toBytes()),
+      merkleTreePubkeyIndex: 0,
+      queuePubkeyIndex: 1,
+      leafIndex: proof.leafIndices[0],
+      rootIndex: proof.rootIndices[0],
+    });
+    const positionData = deserializeCompressedPosition(compressedAccount!.data!.data);
+
+    return { positionData, proof, accountMetaBytes, remainingAccounts };
+  }
+
+  it("Revokes a compressed position and rejects new claims against it", async () => {
+    // The second position (same beneficiary) has no claims in flight
+    const positionIdBytes = Buffer.alloc(8);
+    positionIdBytes.writeBigUInt64LE(BigInt(secondPositionId));
+    const addressSeed = deriveAddressSeedV2([
+      Buffer.from("compressed_position"),
+      organizationPda.toBuffer(),
+      positionIdBytes,
+    ]);
+    const secondAddress = new PublicKey(
+      deriveAddressV2(addressSeed, new PublicKey(batchAddressTree), program.programId),
+    );
+
+    const { positionData, proof, accountMetaBytes, remainingAccounts } =
+      await loadCompressedPositionForUpdate(secondAddress);
+    expect(positionData.isActive).to.equal(1);
+
+    let revoked: any = null;
+    const listener = program.addEventListener("compressedPositionRevoked", (e) => {
+      revoked = e;
+    });
+
+    await program.methods
+      .revokeCompressedPosition(
+        Buffer.from(serializeValidityProof(proof)),
+        Buffer.from(accountMetaBytes),
+        positionData.owner,
+        positionData.organization,
+        positionData.schedule,
+        new anchor.BN(positionData.positionId),
+        Array.from(positionData.beneficiaryCommitment) as any,
+        Array.from(positionData.encryptedTotalAmount) as any,
+        Array.from(positionData.encryptedClaimedAmount) as any,
+        new anchor.BN(positionData.nonce.toString()),
+        new anchor.BN(positionData.startTimestamp),
+        positionData.isActive,
+        positionData.isFullyClaimed,
+        positionData.isStealth,
+        Array.from(positionData.metadataCommitment) as any,
+      )
+      .accountsPartial({
+        admin: admin.publicKey,
+        organization: organizationPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(remainingAccounts)
+      .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 })])
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    await sleep(3000);
+    await program.removeEventListener(listener);
+    expect(revoked).to.not.be.null;
+    expect(revoked.positionId.toNumber()).to.equal(secondPositionId);
+    expect(Buffer.from(revoked.address)).to.deep.equal(secondAddress.toBuffer());
+    expect(revoked.revokedAt.toNumber()).to.be.greaterThan(0);
+
+    const updated = await lightRpc.getCompressedAccount(bn(secondAddress.toBytes()));
+    expect(deserializeCompressedPosition(updated!.data!.data).isActive).to.equal(0);
+
+    // authorize_claim_compressed now receives position_is_active = 0
+    try {
+      await authorizeCompressed(secondAddress, secondPositionId, randomBytes(32));
+      expect.fail("Should have thrown - position revoked");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("PositionNotActive");
+    }
+  });
+
+  it("Revokes a compressed position and refunds its unvested tokens to the treasury", async () => {
+    let refundedAddress: PublicKey;
+    ({ positionId: refundedPositionId, address: refundedAddress } = await createCompressedPosition());
+
+    const treasuryTokenAccount = await createAccount(
+      provider.connection,
+      admin,
+      tokenMint,
+      treasury,
+      Keypair.generate(),
+    );
+    const revokeAndRefund = async (amount: bigint) => {
+      const { positionData, proof, accountMetaBytes, remainingAccounts } =
+        await loadCompressedPositionForUpdate(refundedAddress);
+      return program.methods
+        .revokeCompressedAndRefund(
+          Buffer.from(serializeValidityProof(proof)),
+          Buffer.from(accountMetaBytes),
+          positionData.owner,
+          positionData.organization,
+          positionData.schedule,
+          new anchor.BN(positionData.positionId),
+          Array.from(positionData.beneficiaryCommitment) as any,
+          Array.from(positionData.encryptedTotalAmount) as any,
+          Array.from(positionData.encryptedClaimedAmount) as any,
+          new anchor.BN(positionData.nonce.toString()),
+          new anchor.BN(positionData.startTimestamp),
+          positionData.isActive,
+          positionData.isFullyClaimed,
+          positionData.isStealth,
+          Array.from(positionData.metadataCommitment) as any,
+          new anchor.BN(amount.toString()),
+        )
+        .accountsPartial({
+          admin: admin.publicKey,
+          organization: organizationPda,
+          schedule: schedulePda,
+          vaultAuthority: vaultAuthorityPda,
+          vault: vaultPda,
+          treasuryTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(remainingAccounts)
+        .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 })])
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+    };
+
+    // The refund is bounded by the vault balance
+    const vaultBefore = await getAccount(provider.connection, vaultPda);
+    try {
+      await revokeAndRefund(vaultBefore.amount + BigInt(1));
+      expect.fail("Should have thrown - refund exceeds the vault balance");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("InsufficientVaultBalance");
+    }
+
+    let refunded: any = null;
+    const listener = program.addEventListener("compressedPositionRevokedAndRefunded", (e) => {
+      refunded = e;
+    });
+    await revokeAndRefund(REFUND_AMOUNT);
+    await sleep(3000);
+    await program.removeEventListener(listener);
+
+    expect(refunded).to.not.be.null;
+    expect(refunded.positionId.toNumber()).to.equal(refundedPositionId);
+    expect(Buffer.from(refunded.address)).to.deep.equal(refundedAddress.toBuffer());
+    expect(refunded.treasuryTokenAccount.toString()).to.equal(treasuryTokenAccount.toString());
+    expect(refunded.amount.toString()).to.equal(REFUND_AMOUNT.toString());
+
+    const vaultAfter = await getAccount(provider.connection, vaultPda);
+    expect(vaultAfter.amount).to.equal(vaultBefore.amount - REFUND_AMOUNT);
+    const treasuryAccount = await getAccount(provider.connection, treasuryTokenAccount);
+    expect(treasuryAccount.amount).to.equal(REFUND_AMOUNT);
+
+    const updated = await lightRpc.getCompressedAccount(bn(refundedAddress.toBytes()));
+    expect(deserializeCompressedPosition(updated!.data!.data).isActive).to.equal(0);
+  });
+
   // ============================================================
   // Phase 7: Final State Verification
   // ============================================================
 
   it("Verifies final state consistency", async () => {
-    // Vault balance should be reduced by claim amount
+    // Vault balance should be reduced by the claim and the revocation refund
     const vaultAccount = await getAccount(provider.connection, vaultPda);
     expect(Number(vaultAccount.amount)).to.equal(
-      DEPOSIT_AMOUNT - Number(CLAIM_AMOUNT),
+      DEPOSIT_AMOUNT - Number(CLAIM_AMOUNT) - Number(REFUND_AMOUNT),
     );
     console.log(
       `Final vault balance: ${Number(vaultAccount.amount) / 1_000_000} tokens`,
@@ -1208,7 +1963,7 @@ describe("Compressed Claim & Withdraw (Devnet E2E)", () => {
 
     // Organization state
     const org = await program.account.organization.fetch(organizationPda);
-    expect(org.compressedPositionCount.toNumber()).to.equal(positionId + 1);
+    expect(org.compressedPositionCount.toNumber()).to.equal(refundedPositionId + 1);
     expect(org.isActive).to.be.true;
     console.log(
       "Organization compressed positions:",
@@ -1499,8 +2254,10 @@ function serializeCompressedAccountMeta(meta: {
  *   start_timestamp: i64,    // 8 bytes
  *   is_active: u8,           // 1 byte
  *   is_fully_claimed: u8,    // 1 byte
+ *   is_stealth: u8,          // 1 byte
+ *   metadata_commitment: [u8; 32], // 32 bytes
  * }
- * Total: 226 bytes (no discriminator in data)
+ * Total: 259 bytes (no discriminator in data)
  */
 function deserializeCompressedPosition(data: Buffer | Uint8Array): {
   owner: PublicKey;
@@ -1514,6 +2271,8 @@ function deserializeCompressedPosition(data: Buffer | Uint8Array): {
   startTimestamp: number;
   isActive: number;
   isFullyClaimed: number;
+  isStealth: number;
+  metadataCommitment: Uint8Array;
 } {
   const buf = Buffer.from(data);
   let offset = 0;
@@ -1557,6 +2316,12 @@ function deserializeCompressedPosition(data: Buffer | Uint8Array): {
   const isFullyClaimed = buf[offset];
   offset += 1;
 
+  const isStealth = buf[offset];
+  offset += 1;
+
+  const metadataCommitment = new Uint8Array(buf.slice(offset, offset + 32));
+  offset += 32;
+
   return {
     owner,
     organization,
@@ -1569,6 +2334,8 @@ function deserializeCompressedPosition(data: Buffer | Uint8Array): {
     startTimestamp,
     isActive,
     isFullyClaimed,
+    isStealth,
+    metadataCommitment,
   };
 }
 
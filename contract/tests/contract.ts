@@ -45,6 +45,7 @@ describe("ShadowVest", () => {
   let organizationPda: PublicKey;
   let schedulePda: PublicKey;
   let positionPda: PublicKey;
+  let secondPositionPda: PublicKey;
   let mxePublicKey: Uint8Array;
   let cipher: RescueCipher;
   let privateKey: Uint8Array;
@@ -56,6 +57,12 @@ describe("ShadowVest", () => {
   const tokenMint = Keypair.generate().publicKey;
   const beneficiaryCommitment = createHash("sha256").update("employee123").digest();
 
+  const positionIndexPda = () =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("position_index"), organizationPda.toBuffer()],
+      program.programId,
+    )[0];
+
   before(async () => {
     const payer = readKpJson(`${os.homedir()}/.config/solana/id.json`);
 
@@ -148,7 +155,7 @@ describe("ShadowVest", () => {
     const vestingInterval = new anchor.BN(24 * 60 * 60); // 1 day in seconds
 
     const sig = await program.methods
-      .createVestingSchedule(cliffDuration, totalDuration, vestingInterval)
+      .createVestingSchedule(cliffDuration, totalDuration, vestingInterval, 0, tokenMint)
       .accounts({
         admin: admin.publicKey,
         organization: organizationPda,
@@ -166,6 +173,7 @@ describe("ShadowVest", () => {
     expect(scheduleAccount.cliffDuration.toNumber()).to.equal(cliffDuration.toNumber());
     expect(scheduleAccount.totalDuration.toNumber()).to.equal(totalDuration.toNumber());
     expect(scheduleAccount.vestingInterval.toNumber()).to.equal(vestingInterval.toNumber());
+    expect(scheduleAccount.vestingMode).to.equal(0);
     expect(scheduleAccount.isActive).to.equal(true);
 
     // Verify organization schedule count incremented
@@ -173,6 +181,30 @@ describe("ShadowVest", () => {
     expect(updatedOrg.scheduleCount.toNumber()).to.equal(1);
   });
 
+  it("Rejects migrating a schedule that already uses the current layout", async () => {
+    const info = await provider.connection.getAccountInfo(schedulePda);
+    expect(info.data.length).to.equal(172);
+
+    try {
+      await program.methods
+        .migrateVestingSchedule(new anchor.BN(0))
+        .accounts({
+          admin: admin.publicKey,
+          organization: organizationPda,
+          schedule: schedulePda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("Migrating a current schedule should fail");
+    } catch (err: any) {
+      expect(err.toString()).to.include("ScheduleAlreadyMigrated");
+    }
+
+    const schedule = await program.account.vestingSchedule.fetch(schedulePda);
+    expect(schedule.precision.toNumber()).to.equal(1_000_000);
+  });
+
   it("Creates a vesting position with encrypted amount", async () => {
     const orgAccount = await program.account.organization.fetch(organizationPda);
     const positionId = orgAccount.positionCount;
@@ -247,6 +279,8 @@ describe("ShadowVest", () => {
           Array.from(ciphertext[0]),
           Array.from(publicKey),
           nonceAsBN,
+          0, // sig_scheme: Ed25519
+          Array(32).fill(0),
         )
         .accountsPartial(accounts)
         .preInstructions([modifyComputeUnits, addPriorityFee])
@@ -266,8 +300,7 @@ describe("ShadowVest", () => {
     const positionAccount = await program.account.vestingPosition.fetch(positionPda);
     expect(positionAccount.organization.toString()).to.equal(organizationPda.toString());
     expect(positionAccount.schedule.toString()).to.equal(schedulePda.toString());
-    expect(positionAccount.isActive).to.equal(true);
-    expect(positionAccount.isFullyClaimed).to.equal(false);
+    expect(positionAccount.state).to.deep.equal({ created: {} });
     expect(Buffer.from(positionAccount.beneficiaryCommitment)).to.deep.equal(beneficiaryCommitment);
 
     // Verify organization position count incremented
@@ -288,6 +321,9 @@ describe("ShadowVest", () => {
       120000, // 2 minute timeout
     );
     console.log("Position init_position callback received");
+
+    const initializedPosition = await program.account.vestingPosition.fetch(positionPda);
+    expect(initializedPosition.state).to.deep.equal({ initialized: {} });
   });
 
   it("Calculates vested amount", async () => {
@@ -421,121 +457,1501 @@ describe("ShadowVest", () => {
     );
     console.log("Vested calculation finalized:", finalizeSig);
   });
-});
 
-// Helper functions
+  it("Stores the latest vested calculation in a snapshot", async () => {
+    const [snapshotPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vested_snapshot"), positionPda.toBuffer()],
+      program.programId,
+    );
 
-async function initCompDef(
-  program: Program<Contract>,
-  owner: Keypair,
-  circuitName: string,
-): Promise<string> {
-  const baseSeedCompDefAcc = getArciumAccountBaseSeed(
-    "ComputationDefinitionAccount",
-  );
-  const offset = getCompDefAccOffset(circuitName);
-  const provider = anchor.getProvider() as anchor.AnchorProvider;
+    // Written by the previous calculation's callback
+    const first = await program.account.vestedSnapshot.fetch(snapshotPda);
+    expect(first.position.toString()).to.equal(positionPda.toString());
+    expect(first.calculatedAt.toNumber()).to.be.greaterThan(0);
 
-  const compDefPDA = PublicKey.findProgramAddressSync(
-    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
-    getArciumProgramId(),
-  )[0];
+    // Recalculate as if fully vested (numerator = PRECISION)
+    const totalAmount = BigInt(1000_000_000);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const nonce = randomBytes(16);
+    const ciphertext = cipher.encrypt([totalAmount, BigInt(0), BigInt(1_000_000)], nonce);
+    const [signPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ArciumSignerAccount")],
+      program.programId,
+    );
 
-  console.log(`Comp def PDA for ${circuitName}:`, compDefPDA.toString());
+    await program.methods
+      .calculateVestedAmount(
+        computationOffset,
+        Array.from(ciphertext[0]),
+        Array.from(ciphertext[1]),
+        Array.from(ciphertext[2]),
+        Array.from(publicKey),
+        new anchor.BN(deserializeLE(nonce).toString()),
+      )
+      .accountsPartial({
+        payer: admin.publicKey,
+        organization: organizationPda,
+        schedule: schedulePda,
+        position: positionPda,
+        vestedSnapshot: snapshotPda,
+        signPdaAccount: signPda,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset,
+        ),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("calculate_vested")).readUInt32LE(),
+        ),
+        clusterAccount,
+        poolAccount: getFeePoolAccAddress(),
+        clockAccount: getClockAccAddress(),
+        systemProgram: anchor.web3.SystemProgram.programId,
+        arciumProgram: getArciumProgramId(),
+      })
+      .preInstructions([
+        ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+      ])
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
 
-  // Check if already initialized
-  const accountInfo = await provider.connection.getAccountInfo(compDefPDA);
-  if (accountInfo !== null) {
-    // For OffChain circuit source, comp def is ready as soon as it's initialized
-    // No finalization needed - MPC nodes fetch circuit directly from URL
-    console.log(`Comp def for ${circuitName} already exists - ready for use`);
-    return "already_initialized";
-  }
+    await awaitComputationFinalization(
+      provider as anchor.AnchorProvider,
+      computationOffset,
+      program.programId,
+    );
 
-  // Get LUT address from MXE account (v0.7.0 requirement)
-  const mxeAccountAddr = getMXEAccAddress(program.programId);
-  const arciumProgram = getArciumProgram(provider);
-  const mxeAcc = await arciumProgram.account.mxeAccount.fetch(mxeAccountAddr);
-  const lutAddress = getLookupTableAddress(program.programId, mxeAcc.lutOffsetSlot);
+    const second = await program.account.vestedSnapshot.fetch(snapshotPda);
+    expect(second.calculatedAt.toNumber()).to.be.at.least(first.calculatedAt.toNumber());
+    expect(Buffer.from(second.encryptedVestedAmount)).to.not.deep.equal(
+      Buffer.from(first.encryptedVestedAmount),
+    );
 
-  // Call the appropriate init method based on circuit name
-  let sig: string;
-  if (circuitName === "init_position") {
-    sig = await program.methods
-      .initInitPositionCompDef()
+    const [vested, claimable] = cipher.decrypt(
+      [second.encryptedVestedAmount, second.encryptedClaimableAmount],
+      Uint8Array.from(second.nonce.toArrayLike(Buffer, "le", 16)),
+    );
+    expect(vested).to.equal(totalAmount);
+    expect(claimable).to.equal(totalAmount);
+  });
+
+  it("Rejects a top-up below the position's current total", async () => {
+    await initCompDef(program, admin, "top_up_position");
+    const before = await program.account.vestingPosition.fetch(positionPda);
+
+    // The stored total is 1000; a smaller new total is rejected
+    const rejected = awaitEvent(program, "positionTopUpRejected");
+    await queueTopUp(BigInt(500_000_000));
+
+    const event = await rejected;
+    expect(event.position.toString()).to.equal(positionPda.toString());
+
+    const after = await program.account.vestingPosition.fetch(positionPda);
+    expect(Buffer.from(after.encryptedTotalAmount)).to.deep.equal(
+      Buffer.from(before.encryptedTotalAmount),
+    );
+  });
+
+  it("Tops up a position with a larger total", async () => {
+    const toppedUp = awaitEvent(program, "positionToppedUp");
+    await queueTopUp(BigInt(2000_000_000));
+
+    const event = await toppedUp;
+    expect(event.position.toString()).to.equal(positionPda.toString());
+
+    // The stored amounts are re-encrypted for the position's key: the new
+    // total, with the claimed amount carried over
+    const after = await program.account.vestingPosition.fetch(positionPda);
+    const [total, claimed] = cipher.decrypt(
+      [after.encryptedTotalAmount, after.encryptedClaimedAmount],
+      Uint8Array.from(after.nonce.toArrayLike(Buffer, "le", 16)),
+    );
+    expect(total).to.equal(BigInt(2000_000_000));
+    expect(claimed).to.equal(BigInt(0));
+  });
+
+  it("Creates a schedule and its first position atomically", async () => {
+    const orgAccount = await program.account.organization.fetch(organizationPda);
+    const [newSchedulePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vesting_schedule"),
+        organizationPda.toBuffer(),
+        orgAccount.scheduleCount.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId,
+    );
+    const [newPositionPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vesting_position"),
+        organizationPda.toBuffer(),
+        orgAccount.positionCount.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId,
+    );
+
+    const nonce = randomBytes(16);
+    const ciphertext = cipher.encrypt([BigInt(500_000_000)], nonce);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+
+    const [signPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ArciumSignerAccount")],
+      program.programId,
+    );
+
+    await program.methods
+      .createScheduleWithPosition(
+        computationOffset,
+        new anchor.BN(0),
+        new anchor.BN(365 * 24 * 60 * 60),
+        new anchor.BN(24 * 60 * 60),
+        1, // vesting_mode: linear
+        tokenMint,
+        Array.from(beneficiaryCommitment),
+        Array.from(ciphertext[0]),
+        Array.from(publicKey),
+        new anchor.BN(deserializeLE(nonce).toString()),
+        0, // sig_scheme: Ed25519
+        Array(32).fill(0),
+      )
       .accountsPartial({
-        compDefAccount: compDefPDA,
-        payer: owner.publicKey,
-        mxeAccount: mxeAccountAddr,
-        addressLookupTable: lutAddress,
-        lutProgram: AddressLookupTableProgram.programId,
+        payer: admin.publicKey,
+        admin: admin.publicKey,
+        organization: organizationPda,
+        schedule: newSchedulePda,
+        position: newPositionPda,
+        signPdaAccount: signPda,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset,
+        ),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_position")).readUInt32LE(),
+        ),
+        clusterAccount,
+        poolAccount: getFeePoolAccAddress(),
+        clockAccount: getClockAccAddress(),
+        systemProgram: anchor.web3.SystemProgram.programId,
+        arciumProgram: getArciumProgramId(),
       })
-      .signers([owner])
+      .preInstructions([
+        ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+      ])
+      .signers([admin])
       .rpc({ commitment: "confirmed" });
-  } else if (circuitName === "calculate_vested") {
-    sig = await program.methods
-      .initCalculateVestedCompDef()
+
+    const schedule = await program.account.vestingSchedule.fetch(newSchedulePda);
+    expect(schedule.organization.toString()).to.equal(organizationPda.toString());
+    expect(schedule.positionCount.toNumber()).to.equal(1);
+    expect(schedule.vestingMode).to.equal(1);
+    expect(schedule.tokenMint.toString()).to.equal(tokenMint.toString());
+
+    const position = await program.account.vestingPosition.fetch(newPositionPda);
+    expect(position.schedule.toString()).to.equal(newSchedulePda.toString());
+    secondPositionPda = newPositionPda;
+    expect(position.state).to.deep.equal({ created: {} });
+
+    const updatedOrg = await program.account.organization.fetch(organizationPda);
+    expect(updatedOrg.scheduleCount.toNumber()).to.equal(orgAccount.scheduleCount.toNumber() + 1);
+    expect(updatedOrg.positionCount.toNumber()).to.equal(orgAccount.positionCount.toNumber() + 1);
+  });
+
+  it("Creates five positions in a single batch transaction", async () => {
+    const orgBefore = await program.account.organization.fetch(organizationPda);
+    const firstId = orgBefore.positionCount.toNumber();
+    const indexBefore = await program.account.positionIndex.fetch(positionIndexPda());
+
+    const params = [];
+    const positionPdas: PublicKey[] = [];
+    for (let i = 0; i < 5; i++) {
+      const nonce = randomBytes(16);
+      const ciphertext = cipher.encrypt([BigInt((i + 1) * 100_000_000)], nonce);
+      params.push({
+        beneficiaryCommitment: Array.from(
+          createHash("sha256").update(`batch-employee-${i}`).digest(),
+        ),
+        encryptedTotalAmount: Array.from(ciphertext[0]),
+        pubkey: Array.from(publicKey),
+        nonce: new anchor.BN(deserializeLE(nonce).toString()),
+        metadataCommitment: Array(32).fill(0),
+      });
+      positionPdas.push(
+        PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("vesting_position"),
+            organizationPda.toBuffer(),
+            new anchor.BN(firstId + i).toArrayLike(Buffer, "le", 8),
+          ],
+          program.programId,
+        )[0],
+      );
+    }
+
+    await program.methods
+      .batchCreateVestingPositions(params)
       .accountsPartial({
-        compDefAccount: compDefPDA,
-        payer: owner.publicKey,
-        mxeAccount: mxeAccountAddr,
-        addressLookupTable: lutAddress,
-        lutProgram: AddressLookupTableProgram.programId,
+        payer: admin.publicKey,
+        admin: admin.publicKey,
+        organization: organizationPda,
+        schedule: schedulePda,
+        systemProgram: anchor.web3.SystemProgram.programId,
       })
-      .signers([owner])
+      .remainingAccounts(
+        positionPdas.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })),
+      )
+      .preInstructions([
+        ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+      ])
+      .signers([admin])
       .rpc({ commitment: "confirmed" });
-  } else if (circuitName === "process_claim") {
-    sig = await program.methods
-      .initProcessClaimCompDef()
+
+    const orgAfter = await program.account.organization.fetch(organizationPda);
+    expect(orgAfter.positionCount.toNumber()).to.equal(firstId + 5);
+
+    const indexAfter = await program.account.positionIndex.fetch(positionIndexPda());
+    expect(indexAfter.totalPositions.toNumber()).to.equal(
+      indexBefore.totalPositions.toNumber() + 5,
+    );
+
+    for (let i = 0; i < 5; i++) {
+      const position = await program.account.vestingPosition.fetch(positionPdas[i]);
+      expect(position.positionId.toNumber()).to.equal(firstId + i);
+      expect(position.schedule.toString()).to.equal(schedulePda.toString());
+      expect(position.state).to.deep.equal({ created: {} });
+    }
+
+    // Queue init_position for the first batched position
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const [signPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ArciumSignerAccount")],
+      program.programId,
+    );
+    await program.methods
+      .queueInitPosition(computationOffset)
       .accountsPartial({
-        compDefAccount: compDefPDA,
-        payer: owner.publicKey,
-        mxeAccount: mxeAccountAddr,
-        addressLookupTable: lutAddress,
-        lutProgram: AddressLookupTableProgram.programId,
+        payer: admin.publicKey,
+        organization: organizationPda,
+        position: positionPdas[0],
+        signPdaAccount: signPda,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset,
+        ),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_position")).readUInt32LE(),
+        ),
+        clusterAccount,
+        poolAccount: getFeePoolAccAddress(),
+        clockAccount: getClockAccAddress(),
+        systemProgram: anchor.web3.SystemProgram.programId,
+        arciumProgram: getArciumProgramId(),
       })
-      .signers([owner])
+      .preInstructions([
+        ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+      ])
+      .signers([admin])
       .rpc({ commitment: "confirmed" });
-  } else {
-    throw new Error(`Unknown circuit name: ${circuitName}`);
-  }
 
-  console.log(`Init ${circuitName} computation definition tx:`, sig);
+    await waitForAccountState(
+      provider as anchor.AnchorProvider,
+      program,
+      positionPdas[0],
+      "vestingPosition",
+      (account: any) => "initialized" in account.state,
+      120000,
+    );
+  });
 
-  // For OffChain circuit source, no upload or finalization needed
-  // The comp def is complete with the URL and hash - MPC nodes fetch directly
-  console.log(`Using OffChain circuit source - comp def ready (no finalization needed)`);
+  it("Indexes every created position in order", async () => {
+    const org = await program.account.organization.fetch(organizationPda);
+    const index = await program.account.positionIndex.fetch(positionIndexPda());
+    const count = org.positionCount.toNumber();
+    expect(index.totalPositions.toNumber()).to.equal(count);
+    expect(index.organization.toString()).to.equal(organizationPda.toString());
+
+    // Fewer positions than the index capacity, so slots hold them by position_id
+    for (let id = 0; id < count; id++) {
+      const [expected] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("vesting_position"),
+          organizationPda.toBuffer(),
+          new anchor.BN(id).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId,
+      );
+      expect(index.positions[id].toString()).to.equal(expected.toString());
+    }
+  });
 
-  return sig;
-}
+  it("Reports the cliff status on both sides of the boundary", async () => {
+    const cliffStatus = async (position: PublicKey) => {
+      const { schedule } = await program.account.vestingPosition.fetch(position);
+      const { events } = await program.methods
+        .checkCliff()
+        .accounts({ position, schedule })
+        .simulate();
+      const status = events.find((e: any) => e.name === "cliffStatus");
+      expect(status, "CliffStatus event").to.not.be.undefined;
+      return status.data;
+    };
 
-async function getMXEPublicKeyWithRetry(
-  provider: anchor.AnchorProvider,
-  programId: PublicKey,
-  maxRetries: number = 20,
-  retryDelayMs: number = 500,
-): Promise<Uint8Array> {
-  for (let attempt = 1; attempt <= maxRetries; attempt++) {
-    try {
-      const mxePublicKey = await getMXEPublicKey(provider, programId);
-      if (mxePublicKey) {
-        return mxePublicKey;
-      }
-    } catch (error) {
-      console.log(`Attempt ${attempt} failed to fetch MXE public key:`, error);
-    }
+    // First position: 30-day cliff starting just now
+    const before = await cliffStatus(positionPda);
+    expect(before.cliffReached).to.be.false;
+    expect(before.secondsUntilCliff.toNumber()).to.be.greaterThan(0);
+    expect(before.secondsUntilCliff.toNumber()).to.be.at.most(30 * 24 * 60 * 60);
 
-    if (attempt < maxRetries) {
-      console.log(
-        `Retrying in ${retryDelayMs}ms... (attempt ${attempt}/${maxRetries})`,
-      );
-      await new Promise((resolve) => setTimeout(resolve, retryDelayMs));
-    }
-  }
+    // Schedule-with-position grant has no cliff
+    const after = await cliffStatus(secondPositionPda);
+    expect(after.cliffReached).to.be.true;
+    expect(after.secondsUntilCliff.toNumber()).to.equal(0);
+  });
 
-  throw new Error(
-    `Failed to fetch MXE public key after ${maxRetries} attempts`,
-  );
+  it("Defers init of a lazy position until its first interaction", async () => {
+    await program.methods
+      .setLazyInit(true)
+      .accounts({ admin: admin.publicKey, organization: organizationPda })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    const org = await program.account.organization.fetch(organizationPda);
+    const [lazyPositionPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vesting_position"),
+        organizationPda.toBuffer(),
+        org.positionCount.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId,
+    );
+    const [signPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ArciumSignerAccount")],
+      program.programId,
+    );
+    const arciumAccounts = (computationOffset: anchor.BN, circuitName: string) => ({
+      signPdaAccount: signPda,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      computationAccount: getComputationAccAddress(
+        arciumEnv.arciumClusterOffset,
+        computationOffset,
+      ),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuitName)).readUInt32LE(),
+      ),
+      clusterAccount,
+      poolAccount: getFeePoolAccAddress(),
+      clockAccount: getClockAccAddress(),
+      systemProgram: anchor.web3.SystemProgram.programId,
+      arciumProgram: getArciumProgramId(),
+    });
+
+    // Creation stores the raw ciphertext without queuing init_position
+    const totalAmount = BigInt(300_000_000);
+    const createOffset = new anchor.BN(randomBytes(8), "hex");
+    const createNonce = randomBytes(16);
+    await program.methods
+      .createVestingPosition(
+        createOffset,
+        Array.from(Keypair.generate().publicKey.toBytes()),
+        Array.from(cipher.encrypt([totalAmount], createNonce)[0]),
+        Array.from(publicKey),
+        new anchor.BN(deserializeLE(createNonce).toString()),
+        0, // sig_scheme: Ed25519
+        Array(32).fill(0),
+      )
+      .accountsPartial({
+        payer: admin.publicKey,
+        admin: admin.publicKey,
+        organization: organizationPda,
+        schedule: schedulePda,
+        position: lazyPositionPda,
+        ...arciumAccounts(createOffset, "init_position"),
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    const lazyPosition = await program.account.vestingPosition.fetch(lazyPositionPda);
+    expect(lazyPosition.state).to.deep.equal({ created: {} });
+
+    // First interaction: queue the deferred init alongside calculate_vested_amount
+    const initOffset = new anchor.BN(randomBytes(8), "hex");
+    const calcOffset = new anchor.BN(randomBytes(8), "hex");
+    const calcNonce = randomBytes(16);
+    const calcCiphertext = cipher.encrypt([totalAmount, BigInt(0), BigInt(0)], calcNonce);
+
+    const initIx = await program.methods
+      .queueInitPosition(initOffset)
+      .accountsPartial({
+        payer: admin.publicKey,
+        organization: organizationPda,
+        position: lazyPositionPda,
+        ...arciumAccounts(initOffset, "init_position"),
+      })
+      .instruction();
+
+    await program.methods
+      .calculateVestedAmount(
+        calcOffset,
+        Array.from(calcCiphertext[0]),
+        Array.from(calcCiphertext[1]),
+        Array.from(calcCiphertext[2]),
+        Array.from(publicKey),
+        new anchor.BN(deserializeLE(calcNonce).toString()),
+      )
+      .accountsPartial({
+        payer: admin.publicKey,
+        organization: organizationPda,
+        schedule: schedulePda,
+        position: lazyPositionPda,
+        ...arciumAccounts(calcOffset, "calculate_vested"),
+      })
+      .preInstructions([
+        ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+        initIx,
+      ])
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    await waitForAccountState(
+      provider as anchor.AnchorProvider,
+      program,
+      lazyPositionPda,
+      "vestingPosition",
+      (account: any) => "initialized" in account.state,
+      120000,
+    );
+
+    // The other creators defer init too
+    const cosignedId = (await program.account.organization.fetch(organizationPda)).positionCount;
+    const [cosignedPositionPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vesting_position"),
+        organizationPda.toBuffer(),
+        cosignedId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId,
+    );
+    const cosigner = Keypair.generate();
+    const cosignedOffset = new anchor.BN(randomBytes(8), "hex");
+    const cosignedNonce = randomBytes(16);
+    await program.methods
+      .createVestingPositionCosigned(
+        cosignedOffset,
+        Array.from(cosigner.publicKey.toBytes()),
+        Array.from(publicKey),
+        new anchor.BN(deserializeLE(cosignedNonce).toString()),
+        Array.from(cipher.encrypt([totalAmount], cosignedNonce)[0]),
+        Array(32).fill(0),
+      )
+      .accountsPartial({
+        payer: (provider as anchor.AnchorProvider).wallet.publicKey,
+        admin: admin.publicKey,
+        beneficiary: cosigner.publicKey,
+        organization: organizationPda,
+        schedule: schedulePda,
+        position: cosignedPositionPda,
+        ...arciumAccounts(cosignedOffset, "init_position"),
+      })
+      .signers([admin, cosigner])
+      .rpc({ commitment: "confirmed" });
+    const cosignedPosition = await program.account.vestingPosition.fetch(cosignedPositionPda);
+    expect(cosignedPosition.state).to.deep.equal({ created: {} });
+
+    await program.methods
+      .setLazyInit(false)
+      .accounts({ admin: admin.publicKey, organization: organizationPda })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("Compares encrypted totals of two positions for an auditor", async () => {
+    await initCompDef(program, admin, "compare_totals");
+
+    // Auditor decrypts the equality result with their own x25519 key
+    const auditorPrivateKey = x25519.utils.randomSecretKey();
+    const auditorPublicKey = x25519.getPublicKey(auditorPrivateKey);
+    const auditorCipher = new RescueCipher(
+      x25519.getSharedSecret(auditorPrivateKey, mxePublicKey),
+    );
+
+    // Totals are read from the positions' stored ciphertexts
+    const beneficiary = Keypair.generate().publicKey;
+    const grantA = await createInitializedPosition(beneficiary, BigInt(1000_000_000));
+    const grantB = await createInitializedPosition(beneficiary, BigInt(1000_000_000));
+    const grantC = await createInitializedPosition(beneficiary, BigInt(500_000_000));
+
+    const equal = await compareTotals(grantA, grantB, auditorPublicKey, auditorCipher);
+    expect(equal).to.equal(BigInt(1));
+
+    const unequal = await compareTotals(grantA, grantC, auditorPublicKey, auditorCipher);
+    expect(unequal).to.equal(BigInt(0));
+  });
+
+  it("Merges two positions into one whose total is the sum", async () => {
+    await initCompDef(program, admin, "merge_positions");
+
+    const beneficiary = Keypair.generate();
+    const totalA = BigInt(100_000_000);
+    const totalB = BigInt(250_000_000);
+    const commitment = Array.from(createHash("sha256").update("merge-grant").digest());
+    const positionA = await createInitializedPosition(beneficiary.publicKey, totalA, commitment);
+    const positionB = await createInitializedPosition(beneficiary.publicKey, totalB, commitment);
+    const unrelated = await createInitializedPosition(beneficiary.publicKey, totalB);
+
+    const [signPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ArciumSignerAccount")],
+      program.programId,
+    );
+
+    // The circuit reads both positions' stored amounts; only the merged
+    // position's key and nonce are supplied
+    const merge = async (a: PublicKey, b: PublicKey): Promise<PublicKey> => {
+      const org = await program.account.organization.fetch(organizationPda);
+      const [mergedPositionPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("vesting_position"),
+          organizationPda.toBuffer(),
+          org.positionCount.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId,
+      );
+      const computationOffset = new anchor.BN(randomBytes(8), "hex");
+      const nonce = randomBytes(16);
+
+      await program.methods
+        .mergePositions(
+          computationOffset,
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString()),
+        )
+        .accountsPartial({
+          payer: admin.publicKey,
+          admin: admin.publicKey,
+          beneficiary: beneficiary.publicKey,
+          organization: organizationPda,
+          schedule: schedulePda,
+          positionA: a,
+          positionB: b,
+          mergedPosition: mergedPositionPda,
+          signPdaAccount: signPda,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            computationOffset,
+          ),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("merge_positions")).readUInt32LE(),
+          ),
+          clusterAccount,
+          poolAccount: getFeePoolAccAddress(),
+          clockAccount: getClockAccAddress(),
+          systemProgram: anchor.web3.SystemProgram.programId,
+          arciumProgram: getArciumProgramId(),
+        })
+        .preInstructions([
+          ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+        ])
+        .signers([admin, beneficiary])
+        .rpc({ commitment: "confirmed" });
+      return mergedPositionPda;
+    };
+
+    // Grants under different agreements cannot be merged
+    try {
+      await merge(positionA, unrelated);
+      expect.fail("Should have thrown - metadata commitments differ");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("MetadataCommitmentMismatch");
+    }
+
+    const merged = awaitEvent(program, "positionsMerged");
+    const mergedPositionPda = await merge(positionA, positionB);
+
+    const event = await merged;
+    expect(event.mergedPosition.toString()).to.equal(mergedPositionPda.toString());
+    const [mergedTotal, mergedClaimed] = cipher.decrypt(
+      [event.encryptedTotalAmount, event.encryptedClaimedAmount],
+      Uint8Array.from(event.nonce),
+    );
+    expect(mergedTotal).to.equal(totalA + totalB);
+    expect(mergedClaimed).to.equal(BigInt(0));
+
+    const mergedPosition = await program.account.vestingPosition.fetch(mergedPositionPda);
+    expect(mergedPosition.state).to.deep.equal({ initialized: {} });
+    expect(Buffer.from(mergedPosition.beneficiaryCommitment)).to.deep.equal(
+      beneficiary.publicKey.toBuffer(),
+    );
+    expect(Array.from(mergedPosition.metadataCommitment)).to.deep.equal(commitment);
+    expect(mergedPosition.openClaimCount.toNumber()).to.equal(0);
+    for (const original of [positionA, positionB]) {
+      const account = await program.account.vestingPosition.fetch(original);
+      expect(account.state).to.deep.equal({ deactivated: {} });
+    }
+  });
+
+  it("Re-encrypts a position's amounts under a new key", async () => {
+    await initCompDef(program, admin, "reencrypt_position");
+
+    // Key the amounts are re-wrapped for, e.g. after an MXE key rotation
+    const newPrivateKey = x25519.utils.randomSecretKey();
+    const newPublicKey = x25519.getPublicKey(newPrivateKey);
+    const newCipher = new RescueCipher(
+      x25519.getSharedSecret(newPrivateKey, mxePublicKey),
+    );
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const nonce = randomBytes(16);
+    const [signPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ArciumSignerAccount")],
+      program.programId,
+    );
+
+    const reencrypted = awaitEvent(program, "positionReencrypted");
+    await program.methods
+      .reencryptPosition(
+        computationOffset,
+        Array.from(newPublicKey),
+        new anchor.BN(deserializeLE(nonce).toString()),
+      )
+      .accountsPartial({
+        payer: admin.publicKey,
+        admin: admin.publicKey,
+        organization: organizationPda,
+        position: positionPda,
+        signPdaAccount: signPda,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset,
+        ),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reencrypt_position")).readUInt32LE(),
+        ),
+        clusterAccount,
+        poolAccount: getFeePoolAccAddress(),
+        clockAccount: getClockAccAddress(),
+        systemProgram: anchor.web3.SystemProgram.programId,
+        arciumProgram: getArciumProgramId(),
+      })
+      .preInstructions([
+        ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+      ])
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    const event = await reencrypted;
+    expect(event.position.toString()).to.equal(positionPda.toString());
+
+    // Topped up to 2000 tokens earlier, nothing claimed
+    const position = await program.account.vestingPosition.fetch(positionPda);
+    expect(Buffer.from(position.encryptionPubkey)).to.deep.equal(Buffer.from(newPublicKey));
+    const [total, claimed] = newCipher.decrypt(
+      [position.encryptedTotalAmount, position.encryptedClaimedAmount],
+      Uint8Array.from(event.nonce),
+    );
+    expect(total).to.equal(BigInt(2000_000_000));
+    expect(claimed).to.equal(BigInt(0));
+  });
+
+  it("Creates a position under the organization's default schedule", async () => {
+    const createDefault = async () => {
+      const org = await program.account.organization.fetch(organizationPda);
+      const [pda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("vesting_position"),
+          organizationPda.toBuffer(),
+          org.positionCount.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId,
+      );
+      const nonce = randomBytes(16);
+      const ciphertext = cipher.encrypt([BigInt(10_000_000)], nonce);
+      const computationOffset = new anchor.BN(randomBytes(8), "hex");
+      const [signPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("ArciumSignerAccount")],
+        program.programId,
+      );
+
+      await program.methods
+        .createVestingPositionDefault(
+          computationOffset,
+          Array.from(createHash("sha256").update("default-schedule-employee").digest()),
+          Array.from(ciphertext[0]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString()),
+          0, // sig_scheme: Ed25519
+          Array(32).fill(0),
+        )
+        .accountsPartial({
+          payer: admin.publicKey,
+          admin: admin.publicKey,
+          organization: organizationPda,
+          schedule: schedulePda,
+          position: pda,
+          signPdaAccount: signPda,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            computationOffset,
+          ),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_position")).readUInt32LE(),
+          ),
+          clusterAccount,
+          poolAccount: getFeePoolAccAddress(),
+          clockAccount: getClockAccAddress(),
+          systemProgram: anchor.web3.SystemProgram.programId,
+          arciumProgram: getArciumProgramId(),
+        })
+        .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 })])
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+      return pda;
+    };
+
+    try {
+      await createDefault();
+      expect.fail("Expected NoDefaultSchedule");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("NoDefaultSchedule");
+    }
+
+    await program.methods
+      .setDefaultSchedule(schedulePda)
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+        schedule: schedulePda,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    const org = await program.account.organization.fetch(organizationPda);
+    expect(org.defaultSchedule.toString()).to.equal(schedulePda.toString());
+
+    const position = await program.account.vestingPosition.fetch(await createDefault());
+    expect(position.schedule.toString()).to.equal(schedulePda.toString());
+  });
+
+  it("Stores the metadata commitment on the position and in the creation event", async () => {
+    const commitment = Array.from(
+      createHash("sha256").update("grant-agreement-v1").digest(),
+    );
+
+    const created = awaitEvent(program, "vestingPositionCreated");
+    const positionPda = await createInitializedPosition(
+      Keypair.generate().publicKey,
+      BigInt(10_000_000),
+      commitment,
+    );
+    const event = await created;
+
+    expect(event.position.toString()).to.equal(positionPda.toString());
+    expect(Array.from(event.metadataCommitment)).to.deep.equal(commitment);
+
+    const position = await program.account.vestingPosition.fetch(positionPda);
+    expect(Array.from(position.metadataCommitment)).to.deep.equal(commitment);
+  });
+
+  it("Requires both the admin and the beneficiary to sign a co-signed position", async () => {
+    const beneficiary = Keypair.generate();
+    const createCosigned = async (commitment: PublicKey, signers: Keypair[]) => {
+      const org = await program.account.organization.fetch(organizationPda);
+      const [pda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("vesting_position"),
+          organizationPda.toBuffer(),
+          org.positionCount.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId,
+      );
+      const nonce = randomBytes(16);
+      const ciphertext = cipher.encrypt([BigInt(10_000_000)], nonce);
+      const computationOffset = new anchor.BN(randomBytes(8), "hex");
+      const [signPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("ArciumSignerAccount")],
+        program.programId,
+      );
+
+      await program.methods
+        .createVestingPositionCosigned(
+          computationOffset,
+          Array.from(commitment.toBytes()),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString()),
+          Array.from(ciphertext[0]),
+          Array(32).fill(0),
+        )
+        .accountsPartial({
+          payer: (provider as anchor.AnchorProvider).wallet.publicKey,
+          admin: admin.publicKey,
+          beneficiary: beneficiary.publicKey,
+          organization: organizationPda,
+          schedule: schedulePda,
+          position: pda,
+          signPdaAccount: signPda,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            computationOffset,
+          ),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_position")).readUInt32LE(),
+          ),
+          clusterAccount,
+          poolAccount: getFeePoolAccAddress(),
+          clockAccount: getClockAccAddress(),
+          systemProgram: anchor.web3.SystemProgram.programId,
+          arciumProgram: getArciumProgramId(),
+        })
+        .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 })])
+        .signers(signers)
+        .rpc({ commitment: "confirmed" });
+      return pda;
+    };
+
+    for (const signers of [[admin], [beneficiary]]) {
+      try {
+        await createCosigned(beneficiary.publicKey, signers);
+        expect.fail("Expected a missing signature");
+      } catch (err: any) {
+        expect(err.message || err.toString()).to.include("Signature verification failed");
+      }
+    }
+
+    // The commitment must be the co-signing beneficiary's own key
+    try {
+      await createCosigned(Keypair.generate().publicKey, [admin, beneficiary]);
+      expect.fail("Expected SignerMismatch");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("SignerMismatch");
+    }
+
+    const cosigned = awaitEvent(program, "positionCosigned");
+    const positionPda = await createCosigned(beneficiary.publicKey, [admin, beneficiary]);
+    const event = await cosigned;
+    expect(event.position.toString()).to.equal(positionPda.toString());
+    expect(event.admin.toString()).to.equal(admin.publicKey.toString());
+    expect(event.beneficiary.toString()).to.equal(beneficiary.publicKey.toString());
+
+    const position = await program.account.vestingPosition.fetch(positionPda);
+    expect(Buffer.from(position.beneficiaryCommitment)).to.deep.equal(beneficiary.publicKey.toBuffer());
+  });
+
+  it("Reports the revocation status of active and revoked positions", async () => {
+    const revocationStatus = async (position: PublicKey) => {
+      const { events } = await program.methods
+        .isPositionRevoked()
+        .accounts({ position })
+        .simulate();
+      const status = events.find((e: any) => e.name === "positionRevocationStatus");
+      expect(status, "PositionRevocationStatus event").to.not.be.undefined;
+      return status.data;
+    };
+
+    const positionPda = await createInitializedPosition(
+      Keypair.generate().publicKey,
+      BigInt(10_000_000),
+    );
+
+    const active = await revocationStatus(positionPda);
+    expect(active.isRevoked).to.be.false;
+    expect(active.revokedAt.toNumber()).to.equal(0);
+
+    const revokedEvent = awaitEvent(program, "vestingPositionRevoked");
+    await program.methods
+      .revokePosition()
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+        position: positionPda,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    const revoked = await revocationStatus(positionPda);
+    const position = await program.account.vestingPosition.fetch(positionPda);
+    expect(revoked.isRevoked).to.be.true;
+    expect(revoked.revokedAt.toNumber()).to.equal(position.revokedAt.toNumber());
+    expect(revoked.revokedAt.toNumber()).to.be.greaterThan(0);
+
+    const event = await revokedEvent;
+    expect(event.organization.toBase58()).to.equal(organizationPda.toBase58());
+    expect(event.position.toBase58()).to.equal(positionPda.toBase58());
+    expect(event.revokedAt.toNumber()).to.equal(position.revokedAt.toNumber());
+  });
+
+  it("Calculates the vested amount of a revealed position without MPC", async () => {
+    const TOTAL = 10_000_000;
+    const positionPda = await createInitializedPosition(
+      Keypair.generate().publicKey,
+      BigInt(TOTAL),
+    );
+    const { schedule } = await program.account.vestingPosition.fetch(positionPda);
+    const calculatePlaintext = async () => {
+      const { events } = await program.methods
+        .calculateVestedPlaintext()
+        .accounts({ position: positionPda, schedule })
+        .simulate();
+      const calculated = events.find((e: any) => e.name === "plaintextVestedCalculated");
+      expect(calculated, "PlaintextVestedCalculated event").to.not.be.undefined;
+      return calculated.data;
+    };
+
+    // Encrypted positions cannot use the plaintext path
+    const before = await program.account.vestingPosition.fetch(positionPda);
+    expect(before.privacyMode).to.be.true;
+    expect(before.plaintextTotalAmount).to.be.null;
+    try {
+      await calculatePlaintext();
+      expect.fail("Expected PositionIsPrivate");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("PositionIsPrivate");
+    }
+
+    await program.methods
+      .revealPositionTotal(new anchor.BN(TOTAL))
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+        position: positionPda,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    const after = await program.account.vestingPosition.fetch(positionPda);
+    expect(after.privacyMode).to.be.false;
+    expect(after.plaintextTotalAmount.toNumber()).to.equal(TOTAL);
+
+    const calculated = await calculatePlaintext();
+    expect(calculated.totalAmount.toNumber()).to.equal(TOTAL);
+    expect(calculated.vestedAmount.toNumber()).to.equal(
+      Math.floor((TOTAL * calculated.vestingNumerator.toNumber()) / 1_000_000),
+    );
+  });
+
+  it("Keeps vesting accruing on real elapsed time while a position is paused", async () => {
+    const TOTAL = 10_000_000;
+    const positionPda = await createInitializedPosition(
+      Keypair.generate().publicKey,
+      BigInt(TOTAL),
+    );
+    const { schedule, startTimestamp } = await program.account.vestingPosition.fetch(positionPda);
+    const setPositionPaused = (paused: boolean) =>
+      program.methods
+        .setPositionPaused(paused)
+        .accounts({
+          admin: admin.publicKey,
+          organization: organizationPda,
+          position: positionPda,
+        })
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+    const vestingNumerator = async () => {
+      const { events } = await program.methods
+        .calculateVestedPlaintext()
+        .accounts({ position: positionPda, schedule })
+        .simulate();
+      const calculated = events.find((e: any) => e.name === "plaintextVestedCalculated");
+      expect(calculated, "PlaintextVestedCalculated event").to.not.be.undefined;
+      return calculated.data.vestingNumerator.toNumber();
+    };
+
+    await program.methods
+      .revealPositionTotal(new anchor.BN(TOTAL))
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+        position: positionPda,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    await setPositionPaused(true);
+    const numeratorWhilePaused = await vestingNumerator();
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    await setPositionPaused(false);
+
+    // The paused interval counts toward vesting: nothing is shifted or subtracted
+    const resumed = await program.account.vestingPosition.fetch(positionPda);
+    expect(resumed.isPaused).to.be.false;
+    expect(resumed.startTimestamp.toNumber()).to.equal(startTimestamp.toNumber());
+    expect(await vestingNumerator()).to.be.at.least(numeratorWhilePaused);
+  });
+
+  it("Freezes vesting accrual during pauses when the organization opts in", async () => {
+    const setFreezeVestingOnPause = (freeze: boolean) =>
+      program.methods
+        .setFreezeVestingOnPause(freeze)
+        .accounts({
+          admin: admin.publicKey,
+          organization: organizationPda,
+        })
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+    await setFreezeVestingOnPause(true);
+
+    const TOTAL = 10_000_000;
+    const positionPda = await createInitializedPosition(
+      Keypair.generate().publicKey,
+      BigInt(TOTAL),
+    );
+    const { schedule, startTimestamp } = await program.account.vestingPosition.fetch(positionPda);
+    const setPositionPaused = (paused: boolean) =>
+      program.methods
+        .setPositionPaused(paused)
+        .accounts({
+          admin: admin.publicKey,
+          organization: organizationPda,
+          position: positionPda,
+        })
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+    const vestingNumerator = async () => {
+      const { events } = await program.methods
+        .calculateVestedPlaintext()
+        .accounts({ position: positionPda, schedule })
+        .simulate();
+      const calculated = events.find((e: any) => e.name === "plaintextVestedCalculated");
+      expect(calculated, "PlaintextVestedCalculated event").to.not.be.undefined;
+      return calculated.data.vestingNumerator.toNumber();
+    };
+
+    await program.methods
+      .revealPositionTotal(new anchor.BN(TOTAL))
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+        position: positionPda,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    const numeratorBeforePause = await vestingNumerator();
+
+    await setPositionPaused(true);
+    const paused = await program.account.vestingPosition.fetch(positionPda);
+    expect(paused.pauseFreezesVesting).to.be.true;
+    const numeratorWhilePaused = await vestingNumerator();
+    expect(numeratorWhilePaused).to.be.at.least(numeratorBeforePause);
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    // Nothing accrues during the pause
+    expect(await vestingNumerator()).to.equal(numeratorWhilePaused);
+
+    const resumedEvent = awaitEvent(program, "positionResumed");
+    await setPositionPaused(false);
+    const event = await resumedEvent;
+    expect(event.position.toBase58()).to.equal(positionPda.toBase58());
+    expect(event.totalPausedSeconds.toNumber()).to.be.at.least(2);
+
+    // The paused interval is excluded from vesting time; start_timestamp is untouched
+    const resumed = await program.account.vestingPosition.fetch(positionPda);
+    expect(resumed.isPaused).to.be.false;
+    expect(resumed.pauseFreezesVesting).to.be.false;
+    expect(resumed.pausedAt.toNumber()).to.equal(0);
+    expect(resumed.totalPausedSeconds.toNumber()).to.equal(event.totalPausedSeconds.toNumber());
+    expect(resumed.startTimestamp.toNumber()).to.equal(startTimestamp.toNumber());
+    expect(await vestingNumerator()).to.be.at.least(numeratorWhilePaused);
+
+    // Back to the default for the remaining tests
+    await setFreezeVestingOnPause(false);
+  });
+
+  async function createInitializedPosition(
+    beneficiary: PublicKey,
+    totalAmount: bigint,
+    metadataCommitment: number[] = Array(32).fill(0),
+  ): Promise<PublicKey> {
+    const org = await program.account.organization.fetch(organizationPda);
+    const [pda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vesting_position"),
+        organizationPda.toBuffer(),
+        org.positionCount.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId,
+    );
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const nonce = randomBytes(16);
+    const ciphertext = cipher.encrypt([totalAmount], nonce);
+    const [signPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ArciumSignerAccount")],
+      program.programId,
+    );
+
+    await program.methods
+      .createVestingPosition(
+        computationOffset,
+        Array.from(beneficiary.toBytes()),
+        Array.from(ciphertext[0]),
+        Array.from(publicKey),
+        new anchor.BN(deserializeLE(nonce).toString()),
+        0, // sig_scheme: Ed25519
+        metadataCommitment,
+      )
+      .accountsPartial({
+        payer: admin.publicKey,
+        admin: admin.publicKey,
+        organization: organizationPda,
+        schedule: schedulePda,
+        position: pda,
+        signPdaAccount: signPda,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset,
+        ),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_position")).readUInt32LE(),
+        ),
+        clusterAccount,
+        poolAccount: getFeePoolAccAddress(),
+        clockAccount: getClockAccAddress(),
+        systemProgram: anchor.web3.SystemProgram.programId,
+        arciumProgram: getArciumProgramId(),
+      })
+      .preInstructions([
+        ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+      ])
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    await waitForAccountState(
+      provider as anchor.AnchorProvider,
+      program,
+      pda,
+      "vestingPosition",
+      (account: any) => "initialized" in account.state,
+      120000,
+    );
+    return pda;
+  }
+
+  async function compareTotals(
+    positionA: PublicKey,
+    positionB: PublicKey,
+    auditorPublicKey: Uint8Array,
+    auditorCipher: RescueCipher,
+  ): Promise<bigint> {
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const auditorNonce = randomBytes(16);
+
+    const [signPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ArciumSignerAccount")],
+      program.programId,
+    );
+
+    const compared = awaitEvent(program, "totalsCompared");
+    await program.methods
+      .compareTotals(
+        computationOffset,
+        Array.from(auditorPublicKey),
+        new anchor.BN(deserializeLE(auditorNonce).toString()),
+      )
+      .accountsPartial({
+        payer: admin.publicKey,
+        admin: admin.publicKey,
+        organization: organizationPda,
+        positionA,
+        positionB,
+        signPdaAccount: signPda,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset,
+        ),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("compare_totals")).readUInt32LE(),
+        ),
+        clusterAccount,
+        poolAccount: getFeePoolAccAddress(),
+        clockAccount: getClockAccAddress(),
+        systemProgram: anchor.web3.SystemProgram.programId,
+        arciumProgram: getArciumProgramId(),
+      })
+      .preInstructions([
+        ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+      ])
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    const event = await compared;
+    expect(event.positionA.toString()).to.equal(positionA.toString());
+    return auditorCipher.decrypt([event.encryptedIsEqual], Uint8Array.from(event.nonce))[0];
+  }
+
+  async function queueTopUp(newTotalAmount: bigint): Promise<void> {
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const nonce = randomBytes(16);
+    const nonceAsBN = new anchor.BN(deserializeLE(nonce).toString());
+
+    const [signPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ArciumSignerAccount")],
+      program.programId,
+    );
+
+    await program.methods
+      .topUpPosition(
+        computationOffset,
+        Array.from(cipher.encrypt([newTotalAmount], nonce)[0]),
+        Array.from(publicKey),
+        nonceAsBN,
+      )
+      .accountsPartial({
+        payer: admin.publicKey,
+        admin: admin.publicKey,
+        organization: organizationPda,
+        position: positionPda,
+        signPdaAccount: signPda,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset,
+        ),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("top_up_position")).readUInt32LE(),
+        ),
+        clusterAccount,
+        poolAccount: getFeePoolAccAddress(),
+        clockAccount: getClockAccAddress(),
+        systemProgram: anchor.web3.SystemProgram.programId,
+        arciumProgram: getArciumProgramId(),
+      })
+      .preInstructions([
+        ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+      ])
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+  }
+});
+
+// Helper functions
+
+async function initCompDef(
+  program: Program<Contract>,
+  owner: Keypair,
+  circuitName: string,
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed(
+    "ComputationDefinitionAccount",
+  );
+  const offset = getCompDefAccOffset(circuitName);
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgramId(),
+  )[0];
+
+  console.log(`Comp def PDA for ${circuitName}:`, compDefPDA.toString());
+
+  // Check if already initialized
+  const accountInfo = await provider.connection.getAccountInfo(compDefPDA);
+  if (accountInfo !== null) {
+    // For OffChain circuit source, comp def is ready as soon as it's initialized
+    // No finalization needed - MPC nodes fetch circuit directly from URL
+    console.log(`Comp def for ${circuitName} already exists - ready for use`);
+    return "already_initialized";
+  }
+
+  // Get LUT address from MXE account (v0.7.0 requirement)
+  const mxeAccountAddr = getMXEAccAddress(program.programId);
+  const arciumProgram = getArciumProgram(provider);
+  const mxeAcc = await arciumProgram.account.mxeAccount.fetch(mxeAccountAddr);
+  const lutAddress = getLookupTableAddress(program.programId, mxeAcc.lutOffsetSlot);
+
+  // Call the appropriate init method based on circuit name
+  let sig: string;
+  if (circuitName === "init_position") {
+    sig = await program.methods
+      .initInitPositionCompDef()
+      .accountsPartial({
+        compDefAccount: compDefPDA,
+        payer: owner.publicKey,
+        mxeAccount: mxeAccountAddr,
+        addressLookupTable: lutAddress,
+        lutProgram: AddressLookupTableProgram.programId,
+      })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+  } else if (circuitName === "calculate_vested") {
+    sig = await program.methods
+      .initCalculateVestedCompDef()
+      .accountsPartial({
+        compDefAccount: compDefPDA,
+        payer: owner.publicKey,
+        mxeAccount: mxeAccountAddr,
+        addressLookupTable: lutAddress,
+        lutProgram: AddressLookupTableProgram.programId,
+      })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+  } else if (circuitName === "process_claim") {
+    sig = await program.methods
+      .initProcessClaimCompDef()
+      .accountsPartial({
+        compDefAccount: compDefPDA,
+        payer: owner.publicKey,
+        mxeAccount: mxeAccountAddr,
+        addressLookupTable: lutAddress,
+        lutProgram: AddressLookupTableProgram.programId,
+      })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+  } else if (circuitName === "top_up_position") {
+    sig = await program.methods
+      .initTopUpPositionCompDef()
+      .accountsPartial({
+        compDefAccount: compDefPDA,
+        payer: owner.publicKey,
+        mxeAccount: mxeAccountAddr,
+        addressLookupTable: lutAddress,
+        lutProgram: AddressLookupTableProgram.programId,
+      })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+  } else if (circuitName === "compare_totals") {
+    sig = await program.methods
+      .initCompareTotalsCompDef()
+      .accountsPartial({
+        compDefAccount: compDefPDA,
+        payer: owner.publicKey,
+        mxeAccount: mxeAccountAddr,
+        addressLookupTable: lutAddress,
+        lutProgram: AddressLookupTableProgram.programId,
+      })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+  } else if (circuitName === "merge_positions") {
+    sig = await program.methods
+      .initMergePositionsCompDef()
+      .accountsPartial({
+        compDefAccount: compDefPDA,
+        payer: owner.publicKey,
+        mxeAccount: mxeAccountAddr,
+        addressLookupTable: lutAddress,
+        lutProgram: AddressLookupTableProgram.programId,
+      })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+  } else if (circuitName === "reencrypt_position") {
+    sig = await program.methods
+      .initReencryptPositionCompDef()
+      .accountsPartial({
+        compDefAccount: compDefPDA,
+        payer: owner.publicKey,
+        mxeAccount: mxeAccountAddr,
+        addressLookupTable: lutAddress,
+        lutProgram: AddressLookupTableProgram.programId,
+      })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+  } else if (circuitName === "process_claims_batch") {
+    sig = await program.methods
+      .initProcessClaimsBatchCompDef()
+      .accountsPartial({
+        compDefAccount: compDefPDA,
+        payer: owner.publicKey,
+        mxeAccount: mxeAccountAddr,
+        addressLookupTable: lutAddress,
+        lutProgram: AddressLookupTableProgram.programId,
+      })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+  } else {
+    throw new Error(`Unknown circuit name: ${circuitName}`);
+  }
+
+  console.log(`Init ${circuitName} computation definition tx:`, sig);
+
+  // For OffChain circuit source, no upload or finalization needed
+  // The comp def is complete with the URL and hash - MPC nodes fetch directly
+  console.log(`Using OffChain circuit source - comp def ready (no finalization needed)`);
+
+  return sig;
+}
+
+async function getMXEPublicKeyWithRetry(
+  provider: anchor.AnchorProvider,
+  programId: PublicKey,
+  maxRetries: number = 20,
+  retryDelayMs: number = 500,
+): Promise<Uint8Array> {
+  for (let attempt = 1; attempt <= maxRetries; attempt++) {
+    try {
+      const mxePublicKey = await getMXEPublicKey(provider, programId);
+      if (mxePublicKey) {
+        return mxePublicKey;
+      }
+    } catch (error) {
+      console.log(`Attempt ${attempt} failed to fetch MXE public key:`, error);
+    }
+
+    if (attempt < maxRetries) {
+      console.log(
+        `Retrying in ${retryDelayMs}ms... (attempt ${attempt}/${maxRetries})`,
+      );
+      await new Promise((resolve) => setTimeout(resolve, retryDelayMs));
+    }
+  }
+
+  throw new Error(
+    `Failed to fetch MXE public key after ${maxRetries} attempts`,
+  );
+}
+
+// Resolve with the first emitted event of the given name
+function awaitEvent(
+  program: Program<Contract>,
+  eventName: string,
+  timeoutMs: number = 300000,
+): Promise<any> {
+  return new Promise((resolve, reject) => {
+    const timer = setTimeout(() => {
+      program.removeEventListener(listener);
+      reject(new Error(`Timeout waiting for ${eventName} event`));
+    }, timeoutMs);
+
+    const listener = program.addEventListener(eventName as any, (event: any) => {
+      clearTimeout(timer);
+      program.removeEventListener(listener);
+      resolve(event);
+    });
+  });
 }
 
 function readKpJson(path: string): Keypair {
@@ -0,0 +1,113 @@
+import * as anchor from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { randomBytes } from "crypto";
+import { bn254 } from "@noble/curves/bn254";
+
+// ============================================================
+// Synthetic Groth16 fixtures
+//
+// A verification key whose G2 points are all the generator makes the Groth16
+// equation e(A, B) = e(alpha, beta) * e(vk_x, gamma) * e(C, delta) hold for
+// B = G2 and A = alpha + vk_x + C, so tests can produce proofs the on-chain
+// verifier accepts without a trusted setup or a Noir prover.
+// ============================================================
+
+const G1 = bn254.G1.ProjectivePoint;
+
+/** G2 generator in EIP-197 encoding (imaginary part first) */
+export const G2_GENERATOR = Buffer.from(
+  "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2" +
+    "1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed" +
+    "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b" +
+    "12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+  "hex",
+);
+
+export interface SyntheticWithdrawalInputs {
+  stateRoot: number[];
+  epochId: anchor.BN;
+  nullifier: number[];
+  withdrawalCommitment: number[];
+}
+
+export interface SyntheticVk {
+  alpha: InstanceType<typeof G1>;
+  ic: InstanceType<typeof G1>[];
+  data: Buffer;
+}
+
+function g1Bytes(point: InstanceType<typeof G1>): Buffer {
+  const { x, y } = point.toAffine();
+  return Buffer.concat([bigintTo32(x), bigintTo32(y)]);
+}
+
+function bigintTo32(value: bigint): Buffer {
+  return Buffer.from(value.toString(16).padStart(64, "0"), "hex");
+}
+
+/** Random 31-byte scalar: always below the bn254 scalar field order and non-zero */
+export function randomScalarBytes(): number[] {
+  const bytes = Buffer.concat([Buffer.alloc(1), randomBytes(31)]);
+  bytes[31] |= 1;
+  return Array.from(bytes);
+}
+
+/** A synthetic VK for a circuit with `numInputs` public inputs */
+export function syntheticVk(numInputs: number): SyntheticVk {
+  const alpha = G1.BASE.multiply(3n);
+  // n public inputs -> n + 1 IC points
+  const ic = Array.from({ length: numInputs + 1 }, (_, i) => G1.BASE.multiply(BigInt(5 + 2 * i)));
+  const icLen = Buffer.alloc(4);
+  icLen.writeUInt32LE(ic.length);
+  const data = Buffer.concat([
+    g1Bytes(alpha),
+    G2_GENERATOR,
+    G2_GENERATOR,
+    G2_GENERATOR,
+    icLen,
+    ...ic.map(g1Bytes),
+  ]);
+  return { alpha, ic, data };
+}
+
+const BPF_LOADER_UPGRADEABLE_ID = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+
+/** ProgramData account of the program; only its upgrade authority may store VKs */
+export function programDataAddress(programId: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([programId.toBuffer()], BPF_LOADER_UPGRADEABLE_ID)[0];
+}
+
+export function syntheticWithdrawalVk(): SyntheticVk {
+  return syntheticVk(4);
+}
+
+export function syntheticWithdrawalInputs(): SyntheticWithdrawalInputs {
+  return {
+    stateRoot: randomScalarBytes(),
+    epochId: new anchor.BN(1),
+    nullifier: randomScalarBytes(),
+    withdrawalCommitment: randomScalarBytes(),
+  };
+}
+
+/** A proof the synthetic VK accepts for the given raw 32-byte public inputs */
+export function syntheticProofForScalars(vk: SyntheticVk, scalars: number[][]) {
+  const values = scalars.map((bytes) => BigInt("0x" + Buffer.from(bytes).toString("hex")));
+  const vkX = values.reduce((acc, s, i) => acc.add(vk.ic[i + 1].multiply(s)), vk.ic[0]);
+  const c = G1.BASE.multiply(19n);
+  const a = vk.alpha.add(vkX).add(c);
+  return {
+    a: Array.from(g1Bytes(a)),
+    b: Array.from(G2_GENERATOR),
+    c: Array.from(g1Bytes(c)),
+  };
+}
+
+export function syntheticProof(vk: SyntheticVk, inputs: SyntheticWithdrawalInputs) {
+  return syntheticProofForScalars(vk, [
+    inputs.stateRoot,
+    Array.from(bigintTo32(BigInt(inputs.epochId.toString()))),
+    inputs.nullifier,
+    inputs.withdrawalCommitment,
+  ]);
+}
@@ -0,0 +1,485 @@
+import "dotenv/config";
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  Keypair,
+  ComputeBudgetProgram,
+  Ed25519Program,
+  SystemProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  AddressLookupTableProgram,
+  LAMPORTS_PER_SOL,
+} from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  createMint,
+  createAccount,
+  mintTo,
+  getAccount,
+} from "@solana/spl-token";
+import { Contract } from "../target/types/contract";
+import { randomBytes, createHash } from "crypto";
+import {
+  getArciumEnv,
+  getCompDefAccOffset,
+  getArciumAccountBaseSeed,
+  getArciumProgramId,
+  RescueCipher,
+  deserializeLE,
+  getMXEPublicKey,
+  getMXEAccAddress,
+  getMempoolAccAddress,
+  getCompDefAccAddress,
+  getExecutingPoolAccAddress,
+  getComputationAccAddress,
+  getClusterAccAddress,
+  getFeePoolAccAddress,
+  getClockAccAddress,
+  getLookupTableAddress,
+  getArciumProgram,
+  x25519,
+} from "@arcium-hq/client";
+import * as fs from "fs";
+import * as os from "os";
+import { expect } from "chai";
+
+// One grant per token mint: its own schedule, vault, position and claim
+interface Grant {
+  tokenMint: PublicKey;
+  schedulePda: PublicKey;
+  vaultPda: PublicKey;
+  positionPda: PublicKey;
+  claimAuthPda: PublicKey;
+  destination: PublicKey;
+}
+
+describe("ShadowVest - Multi-Mint Withdrawals", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Contract as Program<Contract>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+
+  const arciumEnv = getArciumEnv();
+  const clusterAccount = getClusterAccAddress(arciumEnv.arciumClusterOffset);
+
+  let admin: Keypair;
+  let organizationPda: PublicKey;
+  let vaultAuthorityPda: PublicKey;
+  let cipher: RescueCipher;
+  let publicKey: Uint8Array;
+  const grants: Grant[] = [];
+
+  const beneficiary = Keypair.generate();
+  const nameHash = createHash("sha256").update("MultiMintTestOrg").digest();
+  const TOTAL_AMOUNT = BigInt(100_000_000); // 100 tokens (6 decimals)
+  const CLAIM_AMOUNT = BigInt(25_000_000); // 25 tokens
+
+  before(async () => {
+    const payer = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    admin = Keypair.generate();
+    const fundTx = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.transfer({
+        fromPubkey: payer.publicKey,
+        toPubkey: admin.publicKey,
+        lamports: LAMPORTS_PER_SOL, // 1 SOL
+      }),
+    );
+    await provider.sendAndConfirm(fundTx, [payer]);
+
+    const privateKey = x25519.utils.randomSecretKey();
+    publicKey = x25519.getPublicKey(privateKey);
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+
+    await initCompDef(program, payer, "init_position");
+    await initCompDef(program, payer, "process_claim_v2");
+
+    [organizationPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("organization"), admin.publicKey.toBuffer()],
+      program.programId,
+    );
+    [vaultAuthorityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_authority"), organizationPda.toBuffer()],
+      program.programId,
+    );
+
+    const mints = [
+      await createMint(provider.connection, admin, admin.publicKey, null, 6),
+      await createMint(provider.connection, admin, admin.publicKey, null, 6),
+    ];
+
+    // The organization's default mint is only used by compressed positions here
+    await program.methods
+      .createOrganization(Array.from(nameHash), Keypair.generate().publicKey, mints[0])
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    for (const [i, tokenMint] of mints.entries()) {
+      const id = new anchor.BN(i).toArrayLike(Buffer, "le", 8);
+      const [schedulePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vesting_schedule"), organizationPda.toBuffer(), id],
+        program.programId,
+      );
+      const [positionPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vesting_position"), organizationPda.toBuffer(), id],
+        program.programId,
+      );
+      const [vaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), organizationPda.toBuffer(), tokenMint.toBuffer()],
+        program.programId,
+      );
+
+      await program.methods
+        .createVestingSchedule(new anchor.BN(0), new anchor.BN(10), new anchor.BN(1), 0, tokenMint)
+        .accounts({
+          admin: admin.publicKey,
+          organization: organizationPda,
+          schedule: schedulePda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+
+      await program.methods
+        .initializeVault()
+        .accounts({
+          admin: admin.publicKey,
+          organization: organizationPda,
+          vaultAuthority: vaultAuthorityPda,
+          vault: vaultPda,
+          tokenMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+
+      await mintTo(provider.connection, admin, tokenMint, vaultPda, admin, Number(TOTAL_AMOUNT));
+
+      grants.push({
+        tokenMint,
+        schedulePda,
+        vaultPda,
+        positionPda,
+        claimAuthPda: PublicKey.default,
+        destination: await createAccount(
+          provider.connection,
+          admin,
+          tokenMint,
+          Keypair.generate().publicKey,
+        ),
+      });
+    }
+  });
+
+  it("Stores each schedule's own token mint", async () => {
+    for (const grant of grants) {
+      const schedule = await program.account.vestingSchedule.fetch(grant.schedulePda);
+      expect(schedule.tokenMint.toString()).to.equal(grant.tokenMint.toString());
+    }
+    expect(grants[0].vaultPda.toString()).to.not.equal(grants[1].vaultPda.toString());
+  });
+
+  it("Authorizes and processes a claim under each schedule", async () => {
+    for (const [i, grant] of grants.entries()) {
+      const positionId = new anchor.BN(i);
+      const nonce = randomBytes(16);
+      const computationOffset = new anchor.BN(randomBytes(8), "hex");
+
+      await program.methods
+        .createVestingPosition(
+          computationOffset,
+          Array.from(beneficiary.publicKey.toBytes()),
+          Array.from(cipher.encrypt([TOTAL_AMOUNT], nonce)[0]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString()),
+          0, // sig_scheme: Ed25519
+          Array(32).fill(0),
+        )
+        .accountsPartial({
+          payer: admin.publicKey,
+          admin: admin.publicKey,
+          organization: organizationPda,
+          schedule: grant.schedulePda,
+          position: grant.positionPda,
+          ...arciumAccounts(computationOffset, "init_position"),
+        })
+        .preInstructions([
+          ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+        ])
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+
+      await waitForAccountState(
+        program,
+        grant.positionPda,
+        "vestingPosition",
+        (account: any) => "initialized" in account.state,
+        300000,
+      );
+
+      const nullifier = randomBytes(32);
+      [grant.claimAuthPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("claim_auth"), grant.positionPda.toBuffer(), nullifier],
+        program.programId,
+      );
+      const [nullifierRecordPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("nullifier"), organizationPda.toBuffer(), Buffer.alloc(8), nullifier],
+        program.programId,
+      );
+
+      // Message: position_id(8) || nullifier(32) || withdrawal_destination(32)
+      const ed25519Ix = Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: beneficiary.secretKey,
+        message: Uint8Array.from(
+          Buffer.concat([
+            positionId.toArrayLike(Buffer, "le", 8),
+            nullifier,
+            grant.destination.toBuffer(),
+          ]),
+        ),
+      });
+
+      await program.methods
+        .authorizeClaim(Array.from(nullifier) as any, grant.destination, new anchor.BN(0))
+        .accounts({
+          payer: admin.publicKey,
+          organization: organizationPda,
+          position: grant.positionPda,
+          claimAuthorization: grant.claimAuthPda,
+          nullifierRecord: nullifierRecordPda,
+          instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        })
+        .preInstructions([ed25519Ix])
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+
+      const processOffset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .queueProcessClaim(
+          processOffset,
+          new anchor.BN(CLAIM_AMOUNT.toString()),
+          new anchor.BN(0), // priority_fee
+        )
+        .accountsPartial({
+          payer: admin.publicKey,
+          organization: organizationPda,
+          schedule: grant.schedulePda,
+          position: grant.positionPda,
+          claimAuthorization: grant.claimAuthPda,
+          vault: grant.vaultPda,
+          ...arciumAccounts(processOffset, "process_claim_v2"),
+        })
+        .preInstructions([
+          ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+        ])
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+
+      await waitForAccountState(
+        program,
+        grant.claimAuthPda,
+        "claimAuthorization",
+        (account: any) => account.isProcessed === true,
+        600000,
+      );
+    }
+  });
+
+  it("Rejects withdrawing from another mint's vault", async () => {
+    const [grant, otherGrant] = grants;
+    try {
+      await withdraw(grant, otherGrant.vaultPda);
+      expect.fail("Should have thrown - vault belongs to a different mint");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("ConstraintSeeds");
+    }
+  });
+
+  it("Reserves each claim against its own mint's vault", async () => {
+    const org = await program.account.organization.fetch(organizationPda);
+    for (const grant of grants) {
+      const reserve = org.vaultReserves.find((r: any) => r.mint.equals(grant.tokenMint));
+      expect(reserve, "vault reserve").to.not.be.undefined;
+      expect(reserve!.reservedAmount.toString()).to.equal(CLAIM_AMOUNT.toString());
+    }
+    expect(org.reservedAmount.toString()).to.equal(
+      (CLAIM_AMOUNT * BigInt(grants.length)).toString(),
+    );
+  });
+
+  it("Withdraws each claim from its own mint's vault", async () => {
+    for (const grant of grants) {
+      const vaultBefore = await getAccount(provider.connection, grant.vaultPda);
+
+      await withdraw(grant, grant.vaultPda);
+
+      const vaultAfter = await getAccount(provider.connection, grant.vaultPda);
+      expect(vaultBefore.amount - vaultAfter.amount).to.equal(CLAIM_AMOUNT);
+
+      const destination = await getAccount(provider.connection, grant.destination);
+      expect(destination.mint.toString()).to.equal(grant.tokenMint.toString());
+      expect(destination.amount).to.equal(CLAIM_AMOUNT);
+    }
+
+    // Fully withdrawn claims free their vaults' reservation slots
+    const org = await program.account.organization.fetch(organizationPda);
+    expect(org.vaultReserves.every((r: any) => r.mint.equals(PublicKey.default))).to.be.true;
+  });
+
+  async function withdraw(grant: Grant, vault: PublicKey) {
+    const [claimHistoryPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("claim_history"), beneficiary.publicKey.toBuffer()],
+      program.programId,
+    );
+
+    await program.methods
+      .withdraw()
+      .accountsPartial({
+        payer: admin.publicKey,
+        organization: organizationPda,
+        position: grant.positionPda,
+        claimAuthorization: grant.claimAuthPda,
+        schedule: grant.schedulePda,
+        vaultAuthority: vaultAuthorityPda,
+        vault,
+        destination: grant.destination,
+        claimHistory: claimHistoryPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  function arciumAccounts(computationOffset: anchor.BN, circuitName: string) {
+    const [signPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ArciumSignerAccount")],
+      program.programId,
+    );
+    return {
+      signPdaAccount: signPda,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      computationAccount: getComputationAccAddress(
+        arciumEnv.arciumClusterOffset,
+        computationOffset,
+      ),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuitName)).readUInt32LE(),
+      ),
+      clusterAccount,
+      poolAccount: getFeePoolAccAddress(),
+      clockAccount: getClockAccAddress(),
+      systemProgram: SystemProgram.programId,
+      arciumProgram: getArciumProgramId(),
+    };
+  }
+});
+
+// Helper functions
+
+async function initCompDef(
+  program: Program<Contract>,
+  owner: Keypair,
+  circuitName: string,
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed("ComputationDefinitionAccount");
+  const offset = getCompDefAccOffset(circuitName);
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgramId(),
+  )[0];
+
+  const accountInfo = await provider.connection.getAccountInfo(compDefPDA);
+  if (accountInfo !== null) {
+    return "already_initialized";
+  }
+
+  const mxeAccountAddr = getMXEAccAddress(program.programId);
+  const arciumProgram = getArciumProgram(provider);
+  const mxeAcc = await arciumProgram.account.mxeAccount.fetch(mxeAccountAddr);
+  const lutAddress = getLookupTableAddress(program.programId, mxeAcc.lutOffsetSlot);
+
+  let method;
+  if (circuitName === "init_position") {
+    method = program.methods.initInitPositionCompDef();
+  } else if (circuitName === "process_claim_v2") {
+    method = program.methods.initProcessClaimV2CompDef();
+  } else {
+    throw new Error(`Unknown circuit name: ${circuitName}`);
+  }
+
+  return method
+    .accountsPartial({
+      compDefAccount: compDefPDA,
+      payer: owner.publicKey,
+      mxeAccount: mxeAccountAddr,
+      addressLookupTable: lutAddress,
+      lutProgram: AddressLookupTableProgram.programId,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+}
+
+async function waitForAccountState(
+  program: Program<Contract>,
+  accountPda: PublicKey,
+  accountName: string,
+  predicate: (account: any) => boolean,
+  timeoutMs: number,
+): Promise<void> {
+  const deadline = Date.now() + timeoutMs;
+  while (Date.now() < deadline) {
+    try {
+      const account = await (program.account as any)[accountName].fetch(accountPda);
+      if (predicate(account)) {
+        return;
+      }
+    } catch (err) {
+      // Account might not exist yet
+    }
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+  }
+  throw new Error(`Timeout waiting for ${accountName} ${accountPda.toString()} after ${timeoutMs}ms`);
+}
+
+async function getMXEPublicKeyWithRetry(
+  provider: anchor.AnchorProvider,
+  programId: PublicKey,
+  maxRetries: number = 20,
+  retryDelayMs: number = 500,
+): Promise<Uint8Array> {
+  for (let attempt = 1; attempt <= maxRetries; attempt++) {
+    try {
+      const mxePublicKey = await getMXEPublicKey(provider, programId);
+      if (mxePublicKey) {
+        return mxePublicKey;
+      }
+    } catch (error) {
+      console.log(`Attempt ${attempt} failed to fetch MXE public key:`, error);
+    }
+    if (attempt < maxRetries) {
+      await new Promise((resolve) => setTimeout(resolve, retryDelayMs));
+    }
+  }
+  throw new Error(`Failed to fetch MXE public key after ${maxRetries} attempts`);
+}
+
+function readKpJson(path: string): Keypair {
+  const file = fs.readFileSync(path);
+  return Keypair.fromSecretKey(
+    new Uint8Array(JSON.parse(file.toString())),
+  );
+}
@@ -35,6 +35,7 @@ import {
   buildVerifyIdentityIx,
   buildVerifyEligibilityIx,
   deriveVkAccountPda,
+  destinationScalar,
 } from '../lib/noir-proof-generator';
 import {
   poseidonHash1,
@@ -462,6 +463,7 @@ describe('NoirJS Proof Generator', () => {
           nullifier: '0x' + '2'.repeat(64),
           position_id: '0x' + '3'.repeat(64),
           position_commitment: '0x' + '4'.repeat(64),
+          withdrawal_destination: '0x' + '5'.repeat(64),
           identity_secret: '',
           vesting_amount: 1000n,
         });
@@ -550,8 +552,8 @@ describe('NoirJS Proof Generator', () => {
         Buffer.from(expectedDiscriminator)
       );
 
-      // Verify data length: 8 (discriminator) + 256 (proof) + 104 (public inputs)
-      expect(instruction.data.length).to.equal(8 + 256 + 104);
+      // Verify data length: 8 (discriminator) + 256 (proof) + 104 (public inputs) + 4 (ttl_seconds)
+      expect(instruction.data.length).to.equal(8 + 256 + 104 + 4);
 
       // Verify compute budget instruction is present
       expect(computeBudgetIx).to.not.be.undefined;
@@ -578,14 +580,14 @@ describe('NoirJS Proof Generator', () => {
         Buffer.from(expectedDiscriminator)
       );
 
-      // Verify data length: 8 (discriminator) + 256 (proof) + 32 (public inputs)
-      expect(instruction.data.length).to.equal(8 + 256 + 32);
+      // Verify data length: 8 (discriminator) + 256 (proof) + 32 (public inputs) + 4 (ttl_seconds)
+      expect(instruction.data.length).to.equal(8 + 256 + 32 + 4);
     });
 
     it('builds verify_eligibility_proof instruction', () => {
       const verifier = Keypair.generate().publicKey;
       const vkAccount = Keypair.generate().publicKey;
-      const proofResult = createMockProofResult(4);
+      const proofResult = createMockProofResult(5);
 
       const { instruction, proofRecordPda, computeBudgetIx } = buildVerifyEligibilityIx(
         TEST_PROGRAM_ID, verifier, vkAccount, proofResult
@@ -603,8 +605,8 @@ describe('NoirJS Proof Generator', () => {
         Buffer.from(expectedDiscriminator)
       );
 
-      // Verify data length: 8 (discriminator) + 256 (proof) + 128 (public inputs)
-      expect(instruction.data.length).to.equal(8 + 256 + 128);
+      // Verify data length: 8 (discriminator) + 256 (proof) + 160 (public inputs) + 4 (ttl_seconds)
+      expect(instruction.data.length).to.equal(8 + 256 + 160 + 4);
     });
 
     it('derives correct proof record PDA for withdrawal', () => {
@@ -657,7 +659,7 @@ describe('NoirJS Proof Generator', () => {
     it('derives correct proof record PDA for eligibility', () => {
       const verifier = Keypair.generate().publicKey;
       const vkAccount = Keypair.generate().publicKey;
-      const proofResult = createMockProofResult(4);
+      const proofResult = createMockProofResult(5);
 
       const { proofRecordPda } = buildVerifyEligibilityIx(
         TEST_PROGRAM_ID, verifier, vkAccount, proofResult
@@ -677,6 +679,16 @@ describe('NoirJS Proof Generator', () => {
       expect(proofRecordPda.equals(expectedPda)).to.be.true;
     });
 
+    it('derives the eligibility withdrawal_destination input as a field element', () => {
+      const destination = Keypair.generate().publicKey;
+      const scalar = Buffer.from(destinationScalar(destination).slice(2), 'hex');
+      const hash = createHash('sha256').update(destination.toBuffer()).digest();
+
+      expect(scalar.length).to.equal(32);
+      expect(scalar[0]).to.equal(0);
+      expect(scalar.slice(1)).to.deep.equal(hash.slice(1));
+    });
+
     it('derives VK account PDA', () => {
       const circuitName = 'withdrawal_proof';
       const vkPda = deriveVkAccountPda(TEST_PROGRAM_ID, circuitName);
@@ -731,6 +743,18 @@ describe('NoirJS Proof Generator', () => {
       const epochValue = Buffer.from(epochBuf).readBigUInt64LE();
       expect(epochValue).to.equal(42n);
     });
+
+    it('encodes ttl_seconds as u32 LE after the public inputs', () => {
+      const verifier = Keypair.generate().publicKey;
+      const vkAccount = Keypair.generate().publicKey;
+
+      const { instruction } = buildVerifyWithdrawalIx(
+        TEST_PROGRAM_ID, verifier, vkAccount, createMockProofResult(4), 3600
+      );
+
+      const ttlOffset = 8 + 256 + 104;
+      expect(Buffer.from(instruction.data).readUInt32LE(ttlOffset)).to.equal(3600);
+    });
   });
 
   // --------------------------------------------------------------------------
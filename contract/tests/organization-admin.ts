@@ -0,0 +1,475 @@
+import "dotenv/config";
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  createMint,
+  createAccount,
+  mintTo,
+  getAccount,
+} from "@solana/spl-token";
+import { Contract } from "../target/types/contract";
+import { createHash, randomBytes } from "crypto";
+import * as fs from "fs";
+import * as os from "os";
+import { expect } from "chai";
+
+describe("ShadowVest - Organization Administration", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Contract as Program<Contract>;
+  const provider = anchor.getProvider();
+
+  let admin: Keypair;
+  let organizationPda: PublicKey;
+  let orgRegistryPda: PublicKey;
+  let schedulePda: PublicKey;
+
+  // name_hash commits to sha256(name || salt) so the name can be revealed later
+  const orgName = `AdminOrg-${Date.now()}`;
+  const nameSalt = randomBytes(32);
+  const nameHash = createHash("sha256").update(orgName).update(nameSalt).digest();
+  const treasury = Keypair.generate().publicKey;
+  const tokenMint = Keypair.generate().publicKey;
+
+  before(async () => {
+    const payer = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    // Generate a fresh admin keypair to avoid stale account issues
+    admin = Keypair.generate();
+    console.log("Fresh admin:", admin.publicKey.toString());
+
+    const fundTx = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.transfer({
+        fromPubkey: payer.publicKey,
+        toPubkey: admin.publicKey,
+        lamports: 1_000_000_000, // 1 SOL
+      }),
+    );
+    await (provider as anchor.AnchorProvider).sendAndConfirm(fundTx, [payer]);
+
+    [organizationPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("organization"), admin.publicKey.toBuffer()],
+      program.programId,
+    );
+    [orgRegistryPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("org_registry")],
+      program.programId,
+    );
+  });
+
+  it("Registers a new organization in the global registry", async () => {
+    // The registry is a singleton - only initialize it on first use
+    const registryInfo = await provider.connection.getAccountInfo(orgRegistryPda);
+    if (registryInfo === null) {
+      await program.methods
+        .initOrgRegistry()
+        .accounts({
+          payer: admin.publicKey,
+          orgRegistry: orgRegistryPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+    }
+
+    const registry = await program.account.orgRegistry.fetch(orgRegistryPda);
+    const sequence = registry.orgCount.toNumber();
+    const pageIndex = new anchor.BN(Math.floor(sequence / ORG_REGISTRY_ENTRIES_PER_PAGE));
+    const registryPagePda = registryPageAddress(program.programId, pageIndex);
+
+    // Create the next page if the current one doesn't exist yet
+    if (pageIndex.toNumber() >= registry.pageCount.toNumber()) {
+      await program.methods
+        .createOrgRegistryPage(pageIndex)
+        .accounts({
+          payer: admin.publicKey,
+          orgRegistry: orgRegistryPda,
+          registryPage: registryPagePda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+    }
+
+    await program.methods
+      .createOrganization(Array.from(nameHash), treasury, tokenMint)
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+        orgRegistry: orgRegistryPda,
+        registryPage: registryPagePda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    const updatedRegistry = await program.account.orgRegistry.fetch(orgRegistryPda);
+    expect(updatedRegistry.orgCount.toNumber()).to.equal(sequence + 1);
+
+    const page = await program.account.orgRegistryPage.fetch(registryPagePda);
+    const entry = page.entries[sequence % ORG_REGISTRY_ENTRIES_PER_PAGE];
+    expect(entry.organization.toString()).to.equal(organizationPda.toString());
+    expect(Buffer.from(entry.nameHash)).to.deep.equal(nameHash);
+  });
+
+  it("Updates the organization treasury", async () => {
+    const newTreasury = Keypair.generate().publicKey;
+
+    await program.methods
+      .updateTreasury(newTreasury)
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    const orgAccount = await program.account.organization.fetch(organizationPda);
+    expect(orgAccount.treasury.toString()).to.equal(newTreasury.toString());
+  });
+
+  it("Rejects treasury update from a non-admin", async () => {
+    const attacker = Keypair.generate();
+
+    try {
+      await program.methods
+        .updateTreasury(attacker.publicKey)
+        .accounts({
+          admin: attacker.publicKey,
+          organization: organizationPda,
+        })
+        .signers([attacker])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("Should have rejected non-admin treasury update");
+    } catch (err: any) {
+      // Seeds are derived from the signer, so the organization PDA won't match
+      expect(err.message).to.not.include("Should have rejected");
+    }
+  });
+
+  it("Reveals the organization name by opening its name hash", async () => {
+    const { events } = await program.methods
+      .revealOrganizationName(orgName, Array.from(nameSalt) as any)
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+      })
+      .signers([admin])
+      .simulate();
+
+    const revealed = events.find((e: any) => e.name === "organizationNameRevealed");
+    expect(revealed, "OrganizationNameRevealed event").to.not.be.undefined;
+    expect((revealed as any).data.organization.toString()).to.equal(organizationPda.toString());
+    expect((revealed as any).data.name).to.equal(orgName);
+
+    // The stored commitment is untouched
+    const orgAccount = await program.account.organization.fetch(organizationPda);
+    expect(Buffer.from(orgAccount.nameHash)).to.deep.equal(nameHash);
+  });
+
+  it("Rejects a name reveal with the wrong salt", async () => {
+    try {
+      await program.methods
+        .revealOrganizationName(orgName, Array.from(randomBytes(32)) as any)
+        .accounts({
+          admin: admin.publicKey,
+          organization: organizationPda,
+        })
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+
+      expect.fail("Should have thrown - salt does not open the name hash");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("NameHashMismatch");
+    }
+  });
+
+  it("Configures a 2.5% withdrawal fee", async () => {
+    const feeDestination = Keypair.generate().publicKey;
+
+    await program.methods
+      .setWithdrawalFee(250, feeDestination)
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    let orgAccount = await program.account.organization.fetch(organizationPda);
+    expect(orgAccount.feeBps).to.equal(250);
+    expect(orgAccount.feeDestination.toString()).to.equal(feeDestination.toString());
+
+    // Back to no fee
+    await program.methods
+      .setWithdrawalFee(0, PublicKey.default)
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    orgAccount = await program.account.organization.fetch(organizationPda);
+    expect(orgAccount.feeBps).to.equal(0);
+  });
+
+  it("Rejects a withdrawal fee above 100%", async () => {
+    try {
+      await program.methods
+        .setWithdrawalFee(10_001, Keypair.generate().publicKey)
+        .accounts({
+          admin: admin.publicKey,
+          organization: organizationPda,
+        })
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("Should have rejected fee_bps > 10_000");
+    } catch (err: any) {
+      expect(err.message).to.include("InvalidFeeBps");
+    }
+  });
+
+  it("Reclaims vault surplus once no positions remain", async () => {
+    // Vault in a real mint; the organization has no positions yet
+    const surplusMint = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+    const adminTokenAccount = await createAccount(
+      provider.connection,
+      admin,
+      surplusMint,
+      admin.publicKey,
+    );
+    const [vaultAuthorityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_authority"), organizationPda.toBuffer()],
+      program.programId,
+    );
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), organizationPda.toBuffer(), surplusMint.toBuffer()],
+      program.programId,
+    );
+
+    await program.methods
+      .initializeVault()
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+        vaultAuthority: vaultAuthorityPda,
+        vault: vaultPda,
+        tokenMint: surplusMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+    await mintTo(provider.connection, admin, surplusMint, vaultPda, admin, 1_000_000);
+
+    const reclaim = (amount: number) =>
+      program.methods
+        .reclaimVaultSurplus(new anchor.BN(amount))
+        .accounts({
+          admin: admin.publicKey,
+          organization: organizationPda,
+          vaultAuthority: vaultAuthorityPda,
+          vault: vaultPda,
+          adminTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+
+    try {
+      await reclaim(1_000_001);
+      expect.fail("Should have rejected reclaiming more than the vault holds");
+    } catch (err: any) {
+      expect(err.message).to.include("InsufficientVaultBalance");
+    }
+
+    await reclaim(1_000_000);
+
+    expect((await getAccount(provider.connection, vaultPda)).amount).to.equal(BigInt(0));
+    expect((await getAccount(provider.connection, adminTokenAccount)).amount).to.equal(
+      BigInt(1_000_000),
+    );
+  });
+
+  it("Updates an unused vesting schedule", async () => {
+    schedulePda = schedulePdaFor(program.programId, organizationPda, 0);
+
+    await program.methods
+      .createVestingSchedule(new anchor.BN(0), new anchor.BN(100), new anchor.BN(10), 0, tokenMint)
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+        schedule: schedulePda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .updateVestingSchedule(new anchor.BN(30), new anchor.BN(365), new anchor.BN(1))
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+        schedule: schedulePda,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    const schedule = await program.account.vestingSchedule.fetch(schedulePda);
+    expect(schedule.cliffDuration.toNumber()).to.equal(30);
+    expect(schedule.totalDuration.toNumber()).to.equal(365);
+    expect(schedule.vestingInterval.toNumber()).to.equal(1);
+  });
+
+  it("Rejects schedule update with invalid params", async () => {
+    try {
+      await program.methods
+        .updateVestingSchedule(new anchor.BN(400), new anchor.BN(365), new anchor.BN(1))
+        .accounts({
+          admin: admin.publicKey,
+          organization: organizationPda,
+          schedule: schedulePda,
+        })
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("Should have rejected cliff > total");
+    } catch (err: any) {
+      expect(err.message).to.include("InvalidScheduleParams");
+    }
+  });
+
+  it("Sets a finer vesting precision on an unused schedule", async () => {
+    const setPrecision = (precision: number) =>
+      program.methods
+        .setSchedulePrecision(new anchor.BN(precision))
+        .accounts({
+          admin: admin.publicKey,
+          organization: organizationPda,
+          schedule: schedulePda,
+        })
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+
+    let schedule = await program.account.vestingSchedule.fetch(schedulePda);
+    expect(schedule.precision.toNumber()).to.equal(1_000_000);
+
+    await setPrecision(1_000_000_000);
+    schedule = await program.account.vestingSchedule.fetch(schedulePda);
+    expect(schedule.precision.toNumber()).to.equal(1_000_000_000);
+
+    try {
+      await setPrecision(0);
+      expect.fail("Should have rejected a zero precision");
+    } catch (err: any) {
+      expect(err.message).to.include("InvalidScheduleParams");
+    }
+
+    // Restore the default for the tests below
+    await setPrecision(1_000_000);
+  });
+
+  it("Rejects a schedule longer than 100 years", async () => {
+    const tooLong = new anchor.BN(100 * 365 * 24 * 60 * 60 + 1);
+    try {
+      await program.methods
+        .createVestingSchedule(new anchor.BN(0), tooLong, new anchor.BN(1), 0, tokenMint)
+        .accounts({
+          admin: admin.publicKey,
+          organization: organizationPda,
+          schedule: schedulePdaFor(program.programId, organizationPda, 1),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("Should have rejected total_duration above the maximum");
+    } catch (err: any) {
+      expect(err.message).to.include("ScheduleDurationTooLong");
+    }
+  });
+
+  it("Creates schedules from each named template", async () => {
+    const DAY = 24 * 60 * 60;
+    const templates: [any, number, number, number][] = [
+      [{ fourYearOneYearCliff: {} }, 365 * DAY, 4 * 365 * DAY, 30 * DAY],
+      [{ fourYearNoCliff: {} }, 0, 4 * 365 * DAY, 30 * DAY],
+      [{ threeYearNoCliff: {} }, 0, 3 * 365 * DAY, 30 * DAY],
+      [{ twoYearSixMonthCliff: {} }, 180 * DAY, 2 * 365 * DAY, 30 * DAY],
+      [{ monthlyOneYear: {} }, 0, 365 * DAY, 30 * DAY],
+    ];
+
+    for (const [template, cliff, total, interval] of templates) {
+      const org = await program.account.organization.fetch(organizationPda);
+      const pda = schedulePdaFor(program.programId, organizationPda, org.scheduleCount.toNumber());
+
+      await program.methods
+        .createScheduleFromTemplate(template, tokenMint)
+        .accounts({
+          admin: admin.publicKey,
+          organization: organizationPda,
+          schedule: pda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+
+      const schedule = await program.account.vestingSchedule.fetch(pda);
+      expect(schedule.cliffDuration.toNumber()).to.equal(cliff);
+      expect(schedule.totalDuration.toNumber()).to.equal(total);
+      expect(schedule.vestingInterval.toNumber()).to.equal(interval);
+      expect(schedule.vestingMode).to.equal(0);
+      expect(schedule.isActive).to.be.true;
+    }
+  });
+
+  it("Rejects a template schedule from a non-admin", async () => {
+    const outsider = Keypair.generate();
+    const org = await program.account.organization.fetch(organizationPda);
+    try {
+      await program.methods
+        .createScheduleFromTemplate({ monthlyOneYear: {} } as any, tokenMint)
+        .accountsPartial({
+          admin: outsider.publicKey,
+          organization: organizationPda,
+          schedule: schedulePdaFor(program.programId, organizationPda, org.scheduleCount.toNumber()),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([outsider])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("Should have rejected a non-admin signer");
+    } catch (err: any) {
+      expect(err.message).to.include("ConstraintSeeds");
+    }
+  });
+});
+
+// Helper functions
+
+const ORG_REGISTRY_ENTRIES_PER_PAGE = 32;
+
+function registryPageAddress(programId: PublicKey, pageIndex: anchor.BN): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("org_registry_page"), pageIndex.toArrayLike(Buffer, "le", 8)],
+    programId,
+  )[0];
+}
+
+function schedulePdaFor(programId: PublicKey, organization: PublicKey, scheduleId: number): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("vesting_schedule"),
+      organization.toBuffer(),
+      new anchor.BN(scheduleId).toArrayLike(Buffer, "le", 8),
+    ],
+    programId,
+  )[0];
+}
+
+function readKpJson(path: string): Keypair {
+  const file = fs.readFileSync(path);
+  return Keypair.fromSecretKey(
+    new Uint8Array(JSON.parse(file.toString())),
+  );
+}
@@ -0,0 +1,372 @@
+import "dotenv/config";
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  Keypair,
+  ComputeBudgetProgram,
+  Ed25519Program,
+  Secp256k1Program,
+  SystemProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  AddressLookupTableProgram,
+} from "@solana/web3.js";
+import { secp256k1 } from "@noble/curves/secp256k1";
+import { Contract } from "../target/types/contract";
+import { randomBytes, createHash } from "crypto";
+import {
+  getArciumEnv,
+  getCompDefAccOffset,
+  getArciumAccountBaseSeed,
+  getArciumProgramId,
+  RescueCipher,
+  deserializeLE,
+  getMXEPublicKey,
+  getMXEAccAddress,
+  getMempoolAccAddress,
+  getCompDefAccAddress,
+  getExecutingPoolAccAddress,
+  getComputationAccAddress,
+  getClusterAccAddress,
+  getFeePoolAccAddress,
+  getClockAccAddress,
+  getLookupTableAddress,
+  getArciumProgram,
+  x25519,
+} from "@arcium-hq/client";
+import * as fs from "fs";
+import * as os from "os";
+import { expect } from "chai";
+
+const SIG_SCHEME_SECP256K1 = 1;
+
+describe("ShadowVest - secp256k1 Claim Authorization", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Contract as Program<Contract>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+
+  const arciumEnv = getArciumEnv();
+  const clusterAccount = getClusterAccAddress(arciumEnv.arciumClusterOffset);
+
+  let admin: Keypair;
+  let organizationPda: PublicKey;
+  let schedulePda: PublicKey;
+  let positionPda: PublicKey;
+  let cipher: RescueCipher;
+  let publicKey: Uint8Array;
+
+  // Ethereum-style beneficiary key
+  const ethPrivateKey = secp256k1.utils.randomPrivateKey();
+  // Uncompressed public key without the 0x04 prefix (64 bytes)
+  const ethPublicKey = secp256k1.getPublicKey(ethPrivateKey, false).slice(1);
+  const ethAddress = Secp256k1Program.publicKeyToEthAddress(ethPublicKey);
+
+  const nameHash = createHash("sha256").update("Secp256k1TestOrg").digest();
+
+  before(async () => {
+    const payer = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    admin = Keypair.generate();
+    const fundTx = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.transfer({
+        fromPubkey: payer.publicKey,
+        toPubkey: admin.publicKey,
+        lamports: 1_000_000_000, // 1 SOL
+      }),
+    );
+    await provider.sendAndConfirm(fundTx, [payer]);
+
+    const privateKey = x25519.utils.randomSecretKey();
+    publicKey = x25519.getPublicKey(privateKey);
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+
+    await initCompDef(program, payer, "init_position");
+
+    [organizationPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("organization"), admin.publicKey.toBuffer()],
+      program.programId,
+    );
+    [schedulePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vesting_schedule"),
+        organizationPda.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId,
+    );
+    [positionPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vesting_position"),
+        organizationPda.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId,
+    );
+
+    await program.methods
+      .createOrganization(Array.from(nameHash), Keypair.generate().publicKey, Keypair.generate().publicKey)
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .createVestingSchedule(new anchor.BN(0), new anchor.BN(10), new anchor.BN(1), 0, Keypair.generate().publicKey)
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+        schedule: schedulePda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("Creates a position owned by an Ethereum address", async () => {
+    // secp256k1 commitments hold the 20-byte address, zero-padded to 32 bytes
+    const beneficiaryCommitment = Buffer.alloc(32);
+    Buffer.from(ethAddress).copy(beneficiaryCommitment, 0);
+
+    const nonce = randomBytes(16);
+    const ciphertext = cipher.encrypt([BigInt(100_000_000)], nonce);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+
+    const [signPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ArciumSignerAccount")],
+      program.programId,
+    );
+
+    await program.methods
+      .createVestingPosition(
+        computationOffset,
+        Array.from(beneficiaryCommitment),
+        Array.from(ciphertext[0]),
+        Array.from(publicKey),
+        new anchor.BN(deserializeLE(nonce).toString()),
+        SIG_SCHEME_SECP256K1,
+        Array(32).fill(0),
+      )
+      .accountsPartial({
+        payer: admin.publicKey,
+        admin: admin.publicKey,
+        organization: organizationPda,
+        schedule: schedulePda,
+        position: positionPda,
+        signPdaAccount: signPda,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset,
+        ),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_position")).readUInt32LE(),
+        ),
+        clusterAccount,
+        poolAccount: getFeePoolAccAddress(),
+        clockAccount: getClockAccAddress(),
+        systemProgram: SystemProgram.programId,
+        arciumProgram: getArciumProgramId(),
+      })
+      .preInstructions([
+        ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+      ])
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    const position = await program.account.vestingPosition.fetch(positionPda);
+    expect(position.sigScheme).to.equal(SIG_SCHEME_SECP256K1);
+
+    // Claims are only accepted once the init_position callback has run
+    await waitForInitialized(program, positionPda, 300000);
+  });
+
+  it("Rejects an Ed25519 signature for a secp256k1 position", async () => {
+    const nullifier = randomBytes(32);
+    const destination = Keypair.generate().publicKey;
+    const message = claimMessage(0n, nullifier, destination);
+
+    const ed25519Ix = Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: Keypair.generate().secretKey,
+      message: Uint8Array.from(message),
+    });
+
+    try {
+      await authorizeClaim(nullifier, destination, ed25519Ix);
+      expect.fail("Should have rejected Ed25519 signature");
+    } catch (err: any) {
+      expect(err.message).to.include("InvalidEligibilitySignature");
+    }
+  });
+
+  it("Authorizes a claim with a secp256k1 signature", async () => {
+    const nullifier = randomBytes(32);
+    const destination = Keypair.generate().publicKey;
+    const message = claimMessage(0n, nullifier, destination);
+
+    // The precompile instruction is first in the transaction (index 0)
+    const secpIx = Secp256k1Program.createInstructionWithPrivateKey({
+      privateKey: ethPrivateKey,
+      message: Uint8Array.from(message),
+      instructionIndex: 0,
+    });
+
+    const claimAuthPda = await authorizeClaim(nullifier, destination, secpIx);
+
+    const claimAuth = await program.account.claimAuthorization.fetch(claimAuthPda);
+    expect(claimAuth.isAuthorized).to.be.true;
+    expect(claimAuth.withdrawalDestination.toString()).to.equal(destination.toString());
+  });
+
+  it("Rejects a secp256k1 signature from a different address", async () => {
+    const nullifier = randomBytes(32);
+    const destination = Keypair.generate().publicKey;
+    const message = claimMessage(0n, nullifier, destination);
+
+    const secpIx = Secp256k1Program.createInstructionWithPrivateKey({
+      privateKey: secp256k1.utils.randomPrivateKey(),
+      message: Uint8Array.from(message),
+      instructionIndex: 0,
+    });
+
+    try {
+      await authorizeClaim(nullifier, destination, secpIx);
+      expect.fail("Should have rejected foreign signer");
+    } catch (err: any) {
+      expect(err.message).to.include("SignerMismatch");
+    }
+  });
+
+  async function authorizeClaim(
+    nullifier: Buffer,
+    destination: PublicKey,
+    sigIx: anchor.web3.TransactionInstruction,
+  ): Promise<PublicKey> {
+    const [claimAuthPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("claim_auth"), positionPda.toBuffer(), nullifier],
+      program.programId,
+    );
+    const [nullifierRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("nullifier"), organizationPda.toBuffer(), Buffer.alloc(8), nullifier],
+      program.programId,
+    );
+
+    await program.methods
+      .authorizeClaim(Array.from(nullifier) as any, destination, new anchor.BN(0))
+      .accounts({
+        payer: admin.publicKey,
+        organization: organizationPda,
+        position: positionPda,
+        claimAuthorization: claimAuthPda,
+        nullifierRecord: nullifierRecordPda,
+        instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+        systemProgram: SystemProgram.programId,
+      })
+      .preInstructions([sigIx])
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    return claimAuthPda;
+  }
+});
+
+// Helper functions
+
+// Message: position_id(8) || nullifier(32) || withdrawal_destination(32)
+function claimMessage(positionId: bigint, nullifier: Buffer, destination: PublicKey): Buffer {
+  const positionIdBuf = Buffer.alloc(8);
+  positionIdBuf.writeBigUInt64LE(positionId);
+  return Buffer.concat([positionIdBuf, nullifier, destination.toBuffer()]);
+}
+
+async function initCompDef(
+  program: Program<Contract>,
+  owner: Keypair,
+  circuitName: string,
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed("ComputationDefinitionAccount");
+  const offset = getCompDefAccOffset(circuitName);
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgramId(),
+  )[0];
+
+  const accountInfo = await provider.connection.getAccountInfo(compDefPDA);
+  if (accountInfo !== null) {
+    return "already_initialized";
+  }
+
+  const mxeAccountAddr = getMXEAccAddress(program.programId);
+  const arciumProgram = getArciumProgram(provider);
+  const mxeAcc = await arciumProgram.account.mxeAccount.fetch(mxeAccountAddr);
+  const lutAddress = getLookupTableAddress(program.programId, mxeAcc.lutOffsetSlot);
+
+  if (circuitName !== "init_position") {
+    throw new Error(`Unknown circuit name: ${circuitName}`);
+  }
+
+  return program.methods
+    .initInitPositionCompDef()
+    .accountsPartial({
+      compDefAccount: compDefPDA,
+      payer: owner.publicKey,
+      mxeAccount: mxeAccountAddr,
+      addressLookupTable: lutAddress,
+      lutProgram: AddressLookupTableProgram.programId,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+}
+
+async function waitForInitialized(
+  program: Program<Contract>,
+  position: PublicKey,
+  timeoutMs: number,
+): Promise<void> {
+  const deadline = Date.now() + timeoutMs;
+  while (Date.now() < deadline) {
+    const account = await program.account.vestingPosition.fetch(position);
+    if ("initialized" in account.state) {
+      return;
+    }
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+  }
+  throw new Error(`Position ${position.toString()} was not initialized within ${timeoutMs}ms`);
+}
+
+async function getMXEPublicKeyWithRetry(
+  provider: anchor.AnchorProvider,
+  programId: PublicKey,
+  maxRetries: number = 20,
+  retryDelayMs: number = 500,
+): Promise<Uint8Array> {
+  for (let attempt = 1; attempt <= maxRetries; attempt++) {
+    try {
+      const mxePublicKey = await getMXEPublicKey(provider, programId);
+      if (mxePublicKey) {
+        return mxePublicKey;
+      }
+    } catch (error) {
+      console.log(`Attempt ${attempt} failed to fetch MXE public key:`, error);
+    }
+    if (attempt < maxRetries) {
+      await new Promise((resolve) => setTimeout(resolve, retryDelayMs));
+    }
+  }
+  throw new Error(`Failed to fetch MXE public key after ${maxRetries} attempts`);
+}
+
+function readKpJson(path: string): Keypair {
+  const file = fs.readFileSync(path);
+  return Keypair.fromSecretKey(
+    new Uint8Array(JSON.parse(file.toString())),
+  );
+}
@@ -65,6 +65,7 @@ import {
   deriveStealthKeypair,
   decryptEphemeralPrivKey,
   isMyStealthPayment,
+  computeViewTag,
   StealthMetaKeys,
   StealthPaymentData,
 } from "../lib/stealth-address";
@@ -123,6 +124,10 @@ describe("Stealth Compressed Flow (E2E)", () => {
   // Stealth payment data (generated by employer)
   let stealthPayment: StealthPaymentData;
 
+  // Organization auditor X25519 secret key and the payload encrypted to it
+  let auditorKeys: Uint8Array;
+  let auditorPayload128: Buffer;
+
   // Compressed position state
   let compressedPositionAddress: PublicKey;
   let positionId: number;
@@ -259,6 +264,7 @@ describe("Stealth Compressed Flow (E2E)", () => {
     // Register stealth meta-address
     await program.methods
       .registerStealthMeta(
+        Array(16).fill(0), // default label
         Array.from(spendPubkeyBytes) as any,
         Array.from(viewPubkeyBytes) as any
       )
@@ -280,6 +286,190 @@ describe("Stealth Compressed Flow (E2E)", () => {
     console.log("  PDA:", stealthMetaPda.toString());
   });
 
+  it("Step 2b: Employee registers a labeled meta-address per employer", async () => {
+    const labels = ["employer-a", "employer-b"].map((name) => {
+      const label = Buffer.alloc(16);
+      label.write(name);
+      return label;
+    });
+
+    const pdas: PublicKey[] = [];
+    for (const label of labels) {
+      const keys = generateStealthMetaKeys();
+      const [pda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stealth_meta"), employeeWallet.publicKey.toBuffer(), label],
+        program.programId
+      );
+      await program.methods
+        .registerStealthMeta(
+          Array.from(label),
+          Array.from(new PublicKey(keys.metaAddress.spendPubkey).toBytes()) as any,
+          Array.from(new PublicKey(keys.metaAddress.viewPubkey).toBytes()) as any
+        )
+        .accountsPartial({
+          stealthMeta: pda,
+          owner: employeeWallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([employeeWallet])
+        .rpc({ commitment: "confirmed" });
+      pdas.push(pda);
+    }
+
+    // Both labeled addresses coexist with the default one
+    expect(new Set([stealthMetaPda, ...pdas].map((p) => p.toString())).size).to.equal(3);
+    for (const pda of pdas) {
+      const meta = await program.account.stealthMetaAddress.fetch(pda);
+      expect(meta.isActive).to.be.true;
+      expect(meta.owner.toString()).to.equal(employeeWallet.publicKey.toString());
+    }
+
+    // Deactivating one label leaves the other active
+    await program.methods
+      .deactivateStealthMeta(Array.from(labels[0]))
+      .accountsPartial({
+        stealthMeta: pdas[0],
+        authority: employeeWallet.publicKey,
+        owner: employeeWallet.publicKey,
+      })
+      .signers([employeeWallet])
+      .rpc({ commitment: "confirmed" });
+    expect((await program.account.stealthMetaAddress.fetch(pdas[0])).isActive).to.be.false;
+    expect((await program.account.stealthMetaAddress.fetch(pdas[1])).isActive).to.be.true;
+  });
+
+  it("Step 2c: Employer quotes an active meta-address; a deactivated one is rejected", async () => {
+    const quoteEvents: any[] = [];
+    const quoteListener = program.addEventListener("stealthMetaQuoted", (event: any) => {
+      quoteEvents.push(event);
+    });
+    await program.methods
+      .quoteStealthPayment(Array(16).fill(0))
+      .accountsPartial({
+        payer: admin.publicKey,
+        owner: employeeWallet.publicKey,
+        stealthMeta: stealthMetaPda,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+    await sleep(2000);
+    await program.removeEventListener(quoteListener);
+    expect(quoteEvents).to.have.length(1);
+    expect(Buffer.from(quoteEvents[0].spendPubkey)).to.deep.equal(
+      Buffer.from(new PublicKey(employeeMetaKeys.metaAddress.spendPubkey).toBytes())
+    );
+    expect(quoteEvents[0].quotedAt.toNumber()).to.be.greaterThan(0);
+
+    // The label deactivated in Step 2b can no longer be quoted
+    const label = Buffer.alloc(16);
+    label.write("employer-a");
+    const [deactivatedPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("stealth_meta"), employeeWallet.publicKey.toBuffer(), label],
+      program.programId
+    );
+    try {
+      await program.methods
+        .quoteStealthPayment(Array.from(label))
+        .accountsPartial({
+          payer: admin.publicKey,
+          owner: employeeWallet.publicKey,
+          stealthMeta: deactivatedPda,
+        })
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("Should have thrown - meta-address is deactivated");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("StealthMetaNotActive");
+    }
+  });
+
+  it("Step 2d: A delegate rotates keys but cannot change the delegate", async () => {
+    const label = Buffer.alloc(16);
+    label.write("custodian");
+    const [pda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("stealth_meta"), employeeWallet.publicKey.toBuffer(), label],
+      program.programId
+    );
+    const custodian = Keypair.generate();
+    const toBytes = (key: string) => Array.from(new PublicKey(key).toBytes()) as any;
+
+    const initialKeys = generateStealthMetaKeys();
+    await program.methods
+      .registerStealthMeta(
+        Array.from(label),
+        toBytes(initialKeys.metaAddress.spendPubkey),
+        toBytes(initialKeys.metaAddress.viewPubkey)
+      )
+      .accountsPartial({
+        stealthMeta: pda,
+        owner: employeeWallet.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([employeeWallet])
+      .rpc({ commitment: "confirmed" });
+
+    // Before being appointed, the custodian cannot rotate keys
+    const rotatedKeys = generateStealthMetaKeys();
+    const rotate = () =>
+      program.methods
+        .updateStealthMeta(
+          Array.from(label),
+          toBytes(rotatedKeys.metaAddress.spendPubkey),
+          toBytes(rotatedKeys.metaAddress.viewPubkey)
+        )
+        .accountsPartial({
+          stealthMeta: pda,
+          authority: custodian.publicKey,
+          owner: employeeWallet.publicKey,
+        })
+        .signers([custodian])
+        .rpc({ commitment: "confirmed" });
+    try {
+      await rotate();
+      expect.fail("Should have thrown - custodian is not the delegate yet");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("UnauthorizedOwner");
+    }
+
+    // The owner appoints the custodian
+    const delegateEvents: any[] = [];
+    const delegateListener = program.addEventListener("stealthDelegateSet", (event: any) => {
+      delegateEvents.push(event);
+    });
+    await program.methods
+      .setStealthDelegate(Array.from(label), custodian.publicKey)
+      .accountsPartial({ stealthMeta: pda, owner: employeeWallet.publicKey })
+      .signers([employeeWallet])
+      .rpc({ commitment: "confirmed" });
+    await sleep(2000);
+    await program.removeEventListener(delegateListener);
+    expect(delegateEvents).to.have.length(1);
+    expect(delegateEvents[0].delegate.toString()).to.equal(custodian.publicKey.toString());
+
+    // The delegate can now rotate keys; ownership is unchanged
+    await rotate();
+    const meta = await program.account.stealthMetaAddress.fetch(pda);
+    expect(meta.owner.toString()).to.equal(employeeWallet.publicKey.toString());
+    expect(meta.delegate!.toString()).to.equal(custodian.publicKey.toString());
+    expect(Buffer.from(meta.spendPubkey)).to.deep.equal(
+      Buffer.from(new PublicKey(rotatedKeys.metaAddress.spendPubkey).toBytes())
+    );
+
+    // ...but cannot change the delegate itself
+    try {
+      await program.methods
+        .setStealthDelegate(Array.from(label), Keypair.generate().publicKey)
+        .accountsPartial({ stealthMeta: pda, owner: custodian.publicKey })
+        .signers([custodian])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("Should have thrown - only the owner can set the delegate");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("ConstraintSeeds");
+    }
+    const after = await program.account.stealthMetaAddress.fetch(pda);
+    expect(after.delegate!.toString()).to.equal(custodian.publicKey.toString());
+  });
+
   // ==================================================
   // STEP 3-4: Employer derives stealth address
   // ==================================================
@@ -346,7 +536,9 @@ describe("Stealth Compressed Flow (E2E)", () => {
       .createVestingSchedule(
         new anchor.BN(0),  // cliff
         new anchor.BN(10), // duration
-        new anchor.BN(1)   // interval
+        new anchor.BN(1),  // interval
+        0,                 // vesting_mode: interval-stepped
+        tokenMint,
       )
       .accountsPartial({
         admin: admin.publicKey,
@@ -364,7 +556,7 @@ describe("Stealth Compressed Flow (E2E)", () => {
       program.programId
     );
     [vaultPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("vault"), organizationPda.toBuffer()],
+      [Buffer.from("vault"), organizationPda.toBuffer(), tokenMint.toBuffer()],
       program.programId
     );
 
@@ -473,6 +665,27 @@ describe("Stealth Compressed Flow (E2E)", () => {
     console.log("  Contains", lookupTableAccount.state.addresses.length, "addresses");
   });
 
+  // ==================================================
+  // Setup: Enable auditor payloads
+  // ==================================================
+  it("Setup: Enables auditor payloads for the organization", async () => {
+    auditorKeys = x25519.utils.randomSecretKey();
+    const auditorPubkey = x25519.getPublicKey(auditorKeys);
+
+    await program.methods
+      .setAuditorKey(Array.from(auditorPubkey) as any)
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    const org = await program.account.organization.fetch(organizationPda);
+    expect(Buffer.from(org.auditorPubkey)).to.deep.equal(Buffer.from(auditorPubkey));
+    console.log("Auditor key set - stealth payments now carry an auditor payload");
+  });
+
   // ==================================================
   // STEP 5: Create compressed stealth vesting position
   // ==================================================
@@ -534,6 +747,28 @@ describe("Stealth Compressed Flow (E2E)", () => {
     const encryptedPayload128 = Buffer.alloc(128);
     encryptedPayloadRaw.copy(encryptedPayload128, 0, 0, Math.min(encryptedPayloadRaw.length, 128));
 
+    // Same payment data for the auditor: the ephemeral pubkey encrypted to the
+    // auditor key. Layout: sender x25519 pubkey(32) || nonce(16) || 2 ciphertexts(64)
+    const auditorSenderKey = x25519.utils.randomSecretKey();
+    const auditorNonce = randomBytes(16);
+    const auditorCipher = new RescueCipher(
+      x25519.getSharedSecret(auditorSenderKey, x25519.getPublicKey(auditorKeys)),
+    );
+    const auditorCiphertext = auditorCipher.encrypt(
+      [
+        deserializeLE(Buffer.from(ephemeralPubkeyBytes.slice(0, 16))),
+        deserializeLE(Buffer.from(ephemeralPubkeyBytes.slice(16, 32))),
+      ],
+      auditorNonce,
+    );
+    auditorPayload128 = Buffer.concat([
+      Buffer.from(x25519.getPublicKey(auditorSenderKey)),
+      auditorNonce,
+      Buffer.from(auditorCiphertext[0]),
+      Buffer.from(auditorCiphertext[1]),
+      Buffer.alloc(16),
+    ]);
+
     // Serialize proof
     const proofBytes = serializeValidityProof(proof);
     const addressTreeInfoBytes = serializePackedAddressTreeInfo({
@@ -557,6 +792,10 @@ describe("Stealth Compressed Flow (E2E)", () => {
     });
 
     // Create compressed stealth vesting position
+    const paymentEvents: any[] = [];
+    const paymentListener = program.addEventListener("stealthPaymentEvent", (event: any) => {
+      paymentEvents.push(event);
+    });
     try {
       await program.methods
         .createCompressedStealthVestingPosition(
@@ -565,9 +804,12 @@ describe("Stealth Compressed Flow (E2E)", () => {
           outputStateTreeIndex,
           stealthPayment.stealthAddress,
           Array.from(ephemeralPubkeyBytes) as any,
+          stealthPayment.viewTag,
           Array.from(encryptedPayload128) as any,
+          Array.from(auditorPayload128) as any,
           Array.from(ciphertext[0]) as any,
-          new anchor.BN(nonceAsBN.toString())
+          new anchor.BN(nonceAsBN.toString()),
+          Array(32).fill(0)
       )
       .accountsPartial({
         feePayer: admin.publicKey,
@@ -601,6 +843,28 @@ describe("Stealth Compressed Flow (E2E)", () => {
       throw err;
     }
 
+    // Both the beneficiary and auditor payloads are published
+    await sleep(3000);
+    await program.removeEventListener(paymentListener);
+    expect(paymentEvents).to.have.length(1);
+    expect(Buffer.from(paymentEvents[0].encryptedPayload)).to.deep.equal(encryptedPayload128);
+    expect(Buffer.from(paymentEvents[0].auditorEncryptedPayload)).to.deep.equal(auditorPayload128);
+    expect(paymentEvents[0].viewTag).to.equal(stealthPayment.viewTag);
+
+    // The auditor can recover the ephemeral pubkey from its payload alone
+    const emitted = Buffer.from(paymentEvents[0].auditorEncryptedPayload);
+    const auditorView = new RescueCipher(
+      x25519.getSharedSecret(auditorKeys, emitted.subarray(0, 32)),
+    );
+    const [lo, hi] = auditorView.decrypt(
+      [Array.from(emitted.subarray(48, 80)), Array.from(emitted.subarray(80, 112))],
+      emitted.subarray(32, 48),
+    );
+    const recovered = Buffer.alloc(32);
+    recovered.set(Buffer.from(lo.toString(16).padStart(32, "0"), "hex").reverse(), 0);
+    recovered.set(Buffer.from(hi.toString(16).padStart(32, "0"), "hex").reverse(), 16);
+    expect(recovered).to.deep.equal(Buffer.from(ephemeralPubkeyBytes));
+
     // Derive nullifier
     nullifier = createHash("sha256")
       .update(Buffer.concat([stealthPayment.stealthAddress.toBuffer(), positionIdBytes]))
@@ -620,6 +884,13 @@ describe("Stealth Compressed Flow (E2E)", () => {
   it("Step 6-7: Employee discovers stealth payment", async () => {
     console.log("\n--- Employee scanning for stealth payments ---");
 
+    // Cheap pre-filter: the one-byte view tag must match before the full derivation
+    const viewTag = await computeViewTag(
+      employeeMetaKeys.viewPrivKey,
+      stealthPayment.ephemeralPubkey
+    );
+    expect(viewTag).to.equal(stealthPayment.viewTag);
+
     // Check if this payment belongs to the employee
     const isMine = await isMyStealthPayment(
       employeeMetaKeys.viewPrivKey,
@@ -687,6 +958,7 @@ describe("Stealth Compressed Flow (E2E)", () => {
       [
         Buffer.from("nullifier"),
         organizationPda.toBuffer(),
+        Buffer.alloc(8), // default nullifier namespace
         nullifier,
       ],
       program.programId
@@ -804,6 +1076,8 @@ describe("Stealth Compressed Flow (E2E)", () => {
         new anchor.BN(positionData.startTimestamp),
         positionData.isActive,
         positionData.isFullyClaimed,
+        positionData.isStealth,
+        Array.from(positionData.metadataCommitment) as any,
         Array.from(nullifier) as any,
         destinationTokenAccount
       )
@@ -812,6 +1086,7 @@ describe("Stealth Compressed Flow (E2E)", () => {
         nullifierRecord: nullifierRecordPda,
         organization: organizationPda,
         feePayer: admin.publicKey,
+        addressTree: new PublicKey(batchAddressTree),
         instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
         systemProgram: SystemProgram.programId,
       })
@@ -939,6 +1214,7 @@ describe("Stealth Compressed Flow (E2E)", () => {
       [
         Buffer.from("nullifier"),
         organizationPda.toBuffer(),
+        Buffer.alloc(8), // default nullifier namespace
         wrongNullifier,
       ],
       program.programId
@@ -960,6 +1236,8 @@ describe("Stealth Compressed Flow (E2E)", () => {
           new anchor.BN(positionData.startTimestamp),
           positionData.isActive,
           positionData.isFullyClaimed,
+          positionData.isStealth,
+          Array.from(positionData.metadataCommitment) as any,
           Array.from(wrongNullifier) as any,
           destinationTokenAccount
         )
@@ -968,6 +1246,7 @@ describe("Stealth Compressed Flow (E2E)", () => {
           nullifierRecord: wrongNullifierRecordPda,
           organization: organizationPda,
           feePayer: admin.publicKey,
+          addressTree: new PublicKey(batchAddressTree),
           instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
           systemProgram: SystemProgram.programId,
         })
@@ -1080,6 +1359,8 @@ describe("Stealth Compressed Flow (E2E)", () => {
           new anchor.BN(positionData.startTimestamp),
           positionData.isActive,
           positionData.isFullyClaimed,
+          positionData.isStealth,
+          Array.from(positionData.metadataCommitment) as any,
           Array.from(nullifier) as any,
           destinationTokenAccount
         )
@@ -1088,6 +1369,7 @@ describe("Stealth Compressed Flow (E2E)", () => {
           nullifierRecord: nullifierRecordPda,
           organization: organizationPda,
           feePayer: admin.publicKey,
+          addressTree: new PublicKey(batchAddressTree),
           instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
           systemProgram: SystemProgram.programId,
         })
@@ -1158,7 +1440,9 @@ describe("Stealth Compressed Flow (E2E)", () => {
         Array.from(beneficiaryCommitment) as any,
         Array.from(scratchCiphertext[0]) as any,
         Array.from(publicKey) as any,
-        scratchNonceAsBN
+        scratchNonceAsBN,
+        0, // sig_scheme: Ed25519
+        Array(32).fill(0),
       )
       .accountsPartial({
         payer: admin.publicKey,
@@ -1203,17 +1487,18 @@ describe("Stealth Compressed Flow (E2E)", () => {
     console.log("Scratch position initialized by MPC");
 
     // Now queue process_claim_v2 for the compressed position
+    // The position's total and claimed amounts, encrypted together; the vesting
+    // numerator is computed on-chain (fully vested after >10s) and the claim
+    // amount is passed in plaintext
     const claimedSoFar = BigInt(0);
-    const PRECISION = BigInt(1_000_000);
-    const vestingNumerator = PRECISION; // Fully vested (>10s elapsed)
 
     const nonce = randomBytes(16);
     const nonceAsBN = new anchor.BN(deserializeLE(nonce).toString());
 
-    const encryptedTotalAmount = cipher.encrypt([TOTAL_AMOUNT], nonce);
-    const encryptedClaimedAmount = cipher.encrypt([claimedSoFar], nonce);
-    const encryptedVestingNumerator = cipher.encrypt([vestingNumerator], nonce);
-    const encryptedClaimAmount = cipher.encrypt([CLAIM_AMOUNT], nonce);
+    const [encryptedTotalAmount, encryptedClaimedAmount] = cipher.encrypt(
+      [TOTAL_AMOUNT, claimedSoFar],
+      nonce
+    );
 
     const computationOffset = new anchor.BN(randomBytes(8), "hex");
 
@@ -1229,10 +1514,8 @@ describe("Stealth Compressed Flow (E2E)", () => {
       .queueProcessClaimCompressed(
         computationOffset,
         new anchor.BN(positionId),
-        Array.from(encryptedTotalAmount[0]) as any,
-        Array.from(encryptedClaimedAmount[0]) as any,
-        Array.from(encryptedVestingNumerator[0]) as any,
-        Array.from(encryptedClaimAmount[0]) as any,
+        Array.from(encryptedTotalAmount) as any,
+        Array.from(encryptedClaimedAmount) as any,
         new anchor.BN(CLAIM_AMOUNT.toString()),
         new anchor.BN(positionData.startTimestamp),
         Array.from(publicKey) as any,
@@ -1244,6 +1527,7 @@ describe("Stealth Compressed Flow (E2E)", () => {
         schedule: schedulePda,
         position: scratchPositionPda,
         claimAuthorization: claimAuthPda,
+        vault: vaultPda,
         signPdaAccount: signPda,
         mxeAccount: getMXEAccAddress(program.programId),
         mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
@@ -1337,12 +1621,10 @@ describe("Stealth Compressed Flow (E2E)", () => {
       Buffer.from(compressedAccount!.data!.data!)
     );
 
-    // Get the new encrypted_claimed_amount from scratch position
-    const scratchPosition = await program.account.vestingPosition.fetch(scratchPositionPda);
-    const newEncryptedClaimedAmount = scratchPosition.encryptedClaimedAmount;
-
-    // Determine if fully claimed
-    const newIsFullyClaimed = CLAIM_AMOUNT >= TOTAL_AMOUNT ? 1 : 0;
+    // The callback recorded the re-encrypted amounts and their nonce on the
+    // authorization, and the fully-claimed flag revealed by the MPC
+    const { compressedIsFullyClaimed: newIsFullyClaimed } =
+      await program.account.claimAuthorization.fetch(claimAuthPda);
 
     const computeIx = ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 });
     const priorityFeeIx = ComputeBudgetProgram.setComputeUnitPrice({ microLamports: 1000 });
@@ -1362,7 +1644,8 @@ describe("Stealth Compressed Flow (E2E)", () => {
         new anchor.BN(positionData.startTimestamp),
         positionData.isActive,
         positionData.isFullyClaimed,
-        Array.from(newEncryptedClaimedAmount) as any,
+        positionData.isStealth,
+        Array.from(positionData.metadataCommitment) as any,
         newIsFullyClaimed
       )
       .accountsPartial({
@@ -1398,10 +1681,19 @@ describe("Stealth Compressed Flow (E2E)", () => {
     const beforeBalance = await getAccount(provider.connection, destinationTokenAccount);
     expect(Number(beforeBalance.amount)).to.equal(0);
 
+    const withdrawalEvents: any[] = [];
+    const withdrawalListener = program.addEventListener("stealthWithdrawalEvent", (event: any) => {
+      withdrawalEvents.push(event);
+    });
+
     await program.methods
       .withdrawCompressed(
         new anchor.BN(positionId),
-        Array.from(nullifier) as any
+        Array.from(nullifier) as any,
+        false, // verify_light_state
+        Buffer.alloc(0),
+        Buffer.alloc(0),
+        Buffer.alloc(0)
       )
       .accountsPartial({
         payer: admin.publicKey,
@@ -1420,6 +1712,18 @@ describe("Stealth Compressed Flow (E2E)", () => {
     expect(Number(afterBalance.amount)).to.equal(Number(CLAIM_AMOUNT));
     console.log(`Withdrawal successful: ${Number(CLAIM_AMOUNT) / 1_000_000} tokens`);
 
+    // The withdrawal is attributed to the stealth address it was spent from
+    await sleep(3000);
+    await program.removeEventListener(withdrawalListener);
+    expect(withdrawalEvents).to.have.length(1);
+    expect(withdrawalEvents[0].stealthAddress.toString()).to.equal(
+      stealthPayment.stealthAddress.toString()
+    );
+    expect(withdrawalEvents[0].destination.toString()).to.equal(
+      destinationTokenAccount.toString()
+    );
+    expect(withdrawalEvents[0].amount.toString()).to.equal(CLAIM_AMOUNT.toString());
+
     // Verify claim is marked as withdrawn
     const claimAuth = await program.account.claimAuthorization.fetch(claimAuthPda);
     expect(claimAuth.isWithdrawn).to.be.true;
@@ -1436,7 +1740,11 @@ describe("Stealth Compressed Flow (E2E)", () => {
       await program.methods
         .withdrawCompressed(
           new anchor.BN(positionId),
-          Array.from(nullifier) as any
+          Array.from(nullifier) as any,
+          false, // verify_light_state
+          Buffer.alloc(0),
+          Buffer.alloc(0),
+          Buffer.alloc(0)
         )
         .accountsPartial({
           payer: admin.publicKey,
@@ -1643,6 +1951,8 @@ interface CompressedPositionData {
   startTimestamp: number;
   isActive: number;
   isFullyClaimed: number;
+  isStealth: number;
+  metadataCommitment: Uint8Array;
 }
 
 function parseCompressedPositionData(data: Buffer): CompressedPositionData {
@@ -1683,6 +1993,12 @@ function parseCompressedPositionData(data: Buffer): CompressedPositionData {
   offset += 1;
 
   const isFullyClaimed = data.readUInt8(offset);
+  offset += 1;
+
+  const isStealth = data.readUInt8(offset);
+  offset += 1;
+
+  const metadataCommitment = new Uint8Array(data.slice(offset, offset + 32));
 
   return {
     owner,
@@ -1696,6 +2012,8 @@ function parseCompressedPositionData(data: Buffer): CompressedPositionData {
     startTimestamp,
     isActive,
     isFullyClaimed,
+    isStealth,
+    metadataCommitment,
   };
 }
 
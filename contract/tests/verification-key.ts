@@ -0,0 +1,431 @@
+import "dotenv/config";
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { Contract } from "../target/types/contract";
+import { createHash, randomBytes } from "crypto";
+import * as fs from "fs";
+import * as os from "os";
+import { expect } from "chai";
+import {
+  SyntheticWithdrawalInputs,
+  programDataAddress,
+  randomScalarBytes,
+  syntheticProof,
+  syntheticProofForScalars,
+  syntheticVk,
+  syntheticWithdrawalInputs,
+  syntheticWithdrawalVk,
+} from "./fixtures/synthetic-groth16";
+
+describe("ShadowVest - Verification Key Export", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Contract as Program<Contract>;
+  const provider = anchor.getProvider();
+
+  let authority: Keypair;
+  let vkAccountPda: PublicKey;
+
+  // Fresh circuit id so the VK PDA does not collide with earlier runs
+  const circuitId = createHash("sha256").update(`export_test_${Date.now()}`).digest();
+
+  // VerificationKey layout: alpha_g1(64) | beta_g2(128) | gamma_g2(128) | delta_g2(128) | ic: Vec<[u8; 64]>
+  const alphaG1 = randomBytes(64);
+  const betaG2 = randomBytes(128);
+  const gammaG2 = randomBytes(128);
+  const deltaG2 = randomBytes(128);
+  // One IC point per withdrawal public input plus the constant term
+  const ic = Array.from({ length: 5 }, () => randomBytes(64));
+  const icLen = Buffer.alloc(4);
+  icLen.writeUInt32LE(ic.length);
+  const vkData = Buffer.concat([alphaG1, betaG2, gammaG2, deltaG2, icLen, ...ic]);
+
+  before(async () => {
+    // The provider wallet deployed the program, so it is the upgrade authority
+    authority = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    [vkAccountPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vk"), circuitId],
+      program.programId,
+    );
+  });
+
+  it("Exports a stored verification key in structured form", async () => {
+    await program.methods
+      .storeVerificationKey(Array.from(circuitId) as any, { withdrawal: {} } as any, vkData)
+      .accountsPartial({
+        authority: authority.publicKey,
+        programData: programDataAddress(program.programId),
+        vkAccount: vkAccountPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+
+    const { events } = await program.methods
+      .exportVerificationKey()
+      .accounts({ vkAccount: vkAccountPda })
+      .simulate();
+
+    const exported = events.find((e: any) => e.name === "verificationKeyExported");
+    expect(exported, "VerificationKeyExported event").to.not.be.undefined;
+    const data = (exported as any).data;
+
+    expect(Buffer.from(data.circuitId)).to.deep.equal(circuitId);
+    expect(data.isActive).to.be.true;
+    expect(Buffer.from(data.vkHash)).to.deep.equal(createHash("sha256").update(vkData).digest());
+    expect(Buffer.from(data.alphaG1)).to.deep.equal(alphaG1);
+    expect(Buffer.from(data.betaG2)).to.deep.equal(betaG2);
+    expect(Buffer.from(data.gammaG2)).to.deep.equal(gammaG2);
+    expect(Buffer.from(data.deltaG2)).to.deep.equal(deltaG2);
+    expect(data.ic.map((p: number[]) => Buffer.from(p))).to.deep.equal(ic);
+  });
+
+  it("Rejects verification with a deactivated key and accepts it again after reactivation", async () => {
+    const setActive = (active: boolean) =>
+      program.methods
+        .setVkActive(active)
+        .accounts({ authority: authority.publicKey, vkAccount: vkAccountPda })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+
+    const nullifier = randomBytes(32);
+    const [proofRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("proof_record"), authority.publicKey.toBuffer(), nullifier],
+      program.programId,
+    );
+    const verify = () =>
+      program.methods
+        .verifyWithdrawalProof(
+          { a: Array.from(randomBytes(64)), b: Array.from(randomBytes(128)), c: Array.from(randomBytes(64)) } as any,
+          {
+            stateRoot: Array.from(randomBytes(32)),
+            epochId: new anchor.BN(1),
+            nullifier: Array.from(nullifier),
+            withdrawalCommitment: Array.from(randomBytes(32)),
+          } as any,
+          0,
+        )
+        .accountsPartial({
+          verifier: authority.publicKey,
+          vkAccount: vkAccountPda,
+          proofRecord: proofRecordPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+
+    // A stranger cannot toggle the key
+    const stranger = Keypair.generate();
+    try {
+      await program.methods
+        .setVkActive(false)
+        .accounts({ authority: stranger.publicKey, vkAccount: vkAccountPda })
+        .signers([stranger])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("Should have thrown - not the VK authority");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("UnauthorizedAdmin");
+    }
+
+    await setActive(false);
+    expect((await program.account.verificationKeyAccount.fetch(vkAccountPda)).isActive).to.be.false;
+    try {
+      await verify();
+      expect.fail("Should have thrown - verification key is deactivated");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("VerificationKeyNotActive");
+    }
+
+    // Once reactivated the key is checked again (the random proof then fails on its points)
+    await setActive(true);
+    expect((await program.account.verificationKeyAccount.fetch(vkAccountPda)).isActive).to.be.true;
+    try {
+      await verify();
+      expect.fail("Should have thrown - random proof is invalid");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.not.include("VerificationKeyNotActive");
+    }
+  });
+});
+
+describe("ShadowVest - Proof Record Lifecycle", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Contract as Program<Contract>;
+  const provider = anchor.getProvider();
+
+  let authority: Keypair;
+  let verifier: Keypair;
+  let vkAccountPda: PublicKey;
+
+  const circuitId = createHash("sha256").update(`proof_record_test_${Date.now()}`).digest();
+  const vk = syntheticWithdrawalVk();
+
+  const recordPda = (verifierKey: PublicKey, nullifier: Buffer) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("proof_record"), verifierKey.toBuffer(), nullifier],
+      program.programId,
+    )[0];
+
+  const verify = (inputs: SyntheticWithdrawalInputs, ttlSeconds: number) =>
+    program.methods
+      .verifyWithdrawalProof(syntheticProof(vk, inputs) as any, inputs as any, ttlSeconds)
+      .accountsPartial({
+        verifier: verifier.publicKey,
+        vkAccount: vkAccountPda,
+        proofRecord: recordPda(verifier.publicKey, Buffer.from(inputs.nullifier)),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .preInstructions([anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 })])
+      .signers([verifier])
+      .rpc({ commitment: "confirmed" });
+
+  before(async () => {
+    const payer = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+    authority = payer;
+    verifier = Keypair.generate();
+    const fundTx = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.transfer({
+        fromPubkey: payer.publicKey,
+        toPubkey: verifier.publicKey,
+        lamports: 100_000_000, // 0.1 SOL
+      }),
+    );
+    await (provider as anchor.AnchorProvider).sendAndConfirm(fundTx, [payer]);
+
+    [vkAccountPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vk"), circuitId],
+      program.programId,
+    );
+    await program.methods
+      .storeVerificationKey(Array.from(circuitId) as any, { withdrawal: {} } as any, vk.data)
+      .accountsPartial({
+        authority: authority.publicKey,
+        programData: programDataAddress(program.programId),
+        vkAccount: vkAccountPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("Records the TTL and lets the verifier close and re-verify a nullifier", async () => {
+    const inputs = syntheticWithdrawalInputs();
+    const pda = recordPda(verifier.publicKey, Buffer.from(inputs.nullifier));
+
+    await verify(inputs, 3600);
+    const record = await program.account.proofRecord.fetch(pda);
+    expect(record.isValid).to.be.true;
+    expect(record.proofValidUntil.toNumber()).to.equal(record.verifiedAt.toNumber() + 3600);
+
+    // Another wallet cannot close the verifier's record
+    const stranger = Keypair.generate();
+    try {
+      await program.methods
+        .closeProofRecord()
+        .accountsPartial({ verifier: stranger.publicKey, proofRecord: pda })
+        .signers([stranger])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("Should have thrown - not the record's verifier");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.match(/ConstraintSeeds|UnauthorizedOwner/);
+    }
+
+    const before = await provider.connection.getBalance(verifier.publicKey);
+    await program.methods
+      .closeProofRecord()
+      .accountsPartial({ verifier: verifier.publicKey, proofRecord: pda })
+      .signers([verifier])
+      .rpc({ commitment: "confirmed" });
+    expect(await provider.connection.getAccountInfo(pda)).to.be.null;
+    expect(await provider.connection.getBalance(verifier.publicKey)).to.be.greaterThan(before);
+
+    // The nullifier can be verified again once its record is gone
+    await verify(inputs, 0);
+    expect((await program.account.proofRecord.fetch(pda)).proofValidUntil.toNumber()).to.equal(0);
+  });
+
+  it("Lets the VK authority close records only once the key is deactivated", async () => {
+    const inputs = syntheticWithdrawalInputs();
+    const pda = recordPda(verifier.publicKey, Buffer.from(inputs.nullifier));
+    await verify(inputs, 60);
+
+    const closeAsAuthority = () =>
+      program.methods
+        .closeInvalidatedProofRecord()
+        .accountsPartial({
+          authority: authority.publicKey,
+          vkAccount: vkAccountPda,
+          verifier: verifier.publicKey,
+          proofRecord: pda,
+        })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+
+    try {
+      await closeAsAuthority();
+      expect.fail("Should have thrown - verification key is still active");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("VerificationKeyStillActive");
+    }
+
+    await program.methods
+      .setVkActive(false)
+      .accounts({ authority: authority.publicKey, vkAccount: vkAccountPda })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+    await closeAsAuthority();
+    expect(await provider.connection.getAccountInfo(pda)).to.be.null;
+  });
+});
+
+describe("ShadowVest - Generic Proof Verification", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Contract as Program<Contract>;
+  const provider = anchor.getProvider();
+
+  let authority: Keypair;
+  let verifier: Keypair;
+
+  // Arity and nullifier position of each circuit's public inputs
+  const variants = [
+    { proofType: { withdrawal: {} }, name: "withdrawal", numInputs: 4, nullifierIndex: 2 },
+    { proofType: { identity: {} }, name: "identity", numInputs: 1, nullifierIndex: 0 },
+    { proofType: { eligibility: {} }, name: "eligibility", numInputs: 5, nullifierIndex: 1 },
+  ];
+
+  before(async () => {
+    const payer = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+    authority = payer;
+    verifier = Keypair.generate();
+    const fundTx = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.transfer({
+        fromPubkey: payer.publicKey,
+        toPubkey: verifier.publicKey,
+        lamports: 100_000_000, // 0.1 SOL
+      }),
+    );
+    await (provider as anchor.AnchorProvider).sendAndConfirm(fundTx, [payer]);
+  });
+
+  const storeVk = async (
+    variant: (typeof variants)[number],
+    vkData: Buffer,
+    signer: Keypair = authority,
+  ) => {
+    const circuitId = createHash("sha256")
+      .update(`dispatch_${variant.name}_${Date.now()}_${randomBytes(4).toString("hex")}`)
+      .digest();
+    const [vkAccountPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vk"), circuitId],
+      program.programId,
+    );
+    await program.methods
+      .storeVerificationKey(Array.from(circuitId) as any, variant.proofType as any, vkData)
+      .accountsPartial({
+        authority: signer.publicKey,
+        programData: programDataAddress(program.programId),
+        vkAccount: vkAccountPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc({ commitment: "confirmed" });
+    return { circuitId, vkAccountPda };
+  };
+
+  for (const variant of variants) {
+    it(`Verifies a ${variant.name} proof through verify_proof`, async () => {
+      const vk = syntheticVk(variant.numInputs);
+      const { circuitId, vkAccountPda } = await storeVk(variant, vk.data);
+
+      const scalars = Array.from({ length: variant.numInputs }, () => randomScalarBytes());
+      const nullifier = Buffer.from(scalars[variant.nullifierIndex]);
+      const [proofRecordPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("proof_record"), verifier.publicKey.toBuffer(), nullifier],
+        program.programId,
+      );
+
+      await program.methods
+        .verifyProof(
+          variant.proofType as any,
+          syntheticProofForScalars(vk, scalars) as any,
+          scalars as any,
+          0,
+        )
+        .accountsPartial({
+          verifier: verifier.publicKey,
+          vkAccount: vkAccountPda,
+          proofRecord: proofRecordPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .preInstructions([anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 })])
+        .signers([verifier])
+        .rpc({ commitment: "confirmed" });
+
+      const record = await program.account.proofRecord.fetch(proofRecordPda);
+      expect(record.isValid).to.be.true;
+      expect(Buffer.from(record.nullifier)).to.deep.equal(nullifier);
+      expect(Buffer.from(record.circuitId)).to.deep.equal(circuitId);
+    });
+  }
+
+  it("Rejects a proof submitted under a different type than its VK", async () => {
+    // An eligibility proof must not be recordable as a withdrawal proof,
+    // which would key a second record on another of its inputs
+    const eligibility = variants[2];
+    const vk = syntheticVk(eligibility.numInputs);
+    const { vkAccountPda } = await storeVk(eligibility, vk.data);
+
+    const scalars = Array.from({ length: eligibility.numInputs }, () => randomScalarBytes());
+    const [proofRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("proof_record"), verifier.publicKey.toBuffer(), Buffer.from(scalars[2])],
+      program.programId,
+    );
+    try {
+      await program.methods
+        .verifyProof(
+          { withdrawal: {} } as any,
+          syntheticProofForScalars(vk, scalars) as any,
+          scalars as any,
+          0,
+        )
+        .accountsPartial({
+          verifier: verifier.publicKey,
+          vkAccount: vkAccountPda,
+          proofRecord: proofRecordPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .preInstructions([anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 })])
+        .signers([verifier])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("Should have thrown - proof type does not match the VK");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("ProofTypeMismatch");
+    }
+  });
+
+  it("Rejects storing a VK from anyone but the upgrade authority", async () => {
+    try {
+      await storeVk(variants[0], syntheticVk(4).data, verifier);
+      expect.fail("Should have thrown - not the upgrade authority");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("UnauthorizedAdmin");
+    }
+  });
+
+  it("Rejects storing a VK whose arity does not match its proof type", async () => {
+    try {
+      await storeVk(variants[1], syntheticVk(4).data);
+      expect.fail("Should have thrown - identity VKs take one public input");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("InvalidVerificationKeyData");
+    }
+  });
+});
+
+function readKpJson(path: string): Keypair {
+  const file = fs.readFileSync(path);
+  return Keypair.fromSecretKey(
+    new Uint8Array(JSON.parse(file.toString())),
+  );
+}
@@ -0,0 +1,454 @@
+import "dotenv/config";
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  PublicKey,
+  Keypair,
+  ComputeBudgetProgram,
+  Ed25519Program,
+  SystemProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  AddressLookupTableProgram,
+  LAMPORTS_PER_SOL,
+} from "@solana/web3.js";
+import {
+  NATIVE_MINT,
+  TOKEN_PROGRAM_ID,
+  createSyncNativeInstruction,
+  getAccount,
+} from "@solana/spl-token";
+import { Contract } from "../target/types/contract";
+import { randomBytes, createHash } from "crypto";
+import {
+  getArciumEnv,
+  getCompDefAccOffset,
+  getArciumAccountBaseSeed,
+  getArciumProgramId,
+  RescueCipher,
+  deserializeLE,
+  getMXEPublicKey,
+  getMXEAccAddress,
+  getMempoolAccAddress,
+  getCompDefAccAddress,
+  getExecutingPoolAccAddress,
+  getComputationAccAddress,
+  getClusterAccAddress,
+  getFeePoolAccAddress,
+  getClockAccAddress,
+  getLookupTableAddress,
+  getArciumProgram,
+  x25519,
+} from "@arcium-hq/client";
+import * as fs from "fs";
+import * as os from "os";
+import { expect } from "chai";
+
+describe("ShadowVest - wSOL Withdraw and Unwrap", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Contract as Program<Contract>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+
+  const arciumEnv = getArciumEnv();
+  const clusterAccount = getClusterAccAddress(arciumEnv.arciumClusterOffset);
+
+  let admin: Keypair;
+  let organizationPda: PublicKey;
+  let schedulePda: PublicKey;
+  let positionPda: PublicKey;
+  let vaultPda: PublicKey;
+  let vaultAuthorityPda: PublicKey;
+  let claimAuthPda: PublicKey;
+  let cipher: RescueCipher;
+  let publicKey: Uint8Array;
+
+  const beneficiary = Keypair.generate();
+  // Native SOL recipient; authorized as the claim's withdrawal destination
+  const recipient = Keypair.generate().publicKey;
+  const nullifier = randomBytes(32);
+
+  const nameHash = createHash("sha256").update("WrappedSolTestOrg").digest();
+  const TOTAL_AMOUNT = BigInt(LAMPORTS_PER_SOL / 10); // 0.1 SOL
+  const CLAIM_AMOUNT = BigInt(LAMPORTS_PER_SOL / 20); // 0.05 SOL
+
+  before(async () => {
+    const payer = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    admin = Keypair.generate();
+    const fundTx = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.transfer({
+        fromPubkey: payer.publicKey,
+        toPubkey: admin.publicKey,
+        lamports: LAMPORTS_PER_SOL, // 1 SOL
+      }),
+    );
+    await provider.sendAndConfirm(fundTx, [payer]);
+
+    const privateKey = x25519.utils.randomSecretKey();
+    publicKey = x25519.getPublicKey(privateKey);
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+
+    await initCompDef(program, payer, "init_position");
+    await initCompDef(program, payer, "process_claim_v2");
+
+    [organizationPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("organization"), admin.publicKey.toBuffer()],
+      program.programId,
+    );
+    [schedulePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vesting_schedule"),
+        organizationPda.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId,
+    );
+    [positionPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vesting_position"),
+        organizationPda.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId,
+    );
+    [vaultAuthorityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_authority"), organizationPda.toBuffer()],
+      program.programId,
+    );
+    [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), organizationPda.toBuffer(), NATIVE_MINT.toBuffer()],
+      program.programId,
+    );
+
+    await program.methods
+      .createOrganization(Array.from(nameHash), Keypair.generate().publicKey, NATIVE_MINT)
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .createVestingSchedule(new anchor.BN(0), new anchor.BN(10), new anchor.BN(1), 0, NATIVE_MINT)
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+        schedule: schedulePda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .initializeVault()
+      .accounts({
+        admin: admin.publicKey,
+        organization: organizationPda,
+        vaultAuthority: vaultAuthorityPda,
+        vault: vaultPda,
+        tokenMint: NATIVE_MINT,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    // Wrap SOL straight into the vault
+    const wrapTx = new anchor.web3.Transaction().add(
+      SystemProgram.transfer({
+        fromPubkey: admin.publicKey,
+        toPubkey: vaultPda,
+        lamports: Number(TOTAL_AMOUNT),
+      }),
+      createSyncNativeInstruction(vaultPda),
+    );
+    await provider.sendAndConfirm(wrapTx, [admin]);
+  });
+
+  it("Creates and authorizes a claim paid in wSOL", async () => {
+    const nonce = randomBytes(16);
+    const ciphertext = cipher.encrypt([TOTAL_AMOUNT], nonce);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+
+    await program.methods
+      .createVestingPosition(
+        computationOffset,
+        Array.from(beneficiary.publicKey.toBytes()),
+        Array.from(ciphertext[0]),
+        Array.from(publicKey),
+        new anchor.BN(deserializeLE(nonce).toString()),
+        0, // sig_scheme: Ed25519
+        Array(32).fill(0),
+      )
+      .accountsPartial({
+        payer: admin.publicKey,
+        admin: admin.publicKey,
+        organization: organizationPda,
+        schedule: schedulePda,
+        position: positionPda,
+        ...arciumAccounts(computationOffset, "init_position"),
+      })
+      .preInstructions([
+        ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+      ])
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    await waitForAccountState(
+      program,
+      positionPda,
+      "vestingPosition",
+      (account: any) => "initialized" in account.state,
+      300000,
+    );
+
+    [claimAuthPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("claim_auth"), positionPda.toBuffer(), nullifier],
+      program.programId,
+    );
+    const [nullifierRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("nullifier"), organizationPda.toBuffer(), Buffer.alloc(8), nullifier],
+      program.programId,
+    );
+
+    // Message: position_id(8) || nullifier(32) || withdrawal_destination(32)
+    const positionIdBuf = Buffer.alloc(8);
+    const ed25519Ix = Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: beneficiary.secretKey,
+      message: Uint8Array.from(Buffer.concat([positionIdBuf, nullifier, recipient.toBuffer()])),
+    });
+
+    await program.methods
+      .authorizeClaim(Array.from(nullifier) as any, recipient, new anchor.BN(0))
+      .accounts({
+        payer: admin.publicKey,
+        organization: organizationPda,
+        position: positionPda,
+        claimAuthorization: claimAuthPda,
+        nullifierRecord: nullifierRecordPda,
+        instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+        systemProgram: SystemProgram.programId,
+      })
+      .preInstructions([ed25519Ix])
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    const processOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .queueProcessClaim(
+        processOffset,
+        new anchor.BN(CLAIM_AMOUNT.toString()),
+        new anchor.BN(0), // priority_fee
+      )
+      .accountsPartial({
+        payer: admin.publicKey,
+        organization: organizationPda,
+        schedule: schedulePda,
+        position: positionPda,
+        claimAuthorization: claimAuthPda,
+        vault: vaultPda,
+        ...arciumAccounts(processOffset, "process_claim_v2"),
+      })
+      .preInstructions([
+        ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+      ])
+      .signers([admin])
+      .rpc({ commitment: "confirmed" });
+
+    await waitForAccountState(
+      program,
+      claimAuthPda,
+      "claimAuthorization",
+      (account: any) => account.isProcessed === true,
+      600000,
+    );
+  });
+
+  it("Withdraws the claim as native SOL", async () => {
+    const [unwrapAccountPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("unwrap"), claimAuthPda.toBuffer()],
+      program.programId,
+    );
+    const setAtaOnly = (ataOnly: boolean) =>
+      program.methods
+        .setAtaOnlyWithdrawals(ataOnly)
+        .accounts({ admin: admin.publicKey, organization: organizationPda })
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+    const withdrawAndUnwrap = () =>
+      program.methods
+        .withdrawAndUnwrap()
+        .accountsPartial({
+          payer: admin.publicKey,
+          organization: organizationPda,
+          position: positionPda,
+          claimAuthorization: claimAuthPda,
+          schedule: schedulePda,
+          vaultAuthority: vaultAuthorityPda,
+          vault: vaultPda,
+          unwrapAccount: unwrapAccountPda,
+          nativeMint: NATIVE_MINT,
+          recipient,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc({ commitment: "confirmed" });
+
+    // The temporary wSOL account is not an ATA, so ATA-only organizations cannot unwrap
+    await setAtaOnly(true);
+    try {
+      await withdrawAndUnwrap();
+      expect.fail("Should have thrown - unwrap does not pay into an ATA");
+    } catch (err: any) {
+      expect(err.message || err.toString()).to.include("NonAtaDestination");
+    }
+    await setAtaOnly(false);
+
+    const vaultBefore = await getAccount(provider.connection, vaultPda);
+    const adminBefore = await provider.connection.getBalance(admin.publicKey);
+
+    await withdrawAndUnwrap();
+
+    // Recipient holds plain lamports, no token account left behind
+    expect(await provider.connection.getBalance(recipient)).to.equal(Number(CLAIM_AMOUNT));
+    expect(await provider.connection.getAccountInfo(unwrapAccountPda)).to.be.null;
+
+    const vaultAfter = await getAccount(provider.connection, vaultPda);
+    expect(vaultBefore.amount - vaultAfter.amount).to.equal(CLAIM_AMOUNT);
+
+    // The payer only spends fees and the claim history rent; temp account rent comes back
+    const adminAfter = await provider.connection.getBalance(admin.publicKey);
+    expect(adminBefore - adminAfter).to.be.lessThan(0.01 * LAMPORTS_PER_SOL);
+
+    const claimAuth = await program.account.claimAuthorization.fetch(claimAuthPda);
+    expect(claimAuth.isWithdrawn).to.be.true;
+  });
+
+  function arciumAccounts(computationOffset: anchor.BN, circuitName: string) {
+    const [signPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ArciumSignerAccount")],
+      program.programId,
+    );
+    return {
+      signPdaAccount: signPda,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      computationAccount: getComputationAccAddress(
+        arciumEnv.arciumClusterOffset,
+        computationOffset,
+      ),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuitName)).readUInt32LE(),
+      ),
+      clusterAccount,
+      poolAccount: getFeePoolAccAddress(),
+      clockAccount: getClockAccAddress(),
+      systemProgram: SystemProgram.programId,
+      arciumProgram: getArciumProgramId(),
+    };
+  }
+});
+
+// Helper functions
+
+async function initCompDef(
+  program: Program<Contract>,
+  owner: Keypair,
+  circuitName: string,
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed("ComputationDefinitionAccount");
+  const offset = getCompDefAccOffset(circuitName);
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgramId(),
+  )[0];
+
+  const accountInfo = await provider.connection.getAccountInfo(compDefPDA);
+  if (accountInfo !== null) {
+    return "already_initialized";
+  }
+
+  const mxeAccountAddr = getMXEAccAddress(program.programId);
+  const arciumProgram = getArciumProgram(provider);
+  const mxeAcc = await arciumProgram.account.mxeAccount.fetch(mxeAccountAddr);
+  const lutAddress = getLookupTableAddress(program.programId, mxeAcc.lutOffsetSlot);
+
+  let method;
+  if (circuitName === "init_position") {
+    method = program.methods.initInitPositionCompDef();
+  } else if (circuitName === "process_claim_v2") {
+    method = program.methods.initProcessClaimV2CompDef();
+  } else {
+    throw new Error(`Unknown circuit name: ${circuitName}`);
+  }
+
+  return method
+    .accountsPartial({
+      compDefAccount: compDefPDA,
+      payer: owner.publicKey,
+      mxeAccount: mxeAccountAddr,
+      addressLookupTable: lutAddress,
+      lutProgram: AddressLookupTableProgram.programId,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+}
+
+async function waitForAccountState(
+  program: Program<Contract>,
+  accountPda: PublicKey,
+  accountName: string,
+  predicate: (account: any) => boolean,
+  timeoutMs: number,
+): Promise<void> {
+  const deadline = Date.now() + timeoutMs;
+  while (Date.now() < deadline) {
+    try {
+      const account = await (program.account as any)[accountName].fetch(accountPda);
+      if (predicate(account)) {
+        return;
+      }
+    } catch (err) {
+      // Account might not exist yet
+    }
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+  }
+  throw new Error(`Timeout waiting for ${accountName} ${accountPda.toString()} after ${timeoutMs}ms`);
+}
+
+async function getMXEPublicKeyWithRetry(
+  provider: anchor.AnchorProvider,
+  programId: PublicKey,
+  maxRetries: number = 20,
+  retryDelayMs: number = 500,
+): Promise<Uint8Array> {
+  for (let attempt = 1; attempt <= maxRetries; attempt++) {
+    try {
+      const mxePublicKey = await getMXEPublicKey(provider, programId);
+      if (mxePublicKey) {
+        return mxePublicKey;
+      }
+    } catch (error) {
+      console.log(`Attempt ${attempt} failed to fetch MXE public key:`, error);
+    }
+    if (attempt < maxRetries) {
+      await new Promise((resolve) => setTimeout(resolve, retryDelayMs));
+    }
+  }
+  throw new Error(`Failed to fetch MXE public key after ${maxRetries} attempts`);
+}
+
+function readKpJson(path: string): Keypair {
+  const file = fs.readFileSync(path);
+  return Keypair.fromSecretKey(
+    new Uint8Array(JSON.parse(file.toString())),
+  );
+}
@@ -184,12 +184,6 @@ export interface PrepareClaimResponse {
   vaultPda: string
   vaultAuthorityPda: string
   vestingProgress: VestingProgressInfo
-  encryptedTotalAmount: number[]
-  encryptedClaimedAmount: number[]
-  encryptedVestingNumerator: number[]
-  encryptedClaimAmount: number[]
-  clientPubkey: number[]
-  nonce: string
   computationOffset: string
   arciumAccounts: {
     mxeAccount: string
@@ -119,8 +119,6 @@ export interface UpdateCompressedPositionParams {
   organization: PublicKey
   compressedAddress: PublicKey
   claimAuthorizationPda: PublicKey
-  /** New encrypted claimed amount from MPC callback */
-  newEncryptedClaimedAmount: number[]
   /** Whether position is now fully claimed */
   newIsFullyClaimed: number
 }
@@ -783,6 +781,7 @@ export async function authorizeClaimCompressed(
       nullifierRecord: nullifierRecordPda,
       organization,
       feePayer: payer,
+      addressTree: getAddressMerkleTree(),
       instructionsSysvar: new PublicKey('Sysvar1nstructions1111111111111111111111111'),
       systemProgram: SystemProgram.programId,
     })
@@ -895,6 +894,7 @@ export async function buildAuthorizeClaimInstruction(
       nullifierRecord: nullifierRecordPda,
       organization,
       feePayer: payer,
+      addressTree: getAddressMerkleTree(),
       instructionsSysvar: new PublicKey('Sysvar1nstructions1111111111111111111111111'),
       systemProgram: SystemProgram.programId,
     })
@@ -928,7 +928,6 @@ export async function updateCompressedPositionClaimed(
     organization,
     compressedAddress,
     claimAuthorizationPda,
-    newEncryptedClaimedAmount,
     newIsFullyClaimed,
   } = params
   const payer = program.provider.publicKey!
@@ -1000,7 +999,6 @@ export async function updateCompressedPositionClaimed(
       new BN(positionData.startTimestamp),
       positionData.isActive,
       positionData.isFullyClaimed,
-      newEncryptedClaimedAmount as number[],
       newIsFullyClaimed
     )
     .accountsPartial({
@@ -102,7 +102,7 @@ export async function createOrganizationWithVault(
   const admin = program.provider.publicKey!
   const [organization] = findOrganizationPda(admin)
   const [vaultAuthority] = findVaultAuthorityPda(organization)
-  const [vault] = findVaultPda(organization)
+  const [vault] = findVaultPda(organization, params.tokenMint)
 
   // Hash the name to 32-byte array
   const nameHash = await hashName(params.name)
@@ -236,7 +236,7 @@ export async function initializeVault(
   if (!orgData) throw new Error('Organization not found')
 
   const [vaultAuthority] = findVaultAuthorityPda(organization)
-  const [vault] = findVaultPda(organization)
+  const [vault] = findVaultPda(organization, orgData.tokenMint)
 
   const signature = await program.methods
     .initializeVault()
@@ -267,7 +267,7 @@ export async function depositToVault(
   if (!orgData) throw new Error('Organization not found')
 
   const [vaultAuthority] = findVaultAuthorityPda(organization)
-  const [vault] = findVaultPda(organization)
+  const [vault] = findVaultPda(organization, orgData.tokenMint)
   const adminTokenAccount = await getAssociatedTokenAddress(
     orgData.tokenMint,
     admin
@@ -391,7 +391,7 @@ export async function getOrganizationStats(
   // Try to fetch vault balance
   let vaultBalance: BN | null = null
   try {
-    const [vault] = findVaultPda(organization)
+    const [vault] = findVaultPda(organization, orgData.tokenMint)
     const vaultAccount = await program.provider.connection.getTokenAccountBalance(vault)
     vaultBalance = new BN(vaultAccount.value.amount)
   } catch {
@@ -320,10 +320,17 @@ export async function withdrawTokens(
     nullifier
   )
   const [vaultAuthority] = findVaultAuthorityPda(organization)
-  const [vault] = findVaultPda(organization)
+  const [vault] = findVaultPda(organization, orgData.tokenMint)
 
   return program.methods
-    .withdrawCompressed(new BN(positionId), Array.from(nullifier))
+    .withdrawCompressed(
+      new BN(positionId),
+      Array.from(nullifier),
+      false, // verify_light_state
+      Buffer.alloc(0),
+      Buffer.alloc(0),
+      Buffer.alloc(0)
+    )
     .accounts({
       payer,
       organization,
@@ -108,11 +108,11 @@ export function findVaultAuthorityPda(organization: PublicKey): [PublicKey, numb
 }
 
 /**
- * Derive Vault PDA
+ * Derive Vault PDA of one of the organization's token mints
  */
-export function findVaultPda(organization: PublicKey): [PublicKey, number] {
+export function findVaultPda(organization: PublicKey, mint: PublicKey): [PublicKey, number] {
   return PublicKey.findProgramAddressSync(
-    [Buffer.from('vault'), organization.toBuffer()],
+    [Buffer.from('vault'), organization.toBuffer(), mint.toBuffer()],
     PROGRAM_ID
   )
 }